@@ -0,0 +1,326 @@
+//! A client for `BrlAPI`, the interface brltty offers to applications, speaking its protocol directly rather than through the C library.
+//!
+//! Only what this server needs is implemented: connecting, authenticating, taking over a tty, reading keys, and showing text.
+//! Every packet starts with two big-endian `u32`s, the size of its payload and its type, followed by the payload.
+
+use std::path::Path;
+
+use eyre::{bail, ensure, Context};
+use tokio::{
+	io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+	net::{
+		unix::{OwnedReadHalf, OwnedWriteHalf},
+		UnixStream,
+	},
+};
+
+const PROTOCOL_VERSION: u32 = 8;
+/// The largest payload brltty sends, or accepts.
+const MAX_PACKET_SIZE: usize = 4096;
+
+// packet types are ASCII letters
+const PACKET_VERSION: u32 = 0x76; // 'v'
+const PACKET_AUTH: u32 = 0x61; // 'a'
+const PACKET_ENTER_TTY_MODE: u32 = 0x74; // 't'
+const PACKET_KEY: u32 = 0x6b; // 'k'
+const PACKET_GET_DISPLAY_SIZE: u32 = 0x73; // 's'
+const PACKET_WRITE: u32 = 0x77; // 'w'
+const PACKET_ACK: u32 = 0x41; // 'A'
+const PACKET_ERROR: u32 = 0x65; // 'e'
+const PACKET_EXCEPTION: u32 = 0x45; // 'E'
+
+const AUTH_NONE: u32 = 0x4e; // 'N'
+const AUTH_KEY: u32 = 0x4b; // 'K'
+const AUTH_CREDENTIALS: u32 = 0x43; // 'C'
+
+// what a write packet holds, in this order
+const WRITE_REGION: u32 = 0x02;
+const WRITE_TEXT: u32 = 0x04;
+const WRITE_CURSOR: u32 = 0x20;
+const WRITE_CHARSET: u32 = 0x40;
+/// The cursor position which shows no cursor.
+const CURSOR_OFF: u32 = 0;
+
+const KEY_TYPE_MASK: u64 = 0xE000_0000;
+const KEY_TYPE_COMMAND: u64 = 0x2000_0000;
+const KEY_BLOCK_MASK: u64 = 0x1FFF_0000;
+const KEY_BLOCK_SHIFT: u32 = 16;
+const KEY_ARGUMENT_MASK: u64 = 0xFFFF;
+const BLOCK_ROUTE: u64 = 1;
+
+/// The names brltty gives its commands without an argument, by number.
+const COMMANDS: [&str; 32] = [
+	"noop",
+	"lnup",
+	"lndn",
+	"winup",
+	"windn",
+	"prdifln",
+	"nxdifln",
+	"attrup",
+	"attrdn",
+	"top",
+	"bot",
+	"top_left",
+	"bot_left",
+	"prpgrph",
+	"nxpgrph",
+	"prprompt",
+	"nxprompt",
+	"prsearch",
+	"nxsearch",
+	"chrlt",
+	"chrrt",
+	"hwinlt",
+	"hwinrt",
+	"fwinlt",
+	"fwinrt",
+	"fwinltskip",
+	"fwinrtskip",
+	"lnbeg",
+	"lnend",
+	"home",
+	"back",
+	"return",
+];
+
+/// A key pressed on the braille display, as the brltty command it is bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+	/// A command without an argument, like `fwinrt` to pan right.
+	Command(u16),
+	/// A routing key, by the number of the cell it is above, from 0.
+	Route(u16),
+	/// Anything else, like a typed character, as its full `BrlAPI` key code.
+	Other(u64),
+}
+
+impl From<u64> for Key {
+	fn from(code: u64) -> Self {
+		if code & KEY_TYPE_MASK != KEY_TYPE_COMMAND {
+			return Key::Other(code);
+		}
+		let argument = u16::try_from(code & KEY_ARGUMENT_MASK).unwrap_or_default();
+		match (code & KEY_BLOCK_MASK) >> KEY_BLOCK_SHIFT {
+			0 => Key::Command(argument),
+			BLOCK_ROUTE => Key::Route(argument),
+			_ => Key::Other(code),
+		}
+	}
+}
+
+impl Key {
+	/// The name this key is bound by: brltty's name for the command (`cmd-<number>` for ones without a name here), `route` for any routing key, or `key-<code>` otherwise.
+	pub fn name(self) -> String {
+		match self {
+			Key::Command(command) => COMMANDS
+				.get(usize::from(command))
+				.map_or_else(|| format!("cmd-{command}"), ToString::to_string),
+			Key::Route(_) => "route".to_string(),
+			Key::Other(code) => format!("key-{code:#x}"),
+		}
+	}
+}
+
+/// The big-endian `u32`s making up `payload`; any trailing bytes are ignored.
+fn words(payload: &[u8]) -> impl Iterator<Item = u32> + '_ {
+	payload.chunks_exact(4)
+		.map(|w| u32::from_be_bytes([w[0], w[1], w[2], w[3]]))
+}
+
+/// Reads the next packet from brltty.
+async fn read(reader: &mut (impl AsyncRead + Unpin)) -> eyre::Result<(u32, Vec<u8>)> {
+	let size = usize::try_from(reader.read_u32().await?)?;
+	let kind = reader.read_u32().await?;
+	ensure!(
+		size <= MAX_PACKET_SIZE,
+		"brltty sent a packet of {size} bytes, which is too large"
+	);
+	let mut payload = vec![0; size];
+	reader.read_exact(&mut payload).await?;
+	Ok((kind, payload))
+}
+
+/// Sends a packet to brltty.
+async fn write(
+	writer: &mut (impl AsyncWrite + Unpin),
+	kind: u32,
+	payload: &[u8],
+) -> eyre::Result<()> {
+	let mut packet = u32::try_from(payload.len())?.to_be_bytes().to_vec();
+	packet.extend(kind.to_be_bytes());
+	packet.extend_from_slice(payload);
+	writer.write_all(&packet).await?;
+	Ok(())
+}
+
+/// A connection to brltty.
+#[derive(Debug)]
+pub struct Connection {
+	reader: OwnedReadHalf,
+	writer: OwnedWriteHalf,
+}
+
+/// The keys of a braille display, from a [`Connection`] split by [`Connection::split`].
+#[derive(Debug)]
+pub struct Keys {
+	reader: OwnedReadHalf,
+}
+
+/// The cells of a braille display, from a [`Connection`] split by [`Connection::split`].
+#[derive(Debug)]
+pub struct Display {
+	writer: OwnedWriteHalf,
+	cells: usize,
+}
+
+impl Connection {
+	/// Connects to brltty at `socket`, authenticating with the key in `key_file` if it asks for one.
+	/// # Errors
+	/// If brltty is not listening on `socket`, speaks another version of the protocol, or refuses the connection.
+	pub async fn connect(socket: &Path, key_file: &Path) -> eyre::Result<Self> {
+		let stream = UnixStream::connect(socket).await.with_context(|| {
+			format!("Could not connect to brltty at {}", socket.display())
+		})?;
+		let (reader, writer) = stream.into_split();
+		let mut connection = Connection { reader, writer };
+		let version = connection.expect(PACKET_VERSION).await?;
+		tracing::debug!(server_version = words(&version).next(), "Connected to brltty");
+		connection
+			.write(PACKET_VERSION, &PROTOCOL_VERSION.to_be_bytes())
+			.await?;
+		let methods: Vec<u32> = words(&connection.expect(PACKET_AUTH).await?).collect();
+		if methods.contains(&AUTH_NONE) {
+			return Ok(connection);
+		}
+		if methods.contains(&AUTH_KEY) {
+			if let Ok(key) = tokio::fs::read(key_file).await {
+				let mut payload = AUTH_KEY.to_be_bytes().to_vec();
+				payload.extend(key);
+				connection.write(PACKET_AUTH, &payload).await?;
+				connection.expect(PACKET_ACK).await.with_context(|| {
+					format!(
+						"brltty did not accept the key in {}",
+						key_file.display()
+					)
+				})?;
+				return Ok(connection);
+			}
+		}
+		if methods.contains(&AUTH_CREDENTIALS) {
+			connection.write(PACKET_AUTH, &AUTH_CREDENTIALS.to_be_bytes()).await?;
+			connection.expect(PACKET_ACK).await.context(
+				"brltty does not allow this user to use the braille display",
+			)?;
+			return Ok(connection);
+		}
+		bail!("Can not authenticate with brltty, which allows {methods:x?}; is {} readable?", key_file.display())
+	}
+	/// Takes over the braille display, and its keys, while the virtual terminal at the end of `ttys` is shown; `ttys` is the path to it, as in `WINDOWPATH`.
+	/// # Errors
+	/// If another application already has it, or brltty refuses.
+	pub async fn enter_tty_mode(&mut self, ttys: &[u32]) -> eyre::Result<()> {
+		let mut payload = u32::try_from(ttys.len())?.to_be_bytes().to_vec();
+		for tty in ttys {
+			payload.extend(tty.to_be_bytes());
+		}
+		// no driver name, so that keys are sent as commands, rather than as driver-specific codes
+		payload.push(0);
+		self.write(PACKET_ENTER_TTY_MODE, &payload).await?;
+		self.expect(PACKET_ACK)
+			.await
+			.context("brltty did not give us the braille display")?;
+		Ok(())
+	}
+	/// Asks for the size of the braille display, then splits the connection into its keys and its cells, so that both can be used at once.
+	/// # Errors
+	/// If brltty does not say how large the display is.
+	pub async fn split(mut self) -> eyre::Result<(Keys, Display)> {
+		self.write(PACKET_GET_DISPLAY_SIZE, &[]).await?;
+		let payload = self.expect(PACKET_GET_DISPLAY_SIZE).await?;
+		let mut size = words(&payload);
+		let (Some(columns), Some(rows)) = (size.next(), size.next()) else {
+			bail!("brltty did not say how large the braille display is");
+		};
+		let cells = usize::try_from(columns * rows)?;
+		tracing::debug!(columns, rows, "Braille display size");
+		Ok((Keys { reader: self.reader }, Display { writer: self.writer, cells }))
+	}
+	async fn read(&mut self) -> eyre::Result<(u32, Vec<u8>)> {
+		read(&mut self.reader).await
+	}
+	async fn write(&mut self, kind: u32, payload: &[u8]) -> eyre::Result<()> {
+		write(&mut self.writer, kind, payload).await
+	}
+	/// Reads the next packet, which has to be of type `kind`.
+	async fn expect(&mut self, kind: u32) -> eyre::Result<Vec<u8>> {
+		let (got, payload) = self.read().await?;
+		match got {
+			_ if got == kind => Ok(payload),
+			PACKET_ERROR | PACKET_EXCEPTION => {
+				bail!("brltty reported error {:?}", words(&payload).next())
+			}
+			_ => bail!(
+				"Expected a packet of type {kind:#x} from brltty, but got {got:#x}"
+			),
+		}
+	}
+}
+
+impl Keys {
+	/// Waits for the next key.
+	/// # Errors
+	/// If the connection is lost, or brltty reports an error.
+	pub async fn read_key(&mut self) -> eyre::Result<Key> {
+		loop {
+			let (kind, payload) = read(&mut self.reader).await?;
+			match kind {
+				PACKET_KEY => {
+					let mut code = words(&payload);
+					let (Some(high), Some(low)) = (code.next(), code.next())
+					else {
+						bail!(
+							"brltty sent a key packet of {} bytes",
+							payload.len()
+						);
+					};
+					return Ok(Key::from(
+						u64::from(high) << 32 | u64::from(low),
+					));
+				}
+				PACKET_ERROR | PACKET_EXCEPTION => {
+					bail!("brltty reported error {:?}", words(&payload).next());
+				}
+				_ => tracing::debug!(kind, "Ignoring packet from brltty"),
+			}
+		}
+	}
+}
+
+impl Display {
+	/// How many cells the display has.
+	pub fn cells(&self) -> usize {
+		self.cells
+	}
+	/// Shows `text` on the display, cut off or padded with blanks to fill it, without a cursor.
+	/// brltty does not answer writes; if it refuses one, the error comes with the next key.
+	/// # Errors
+	/// If the connection is lost.
+	pub async fn show(&mut self, text: &str) -> eyre::Result<()> {
+		let shown: String =
+			text.chars().chain(std::iter::repeat(' ')).take(self.cells).collect();
+		let charset = b"UTF-8";
+		let mut payload = (WRITE_REGION | WRITE_TEXT | WRITE_CURSOR | WRITE_CHARSET)
+			.to_be_bytes()
+			.to_vec();
+		// regions start at 1
+		payload.extend(1u32.to_be_bytes());
+		payload.extend(u32::try_from(self.cells)?.to_be_bytes());
+		payload.extend(u32::try_from(shown.len())?.to_be_bytes());
+		payload.extend(shown.as_bytes());
+		payload.extend(CURSOR_OFF.to_be_bytes());
+		payload.push(u8::try_from(charset.len())?);
+		payload.extend(charset);
+		write(&mut self.writer, PACKET_WRITE, &payload).await
+	}
+}
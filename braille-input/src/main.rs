@@ -0,0 +1,279 @@
+#![deny(
+	clippy::all,
+	clippy::pedantic,
+	clippy::cargo,
+	clippy::map_unwrap_or,
+	clippy::unwrap_used,
+	unsafe_code
+)]
+#![allow(clippy::multiple_crate_versions)]
+
+//! Turns the keys of a braille display into Odilia events, and shows Odilia's messages on it.
+//!
+//! This connects to brltty, takes over the braille display while the graphical session's virtual terminal is shown, and sends an event over Odilia's input socket for every key bound in `braille-input.toml`.
+//! Whatever Odilia shows in braille, which it signals on D-Bus while its messages go to braille, is written to the display.
+//! Keys pressed while Odilia restarts are held for a few seconds, and sent once it is back.
+
+mod brlapi;
+
+use std::{collections::HashMap, env, path::PathBuf, time::Duration};
+
+use brlapi::Key;
+use eyre::{bail, Context};
+use futures::{stream, StreamExt};
+use odilia_common::events::{Direction, ScreenReaderEvent};
+use odilia_input::EventQueue;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing_subscriber::EnvFilter;
+
+/// Where brltty usually puts its socket, without the number of the display.
+const SOCKET_DIRS: [&str; 2] = ["/run/brltty/BrlAPI", "/var/lib/BrlAPI"];
+
+///the configuration of the braille input server, read from `braille-input.toml` in odilia's configuration directory
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct Settings {
+	///brltty's socket; by default, `BRLAPI_HOST` is used, or the usual locations are tried in turn
+	socket: Option<PathBuf>,
+	///the key brltty may ask for
+	key_file: PathBuf,
+	///how often to tell odilia that this server is still running, in milliseconds
+	heartbeat_ms: u64,
+	///the event sent for each key, by brltty's name for its command, for example `lndn` for the next line
+	/// for `route`, where the routing key pressed is in the message shown, in characters from 0, is added to the arguments of a `custom` event
+	/// a `custom` event named `braille-pan`, with `left` or `right`, is not sent, but pans the message shown by the width of the display
+	bindings: HashMap<String, ScreenReaderEvent>,
+}
+
+/// The name of the `custom` event which pans the message shown, rather than being sent to Odilia.
+const PAN: &str = "braille-pan";
+
+fn custom(name: &str, args: &[&str]) -> ScreenReaderEvent {
+	ScreenReaderEvent::Custom {
+		name: name.to_string(),
+		args: args.iter().map(ToString::to_string).collect(),
+	}
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Self {
+			socket: None,
+			key_file: "/etc/brlapi.key".into(),
+			heartbeat_ms: 5000,
+			bindings: HashMap::from([
+				("route".to_string(), custom("braille-route", &[])),
+				("fwinlt".to_string(), custom(PAN, &["left"])),
+				("fwinrt".to_string(), custom(PAN, &["right"])),
+				(
+					"lnup".to_string(),
+					ScreenReaderEvent::ReviewLine(Direction::Backward),
+				),
+				(
+					"lndn".to_string(),
+					ScreenReaderEvent::ReviewLine(Direction::Forward),
+				),
+			]),
+		}
+	}
+}
+
+fn load_settings() -> eyre::Result<Settings> {
+	let dirs = xdg::BaseDirectories::with_prefix("odilia")?;
+	let Some(path) = dirs.find_config_file("braille-input.toml") else {
+		return Ok(Settings::default());
+	};
+	let text = std::fs::read_to_string(&path)
+		.with_context(|| format!("Could not read {}", path.display()))?;
+	toml::from_str(&text).with_context(|| format!("{} is invalid", path.display()))
+}
+
+/// The sockets to try connecting to brltty with, in order.
+fn sockets(settings: &Settings) -> Vec<PathBuf> {
+	if let Some(socket) = &settings.socket {
+		return vec![socket.clone()];
+	}
+	// `:1` is the second display; other hosts are reached over TCP, which is not supported
+	let display = env::var("BRLAPI_HOST")
+		.ok()
+		.and_then(|host| host.strip_prefix(':').map(ToString::to_string))
+		.unwrap_or_else(|| "0".to_string());
+	SOCKET_DIRS
+		.iter()
+		.map(|dir| PathBuf::from(dir).join(&display))
+		.collect()
+}
+
+/// The virtual terminal the graphical session runs on, as a path of ttys like brltty expects.
+fn ttys() -> eyre::Result<Vec<u32>> {
+	if let Ok(path) = env::var("WINDOWPATH") {
+		return path
+			.split(':')
+			.map(|tty| {
+				tty.parse().with_context(|| format!("Invalid WINDOWPATH {path}"))
+			})
+			.collect();
+	}
+	if let Ok(vt) = env::var("XDG_VTNR") {
+		return Ok(vec![vt.parse().with_context(|| format!("Invalid XDG_VTNR {vt}"))?]);
+	}
+	bail!("Can not tell which virtual terminal this session is on; neither WINDOWPATH nor XDG_VTNR is set")
+}
+
+async fn connect(settings: &Settings) -> eyre::Result<brlapi::Connection> {
+	let mut last_error = None;
+	for socket in sockets(settings) {
+		match brlapi::Connection::connect(&socket, &settings.key_file).await {
+			Ok(connection) => return Ok(connection),
+			Err(e) => last_error = Some(e),
+		}
+	}
+	Err(last_error.unwrap_or_else(|| eyre::eyre!("No brltty socket to connect to")))
+}
+
+/// How long to wait for Odilia to create its input socket, when started along with it.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many keys are held while Odilia can not be reached, like while it restarts.
+const QUEUED_KEYS: usize = 64;
+/// How long keys are held while Odilia can not be reached; older ones are dropped rather than acted on late.
+const QUEUED_FOR: Duration = Duration::from_secs(5);
+
+/// The message shown on the display, and which part of it fits.
+#[derive(Debug)]
+struct Window {
+	text: Vec<char>,
+	/// The first character shown.
+	start: usize,
+	cells: usize,
+}
+
+impl Window {
+	fn new(cells: usize) -> Self {
+		Window { text: Vec::new(), start: 0, cells }
+	}
+	/// Shows the start of a new message.
+	fn replace(&mut self, text: &str) {
+		self.text = text.chars().collect();
+		self.start = 0;
+	}
+	/// Moves to the next (or previous) part of the message, returning whether there was one.
+	fn pan(&mut self, right: bool) -> bool {
+		let start = if right {
+			self.start + self.cells
+		} else {
+			self.start.saturating_sub(self.cells)
+		};
+		if start == self.start || start >= self.text.len() {
+			return false;
+		}
+		self.start = start;
+		true
+	}
+	/// The part of the message which fits on the display.
+	fn shown(&self) -> String {
+		self.text.iter().skip(self.start).take(self.cells).collect()
+	}
+}
+
+/// Queues the event bound to `key`, if any, or pans `window`; returns whether what is shown changed.
+fn handle(key: Key, settings: &Settings, queue: &EventQueue, window: &mut Window) -> bool {
+	// every key counts as activity, whether or not it is bound, like any other key press
+	queue.send(ScreenReaderEvent::UserActivity);
+	let name = key.name();
+	let Some(event) = settings.bindings.get(&name) else {
+		tracing::debug!(key = name, "Key is not bound");
+		return false;
+	};
+	let mut event = event.clone();
+	match (key, &mut event) {
+		(_, ScreenReaderEvent::Custom { name, args }) if name == PAN => {
+			return window.pan(args.first().is_some_and(|side| side == "right"));
+		}
+		(Key::Route(cell), ScreenReaderEvent::Custom { args, .. }) => {
+			args.push((window.start + usize::from(cell)).to_string());
+		}
+		_ => {}
+	}
+	queue.send(event);
+	false
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+	tracing_subscriber::fmt()
+		.with_env_filter(EnvFilter::from_default_env())
+		.init();
+	let settings = load_settings()?;
+	if let Err(e) = odilia_input::wait_for_socket(STARTUP_TIMEOUT).await {
+		tracing::error!("{e}");
+		std::process::exit(odilia_input::EXIT_UNAVAILABLE);
+	}
+	let mut brltty = connect(&settings).await?;
+	brltty.enter_tty_mode(&ttys()?).await?;
+	let (mut keys, mut braille) = brltty.split().await?;
+	tracing::info!(cells = braille.cells(), "Sending braille display keys to Odilia");
+	// keys are read by a task of their own, since a key read only in part can not be finished later
+	let (key_tx, mut key_rx) = mpsc::channel(QUEUED_KEYS);
+	let reader = tokio::spawn(async move {
+		loop {
+			let key = keys.read_key().await?;
+			if key_tx.send(key).await.is_err() {
+				return eyre::Ok(());
+			}
+		}
+	});
+	let odilia = match odilia_client::Client::connect().await {
+		Ok(odilia) => Some(odilia),
+		Err(e) => {
+			tracing::warn!("Not showing Odilia's messages, since its D-Bus interface can not be reached: {e}");
+			None
+		}
+	};
+	let mut window = Window::new(braille.cells());
+	let mut messages = match &odilia {
+		Some(odilia) => {
+			if let Ok(message) = odilia.braille().await {
+				window.replace(&message);
+				braille.show(&window.shown()).await?;
+			}
+			odilia.braille_changes().await.left_stream()
+		}
+		None => stream::pending().right_stream(),
+	};
+	let shutdown = CancellationToken::new();
+	let heartbeats = odilia_input::send_heartbeats(
+		"braille-input".to_string(),
+		Duration::from_millis(settings.heartbeat_ms),
+		shutdown.clone(),
+	);
+	let heartbeats = tokio::spawn(heartbeats);
+	let queue = EventQueue::spawn(QUEUED_KEYS, QUEUED_FOR, shutdown.clone());
+	loop {
+		tokio::select! {
+			key = key_rx.recv() => {
+				let Some(key) = key else {
+					break;
+				};
+				if handle(key, &settings, &queue, &mut window) {
+					braille.show(&window.shown()).await?;
+				}
+			}
+			Some(message) = messages.next() => {
+				window.replace(&message);
+				braille.show(&window.shown()).await?;
+			}
+			_ = tokio::signal::ctrl_c() => break,
+		}
+	}
+	shutdown.cancel();
+	heartbeats.await?;
+	reader.abort();
+	match reader.await {
+		Ok(result) => result,
+		Err(e) if e.is_cancelled() => Ok(()),
+		Err(e) => Err(e.into()),
+	}
+}
@@ -0,0 +1,74 @@
+use std::{
+	alloc::{GlobalAlloc, Layout, System},
+	hint::black_box,
+	sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use odilia_cache::CacheItem;
+use odilia_common::cache::AccessiblePrimitive;
+
+/// Counts every allocation, so that the benchmarks can report how many a key operation needs.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+		System.alloc(layout)
+	}
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		System.dealloc(ptr, layout);
+	}
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Runs `f`, and prints how many allocations it made per key.
+fn report_allocations<T>(name: &str, keys: usize, f: impl FnOnce() -> T) -> T {
+	let before = ALLOCATIONS.load(Ordering::Relaxed);
+	let result = f();
+	let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+	#[allow(clippy::cast_precision_loss)]
+	let per_key = allocations as f64 / keys as f64;
+	println!("{name}: {allocations} allocations for {keys} keys ({per_key:.2} per key)");
+	result
+}
+
+fn keys(c: &mut Criterion) {
+	let items: Vec<CacheItem> =
+		serde_json::from_str(include_str!("./zbus_docs_cache_items.json")).unwrap();
+	let keys: Vec<AccessiblePrimitive> = items.iter().map(|item| item.object.clone()).collect();
+
+	report_allocations("clone", keys.len(), || black_box(keys.clone()));
+	report_allocations("clone items", items.len(), || black_box(items.clone()));
+	report_allocations("from (sender, path)", keys.len(), || {
+		black_box(
+			keys.iter()
+				.map(|key| {
+					AccessiblePrimitive::from((
+						key.sender.to_string(),
+						zbus::zvariant::ObjectPath::try_from(
+							key.id.as_str(),
+						)
+						.unwrap(),
+					))
+				})
+				.collect::<Vec<_>>(),
+		)
+	});
+
+	let mut group = c.benchmark_group("keys");
+	group.bench_function(BenchmarkId::new("clone", "zbus-docs"), |b| {
+		b.iter_batched(|| (), |()| keys.clone(), BatchSize::SmallInput);
+	});
+	group.bench_function(BenchmarkId::new("compare", "zbus-docs"), |b| {
+		b.iter(|| keys.iter().filter(|key| **key == keys[0]).count());
+	});
+	group.finish();
+}
+
+criterion_group!(benches, keys);
+criterion_main!(benches);
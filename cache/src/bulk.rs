@@ -0,0 +1,21 @@
+//! Doing the same thing to many items, a bounded number at a time.
+//!
+//! Fetching items one after another leaves most of the time waiting on the bus, while fetching all of them at once floods an application, which answers one call at a time anyway.
+
+use std::future::Future;
+
+use futures::{stream, StreamExt, TryStreamExt};
+
+/// Calls `f` on every item, with at most `limit` calls in flight at once, and returns their results in the order of `items`, not the order they finished in.
+///
+/// Stops at the first error, in the order of `items`: the calls still in flight are cancelled, and later items are never started.
+/// Dropping the returned future cancels the calls in flight the same way.
+/// A `limit` of 0 is taken as 1.
+pub async fn try_map_bounded<I, F, Fut, T, E>(items: I, limit: usize, f: F) -> Result<Vec<T>, E>
+where
+	I: IntoIterator,
+	F: FnMut(I::Item) -> Fut,
+	Fut: Future<Output = Result<T, E>>,
+{
+	stream::iter(items).map(f).buffered(limit.max(1)).try_collect().await
+}
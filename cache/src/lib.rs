@@ -15,17 +15,24 @@ mod convertable;
 pub use convertable::Convertable;
 mod accessible_ext;
 pub use accessible_ext::AccessibleExt;
+pub mod bulk;
+pub mod repair;
 
 use std::{
 	collections::HashMap,
 	fmt::Debug,
 	future::Future,
 	sync::{Arc, RwLock, Weak},
+	time::Duration,
 };
 
 use atspi_common::{
-	ClipType, CoordType, EventProperties, Granularity, InterfaceSet, RelationType, Role,
-	StateSet,
+	events::{
+		object::{Property, TextChangedEvent},
+		Event, ObjectEvents,
+	},
+	ClipType, CoordType, EventProperties, Granularity, Interface, InterfaceSet, Operation,
+	RelationType, Role, StateSet,
 };
 use atspi_proxies::{accessible::AccessibleProxy, text::TextProxy};
 use dashmap::DashMap;
@@ -33,9 +40,11 @@ use fxhash::FxBuildHasher;
 use odilia_common::{
 	cache::AccessiblePrimitive,
 	errors::{CacheError, OdiliaError},
+	privacy::Redacted,
 	result::OdiliaResult,
 };
 use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 use zbus::proxy::CacheProperties;
 
 trait AllText {
@@ -52,7 +61,7 @@ type CacheKey = AccessiblePrimitive;
 type InnerCache = DashMap<CacheKey, Arc<RwLock<CacheItem>>, FxBuildHasher>;
 type ThreadSafeCache = Arc<InnerCache>;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 /// A struct representing an accessible. To get any information from the cache other than the stored information like role, interfaces, and states, you will need to instantiate an [`atspi_proxies::accessible::AccessibleProxy`] or other `*Proxy` type from atspi to query further info.
 pub struct CacheItem {
 	/// The accessible object (within the application)    (so)
@@ -75,10 +84,35 @@ pub struct CacheItem {
 	pub text: String,
 	/// The children (ids) of the accessible
 	pub children: Vec<CacheRef>,
+	/// The key binding of the accessible's first action, as reported by the `Action` interface; like `<Alt>s;<Alt>f:s;<Control>s` (mnemonic, full sequence and shortcut), or only the shortcut.
+	#[serde(default)]
+	pub shortcut: Option<String>,
+	/// The value of a slider, spin button, progress bar or similar, as last read; see [`CacheItem::read_value`].
+	#[serde(default)]
+	pub value: Option<Value>,
 
 	#[serde(skip)]
 	pub cache: Weak<Cache>,
 }
+impl std::fmt::Debug for CacheItem {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("CacheItem")
+			.field("object", &self.object)
+			.field("app", &self.app)
+			.field("parent", &self.parent)
+			.field("index", &self.index)
+			.field("children_num", &self.children_num)
+			.field("interfaces", &self.interfaces)
+			.field("role", &self.role)
+			.field("states", &self.states)
+			.field("text", &Redacted(&self.text))
+			.field("children", &self.children)
+			.field("shortcut", &self.shortcut)
+			.field("value", &self.value)
+			.field("cache", &self.cache)
+			.finish()
+	}
+}
 impl CacheItem {
 	/// Return a *reference* to a parent. This is *much* cheaper than getting the parent element outright via [`Self::parent`].
 	/// # Errors
@@ -133,15 +167,17 @@ impl CacheItem {
 		cache: Weak<Cache>,
 		connection: &zbus::Connection,
 	) -> OdiliaResult<Self> {
-		let children: Vec<CacheRef> =
-			AccessiblePrimitive::from(atspi_cache_item.object.clone())
-				.into_accessible(connection)
-				.await?
-				.get_children()
-				.await?
-				.into_iter()
-				.map(|child_object_pair| CacheRef::new(child_object_pair.into()))
-				.collect();
+		let accessible = AccessiblePrimitive::from(atspi_cache_item.object.clone())
+			.into_accessible(connection)
+			.await?;
+		let children: Vec<CacheRef> = accessible
+			.get_children()
+			.await?
+			.into_iter()
+			.map(|child_object_pair| CacheRef::new(child_object_pair.into()))
+			.collect();
+		let shortcut = key_binding(&accessible, atspi_cache_item.ifaces).await;
+		let value = cached_value(&accessible, atspi_cache_item.ifaces).await;
 		Ok(Self {
 			object: atspi_cache_item.object.into(),
 			app: atspi_cache_item.app.into(),
@@ -154,6 +190,8 @@ impl CacheItem {
 			text: atspi_cache_item.name,
 			cache,
 			children,
+			shortcut,
+			value,
 		})
 	}
 	/// Convert an [`atspi::LegacyCacheItem`] into a [`crate::CacheItem`].
@@ -172,11 +210,12 @@ impl CacheItem {
 		cache: Weak<Cache>,
 		connection: &zbus::Connection,
 	) -> OdiliaResult<Self> {
-		let index: i32 = AccessiblePrimitive::from(atspi_cache_item.object.clone())
+		let accessible = AccessiblePrimitive::from(atspi_cache_item.object.clone())
 			.into_accessible(connection)
-			.await?
-			.get_index_in_parent()
 			.await?;
+		let index: i32 = accessible.get_index_in_parent().await?;
+		let shortcut = key_binding(&accessible, atspi_cache_item.ifaces).await;
+		let value = cached_value(&accessible, atspi_cache_item.ifaces).await;
 		Ok(Self {
 			object: atspi_cache_item.object.into(),
 			app: atspi_cache_item.app.into(),
@@ -193,6 +232,8 @@ impl CacheItem {
 				.into_iter()
 				.map(|or| CacheRef::new(or.into()))
 				.collect(),
+			shortcut,
+			value,
 		})
 	}
 	// Same as [`AccessibleProxy::get_children`], just offered as a non-async version.
@@ -311,6 +352,22 @@ impl CacheItem {
 	pub async fn description(&self) -> Result<String, OdiliaError> {
 		Ok(as_accessible(self).await?.description().await?)
 	}
+	/// See [`atspi_proxies::accessible::AccessibleProxy::help_text`]
+	/// # Errors
+	/// - If the item is no longer available over the AT-SPI connection.
+	pub async fn help_text(&self) -> Result<String, OdiliaError> {
+		Ok(as_accessible(self).await?.help_text().await?)
+	}
+	/// Reads the value of a slider, spin button, progress bar or similar again, and keeps it in the cache for next time; see [`Value`].
+	/// # Errors
+	/// - If the item is no longer available over the AT-SPI connection.
+	/// - If the item has no value.
+	pub async fn read_value(&self) -> Result<Value, OdiliaError> {
+		let value = value_of(&as_accessible(self).await?).await?;
+		strong_cache(&self.cache)?
+			.modify_item(&self.object, |item| item.value = Some(value.clone()))?;
+		Ok(value)
+	}
 	/// See [`atspi_proxies::accessible::AccessibleProxy::get_relation_set`]
 	/// # Errors
 	/// - If the item is no longer available over the AT-SPI connection.
@@ -322,12 +379,9 @@ impl CacheItem {
 		let ipc_rs = as_accessible(self).await?.get_relation_set().await?;
 		let mut relations = Vec::new();
 		for (relation, object_pairs) in ipc_rs {
-			let mut cache_keys = Vec::new();
-			for object_pair in object_pairs {
-				let cached = cache.get_ipc(&object_pair.into()).await?;
-				cache_keys.push(cached);
-			}
-			relations.push((relation, cache_keys));
+			let keys: Vec<CacheKey> =
+				object_pairs.into_iter().map(Into::into).collect();
+			relations.push((relation, cache.get_or_create_all(&keys).await?));
 		}
 		Ok(relations)
 	}
@@ -603,6 +657,53 @@ impl CacheItem {
 pub struct Cache {
 	pub by_id: ThreadSafeCache,
 	pub connection: zbus::Connection,
+	/// Every change to an item, for [`Cache::watch`].
+	changes: broadcast::Sender<(CacheKey, Change)>,
+}
+
+/// How an item watched with [`Cache::watch`] changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+	/// Its text or its name changed.
+	Text,
+	/// Children were added, removed or reordered.
+	Children,
+	/// It was removed from the cache; no more changes will follow.
+	Removed,
+	/// The watcher fell behind, and some changes were missed; the item should be read again.
+	Lagged,
+}
+
+/// Changes to one item, see [`Cache::watch`].
+#[derive(Debug)]
+pub struct Watch {
+	key: CacheKey,
+	changes: broadcast::Receiver<(CacheKey, Change)>,
+	removed: bool,
+}
+
+impl Watch {
+	/// Waits for the next change to the watched item.
+	/// Returns `None` after [`Change::Removed`], or once the cache itself is gone.
+	pub async fn next(&mut self) -> Option<Change> {
+		// do not report anything for a new item which reuses the key
+		if self.removed {
+			return None;
+		}
+		loop {
+			match self.changes.recv().await {
+				Ok((key, change)) if key == self.key => {
+					self.removed = change == Change::Removed;
+					return Some(change);
+				}
+				Ok(_) => {}
+				Err(broadcast::error::RecvError::Lagged(_)) => {
+					return Some(Change::Lagged)
+				}
+				Err(broadcast::error::RecvError::Closed) => return None,
+			}
+		}
+	}
 }
 
 impl std::fmt::Debug for Cache {
@@ -611,11 +712,22 @@ impl std::fmt::Debug for Cache {
 	}
 }
 
+/// How many items [`CacheExt::get_or_create_all`] fetches from applications at once.
+pub const FETCH_CONCURRENCY: usize = 8;
+/// How long [`CacheExt::get_ipc`] waits for an application to describe an item, before giving up on it as hung.
+pub const IPC_TIMEOUT: Duration = Duration::from_secs(2);
+
 pub trait CacheExt {
 	fn get_ipc(
 		&self,
 		id: &CacheKey,
 	) -> impl Future<Output = Result<CacheItem, OdiliaError>> + Send;
+	/// Like [`CacheExt::get_ipc`], for every item in `ids`, fetching up to [`FETCH_CONCURRENCY`] of them at once; the items come back in the order of `ids`.
+	/// Items which were not in the cache yet are added to it, like by [`Cache::get_or_create`].
+	fn get_or_create_all(
+		&self,
+		ids: &[CacheKey],
+	) -> impl Future<Output = Result<Vec<CacheItem>, OdiliaError>> + Send;
 	fn item_from_event<T: EventProperties + Sync>(
 		&self,
 		ev: &T,
@@ -624,15 +736,38 @@ pub trait CacheExt {
 
 impl CacheExt for Arc<Cache> {
 	/// Get a single item from the cache. This will also get the information from DBus if it does not
-	/// exist in the cache.
+	/// exist in the cache; an application which does not answer within [`IPC_TIMEOUT`] is an [`OdiliaError::ServiceUnavailable`].
 	#[must_use]
 	#[tracing::instrument(level = "trace", ret)]
 	async fn get_ipc(&self, id: &CacheKey) -> Result<CacheItem, OdiliaError> {
 		if let Some(ci) = self.get(id) {
 			return Ok(ci);
 		}
-		let acc = id.clone().into_accessible(&self.connection).await?;
-		accessible_to_cache_item(&acc, Arc::downgrade(self)).await
+		let fetch = async {
+			let acc = id.clone().into_accessible(&self.connection).await?;
+			accessible_to_cache_item(&acc, Arc::downgrade(self)).await
+		};
+		tokio::time::timeout(IPC_TIMEOUT, fetch).await.map_err(|_| {
+			OdiliaError::ServiceUnavailable(format!(
+				"{id:?} was not described within {IPC_TIMEOUT:?}"
+			))
+		})?
+	}
+	#[tracing::instrument(level = "trace", skip(self), fields(items = ids.len()))]
+	async fn get_or_create_all(&self, ids: &[CacheKey]) -> Result<Vec<CacheItem>, OdiliaError> {
+		// owned keys and handles, since borrowed ones make the future not provably `Send`
+		let fetch = |id: CacheKey| {
+			let cache = Arc::clone(self);
+			async move {
+				if let Some(item) = cache.get(&id) {
+					return Ok(item);
+				}
+				let item = cache.get_ipc(&id).await?;
+				cache.add(item.clone())?;
+				Ok(item)
+			}
+		};
+		bulk::try_map_bounded(ids.to_vec(), FETCH_CONCURRENCY, fetch).await
 	}
 	async fn item_from_event<T: EventProperties + Sync>(
 		&self,
@@ -661,8 +796,19 @@ impl Cache {
 				FxBuildHasher::default(),
 			)),
 			connection: conn,
+			changes: broadcast::channel(256).0,
 		}
 	}
+	/// Reports every change to the item `id` from now on, so that anything showing it, like a braille display, can be updated when it changes instead of polling the cache.
+	/// The item does not have to be in the cache yet.
+	#[must_use]
+	pub fn watch(&self, id: CacheKey) -> Watch {
+		Watch { key: id, changes: self.changes.subscribe(), removed: false }
+	}
+	fn notify(&self, id: &CacheKey, change: Change) {
+		// only fails when nobody is watching
+		let _ = self.changes.send((id.clone(), change));
+	}
 	/// add a single new item to the cache. Note that this will empty the bucket
 	/// before inserting the `CacheItem` into the cache (this is so there is
 	/// never two items with the same ID stored in the cache at the same time).
@@ -690,7 +836,9 @@ impl Cache {
 	/// Remove a single cache item. This function can not fail.
 	#[tracing::instrument(level = "trace", ret)]
 	pub fn remove(&self, id: &CacheKey) {
-		self.by_id.remove(id);
+		if self.by_id.remove(id).is_some() {
+			self.notify(id, Change::Removed);
+		}
 	}
 
 	/// Get a single item from the cache, this only gets a reference to an item, not the item itself.
@@ -737,11 +885,68 @@ impl Cache {
 			.into_iter()
 			.try_for_each(|item| Self::populate_references(&self.by_id, &item))
 	}
+	/// A copy of every item in the cache, for example to be written to disk.
+	#[must_use]
+	#[tracing::instrument(level = "debug", skip(self))]
+	pub fn snapshot(&self) -> Vec<CacheItem> {
+		self.by_id
+			.iter()
+			.filter_map(|entry| entry.value().read().ok().map(|item| item.clone()))
+			.collect()
+	}
+	/// Adds items taken with [`Cache::snapshot`] (possibly by another process) back into this cache.
+	/// # Errors
+	/// See [`Cache::add_all`].
+	#[tracing::instrument(level = "debug", skip_all, err)]
+	pub fn restore(self: &Arc<Self>, mut cache_items: Vec<CacheItem>) -> OdiliaResult<()> {
+		for item in &mut cache_items {
+			item.cache = Arc::downgrade(self);
+		}
+		self.add_all(cache_items)
+	}
+	/// A copy of `root` and every cached item under it.
+	#[must_use]
+	pub fn subtree(&self, root: &CacheKey) -> Vec<CacheItem> {
+		let mut items = Vec::new();
+		let mut stack = vec![root.clone()];
+		while let Some(key) = stack.pop() {
+			let Some(item) = self.get(&key) else {
+				continue;
+			};
+			stack.extend(item.children.iter().map(|child| child.key.clone()));
+			items.push(item);
+		}
+		items
+	}
+	/// Applies the repair passes in `rules` to the cached subtree under `root`, in place, so that references to the items which stay remain valid; see [`repair::repair`].
+	/// # Errors
+	/// If a changed item can not be locked for writing.
+	#[tracing::instrument(level = "debug", skip(self), err)]
+	pub fn repair(&self, root: &CacheKey, rules: repair::Rules) -> OdiliaResult<()> {
+		let repaired = repair::repair(self.subtree(root), root, rules);
+		tracing::debug!(
+			changed = repaired.changed.len(),
+			removed = repaired.removed.len(),
+			"Repaired subtree"
+		);
+		self.remove_all(&repaired.removed);
+		for item in repaired.changed {
+			let key = item.object.clone();
+			self.modify_item(&key, |cached| *cached = item)?;
+		}
+		Ok(())
+	}
+	/// Forgets `root` and every cached item under it, so that they are fetched again when they are next needed; for when an application replaces a whole subtree at once, like a document being reloaded.
+	#[tracing::instrument(level = "debug", skip(self))]
+	pub fn invalidate(&self, root: &CacheKey) {
+		let keys = self.subtree(root).into_iter().map(|item| item.object).collect();
+		self.remove_all(&keys);
+	}
 	/// Bulk remove all ids in the cache; this only refreshes the cache after removing all items.
 	#[tracing::instrument(level = "trace", ret)]
 	pub fn remove_all(&self, ids: &Vec<CacheKey>) {
 		for id in ids {
-			self.by_id.remove(id);
+			self.remove(id);
 		}
 	}
 
@@ -769,10 +974,92 @@ impl Cache {
 			return Ok(false);
 		};
 		let mut cache_item = entry.write()?;
+		if self.changes.receiver_count() == 0 {
+			modify(&mut cache_item);
+			return Ok(true);
+		}
+		let (text, children) = (cache_item.text.clone(), cache_item.children.clone());
 		modify(&mut cache_item);
+		let text_changed = cache_item.text != text;
+		let children_changed = cache_item.children.len() != children.len()
+			|| cache_item
+				.children
+				.iter()
+				.zip(&children)
+				.any(|(new, old)| new.key != old.key);
+		drop(cache_item);
+		if text_changed {
+			self.notify(id, Change::Text);
+		}
+		if children_changed {
+			self.notify(id, Change::Children);
+		}
 		Ok(true)
 	}
 
+	/// Applies the change an AT-SPI event reports to the cached item it is about, so that whatever reads the item afterwards sees it as it is after the event.
+	/// Events have to be applied in the order they arrive, before they are handled, so that no handler reads an item as it was before the event it is handling; otherwise, a focused item may be announced with the name or states it had when it was first cached.
+	/// States, roles, names (of items without text, whose text is their name) and text insertions and deletions are applied; other events change nothing.
+	/// Returns whether the item is cached.
+	/// # Errors
+	/// If the item can not be locked for writing.
+	#[tracing::instrument(level = "trace", skip_all, err)]
+	pub fn apply_event(&self, event: &Event) -> OdiliaResult<bool> {
+		match event {
+			Event::Object(ObjectEvents::StateChanged(changed)) => {
+				let key = CacheKey::from(changed.item.clone());
+				self.modify_item(&key, |item| {
+					if changed.enabled {
+						item.states.insert(changed.state);
+					} else {
+						item.states.remove(changed.state);
+					}
+				})
+			}
+			Event::Object(ObjectEvents::PropertyChange(changed)) => {
+				let key = CacheKey::from(changed.item.clone());
+				match &changed.value {
+					Property::Name(name) => {
+						let mut has_text = false;
+						let cached = self.modify_item(&key, |item| {
+							has_text = item
+								.interfaces
+								.contains(Interface::Text);
+							if !has_text {
+								item.text.clone_from(name);
+							}
+						})?;
+						// the name of an item with text is not cached, so changing the item did not tell its watchers
+						if has_text {
+							self.notify(&key, Change::Text);
+						}
+						Ok(cached)
+					}
+					Property::Role(role) => {
+						self.modify_item(&key, |item| item.role = *role)
+					}
+					_ => Ok(false),
+				}
+			}
+			Event::Object(ObjectEvents::TextChanged(changed)) => {
+				let key = CacheKey::from(changed.item.clone());
+				let mut fits = true;
+				let cached = self.modify_item(&key, |item| {
+					fits = apply_text_change(&mut item.text, changed);
+				})?;
+				if !fits {
+					tracing::debug!(
+						?key,
+						"Cached text is out of date; forgetting the item"
+					);
+					self.remove(&key);
+				}
+				Ok(cached)
+			}
+			_ => Ok(false),
+		}
+	}
+
 	/// Get a single item from the cache (note that this copies some integers to a new struct).
 	/// If the `CacheItem` is not found, create one, add it to the cache, and return it.
 	/// # Errors
@@ -780,11 +1067,13 @@ impl Cache {
 	/// 1. The `accessible` can not be turned into an `AccessiblePrimitive`. This should never happen, but is technically possible.
 	/// 2. The [`Self::add`] function fails.
 	/// 3. The [`accessible_to_cache_item`] function fails.
+	/// 4. The application does not answer within `timeout`, for example because it is hung; this is an [`OdiliaError::ServiceUnavailable`].
 	#[tracing::instrument(level = "debug", ret, err)]
 	pub async fn get_or_create(
 		&self,
 		accessible: &AccessibleProxy<'_>,
 		cache: Arc<Cache>,
+		timeout: Duration,
 	) -> OdiliaResult<CacheItem> {
 		// if the item already exists in the cache, return it
 		let primitive = accessible.try_into()?;
@@ -793,8 +1082,16 @@ impl Cache {
 		}
 		// otherwise, build a cache item
 		let start = std::time::Instant::now();
-		let cache_item =
-			accessible_to_cache_item(accessible, Arc::downgrade(&cache)).await?;
+		let cache_item = tokio::time::timeout(
+			timeout,
+			accessible_to_cache_item(accessible, Arc::downgrade(&cache)),
+		)
+		.await
+		.map_err(|_| {
+			OdiliaError::ServiceUnavailable(format!(
+				"{primitive:?} was not described within {timeout:?}"
+			))
+		})??;
 		let end = std::time::Instant::now();
 		let diff = end - start;
 		tracing::debug!("Time to create cache item: {:?}", diff);
@@ -804,6 +1101,45 @@ impl Cache {
 		Ok(cache_item)
 	}
 
+	/// Add an item which an application pushed through the `org.a11y.atspi.Cache` interface.
+	/// If the item was already fetched lazily, it is updated in place instead: this avoids the `DBus` calls needed for a new item, and keeps references to the existing item valid.
+	/// Returns `true` if the item was not in the cache before.
+	/// # Errors
+	/// The function will return an error if:
+	/// 1. The existing item can not be locked for writing.
+	/// 2. A new item can not be built; see [`CacheItem::from_atspi_cache_item`].
+	/// 3. The [`Self::add`] function fails.
+	#[tracing::instrument(level = "debug", skip(self), ret, err)]
+	pub async fn add_or_update_atspi_item(
+		self: &Arc<Self>,
+		atspi_cache_item: atspi_common::CacheItem,
+	) -> OdiliaResult<bool> {
+		let key: CacheKey = atspi_cache_item.object.clone().into();
+		if self.by_id.contains_key(&key) {
+			self.modify_item(&key, |item| {
+				item.app = atspi_cache_item.app.into();
+				if item.parent.key != atspi_cache_item.parent.clone().into() {
+					item.parent = CacheRef::new(atspi_cache_item.parent.into());
+				}
+				item.index = atspi_cache_item.index.try_into().ok();
+				item.children_num = atspi_cache_item.children.try_into().ok();
+				item.interfaces = atspi_cache_item.ifaces;
+				item.role = atspi_cache_item.role;
+				item.states = atspi_cache_item.states;
+				item.text = atspi_cache_item.name;
+			})?;
+			return Ok(false);
+		}
+		let cache_item = CacheItem::from_atspi_cache_item(
+			atspi_cache_item,
+			Arc::downgrade(self),
+			&self.connection,
+		)
+		.await?;
+		self.add(cache_item)?;
+		Ok(true)
+	}
+
 	/// Populate children and parent references given a cache and an `Arc<RwLock<CacheItem>>`.
 	/// This will unlock the `RwLock<_>`, update the references for children and parents, then go to the parent and children and do the same: update the parent for the children, then update the children referneces for the parent.
 	/// # Errors
@@ -847,6 +1183,32 @@ impl Cache {
 	}
 }
 
+/// Applies the insertion or deletion `changed` reports to `text`, counting in characters; returns false, leaving `text` alone, if it does not fit, which means the text was out of date already.
+fn apply_text_change(text: &mut String, changed: &TextChangedEvent) -> bool {
+	let (Ok(start), Ok(length)) =
+		(usize::try_from(changed.start_pos), usize::try_from(changed.length))
+	else {
+		return false;
+	};
+	let byte =
+		|chars: usize| text.char_indices().map(|(i, _)| i).chain([text.len()]).nth(chars);
+	match changed.operation {
+		Operation::Insert => {
+			let Some(at) = byte(start) else {
+				return false;
+			};
+			text.insert_str(at, &changed.text);
+		}
+		Operation::Delete => {
+			let (Some(from), Some(to)) = (byte(start), byte(start + length)) else {
+				return false;
+			};
+			text.replace_range(from..to, "");
+		}
+	}
+	true
+}
+
 /// Convert an [`atspi_proxies::accessible::AccessibleProxy`] into a [`crate::CacheItem`].
 /// This runs a bunch of long-awaiting code and can take quite some time; use this sparingly.
 /// This takes most properties and some function calls through the `AccessibleProxy` structure and generates a new `CacheItem`, which will be written to cache before being sent back.
@@ -891,6 +1253,66 @@ pub async fn accessible_to_cache_item(
 		states,
 		text,
 		children: children.into_iter().map(|k| CacheRef::new(k.into())).collect(),
+		shortcut: key_binding(accessible, interfaces).await,
+		value: cached_value(accessible, interfaces).await,
 		cache,
 	})
 }
+
+/// What the `Value` interface of a slider, spin button, progress bar or similar says; see [`CacheItem::read_value`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Value {
+	pub current: f64,
+	pub minimum: f64,
+	pub maximum: f64,
+	/// How the application describes the current value, like "medium" or "3 of 5 stars", if it does; this means more to the user than the number.
+	pub text: Option<String>,
+}
+
+impl Value {
+	/// How far the current value is along the range, in percent; `None` if the range is empty.
+	#[must_use]
+	pub fn percent(&self) -> Option<f64> {
+		let range = self.maximum - self.minimum;
+		(range > 0.0).then(|| (self.current - self.minimum) / range * 100.0)
+	}
+}
+
+/// Reads the value of `accessible`.
+async fn value_of(accessible: &AccessibleProxy<'_>) -> OdiliaResult<Value> {
+	let value = accessible.to_value().await?;
+	let (current, minimum, maximum) = tokio::try_join!(
+		value.current_value(),
+		value.minimum_value(),
+		value.maximum_value(),
+	)?;
+	// the `Text` property is newer than the interface; browsers give `aria-valuetext` as an attribute instead
+	let text = match value.inner().get_property::<String>("Text").await {
+		Ok(text) if !text.trim().is_empty() => Some(text),
+		_ => accessible
+			.get_attributes()
+			.await
+			.ok()
+			.and_then(|mut attributes| attributes.remove("valuetext"))
+			.filter(|text| !text.trim().is_empty()),
+	};
+	Ok(Value { current, minimum, maximum, text })
+}
+
+/// The value of `accessible`, if it has the `Value` interface; like [`key_binding`], failing to read it is not an error.
+async fn cached_value(accessible: &AccessibleProxy<'_>, interfaces: InterfaceSet) -> Option<Value> {
+	if !interfaces.contains(Interface::Value) {
+		return None;
+	}
+	value_of(accessible).await.ok()
+}
+
+/// The key binding of the first action of `accessible`, if it has the `Action` interface and the binding is not empty.
+/// Failing to get it is not an error, since the item is still usable without it.
+async fn key_binding(accessible: &AccessibleProxy<'_>, interfaces: InterfaceSet) -> Option<String> {
+	if !interfaces.contains(Interface::Action) {
+		return None;
+	}
+	let binding = accessible.to_action().await.ok()?.get_key_binding(0).await.ok()?;
+	(!binding.trim().is_empty()).then_some(binding)
+}
@@ -0,0 +1,213 @@
+//! Repair passes for accessibility trees which toolkits are known to get wrong.
+//!
+//! Chromium, and so every Electron app (whether installed as a Flatpak or not), wraps content in layers of nameless sections, leaves controls unnamed when their name is in a child, and exposes nodes nobody can see.
+//! Each pass fixes one of those in a subtree taken from the cache; [`Rules`] picks the passes for a toolkit, and [`crate::Cache::repair`] applies them to the cache.
+
+use std::collections::{HashMap, HashSet};
+
+use atspi_common::{Role, State};
+
+use crate::{CacheItem, CacheKey};
+
+/// Which repair passes run on a tree; none by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rules {
+	/// Removes sections with no text of their own and a single child, moving the child up in their place.
+	pub flatten_sections: bool,
+	/// Names unnamed controls after the text inside them.
+	pub name_from_children: bool,
+	/// Removes leaves which have no text and are not on screen, like the zero-size nodes Chromium keeps for layout.
+	pub strip_hidden: bool,
+}
+
+impl Rules {
+	/// The passes needed for `toolkit`, as named by its application's `ToolkitName`.
+	#[must_use]
+	pub fn for_toolkit(toolkit: &str) -> Self {
+		match toolkit.to_ascii_lowercase().as_str() {
+			"chromium" | "chrome" | "electron" => Rules {
+				flatten_sections: true,
+				name_from_children: true,
+				strip_hidden: true,
+			},
+			_ => Rules::default(),
+		}
+	}
+}
+
+/// Roles which only group other objects, and say nothing themselves.
+const WRAPPERS: [Role; 3] = [Role::Section, Role::Panel, Role::Filler];
+/// Roles which are named after their content when the toolkit does not name them.
+const NAMED_BY_CONTENT: [Role; 8] = [
+	Role::Button,
+	Role::ToggleButton,
+	Role::Link,
+	Role::MenuItem,
+	Role::PageTab,
+	Role::CheckBox,
+	Role::RadioButton,
+	Role::Heading,
+];
+
+/// A subtree being repaired, by key.
+struct Tree {
+	items: HashMap<CacheKey, CacheItem>,
+	removed: Vec<CacheKey>,
+	changed: HashSet<CacheKey>,
+}
+
+impl Tree {
+	/// Renumbers the children of `parent`, after some were removed or replaced.
+	fn reindex(&mut self, parent: &CacheKey) {
+		let Some(children) = self.items.get(parent).map(|item| item.children.clone())
+		else {
+			return;
+		};
+		if let Some(parent) = self.items.get_mut(parent) {
+			parent.children_num = Some(children.len());
+		}
+		for (index, child) in children.iter().enumerate() {
+			if let Some(child) = self.items.get_mut(&child.key) {
+				if child.index != Some(index) {
+					child.index = Some(index);
+					self.changed.insert(child.object.clone());
+				}
+			}
+		}
+		self.changed.insert(parent.clone());
+	}
+	fn remove(&mut self, key: &CacheKey) {
+		self.items.remove(key);
+		self.changed.remove(key);
+		self.removed.push(key.clone());
+	}
+	fn children(&self, key: &CacheKey) -> Vec<CacheKey> {
+		self.items
+			.get(key)
+			.map(|item| item.children.iter().map(|child| child.key.clone()).collect())
+			.unwrap_or_default()
+	}
+	/// Removes hidden, empty leaves under `key`, children first, so that wrappers left empty go too.
+	fn strip_hidden(&mut self, key: &CacheKey, root: &CacheKey) {
+		for child in self.children(key) {
+			self.strip_hidden(&child, root);
+		}
+		let Some(item) = self.items.get(key) else {
+			return;
+		};
+		let hidden = item.children.is_empty()
+			&& item.text.trim().is_empty()
+			&& !item.states.contains(State::Showing)
+			&& !item.states.contains(State::Focusable);
+		if !hidden || key == root {
+			return;
+		}
+		let parent = item.parent.key.clone();
+		self.remove(key);
+		if let Some(parent_item) = self.items.get_mut(&parent) {
+			parent_item.children.retain(|child| &child.key != key);
+			self.reindex(&parent);
+		}
+	}
+	/// Replaces each redundant wrapper under `key` with its only child.
+	fn flatten_sections(&mut self, key: &CacheKey, root: &CacheKey) {
+		let mut key = key.clone();
+		while let Some(item) = self.items.get(&key) {
+			let redundant = key != *root
+				&& WRAPPERS.contains(&item.role)
+				&& item.text.trim().is_empty()
+				&& item.children.len() == 1 && !item
+				.states
+				.contains(State::Focusable);
+			if !redundant {
+				break;
+			}
+			let (parent, child) = (item.parent.clone(), item.children[0].clone());
+			let Some(child_item) = self.items.get_mut(&child.key) else {
+				break;
+			};
+			child_item.parent = parent.clone();
+			self.changed.insert(child.key.clone());
+			if let Some(parent_item) = self.items.get_mut(&parent.key) {
+				for sibling in &mut parent_item.children {
+					if sibling.key == key {
+						*sibling = child.clone();
+					}
+				}
+			}
+			self.remove(&key);
+			self.reindex(&parent.key);
+			key = child.key;
+		}
+		for child in self.children(&key) {
+			self.flatten_sections(&child, root);
+		}
+	}
+	/// The text inside `key`, in reading order.
+	fn inner_text(&self, key: &CacheKey) -> Vec<String> {
+		let Some(item) = self.items.get(key) else {
+			return Vec::new();
+		};
+		if !item.text.trim().is_empty() {
+			return vec![item.text.trim().to_string()];
+		}
+		item.children
+			.iter()
+			.flat_map(|child| self.inner_text(&child.key))
+			.collect()
+	}
+	fn name_from_children(&mut self) {
+		let unnamed: Vec<CacheKey> = self
+			.items
+			.values()
+			.filter(|item| {
+				NAMED_BY_CONTENT.contains(&item.role)
+					&& item.text.trim().is_empty() && !item.children.is_empty()
+			})
+			.map(|item| item.object.clone())
+			.collect();
+		for key in unnamed {
+			let name = self.inner_text(&key).join(" ");
+			if name.is_empty() {
+				continue;
+			}
+			if let Some(item) = self.items.get_mut(&key) {
+				item.text = name;
+				self.changed.insert(key);
+			}
+		}
+	}
+}
+
+/// What [`repair`] did to a subtree.
+#[derive(Debug, Default)]
+pub struct Repaired {
+	/// The items which were changed, as they are now.
+	pub changed: Vec<CacheItem>,
+	/// The keys of the items which were removed.
+	pub removed: Vec<CacheKey>,
+}
+
+/// Applies `rules` to `items`, the subtree under `root` as taken from the cache; `root` itself is never removed.
+#[must_use]
+pub fn repair(items: Vec<CacheItem>, root: &CacheKey, rules: Rules) -> Repaired {
+	let mut tree = Tree {
+		items: items.into_iter().map(|item| (item.object.clone(), item)).collect(),
+		removed: Vec::new(),
+		changed: HashSet::new(),
+	};
+	if !tree.items.contains_key(root) {
+		return Repaired::default();
+	}
+	if rules.strip_hidden {
+		tree.strip_hidden(root, root);
+	}
+	if rules.flatten_sections {
+		tree.flatten_sections(root, root);
+	}
+	if rules.name_from_children {
+		tree.name_from_children();
+	}
+	let Tree { mut items, removed, changed } = tree;
+	Repaired { changed: changed.iter().filter_map(|key| items.remove(key)).collect(), removed }
+}
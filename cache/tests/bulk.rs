@@ -0,0 +1,94 @@
+//! Bounded concurrent mapping, over every small number of items and limit: results keep the order of the items whatever order the calls finish in, no more than the limit run at once, and nothing is started after an error or after the caller gives up.
+
+use std::{
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+
+use odilia_cache::bulk::try_map_bounded;
+use tokio::time::sleep;
+
+/// Counts the calls started, and the most in flight at once.
+#[derive(Default)]
+struct Calls {
+	started: AtomicUsize,
+	in_flight: AtomicUsize,
+	most_in_flight: AtomicUsize,
+}
+
+impl Calls {
+	/// Sleeps for `ms`, counted as a call in flight, then returns `value`.
+	async fn run<T>(&self, ms: u64, value: T) -> T {
+		self.started.fetch_add(1, Ordering::SeqCst);
+		let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+		self.most_in_flight.fetch_max(now, Ordering::SeqCst);
+		sleep(Duration::from_millis(ms)).await;
+		self.in_flight.fetch_sub(1, Ordering::SeqCst);
+		value
+	}
+}
+
+#[tokio::test]
+async fn results_keep_the_order_of_the_items() {
+	for len in 0..8u64 {
+		for limit in 0..5 {
+			let calls = Calls::default();
+			// later items finish first
+			let results = try_map_bounded(0..len, limit, |i| {
+				calls.run(len - i, Ok::<_, ()>(i))
+			})
+			.await;
+			assert_eq!(results, Ok((0..len).collect()), "{len} items, {limit} at once");
+		}
+	}
+}
+
+#[tokio::test]
+async fn no_more_than_the_limit_run_at_once() {
+	for len in 0..8usize {
+		for limit in 0..5 {
+			let calls = Calls::default();
+			try_map_bounded(0..len, limit, |i| calls.run(2, Ok::<_, ()>(i)))
+				.await
+				.expect("no call fails");
+			let most = calls.most_in_flight.load(Ordering::SeqCst);
+			assert_eq!(most, len.min(limit.max(1)), "{len} items, {limit} at once");
+		}
+	}
+}
+
+#[tokio::test]
+async fn nothing_is_started_after_an_error() {
+	for failing in 0..6usize {
+		for limit in 1..4 {
+			let calls = Calls::default();
+			let result = try_map_bounded(0..6, limit, |i| {
+				calls.run(1, if i == failing { Err(i) } else { Ok(i) })
+			})
+			.await;
+			assert_eq!(result, Err(failing));
+			// the failing item, and at most the ones in flight next to it
+			let started = calls.started.load(Ordering::SeqCst);
+			assert!(started <= failing + limit, "{started} started, {limit} at once");
+		}
+	}
+}
+
+#[tokio::test]
+async fn giving_up_cancels_the_calls_in_flight() {
+	let calls = Arc::new(Calls::default());
+	let mapped = try_map_bounded(0..10, 2, |i| {
+		let calls = Arc::clone(&calls);
+		async move { calls.run(50, Ok::<_, ()>(i)).await }
+	});
+	assert!(tokio::time::timeout(Duration::from_millis(10), mapped).await.is_err());
+	let started = calls.started.load(Ordering::SeqCst);
+	sleep(Duration::from_millis(100)).await;
+	assert_eq!(started, 2);
+	assert_eq!(calls.started.load(Ordering::SeqCst), started, "nothing started later");
+	// dropped while sleeping, so never finished
+	assert_eq!(calls.in_flight.load(Ordering::SeqCst), 2);
+}
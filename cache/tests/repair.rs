@@ -0,0 +1,77 @@
+//! The repair passes, on a hand-built tree shaped like the ones Electron apps expose: a link and a button named only by their content, wrapped in nameless sections, next to zero-size images.
+
+use std::collections::HashMap;
+
+use odilia_cache::{
+	repair::{repair, Rules},
+	CacheItem,
+};
+use odilia_common::cache::AccessiblePrimitive;
+
+fn key(n: u32) -> AccessiblePrimitive {
+	AccessiblePrimitive {
+		id: format!("/org/a11y/atspi/accessible/{n}").into(),
+		sender: ":1.87".into(),
+	}
+}
+
+fn electron_like_tree() -> Vec<CacheItem> {
+	serde_json::from_str(include_str!("./electron_like_tree.json"))
+		.expect("the hand-built tree is valid")
+}
+
+/// The tree after repairing it, by key: the items which were not changed, and the changed ones in their place.
+fn repaired(rules: Rules) -> (HashMap<AccessiblePrimitive, CacheItem>, Vec<AccessiblePrimitive>) {
+	let items = electron_like_tree();
+	let result = repair(items.clone(), &key(1), rules);
+	let mut tree: HashMap<_, _> =
+		items.into_iter().map(|item| (item.object.clone(), item)).collect();
+	for removed in &result.removed {
+		tree.remove(removed);
+	}
+	for item in result.changed {
+		tree.insert(item.object.clone(), item);
+	}
+	(tree, result.removed)
+}
+
+#[test]
+fn other_toolkits_are_left_alone() {
+	assert_eq!(Rules::for_toolkit("GTK"), Rules::default());
+	let (tree, removed) = repaired(Rules::for_toolkit("GTK"));
+	assert!(removed.is_empty());
+	assert_eq!(tree.len(), electron_like_tree().len());
+}
+
+#[test]
+fn chromium_trees_are_repaired() {
+	let (tree, mut removed) = repaired(Rules::for_toolkit("Chromium"));
+	removed.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+	// the nameless wrappers around the link, and the zero-size images (with the section holding one of them)
+	assert_eq!(removed, [key(10), key(2), key(3), key(6), key(8)]);
+	let document = &tree[&key(1)];
+	let children: Vec<_> = document.children.iter().map(|child| child.key.clone()).collect();
+	assert_eq!(children, [key(4), key(7)]);
+	assert_eq!(document.children_num, Some(2));
+
+	let link = &tree[&key(4)];
+	assert_eq!(link.parent.key, key(1));
+	assert_eq!(link.index, Some(0));
+	assert_eq!(link.text, "Home");
+
+	let button = &tree[&key(7)];
+	assert_eq!(button.index, Some(1));
+	assert_eq!(button.text, "Send");
+	assert_eq!(button.children.len(), 1);
+	assert_eq!(tree[&key(9)].index, Some(0));
+}
+
+#[test]
+fn the_root_is_never_removed() {
+	let root = electron_like_tree()
+		.into_iter()
+		.filter(|item| item.object == key(10))
+		.collect();
+	let result = repair(root, &key(10), Rules::for_toolkit("Chromium"));
+	assert!(result.removed.is_empty());
+}
@@ -0,0 +1,116 @@
+//! Watching a cached item: changes to its text or name are reported, whether or not it has the Text interface, and nothing is reported once it is removed.
+
+use std::time::Duration;
+
+use atspi_common::{
+	events::{
+		object::{Property, PropertyChangeEvent},
+		Event, ObjectEvents,
+	},
+	Interface, InterfaceSet, ObjectRef,
+};
+use odilia_cache::{Cache, CacheItem, Change, Watch};
+use odilia_common::cache::AccessiblePrimitive;
+use tokio::net::UnixStream;
+use zbus::{connection::Builder, Guid};
+
+const SENDER: &str = ":1.87";
+const PATH: &str = "/org/a11y/atspi/accessible/1";
+
+/// A cache on a peer-to-peer connection, since nothing here talks to an application.
+async fn cache() -> zbus::Result<Cache> {
+	let (ours, theirs) = UnixStream::pair()?;
+	let guid = Guid::generate();
+	let (connection, _peer) = tokio::try_join!(
+		Builder::unix_stream(ours).p2p().build(),
+		Builder::unix_stream(theirs).server(guid)?.p2p().build(),
+	)?;
+	Ok(Cache::new(connection))
+}
+
+/// A document called "Inbox", with `interfaces`.
+fn document(interfaces: InterfaceSet) -> CacheItem {
+	let mut item: CacheItem = serde_json::from_value(serde_json::json!({
+		"object": { "id": PATH, "sender": SENDER },
+		"app": { "id": "/org/a11y/atspi/accessible/root", "sender": SENDER },
+		"parent": { "key": { "id": "/org/a11y/atspi/accessible/0", "sender": SENDER } },
+		"index": 0,
+		"children_num": 0,
+		"interfaces": [],
+		"role": "DocumentWeb",
+		"states": [0, 0],
+		"text": "Inbox",
+		"children": [],
+	}))
+	.expect("the item is valid");
+	item.interfaces = interfaces;
+	item
+}
+
+fn key() -> AccessiblePrimitive {
+	AccessiblePrimitive { id: PATH.into(), sender: SENDER.into() }
+}
+
+/// The next change `watch` reports; a change which is never reported fails the test, rather than hanging it.
+async fn next(watch: &mut Watch) -> Option<Change> {
+	tokio::time::timeout(Duration::from_secs(1), watch.next())
+		.await
+		.expect("the change is reported")
+}
+
+fn renamed(name: &str) -> Event {
+	Event::Object(ObjectEvents::PropertyChange(PropertyChangeEvent {
+		item: ObjectRef {
+			name: SENDER.try_into().expect("the sender is a unique name"),
+			path: PATH.try_into().expect("the path is an object path"),
+		},
+		property: "accessible-name".to_string(),
+		value: Property::Name(name.to_string()),
+	}))
+}
+
+#[tokio::test]
+async fn renaming_an_item_with_text_is_reported() -> zbus::Result<()> {
+	let cache = cache().await?;
+	cache.add(document(InterfaceSet::new(Interface::Accessible | Interface::Text)))
+		.expect("the item is added");
+	let mut watch = cache.watch(key());
+	assert!(cache
+		.apply_event(&renamed("Outbox"))
+		.expect("the item can be changed"));
+	assert_eq!(next(&mut watch).await, Some(Change::Text));
+	// its text is what it shows, not its name
+	assert_eq!(cache.get(&key()).expect("the item is cached").text, "Inbox");
+	Ok(())
+}
+
+#[tokio::test]
+async fn renaming_an_item_without_text_changes_its_text() -> zbus::Result<()> {
+	let cache = cache().await?;
+	cache.add(document(Interface::Accessible.into()))
+		.expect("the item is added");
+	let mut watch = cache.watch(key());
+	assert!(cache
+		.apply_event(&renamed("Outbox"))
+		.expect("the item can be changed"));
+	assert_eq!(next(&mut watch).await, Some(Change::Text));
+	assert_eq!(cache.get(&key()).expect("the item is cached").text, "Outbox");
+	Ok(())
+}
+
+#[tokio::test]
+async fn nothing_is_reported_after_an_item_is_removed() -> zbus::Result<()> {
+	let cache = cache().await?;
+	cache.add(document(Interface::Accessible.into()))
+		.expect("the item is added");
+	let mut watch = cache.watch(key());
+	cache.remove(&key());
+	assert_eq!(next(&mut watch).await, Some(Change::Removed));
+	cache.add(document(Interface::Accessible.into()))
+		.expect("the item is added again");
+	assert!(cache
+		.apply_event(&renamed("Outbox"))
+		.expect("the item can be changed"));
+	assert_eq!(next(&mut watch).await, None);
+	Ok(())
+}
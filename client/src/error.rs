@@ -0,0 +1,23 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[allow(clippy::module_name_repetitions)]
+pub enum ClientError {
+	#[error("Odilia is not running, or its D-Bus interface can not be reached: {0}")]
+	Dbus(#[source] zbus::Error),
+	#[error("Odilia refused the request: {0}")]
+	DbusSpec(#[from] zbus::fdo::Error),
+	#[error("could not send the event over Odilia's input socket: {0}")]
+	Socket(String),
+}
+
+// an error Odilia answered with is a refusal, rather than Odilia not being there
+impl From<zbus::Error> for ClientError {
+	fn from(e: zbus::Error) -> Self {
+		match e {
+			zbus::Error::FDO(e) => ClientError::DbusSpec(*e),
+			e @ zbus::Error::MethodError(..) => ClientError::DbusSpec(e.into()),
+			e => ClientError::Dbus(e),
+		}
+	}
+}
@@ -0,0 +1,164 @@
+#![deny(
+	clippy::all,
+	clippy::pedantic,
+	clippy::cargo,
+	clippy::map_unwrap_or,
+	clippy::unwrap_used,
+	unsafe_code
+)]
+#![allow(clippy::multiple_crate_versions)]
+#![doc = include_str!("../README.md")]
+
+mod error;
+
+pub use error::ClientError;
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+use odilia_common::events::ScreenReaderEvent;
+use zbus::proxy;
+
+#[proxy(
+	interface = "app.odilia.Screenreader.Status",
+	default_service = "app.odilia.Screenreader",
+	default_path = "/app/odilia/Screenreader"
+)]
+trait Status {
+	fn speak(&self, text: &str) -> zbus::Result<()>;
+	fn get_status(&self) -> zbus::Result<(String, bool, String, String, String, u64)>;
+	#[zbus(property)]
+	fn version(&self) -> zbus::Result<String>;
+	#[zbus(property)]
+	fn mode(&self) -> zbus::Result<String>;
+	#[zbus(property)]
+	fn braille(&self) -> zbus::Result<String>;
+}
+
+#[proxy(
+	interface = "app.odilia.Screenreader.Handlers",
+	default_service = "app.odilia.Screenreader",
+	default_path = "/app/odilia/Screenreader"
+)]
+trait Handlers {
+	fn list(&self) -> zbus::Result<Vec<(String, String, bool)>>;
+	fn set_enabled(&self, name: &str, enabled: bool) -> zbus::Result<()>;
+}
+
+/// What Odilia is doing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+	/// The name of the mode Odilia is in.
+	pub mode: String,
+	/// Whether Odilia is likely still speaking; an estimate.
+	pub speaking: bool,
+	/// The application, role and name of the focused object; empty if nothing was focused yet.
+	pub app: String,
+	pub role: String,
+	pub name: String,
+	pub uptime: Duration,
+}
+
+/// One of Odilia's event handlers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Handler {
+	pub name: String,
+	/// What the handler handles: `atspi`, `command`, `input` or `custom` events.
+	pub kind: String,
+	pub enabled: bool,
+}
+
+/// A connection to the running instance of Odilia; cheap to clone.
+#[derive(Debug, Clone)]
+pub struct Client {
+	status: StatusProxy<'static>,
+	handlers: HandlersProxy<'static>,
+}
+
+impl Client {
+	/// Connects to Odilia on the session bus.
+	/// # Errors
+	/// If the session bus can not be reached.
+	pub async fn connect() -> Result<Self, ClientError> {
+		Self::with_connection(&zbus::Connection::session().await?).await
+	}
+	/// Connects to Odilia over `connection`, which has to be on the bus Odilia runs on; useful for tests on a private bus.
+	/// # Errors
+	/// If the proxies can not be created.
+	pub async fn with_connection(connection: &zbus::Connection) -> Result<Self, ClientError> {
+		Ok(Client {
+			status: StatusProxy::new(connection).await?,
+			handlers: HandlersProxy::new(connection).await?,
+		})
+	}
+	/// Sends `event` over Odilia's input socket, as if an input server had.
+	/// # Errors
+	/// If Odilia is not running, or refuses the connection.
+	pub async fn send_event(&self, event: &ScreenReaderEvent) -> Result<(), ClientError> {
+		odilia_input::send_event(event)
+			.await
+			.map(|_| ())
+			.map_err(|e| ClientError::Socket(format!("{e:#}")))
+	}
+	/// Makes Odilia say `text`.
+	/// # Errors
+	/// If Odilia is not running, or can not speak.
+	pub async fn speak(&self, text: &str) -> Result<(), ClientError> {
+		Ok(self.status.speak(text).await?)
+	}
+	/// The version of Odilia running.
+	/// # Errors
+	/// If Odilia is not running.
+	pub async fn version(&self) -> Result<String, ClientError> {
+		Ok(self.status.version().await?)
+	}
+	/// The name of the mode Odilia is in, like `Focus`.
+	/// # Errors
+	/// If Odilia is not running.
+	pub async fn mode(&self) -> Result<String, ClientError> {
+		Ok(self.status.mode().await?)
+	}
+	/// What Odilia is doing, all at once.
+	/// # Errors
+	/// If Odilia is not running.
+	pub async fn status(&self) -> Result<Status, ClientError> {
+		let (mode, speaking, app, role, name, uptime) = self.status.get_status().await?;
+		Ok(Status { mode, speaking, app, role, name, uptime: Duration::from_secs(uptime) })
+	}
+	/// The name of every mode Odilia switches to from now on.
+	pub async fn mode_changes(&self) -> impl Stream<Item = String> + Unpin + '_ {
+		let changes = self.status.receive_mode_changed().await;
+		Box::pin(changes.filter_map(|change| async move { change.get().await.ok() }))
+	}
+	/// The last message Odilia showed in braille; it only changes while Odilia's messages go to braille.
+	/// # Errors
+	/// If Odilia is not running.
+	pub async fn braille(&self) -> Result<String, ClientError> {
+		Ok(self.status.braille().await?)
+	}
+	/// Every message Odilia shows in braille from now on, for whatever drives the braille display.
+	pub async fn braille_changes(&self) -> impl Stream<Item = String> + Unpin + '_ {
+		let changes = self.status.receive_braille_changed().await;
+		Box::pin(changes.filter_map(|change| async move { change.get().await.ok() }))
+	}
+	/// Every event handler Odilia has.
+	/// # Errors
+	/// If Odilia is not running.
+	pub async fn handlers(&self) -> Result<Vec<Handler>, ClientError> {
+		Ok(self.handlers
+			.list()
+			.await?
+			.into_iter()
+			.map(|(name, kind, enabled)| Handler { name, kind, enabled })
+			.collect())
+	}
+	/// Enables or disables the handler called `name`, until Odilia exits.
+	/// # Errors
+	/// If Odilia is not running, or has no handler called `name`.
+	pub async fn set_handler_enabled(
+		&self,
+		name: &str,
+		enabled: bool,
+	) -> Result<(), ClientError> {
+		Ok(self.handlers.set_enabled(name, enabled).await?)
+	}
+}
@@ -0,0 +1,172 @@
+welcome = Willkommen bei Odilia!
+crashed = Odilia ist abgestürzt.
+quitting = Odilia wird beendet
+press-again-to-quit = Zum Beenden erneut drücken
+document-loaded = Dokument geladen
+page-summary = { $title }: { $headings ->
+        [one] { $headings } Überschrift
+       *[other] { $headings } Überschriften
+    }, { $links ->
+        [one] { $links } Link
+       *[other] { $links } Links
+    }
+frame = Rahmen: { $title }
+out-of-frame = Rahmen verlassen
+new-notification = neue Benachrichtigung: { $app }, { $title }, { $body }.
+while-you-were-away-one = Während Sie weg waren: 1 Benachrichtigung
+while-you-were-away = Während Sie weg waren: { $count } Benachrichtigungen
+caps-lock-on = Feststelltaste ein
+caps-lock-off = Feststelltaste aus
+num-lock-on = Num-Taste ein
+num-lock-off = Num-Taste aus
+scroll-lock-on = Rollen ein
+scroll-lock-off = Rollen aus
+keyboard-layout = Tastaturbelegung: { $name }
+input-server-unresponsive = { $server } reagiert nicht mehr; Tasten haben möglicherweise keine Wirkung
+event-diagnostics-busiest = { $app }: { $count } { $kind }-Ereignisse
+setting-changed = { $setting }: { $value }
+setting-punctuation = Satzzeichen
+setting-echo = Tastaturecho
+setting-event-diagnostics = Ereignisdiagnose
+on = ein
+off = aus
+punctuation-none = keine
+punctuation-some = einige
+punctuation-most = die meisten
+punctuation-all = alle
+echo-character = Zeichen
+echo-word = Wörter
+echo-character-and-word = Zeichen und Wörter
+echo-none = aus
+adjustments-reset = Einstellungen auf die konfigurierten zurückgesetzt
+screen-top = oben
+screen-middle = mittig
+screen-bottom = unten
+screen-left = links
+screen-center = Mitte
+screen-right = rechts
+screen-region = { $vertical } { $horizontal }
+screen-region-on-monitor = { $region } auf { $monitor }
+location-unknown = Position unbekannt
+console-mode = Lese die Konsole
+console-unreadable = Die Konsole kann nicht aus { $device } gelesen werden; ist dein Benutzer in der Gruppe tty?
+blank-line = leer
+input-rejected = Eingabe von { $program } abgelehnt, da es als anderer Benutzer läuft
+unknown-program = einem unbekannten Programm
+log-level = Protokollierung auf { $level }
+invalid-log-level = { $level } ist keine gültige Protokollstufe
+tray-title = Odilia, Modus { $mode }
+tray-stop-speech = Sprache anhalten
+tray-next-mode = Nächster Modus
+tray-punctuation = Satzzeichenstufe ändern
+tray-echo = Tastaturecho ändern
+tray-reset = Geänderte Einstellungen zurücksetzen
+tray-quit = Odilia beenden
+key-plus = plus
+key-control = Steuerung
+key-shift = Umschalt
+key-alt = Alt
+key-super = Super
+access-key = Zugriffstaste { $keys }
+inspector = { $role } { $name }; Schnittstellen: { $interfaces }; Zustände: { $states }; Attribute: { $attributes }; Pfad { $path } von { $app }
+navigator-no-parent = Kein übergeordnetes Objekt
+navigator-no-children = Keine untergeordneten Objekte
+navigator-last = Letztes Objekt
+navigator-first = Erstes Objekt
+cannot-activate = Dies kann nicht aktiviert werden
+window-selection = { $window }, { $item }
+loading = Wird geladen
+finished-loading = Fertig geladen, { $headings ->
+        [one] { $headings } Überschrift
+       *[other] { $headings } Überschriften
+    }, { $links ->
+        [one] { $links } Link
+       *[other] { $links } Links
+    }
+calendar-day = { $weekday } { $day }
+calendar-day-selected = { $weekday } { $day }, ausgewählt
+no-languages = Es sind keine Sprachen zum Wechseln eingestellt
+history-top = Anfang des Sprachverlaufs
+history-bottom = Ende des Sprachverlaufs
+history-not-found = Nichts Gesagtes enthält { $text }
+setting-output = Ausgabe
+output-speech = Sprache
+output-braille = Braille
+output-both = Sprache und Braille
+output-neither = nichts
+page = Seite { $page }
+page-of = Seite { $page } von { $count }
+value-percent = { $value }, { $percent } Prozent
+no-more-links = Keine weiteren Links
+no-more-of-role = Keine weiteren: { $role }
+enter-text = Text eingeben, dann Eingabe drücken
+text-entry-cancelled = Abgebrochen
+volume = Lautstärke { $percent } Prozent
+volume-unavailable = Lautstärkeänderungen können nicht angesagt werden, da pactl nicht installiert ist
+muted = Stumm
+input-backlog = Eingaben stauen sich, manche Befehle können sich verzögern
+atspi-lost = Verbindung zu den Anwendungen verloren, verbinde neu
+startup-speech-ready = Sprachausgabe bereit
+startup-bus-ready = Barrierefreiheits-Bus verbunden
+startup-bus-failed = Anwendungen auf dem Barrierefreiheits-Bus können nicht verfolgt werden, Odilia wird beendet
+startup-keyboard-ready = Tastatur bereit
+startup-keyboard-failed = Der Eingabe-Socket konnte nicht geöffnet werden, Tasten werden nicht funktionieren
+startup-keyboard-timeout = Kein Eingabeserver hat sich verbunden, Tasten tun nichts, bis sich einer verbindet
+unknown-mode = Es gibt keinen Modus { $name }
+value-range = { $value }, { $min } bis { $max }
+not-in-table = Nicht in einer Tabelle
+
+# States: the long name is spoken, `.short` when space is tight, `.braille` on a braille display.
+state-checked = aktiviert
+state-pressed = gedrückt
+state-expanded = erweitert
+state-collapsed = reduziert
+state-selected = ausgewählt
+state-required = erforderlich
+state-read-only = schreibgeschützt
+state-unavailable = nicht verfügbar
+
+# Roles, named after the AT-SPI role name with dashes for spaces.
+role-button = Schalter
+role-check-box = Kontrollkästchen
+role-radio-button = Auswahlschalter
+role-toggle-button = Umschalter
+role-combo-box = Kombinationsfeld
+role-entry = Eingabefeld
+role-password-text = Passwortfeld
+role-link = Link
+role-heading = Überschrift
+role-list = Liste
+role-list-item = Listeneintrag
+    .short = Eintrag
+role-menu = Menü
+role-menu-bar = Menüleiste
+role-menu-item = Menüeintrag
+    .short = Eintrag
+role-page-tab = Registerkarte
+role-page-tab-list = Registerkartenliste
+role-table = Tabelle
+role-table-cell = Tabellenzelle
+    .short = Zelle
+role-tree = Baumansicht
+role-tree-item = Baumeintrag
+    .short = Eintrag
+role-dialog = Dialog
+role-frame = Rahmen
+    .short = Fenster
+role-slider = Schieberegler
+role-progress-bar = Fortschrittsbalken
+role-document-web = Webdokument
+    .short = Dokument
+role-image = Grafik
+role-list-box = Listenfeld
+role-check-menu-item = Menüeintrag mit Häkchen
+    .short = Eintrag
+role-radio-menu-item = Menüeintrag mit Auswahl
+    .short = Eintrag
+role-spin-button = Drehfeld
+role-scroll-bar = Bildlaufleiste
+role-tool-bar = Symbolleiste
+role-status-bar = Statusleiste
+role-separator = Trennlinie
+role-label = Beschriftung
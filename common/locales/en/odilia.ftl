@@ -0,0 +1,219 @@
+# Messages Odilia speaks, in the Fluent syntax (https://projectfluent.org/); only simple messages with `{ $variable }` placeables, selectors on the plural category of a number, and attributes, are supported.
+welcome = Welcome to Odilia!
+crashed = Odilia has crashed.
+quitting = Quitting Odilia
+press-again-to-quit = Press again to quit
+document-loaded = Doc loaded
+page-summary = { $title }: { $headings ->
+        [one] { $headings } heading
+       *[other] { $headings } headings
+    }, { $links ->
+        [one] { $links } link
+       *[other] { $links } links
+    }
+frame = frame: { $title }
+out-of-frame = out of frame
+new-notification = new notification: { $app }, { $title }, { $body }.
+while-you-were-away-one = While you were away: 1 notification
+while-you-were-away = While you were away: { $count } notifications
+caps-lock-on = Caps lock on
+caps-lock-off = Caps lock off
+num-lock-on = Num lock on
+num-lock-off = Num lock off
+scroll-lock-on = Scroll lock on
+scroll-lock-off = Scroll lock off
+keyboard-layout = layout: { $name }
+input-server-unresponsive = { $server } has stopped responding; keys may do nothing
+event-diagnostics-busiest = { $app }: { $count } { $kind } events
+setting-changed = { $setting }: { $value }
+setting-punctuation = Punctuation
+setting-echo = Echo
+setting-event-diagnostics = Event diagnostics
+on = on
+off = off
+punctuation-none = none
+punctuation-some = some
+punctuation-most = most
+punctuation-all = all
+echo-character = characters
+echo-word = words
+echo-character-and-word = characters and words
+echo-none = none
+adjustments-reset = Settings reset to the configured ones
+screen-top = top
+screen-middle = middle
+screen-bottom = bottom
+screen-left = left
+screen-center = center
+screen-right = right
+screen-region = { $vertical } { $horizontal }
+screen-region-on-monitor = { $region } of { $monitor }
+location-unknown = Location unknown
+console-mode = Reading the console
+console-unreadable = Can not read the console from { $device }; is your user in the tty group?
+blank-line = blank
+input-rejected = Refused input from { $program }, which runs as another user
+unknown-program = an unknown program
+log-level = Logging at { $level }
+invalid-log-level = { $level } is not a valid log level
+tray-title = Odilia, { $mode } mode
+tray-stop-speech = Stop speech
+tray-next-mode = Next mode
+tray-punctuation = Change punctuation level
+tray-echo = Change typing echo
+tray-reset = Reset changed settings
+tray-quit = Quit Odilia
+key-plus = plus
+key-control = Control
+key-shift = Shift
+key-alt = Alt
+key-super = Super
+access-key = access key { $keys }
+inspector = { $role } { $name }; interfaces: { $interfaces }; states: { $states }; attributes: { $attributes }; path { $path } of { $app }
+navigator-no-parent = No parent
+navigator-no-children = No children
+navigator-last = Last object
+navigator-first = First object
+cannot-activate = This can not be activated
+window-selection = { $window }, { $item }
+loading = Loading
+finished-loading = Finished loading, { $headings ->
+        [one] { $headings } heading
+       *[other] { $headings } headings
+    }, { $links ->
+        [one] { $links } link
+       *[other] { $links } links
+    }
+calendar-day = { $weekday } { $day }
+calendar-day-selected = { $weekday } { $day }, selected
+no-languages = No languages to switch between are configured
+history-top = Start of speech history
+history-bottom = End of speech history
+history-not-found = Nothing said contains { $text }
+setting-output = Output
+output-speech = speech
+output-braille = braille
+output-both = speech and braille
+output-neither = nothing
+page = Page { $page }
+page-of = Page { $page } of { $count }
+value-percent = { $value }, { $percent } percent
+no-more-links = No more links
+no-more-of-role = No more: { $role }
+enter-text = Type the text, then press Enter
+text-entry-cancelled = Cancelled
+volume = Volume { $percent } percent
+volume-unavailable = Volume changes can not be announced, since pactl is not installed
+muted = Muted
+input-backlog = Input backlog, some commands may be delayed
+atspi-lost = Lost the connection to applications, reconnecting
+startup-speech-ready = Speech ready
+startup-bus-ready = Accessibility bus connected
+startup-bus-failed = Could not listen to applications on the accessibility bus, Odilia is exiting
+startup-keyboard-ready = Keyboard ready
+startup-keyboard-failed = Could not open the input socket, keys will not work
+startup-keyboard-timeout = No input server has connected, keys will do nothing until one does
+unknown-mode = There is no { $name } mode
+value-range = { $value }, { $min } to { $max }
+not-in-table = Not in a table
+
+# States: the long name is spoken, `.short` when space is tight, `.braille` on a braille display.
+state-checked = checked
+    .braille = (x)
+state-pressed = pressed
+    .braille = prsd
+state-expanded = expanded
+    .braille = -
+state-collapsed = collapsed
+    .braille = +
+state-selected = selected
+    .braille = sel
+state-required = required
+    .braille = req
+state-read-only = read only
+    .braille = ro
+state-unavailable = unavailable
+    .braille = unav
+
+# Roles, named after the AT-SPI role name with dashes for spaces.
+role-button = button
+    .braille = btn
+role-check-box = check box
+    .braille = chk
+role-radio-button = radio button
+    .braille = rb
+role-toggle-button = toggle button
+    .braille = tgbtn
+role-combo-box = combo box
+    .braille = cbo
+role-entry = entry
+    .braille = edt
+role-password-text = password text
+    .short = password
+    .braille = pwd
+role-link = link
+    .braille = lnk
+role-heading = heading
+    .braille = h
+role-list = list
+    .braille = lst
+role-list-box = list box
+    .braille = lstbx
+role-list-item = list item
+    .short = item
+    .braille = lstitm
+role-menu = menu
+    .braille = mnu
+role-menu-bar = menu bar
+    .braille = mnubar
+role-menu-item = menu item
+    .short = item
+    .braille = mnuitm
+role-check-menu-item = check menu item
+    .short = item
+    .braille = chkmnuitm
+role-radio-menu-item = radio menu item
+    .short = item
+    .braille = rbmnuitm
+role-page-tab = page tab
+    .short = tab
+    .braille = tab
+role-page-tab-list = page tab list
+    .short = tab list
+    .braille = tablst
+role-table = table
+    .braille = tbl
+role-table-cell = table cell
+    .short = cell
+    .braille = cell
+role-tree = tree
+    .braille = tree
+role-tree-item = tree item
+    .short = item
+    .braille = tritm
+role-dialog = dialog
+    .braille = dlg
+role-frame = frame
+    .short = window
+    .braille = frm
+role-slider = slider
+    .braille = sldr
+role-spin-button = spin button
+    .braille = spnbtn
+role-progress-bar = progress bar
+    .braille = prgbar
+role-scroll-bar = scroll bar
+    .braille = scrlbar
+role-tool-bar = tool bar
+    .braille = tlbar
+role-status-bar = status bar
+    .braille = stbar
+role-document-web = document web
+    .short = document
+    .braille = doc
+role-image = image
+    .braille = img
+role-separator = separator
+    .braille = sep
+role-label = label
+    .braille = lbl
@@ -0,0 +1,174 @@
+welcome = ¡Bienvenido a Odilia!
+crashed = Odilia se ha cerrado inesperadamente.
+quitting = Cerrando Odilia
+press-again-to-quit = Pulse de nuevo para salir
+document-loaded = Documento cargado
+page-summary = { $title }: { $headings ->
+        [one] { $headings } encabezado
+       *[other] { $headings } encabezados
+    }, { $links ->
+        [one] { $links } enlace
+       *[other] { $links } enlaces
+    }
+frame = marco: { $title }
+out-of-frame = fuera del marco
+new-notification = nueva notificación: { $app }, { $title }, { $body }.
+while-you-were-away-one = Mientras estaba ausente: 1 notificación
+while-you-were-away = Mientras estaba ausente: { $count } notificaciones
+caps-lock-on = Bloqueo de mayúsculas activado
+caps-lock-off = Bloqueo de mayúsculas desactivado
+num-lock-on = Bloqueo numérico activado
+num-lock-off = Bloqueo numérico desactivado
+scroll-lock-on = Bloqueo de desplazamiento activado
+scroll-lock-off = Bloqueo de desplazamiento desactivado
+keyboard-layout = distribución: { $name }
+input-server-unresponsive = { $server } ha dejado de responder; es posible que las teclas no hagan nada
+event-diagnostics-busiest = { $app }: { $count } eventos { $kind }
+setting-changed = { $setting }: { $value }
+setting-punctuation = Puntuación
+setting-echo = Eco
+setting-event-diagnostics = Diagnóstico de eventos
+on = activado
+off = desactivado
+punctuation-none = ninguna
+punctuation-some = alguna
+punctuation-most = la mayoría
+punctuation-all = toda
+echo-character = caracteres
+echo-word = palabras
+echo-character-and-word = caracteres y palabras
+echo-none = nada
+adjustments-reset = Ajustes restablecidos a los configurados
+screen-top = arriba
+screen-middle = en medio
+screen-bottom = abajo
+screen-left = a la izquierda
+screen-center = en el centro
+screen-right = a la derecha
+screen-region = { $vertical } { $horizontal }
+screen-region-on-monitor = { $region } en { $monitor }
+location-unknown = Ubicación desconocida
+console-mode = Leyendo la consola
+console-unreadable = No se puede leer la consola desde { $device }; ¿está tu usuario en el grupo tty?
+blank-line = en blanco
+input-rejected = Entrada de { $program } rechazada, porque se ejecuta como otro usuario
+unknown-program = un programa desconocido
+log-level = Registro en nivel { $level }
+invalid-log-level = { $level } no es un nivel de registro válido
+tray-title = Odilia, modo { $mode }
+tray-stop-speech = Detener la voz
+tray-next-mode = Modo siguiente
+tray-punctuation = Cambiar el nivel de puntuación
+tray-echo = Cambiar el eco del teclado
+tray-reset = Restablecer los ajustes cambiados
+tray-quit = Salir de Odilia
+key-plus = más
+key-control = Control
+key-shift = Mayúsculas
+key-alt = Alt
+key-super = Súper
+access-key = tecla de acceso { $keys }
+inspector = { $role } { $name }; interfaces: { $interfaces }; estados: { $states }; atributos: { $attributes }; ruta { $path } de { $app }
+navigator-no-parent = Sin objeto superior
+navigator-no-children = Sin objetos inferiores
+navigator-last = Último objeto
+navigator-first = Primer objeto
+cannot-activate = Esto no se puede activar
+window-selection = { $window }, { $item }
+loading = Cargando
+finished-loading = Carga terminada, { $headings ->
+        [one] { $headings } encabezado
+       *[other] { $headings } encabezados
+    }, { $links ->
+        [one] { $links } enlace
+       *[other] { $links } enlaces
+    }
+calendar-day = { $weekday } { $day }
+calendar-day-selected = { $weekday } { $day }, seleccionado
+no-languages = No hay idiomas configurados entre los que cambiar
+history-top = Inicio del historial de voz
+history-bottom = Fin del historial de voz
+history-not-found = Nada de lo dicho contiene { $text }
+setting-output = Salida
+output-speech = voz
+output-braille = braille
+output-both = voz y braille
+output-neither = nada
+page = Página { $page }
+page-of = Página { $page } de { $count }
+value-percent = { $value }, { $percent } por ciento
+no-more-links = No hay más enlaces
+no-more-of-role = No hay más: { $role }
+enter-text = Escriba el texto y pulse Intro
+text-entry-cancelled = Cancelado
+volume = Volumen { $percent } por ciento
+volume-unavailable = No se pueden anunciar los cambios de volumen, porque pactl no está instalado
+muted = Silenciado
+input-backlog = Entrada acumulada, algunas órdenes pueden retrasarse
+atspi-lost = Se perdió la conexión con las aplicaciones, reconectando
+startup-speech-ready = Voz lista
+startup-bus-ready = Bus de accesibilidad conectado
+startup-bus-failed = No se pueden escuchar las aplicaciones en el bus de accesibilidad, Odilia se cerrará
+startup-keyboard-ready = Teclado listo
+startup-keyboard-failed = No se pudo abrir el socket de entrada, las teclas no funcionarán
+startup-keyboard-timeout = Ningún servidor de entrada se ha conectado, las teclas no harán nada hasta que uno lo haga
+unknown-mode = No existe el modo { $name }
+value-range = { $value }, de { $min } a { $max }
+not-in-table = No está en una tabla
+
+# States: the long name is spoken, `.short` when space is tight, `.braille` on a braille display.
+state-checked = marcado
+state-pressed = pulsado
+state-expanded = expandido
+state-collapsed = contraído
+state-selected = seleccionado
+state-required = obligatorio
+state-read-only = solo lectura
+state-unavailable = no disponible
+
+# Roles, named after the AT-SPI role name with dashes for spaces.
+role-button = botón
+role-check-box = casilla de verificación
+    .short = casilla
+role-radio-button = botón de opción
+role-toggle-button = botón de alternancia
+role-combo-box = cuadro combinado
+role-entry = campo de texto
+role-password-text = campo de contraseña
+    .short = contraseña
+role-link = enlace
+role-heading = encabezado
+role-list = lista
+role-list-item = elemento de lista
+    .short = elemento
+role-menu = menú
+role-menu-bar = barra de menú
+role-menu-item = elemento de menú
+    .short = elemento
+role-page-tab = pestaña
+role-page-tab-list = lista de pestañas
+role-table = tabla
+role-table-cell = celda de tabla
+    .short = celda
+role-tree = árbol
+role-tree-item = elemento de árbol
+    .short = elemento
+role-dialog = diálogo
+role-frame = marco
+    .short = ventana
+role-slider = control deslizante
+role-progress-bar = barra de progreso
+role-document-web = documento web
+    .short = documento
+role-image = imagen
+role-list-box = cuadro de lista
+role-check-menu-item = elemento de menú de verificación
+    .short = elemento
+role-radio-menu-item = elemento de menú de opción
+    .short = elemento
+role-spin-button = cuadro de número
+role-scroll-bar = barra de desplazamiento
+role-tool-bar = barra de herramientas
+role-status-bar = barra de estado
+role-separator = separador
+role-label = etiqueta
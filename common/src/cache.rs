@@ -1,4 +1,4 @@
-use crate::{errors::AccessiblePrimitiveConversionError, ObjectPath};
+use crate::{errors::AccessiblePrimitiveConversionError, intern::Atom, ObjectPath};
 use atspi::{EventProperties, ObjectRef};
 use atspi_proxies::{accessible::AccessibleProxy, text::TextProxy};
 use serde::{Deserialize, Serialize};
@@ -10,6 +10,7 @@ use zbus::{
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 /// A struct which represents the bare minimum of an accessible for purposes of caching.
 /// This makes some *possibly eronious* assumptions about what the sender is.
+/// Both parts are [`Atom`]s, so cloning a primitive never allocates.
 pub struct AccessiblePrimitive {
 	/// The accessible ID, which is an arbitrary string specified by the application.
 	/// It is guaranteed to be unique per application.
@@ -18,13 +19,13 @@ pub struct AccessiblePrimitive {
 	/// * /org/a11y/atspi/accessible/null
 	/// * /org/a11y/atspi/accessible/root
 	/// * /org/Gnome/GTK/abab22-bbbb33-2bba2
-	pub id: String,
+	pub id: Atom,
 	/// Assuming that the sender is ":x.y", this stores the (x,y) portion of this sender.
 	/// Examples:
 	/// * :1.1 (the first window has opened)
 	/// * :2.5 (a second session exists, where at least 5 applications have been lauinched)
 	/// * :1.262 (many applications have been started on this bus)
-	pub sender: smartstring::alias::String,
+	pub sender: Atom,
 }
 
 impl AccessiblePrimitive {
@@ -33,8 +34,7 @@ impl AccessiblePrimitive {
 	pub fn from_event<T: EventProperties>(event: &T) -> Self {
 		let sender = event.sender();
 		let path = event.path();
-		let id = path.to_string();
-		Self { id, sender: sender.as_str().into() }
+		Self { id: path.as_str().into(), sender: sender.as_str().into() }
 	}
 
 	/// Convert into an [`atspi_proxies::accessible::AccessibleProxy`]. Must be async because the creation of an async proxy requires async itself.
@@ -45,12 +45,10 @@ impl AccessiblePrimitive {
 		self,
 		conn: &zbus::Connection,
 	) -> zbus::Result<AccessibleProxy<'a>> {
-		let id = self.id;
-		let sender = self.sender.clone();
-		let path: ObjectPath<'a> = id.try_into()?;
+		let path: ObjectPath<'a> = self.id.to_string().try_into()?;
 		ProxyBuilder::new(conn)
 			.path(path)?
-			.destination(sender.as_str().to_owned())?
+			.destination(self.sender.to_string())?
 			.cache_properties(CacheProperties::No)
 			.build()
 			.await
@@ -60,12 +58,10 @@ impl AccessiblePrimitive {
 	/// Will return a [`zbus::Error`] in the case of an invalid destination, path, or failure to create a `Proxy` from those properties.
 	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, level = "trace", ret, err))]
 	pub async fn into_text<'a>(self, conn: &zbus::Connection) -> zbus::Result<TextProxy<'a>> {
-		let id = self.id;
-		let sender = self.sender.clone();
-		let path: ObjectPath<'a> = id.try_into()?;
+		let path: ObjectPath<'a> = self.id.to_string().try_into()?;
 		ProxyBuilder::new(conn)
 			.path(path)?
-			.destination(sender.as_str().to_owned())?
+			.destination(self.sender.to_string())?
 			.cache_properties(CacheProperties::No)
 			.build()
 			.await
@@ -82,20 +78,23 @@ impl From<ObjectRef> for AccessiblePrimitive {
 impl From<(OwnedUniqueName, OwnedObjectPath)> for AccessiblePrimitive {
 	fn from(so: (OwnedUniqueName, OwnedObjectPath)) -> AccessiblePrimitive {
 		let accessible_id = so.1;
-		AccessiblePrimitive { id: accessible_id.to_string(), sender: so.0.as_str().into() }
+		AccessiblePrimitive {
+			id: accessible_id.as_str().into(),
+			sender: so.0.as_str().into(),
+		}
 	}
 }
 impl From<(String, OwnedObjectPath)> for AccessiblePrimitive {
 	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, level = "trace", ret))]
 	fn from(so: (String, OwnedObjectPath)) -> AccessiblePrimitive {
 		let accessible_id = so.1;
-		AccessiblePrimitive { id: accessible_id.to_string(), sender: so.0.into() }
+		AccessiblePrimitive { id: accessible_id.as_str().into(), sender: so.0.into() }
 	}
 }
 impl<'a> From<(String, ObjectPath<'a>)> for AccessiblePrimitive {
 	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, level = "trace", ret))]
 	fn from(so: (String, ObjectPath<'a>)) -> AccessiblePrimitive {
-		AccessiblePrimitive { id: so.1.to_string(), sender: so.0.into() }
+		AccessiblePrimitive { id: so.1.as_str().into(), sender: so.0.into() }
 	}
 }
 impl<'a> TryFrom<&AccessibleProxy<'a>> for AccessiblePrimitive {
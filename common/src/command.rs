@@ -2,7 +2,10 @@
 
 use crate::cache::AccessiblePrimitive;
 use crate::errors::OdiliaError;
+use crate::privacy::Redacted;
+use crate::settings::navigation::ScrollAnchor;
 use enum_dispatch::enum_dispatch;
+use serde::Serialize;
 use ssip::Priority;
 use std::convert::Infallible;
 
@@ -55,14 +58,54 @@ impl IntoCommands for Focus {
 		vec![self.into()]
 	}
 }
-impl IntoCommands for (Priority, &str) {
+impl IntoCommands for SetCaret {
 	fn into_commands(self) -> Vec<OdiliaCommand> {
-		vec![Speak(self.1.to_string(), self.0).into()]
+		vec![self.into()]
+	}
+}
+impl IntoCommands for SpeakChar {
+	fn into_commands(self) -> Vec<OdiliaCommand> {
+		vec![self.into()]
+	}
+}
+impl IntoCommands for SpeechControl {
+	fn into_commands(self) -> Vec<OdiliaCommand> {
+		vec![self.into()]
 	}
 }
-impl IntoCommands for (Priority, String) {
+impl IntoCommands for Silence {
 	fn into_commands(self) -> Vec<OdiliaCommand> {
-		vec![Speak(self.1, self.0).into()]
+		vec![self.into()]
+	}
+}
+impl IntoCommands for Route {
+	fn into_commands(self) -> Vec<OdiliaCommand> {
+		vec![self.into()]
+	}
+}
+impl IntoCommands for ScrollIntoView {
+	fn into_commands(self) -> Vec<OdiliaCommand> {
+		vec![self.into()]
+	}
+}
+impl IntoCommands for Activate {
+	fn into_commands(self) -> Vec<OdiliaCommand> {
+		vec![self.into()]
+	}
+}
+impl IntoCommands for Announcement {
+	fn into_commands(self) -> Vec<OdiliaCommand> {
+		vec![Speak(self).into()]
+	}
+}
+impl IntoCommands for (Category, Priority, &str) {
+	fn into_commands(self) -> Vec<OdiliaCommand> {
+		Announcement::new(self.0, self.1, self.2).into_commands()
+	}
+}
+impl IntoCommands for (Category, Priority, String) {
+	fn into_commands(self) -> Vec<OdiliaCommand> {
+		Announcement::new(self.0, self.1, self.2).into_commands()
 	}
 }
 impl IntoCommands for () {
@@ -134,12 +177,123 @@ impl<T: CommandType> CommandTypeDynamic for T {
 #[derive(Debug, Clone)]
 pub struct CaretPos(pub usize);
 
+/// What a message is about, so that what is done with it can depend on that, rather than on its priority alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Category {
+	/// The object which has focus, or something which changed about it.
+	Focus,
+	/// What the user moved to, or asked to have read, like a line of text or the row of a table.
+	Navigation,
+	/// A notification, from an application or from Odilia.
+	Notification,
+	/// Odilia itself, like a setting which was changed, or the mode it is in.
+	System,
+	/// What the user typed.
+	Echo,
+}
+
+/// A message to be spoken: its text, its priority, and what it is about.
+#[derive(Clone)]
+pub struct Announcement {
+	pub text: String,
+	pub priority: Priority,
+	pub category: Category,
+	/// Whether the message goes into the speech history; messages read from the history itself do not.
+	pub remembered: bool,
+	/// Index marks in the text, as the offset (in characters) each is before and its name, in order; speech-dispatcher says when speech gets to each.
+	pub marks: Vec<(usize, String)>,
+}
+
+impl Announcement {
+	#[must_use]
+	pub fn new(category: Category, priority: Priority, text: impl Into<String>) -> Self {
+		Announcement {
+			text: text.into(),
+			priority,
+			category,
+			remembered: true,
+			marks: Vec::new(),
+		}
+	}
+	/// The same message, kept out of the speech history.
+	#[must_use]
+	pub fn forgotten(self) -> Self {
+		Announcement { remembered: false, ..self }
+	}
+	/// The same message, with index marks; see [`Announcement::marks`].
+	#[must_use]
+	pub fn marked(self, marks: Vec<(usize, String)>) -> Self {
+		Announcement { marks, ..self }
+	}
+}
+
+impl std::fmt::Debug for Announcement {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Announcement")
+			.field("text", &Redacted(&self.text))
+			.field("priority", &self.priority)
+			.field("category", &self.category)
+			.field("remembered", &self.remembered)
+			.field("marks", &self.marks)
+			.finish()
+	}
+}
+
 #[derive(Debug, Clone)]
-pub struct Speak(pub String, pub Priority);
+pub struct Speak(pub Announcement);
 
 #[derive(Debug, Clone)]
 pub struct Focus(pub AccessiblePrimitive);
 
+/// Moves the caret of an application to `offset`, in characters, in the text of `item`.
+#[derive(Debug, Clone)]
+pub struct SetCaret {
+	pub item: AccessiblePrimitive,
+	pub offset: usize,
+}
+
+/// A single character to be spoken as such, so that punctuation and symbols are named; for echoing typed characters.
+#[derive(Debug, Clone)]
+pub struct SpeakChar {
+	pub ch: char,
+	pub priority: Priority,
+}
+
+/// A request to speech-dispatcher which is not a message, like changing the language, or cancelling the last message.
+#[derive(Debug, Clone)]
+pub struct SpeechControl(pub ssip::Request);
+
+/// Stops speech.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Silence {
+	/// Cancels whatever is being said, and drops every queued message.
+	All,
+	/// Cuts off only speech which may be interrupted, like reading, but not an important message; as when the user starts typing.
+	Interruptible,
+}
+
+/// Sends messages from now on to speech, to the braille display, to both, or (with both off) nowhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Route {
+	pub speech: bool,
+	pub braille: bool,
+}
+
+/// Scrolls `item` into view, anchored at `anchor`; failing to is only logged, since it is never essential.
+#[derive(Debug, Clone)]
+pub struct ScrollIntoView {
+	pub item: AccessiblePrimitive,
+	pub anchor: ScrollAnchor,
+}
+
+/// Activates `item`, as if it was clicked; if that is not possible, `otherwise` is spoken instead.
+#[derive(Debug, Clone)]
+pub struct Activate {
+	pub item: AccessiblePrimitive,
+	pub otherwise: Announcement,
+}
+
 impl CommandType for Speak {
 	const CTYPE: OdiliaCommandDiscriminants = OdiliaCommandDiscriminants::Speak;
 }
@@ -149,6 +303,27 @@ impl CommandType for Focus {
 impl CommandType for CaretPos {
 	const CTYPE: OdiliaCommandDiscriminants = OdiliaCommandDiscriminants::CaretPos;
 }
+impl CommandType for SetCaret {
+	const CTYPE: OdiliaCommandDiscriminants = OdiliaCommandDiscriminants::SetCaret;
+}
+impl CommandType for SpeakChar {
+	const CTYPE: OdiliaCommandDiscriminants = OdiliaCommandDiscriminants::SpeakChar;
+}
+impl CommandType for SpeechControl {
+	const CTYPE: OdiliaCommandDiscriminants = OdiliaCommandDiscriminants::SpeechControl;
+}
+impl CommandType for Silence {
+	const CTYPE: OdiliaCommandDiscriminants = OdiliaCommandDiscriminants::Silence;
+}
+impl CommandType for Route {
+	const CTYPE: OdiliaCommandDiscriminants = OdiliaCommandDiscriminants::Route;
+}
+impl CommandType for ScrollIntoView {
+	const CTYPE: OdiliaCommandDiscriminants = OdiliaCommandDiscriminants::ScrollIntoView;
+}
+impl CommandType for Activate {
+	const CTYPE: OdiliaCommandDiscriminants = OdiliaCommandDiscriminants::Activate;
+}
 
 #[derive(Debug, Clone, EnumDiscriminants)]
 #[strum_discriminants(derive(Ord, PartialOrd, Display))]
@@ -157,4 +332,11 @@ pub enum OdiliaCommand {
 	Speak(Speak),
 	Focus(Focus),
 	CaretPos(CaretPos),
+	SetCaret(SetCaret),
+	SpeakChar(SpeakChar),
+	SpeechControl(SpeechControl),
+	Silence(Silence),
+	Route(Route),
+	ScrollIntoView(ScrollIntoView),
+	Activate(Activate),
 }
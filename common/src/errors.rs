@@ -5,6 +5,23 @@ use serde_plain::Error as SerdePlainError;
 use smartstring::alias::String as SmartString;
 use std::{error::Error, fmt, str::FromStr};
 
+/// What part of Odilia (or of the desktop) an [`OdiliaError`] comes from, for logs and for deciding how to report it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display)]
+pub enum ErrorCategory {
+	/// D-Bus or AT-SPI, including applications which do not answer.
+	Bus,
+	/// The cache of accessible objects.
+	Cache,
+	/// Speech-dispatcher, or the task talking to it.
+	Speech,
+	/// Events from input servers, or parsing them.
+	Input,
+	/// The configuration file.
+	Config,
+	/// Odilia's own logic.
+	App,
+}
+
 #[derive(Debug)]
 pub enum OdiliaError {
 	AtspiError(AtspiError),
@@ -24,7 +41,73 @@ pub enum OdiliaError {
 	Generic(String),
 	Static(&'static str),
 	ServiceNotFound(String),
+	/// Something Odilia depends on, like an application or the cache, did not answer in time.
+	ServiceUnavailable(String),
 	PredicateFailure(String),
+	/// Several handlers for the same event failed, by name.
+	Handlers(Vec<(&'static str, OdiliaError)>),
+}
+
+impl OdiliaError {
+	/// Which part of Odilia the error comes from.
+	#[must_use]
+	pub fn category(&self) -> ErrorCategory {
+		match self {
+			Self::AtspiError(_)
+			| Self::AtspiTypesError(_)
+			| Self::Zbus(_)
+			| Self::ZbusFdo(_)
+			| Self::Zvariant(_)
+			| Self::ServiceUnavailable(_) => ErrorCategory::Bus,
+			Self::Cache(_)
+			| Self::PoisoningError
+			| Self::PrimitiveConversionError(_)
+			| Self::NoAttributeError(_) => ErrorCategory::Cache,
+			Self::SendError(SendError::Ssip(_)) => ErrorCategory::Speech,
+			Self::SerdeError(_) => ErrorCategory::Input,
+			Self::Config(_) => ErrorCategory::Config,
+			Self::SendError(_)
+			| Self::InfallibleConversion(_)
+			| Self::ConversionError(_)
+			| Self::Generic(_)
+			| Self::Static(_)
+			| Self::ServiceNotFound(_)
+			| Self::PredicateFailure(_) => ErrorCategory::App,
+			// the category they share, if they do
+			Self::Handlers(failures) => match failures.split_first() {
+				Some(((_, first), rest))
+					if rest.iter()
+						.all(|(_, e)| e.category() == first.category()) =>
+				{
+					first.category()
+				}
+				_ => ErrorCategory::App,
+			},
+		}
+	}
+	/// Whether the user can do something about the error, like fixing the configuration or closing an application which hangs, rather than it being a bug in Odilia.
+	/// Those are worth telling the user about; the rest are only for the logs.
+	#[must_use]
+	pub fn is_user_actionable(&self) -> bool {
+		match self {
+			Self::Config(_) | Self::ServiceUnavailable(_) => true,
+			Self::Handlers(failures) => {
+				failures.iter().all(|(_, e)| e.is_user_actionable())
+			}
+			_ => false,
+		}
+	}
+}
+
+impl From<OdiliaError> for zbus::fdo::Error {
+	fn from(e: OdiliaError) -> Self {
+		match e {
+			OdiliaError::ZbusFdo(e) => e,
+			OdiliaError::Config(_) => Self::InvalidArgs(e.to_string()),
+			OdiliaError::ServiceUnavailable(_) => Self::NoReply(e.to_string()),
+			e => Self::Failed(e.to_string()),
+		}
+	}
 }
 
 impl From<&'static str> for OdiliaError {
@@ -51,6 +134,17 @@ macro_rules! send_err_impl {
 	};
 }
 
+impl fmt::Display for SendError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let what = match self {
+			Self::Atspi(_) => "an AT-SPI event",
+			Self::Command(_) => "a command",
+			Self::Ssip(_) => "a speech request",
+		};
+		write!(f, "Could not pass on {what}, since the task handling it has stopped")
+	}
+}
+
 send_err_impl!(tokio::sync::broadcast::error::SendError<atspi::Event>, SendError::Atspi);
 send_err_impl!(tokio::sync::mpsc::error::SendError<atspi::Event>, SendError::Atspi);
 send_err_impl!(tokio::sync::broadcast::error::SendError<OdiliaCommand>, SendError::Command);
@@ -145,9 +239,42 @@ impl From<AtspiError> for OdiliaError {
 		Self::AtspiError(err)
 	}
 }
+/// Messages which stay the same however the errors inside are laid out, unlike their `Debug` output; the category goes in front, like `Bus: ...`.
 impl fmt::Display for OdiliaError {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "{self:?}")
+		write!(f, "{}: ", self.category())?;
+		match self {
+			Self::AtspiError(e) => write!(f, "AT-SPI error: {e}"),
+			Self::AtspiTypesError(e) => write!(f, "Invalid AT-SPI data: {e}"),
+			Self::PrimitiveConversionError(e) => {
+				write!(f, "Invalid reference to an accessible object: {e}")
+			}
+			Self::NoAttributeError(name) => write!(f, "No attribute {name}"),
+			Self::SerdeError(e) => write!(f, "Could not parse: {e}"),
+			Self::Zbus(e) => write!(f, "D-Bus error: {e}"),
+			Self::ZbusFdo(e) => write!(f, "D-Bus error: {e}"),
+			Self::Zvariant(e) => write!(f, "Invalid D-Bus value: {e}"),
+			Self::SendError(e) => e.fmt(f),
+			Self::Cache(e) => e.fmt(f),
+			Self::InfallibleConversion(never) => match *never {},
+			Self::ConversionError(e) => write!(f, "Number out of range: {e}"),
+			Self::Config(e) => write!(f, "Invalid configuration: {e}"),
+			Self::PoisoningError => f.write_str(
+				"A lock is unusable, since a task panicked while holding it",
+			),
+			Self::Generic(message)
+			| Self::ServiceNotFound(message)
+			| Self::PredicateFailure(message) => f.write_str(message),
+			Self::Static(message) => f.write_str(message),
+			Self::ServiceUnavailable(message) => write!(f, "Not responding: {message}"),
+			Self::Handlers(failures) => {
+				write!(f, "{} handlers failed", failures.len())?;
+				for (name, e) in failures {
+					write!(f, "; {name}: {e}")?;
+				}
+				Ok(())
+			}
+		}
 	}
 }
 
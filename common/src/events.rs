@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use crate::modes::ScreenReaderMode;
+use crate::{errors::OdiliaError, modes::ScreenReaderMode, privacy::Redacted};
 use atspi_common::Role;
+use strum::{Display, EnumDiscriminants};
 
-#[derive(Eq, PartialEq, Clone, Hash, Serialize, Deserialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash, Serialize, Deserialize)]
 /// A list of features supported natively by Odilia.
 pub enum Feature {
 	/// Unimplemented, but will eventually stop all speech until re-activated.
@@ -12,14 +13,58 @@ pub enum Feature {
 	Braille, // TODO
 }
 
-#[derive(Eq, PartialEq, Clone, Hash, Serialize, Deserialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash, Serialize, Deserialize)]
 #[serde(tag = "direction")]
 pub enum Direction {
 	Forward,
 	Backward,
 }
 
-#[derive(Eq, PartialEq, Clone, Hash, Serialize, Deserialize)]
+/// Where the object navigator moves, in the tree of accessibles.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Serialize, Deserialize)]
+#[serde(tag = "direction")]
+pub enum ObjectDirection {
+	Parent,
+	FirstChild,
+	NextSibling,
+	PreviousSibling,
+}
+
+/// How far [`ScreenReaderEvent::SkipSayAll`] skips.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TextUnit {
+	Word,
+	Sentence,
+	/// One piece of text read, like a paragraph, list item or table cell.
+	Paragraph,
+}
+
+/// What a line of text typed for [`ScreenReaderEvent::EnterText`] is used for, once it is entered.
+#[derive(Debug, Eq, PartialEq, Clone, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TextPurpose {
+	/// Searching what Odilia said for it, like [`ScreenReaderEvent::SearchHistory`].
+	SearchHistory,
+	/// The only argument of the custom event with this name, for plugins and scripts which need text from the user.
+	Custom(String),
+}
+
+impl TextPurpose {
+	/// The event which uses `text` for this purpose.
+	#[must_use]
+	pub fn event(self, text: String) -> ScreenReaderEvent {
+		match self {
+			TextPurpose::SearchHistory => ScreenReaderEvent::SearchHistory(text),
+			TextPurpose::Custom(name) => {
+				ScreenReaderEvent::Custom { name, args: vec![text] }
+			}
+		}
+	}
+}
+
+#[derive(Eq, PartialEq, Clone, Hash, Serialize, Deserialize, EnumDiscriminants)]
+#[strum_discriminants(derive(Ord, PartialOrd, Display))]
 #[serde(tag = "event", content = "args", rename_all = "camelCase")]
 /// Events which can be trigged through Odilia's external API.
 /// Subject to change without notice until v1.0, but we're [open to suggestions on our Github](https://github.com/odilia-app/odilia/); please reach out with features you'd like to see.
@@ -33,6 +78,800 @@ pub enum ScreenReaderEvent {
 	/// Disable a feature.
 	Disable(Feature),
 	/// Change mode of the screen reader. This is currently global, but it should be per application, and an update should only affect the current application.
+	/// The mode has to be one of `modes.names`.
 	ChangeMode(ScreenReaderMode),
+	/// Switch to the next (or previous) of `modes.names`, wrapping around.
+	CycleMode(Direction),
+	/// Say which mode the screen reader is in.
+	SayMode,
+	/// Move the object navigator to the next (or previous) object with a role, like a heading, in the document, and read it.
 	StructuralNavigation(Direction, Role),
+	/// Read the whole row of the table cell which has focus.
+	ReadRow,
+	/// Read the whole column of the table cell which has focus.
+	ReadColumn,
+	/// Read from the object which has focus to the end of the list, table, article or other container it is in, like a single email or comment thread.
+	ReadContainer,
+	/// Move the review cursor to the next (or previous) line of the console, and read it, in console mode, where Odilia reads a virtual terminal instead of the desktop.
+	/// Otherwise, move through what Odilia said, one utterance at a time, starting from the last; see `speech.history_size`.
+	ReviewLine(Direction),
+	/// The user did something, like pressing any key (even one which is not bound to anything).
+	/// Input servers should send this on every key press; it interrupts low-priority speech, if that is enabled.
+	UserActivity,
+	/// Text the user typed, usually a single character; input servers send this along with [`ScreenReaderEvent::UserActivity`] for keys which produce text.
+	/// A backspace is sent as `"\u{8}"`. Odilia echoes it according to the `echo` settings.
+	KeyEcho(String),
+	/// Restart Odilia in place, for example after it has been updated, keeping the cache and settings.
+	Restart,
+	/// Stop Odilia; if `input.confirm_quit` is set, this has to be sent twice in a row.
+	Quit,
+	/// Turn event diagnostics on or off: while on, the AT-SPI events Odilia receives are counted by the application sending them, and the busiest are spoken and logged every `diagnostics.window_secs`.
+	/// This finds out which application is making Odilia slow, by flooding it with events.
+	EventDiagnostics,
+	/// Switch to the next punctuation level (none, some, most, all), until Odilia exits.
+	CyclePunctuation,
+	/// Switch to the next echo mode (characters, words, both, nothing), until Odilia exits; entries in `echo.apps` still take precedence.
+	CycleEcho,
+	/// Switch the voice to the next of the languages in `speech.languages`, until Odilia exits; the new language is named in itself.
+	CycleLanguage,
+	/// Switch where Odilia's messages go to the next of speech, braille, and both, until Odilia exits; the change is announced where they go now.
+	CycleOutput,
+	/// Undo every setting changed while Odilia runs (like with [`ScreenReaderEvent::CyclePunctuation`]), going back to the configured ones, and the first mode.
+	/// Otherwise, those changes are kept across restarts.
+	ResetAdjustments,
+	/// Say where on screen the object which has focus is, like "top left", and on which monitor if there are several.
+	SayLocation,
+	/// Change which messages are logged until Odilia exits, like `debug` or `odilia=trace,zbus=warn`; see `log.level`.
+	SetLogLevel(String),
+	/// Speak and log everything about the focused object: its role, interfaces, states, attributes, object path and application's bus name.
+	/// This lets application developers check what their application exposes, with Odilia itself.
+	Inspect,
+	/// Move the object navigator to the parent, first child or next or previous sibling of the object it is on, and speak that object.
+	/// This reaches objects which can not be focused, like the contents of containers which Tab skips; the navigator starts from the focused object, and goes back to it whenever focus moves.
+	NavigateObject(ObjectDirection),
+	/// Activate the object under the object navigator (the focused object, unless it was moved), as if it was clicked: its default action is done, or if it has none, a click is made in its middle.
+	ActivateReviewed,
+	/// Go back through what Odilia said to the last utterance containing this text, ignoring case, and read it; [`ScreenReaderEvent::ReviewLine`] then moves on from there.
+	SearchHistory(String),
+	/// Move the caret to the next or previous link in the document, and read it; with `unvisited`, only to links which were not followed yet.
+	/// This finds links whether the application gives them objects of their own, or only marks ranges of text as links.
+	NavigateLink {
+		direction: Direction,
+		#[serde(default)]
+		unvisited: bool,
+	},
+	/// While reading continuously, skip to the next word, sentence or paragraph, or back to the previous one, and read on from there.
+	/// This also works right after a key press stopped reading, since input servers report the key press first.
+	SkipSayAll { unit: TextUnit, direction: Direction },
+	/// Ask the input server to collect a line of typed text, which is then used for the given purpose, instead of treating keys as commands; see [`ScreenReaderEvent::TextEntered`].
+	EnterText(TextPurpose),
+	/// A line of text the user typed, sent by the input server once Enter is pressed after Odilia asked for text with [`ScreenReaderEvent::EnterText`]; an empty line cancels.
+	/// Input servers edit and echo the line themselves while it is typed.
+	TextEntered(String),
+	/// Sent by an input server every `interval_ms` milliseconds, to show that it is still running.
+	/// Once a server has sent one, Odilia announces when it misses `input.heartbeat_missed` of them in a row, since otherwise the user would only find out by keys doing nothing.
+	Heartbeat { server: String, interval_ms: u64 },
+	/// An event which Odilia itself does not know about, for plugins and alternative input servers.
+	/// It is handled by whichever handlers were registered for `name`, which get `args` as they were sent.
+	Custom {
+		name: String,
+		#[serde(default)]
+		args: Vec<String>,
+	},
+}
+
+/// Text the user typed, or searched for, is [`Redacted`].
+impl std::fmt::Debug for ScreenReaderEvent {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::KeyEcho(text) => {
+				f.debug_tuple("KeyEcho").field(&Redacted(text)).finish()
+			}
+			Self::SearchHistory(text) => {
+				f.debug_tuple("SearchHistory").field(&Redacted(text)).finish()
+			}
+			Self::TextEntered(text) => {
+				f.debug_tuple("TextEntered").field(&Redacted(text)).finish()
+			}
+			Self::Custom { name, args } => {
+				let args: Vec<Redacted<'_>> =
+					args.iter().map(|arg| Redacted(arg)).collect();
+				f.debug_struct("Custom")
+					.field("name", name)
+					.field("args", &args)
+					.finish()
+			}
+			Self::Enable(feature) => f.debug_tuple("Enable").field(feature).finish(),
+			Self::Disable(feature) => f.debug_tuple("Disable").field(feature).finish(),
+			Self::ChangeMode(mode) => f.debug_tuple("ChangeMode").field(mode).finish(),
+			Self::CycleMode(direction) => {
+				f.debug_tuple("CycleMode").field(direction).finish()
+			}
+			Self::StructuralNavigation(direction, role) => f
+				.debug_tuple("StructuralNavigation")
+				.field(direction)
+				.field(role)
+				.finish(),
+			Self::ReviewLine(direction) => {
+				f.debug_tuple("ReviewLine").field(direction).finish()
+			}
+			Self::SetLogLevel(level) => {
+				f.debug_tuple("SetLogLevel").field(level).finish()
+			}
+			Self::NavigateObject(direction) => {
+				f.debug_tuple("NavigateObject").field(direction).finish()
+			}
+			Self::NavigateLink { direction, unvisited } => f
+				.debug_struct("NavigateLink")
+				.field("direction", direction)
+				.field("unvisited", unvisited)
+				.finish(),
+			Self::SkipSayAll { unit, direction } => f
+				.debug_struct("SkipSayAll")
+				.field("unit", unit)
+				.field("direction", direction)
+				.finish(),
+			Self::EnterText(purpose) => {
+				f.debug_tuple("EnterText").field(purpose).finish()
+			}
+			Self::Heartbeat { server, interval_ms } => f
+				.debug_struct("Heartbeat")
+				.field("server", server)
+				.field("interval_ms", interval_ms)
+				.finish(),
+			// the rest have no fields
+			_ => write!(f, "{}", ScreenReaderEventDiscriminants::from(self)),
+		}
+	}
+}
+
+/// A type which can be extracted from exactly one variant of [`ScreenReaderEvent`].
+/// This is what allows input handlers to only be called for the events they are interested in.
+pub trait EventType {
+	const ETYPE: ScreenReaderEventDiscriminants;
+}
+
+/// See [`ScreenReaderEvent::StopSpeech`].
+#[derive(Debug, Clone, Copy)]
+pub struct StopSpeech;
+
+impl EventType for StopSpeech {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::StopSpeech;
+}
+impl TryFrom<ScreenReaderEvent> for StopSpeech {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::StopSpeech => Ok(StopSpeech),
+			_ => Err(OdiliaError::Static(
+				"Only a StopSpeech event can be converted into StopSpeech",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::Restart`].
+#[derive(Debug, Clone, Copy)]
+pub struct Restart;
+
+impl EventType for Restart {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::Restart;
+}
+impl TryFrom<ScreenReaderEvent> for Restart {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::Restart => Ok(Restart),
+			_ => Err(OdiliaError::Static(
+				"Only a Restart event can be converted into Restart",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::EventDiagnostics`].
+#[derive(Debug, Clone, Copy)]
+pub struct EventDiagnostics;
+
+impl EventType for EventDiagnostics {
+	const ETYPE: ScreenReaderEventDiscriminants =
+		ScreenReaderEventDiscriminants::EventDiagnostics;
+}
+impl TryFrom<ScreenReaderEvent> for EventDiagnostics {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::EventDiagnostics => Ok(EventDiagnostics),
+			_ => Err(OdiliaError::Static(
+				"Only an EventDiagnostics event can be converted into EventDiagnostics",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::CyclePunctuation`].
+#[derive(Debug, Clone, Copy)]
+pub struct CyclePunctuation;
+
+impl EventType for CyclePunctuation {
+	const ETYPE: ScreenReaderEventDiscriminants =
+		ScreenReaderEventDiscriminants::CyclePunctuation;
+}
+impl TryFrom<ScreenReaderEvent> for CyclePunctuation {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::CyclePunctuation => Ok(CyclePunctuation),
+			_ => Err(OdiliaError::Static(
+				"Only a CyclePunctuation event can be converted into CyclePunctuation",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::CycleEcho`].
+#[derive(Debug, Clone, Copy)]
+pub struct CycleEcho;
+
+impl EventType for CycleEcho {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::CycleEcho;
+}
+impl TryFrom<ScreenReaderEvent> for CycleEcho {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::CycleEcho => Ok(CycleEcho),
+			_ => Err(OdiliaError::Static(
+				"Only a CycleEcho event can be converted into CycleEcho",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::CycleOutput`].
+#[derive(Debug, Clone, Copy)]
+pub struct CycleOutput;
+
+impl EventType for CycleOutput {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::CycleOutput;
+}
+impl TryFrom<ScreenReaderEvent> for CycleOutput {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::CycleOutput => Ok(CycleOutput),
+			_ => Err(OdiliaError::Static(
+				"Only a CycleOutput event can be converted into CycleOutput",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::CycleLanguage`].
+#[derive(Debug, Clone, Copy)]
+pub struct CycleLanguage;
+
+impl EventType for CycleLanguage {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::CycleLanguage;
+}
+impl TryFrom<ScreenReaderEvent> for CycleLanguage {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::CycleLanguage => Ok(CycleLanguage),
+			_ => Err(OdiliaError::Static(
+				"Only a CycleLanguage event can be converted into CycleLanguage",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::ResetAdjustments`].
+#[derive(Debug, Clone, Copy)]
+pub struct ResetAdjustments;
+
+impl EventType for ResetAdjustments {
+	const ETYPE: ScreenReaderEventDiscriminants =
+		ScreenReaderEventDiscriminants::ResetAdjustments;
+}
+impl TryFrom<ScreenReaderEvent> for ResetAdjustments {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::ResetAdjustments => Ok(ResetAdjustments),
+			_ => Err(OdiliaError::Static(
+				"Only a ResetAdjustments event can be converted into ResetAdjustments",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::SayLocation`].
+#[derive(Debug, Clone, Copy)]
+pub struct SayLocation;
+
+impl EventType for SayLocation {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::SayLocation;
+}
+impl TryFrom<ScreenReaderEvent> for SayLocation {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::SayLocation => Ok(SayLocation),
+			_ => Err(OdiliaError::Static(
+				"Only a SayLocation event can be converted into SayLocation",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::StructuralNavigation`].
+#[derive(Debug, Clone)]
+pub struct StructuralNavigation(pub Direction, pub Role);
+
+impl EventType for StructuralNavigation {
+	const ETYPE: ScreenReaderEventDiscriminants =
+		ScreenReaderEventDiscriminants::StructuralNavigation;
+}
+impl TryFrom<ScreenReaderEvent> for StructuralNavigation {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::StructuralNavigation(direction, role) => {
+				Ok(StructuralNavigation(direction, role))
+			}
+			_ => Err(OdiliaError::Static(
+				"Only a StructuralNavigation event can be converted into StructuralNavigation",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::ReviewLine`].
+#[derive(Debug, Clone)]
+pub struct ReviewLine(pub Direction);
+
+impl EventType for ReviewLine {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::ReviewLine;
+}
+impl TryFrom<ScreenReaderEvent> for ReviewLine {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::ReviewLine(direction) => Ok(ReviewLine(direction)),
+			_ => Err(OdiliaError::Static(
+				"Only a ReviewLine event can be converted into ReviewLine",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::SetLogLevel`].
+#[derive(Debug, Clone)]
+pub struct SetLogLevel(pub String);
+
+impl EventType for SetLogLevel {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::SetLogLevel;
+}
+impl TryFrom<ScreenReaderEvent> for SetLogLevel {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::SetLogLevel(level) => Ok(SetLogLevel(level)),
+			_ => Err(OdiliaError::Static(
+				"Only a SetLogLevel event can be converted into SetLogLevel",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::Inspect`].
+#[derive(Debug, Clone, Copy)]
+pub struct Inspect;
+
+impl EventType for Inspect {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::Inspect;
+}
+impl TryFrom<ScreenReaderEvent> for Inspect {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::Inspect => Ok(Inspect),
+			_ => Err(OdiliaError::Static(
+				"Only an Inspect event can be converted into Inspect",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::NavigateObject`].
+#[derive(Debug, Clone, Copy)]
+pub struct NavigateObject(pub ObjectDirection);
+
+impl EventType for NavigateObject {
+	const ETYPE: ScreenReaderEventDiscriminants =
+		ScreenReaderEventDiscriminants::NavigateObject;
+}
+impl TryFrom<ScreenReaderEvent> for NavigateObject {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::NavigateObject(direction) => {
+				Ok(NavigateObject(direction))
+			}
+			_ => Err(OdiliaError::Static(
+				"Only a NavigateObject event can be converted into NavigateObject",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::ActivateReviewed`].
+#[derive(Debug, Clone, Copy)]
+pub struct ActivateReviewed;
+
+impl EventType for ActivateReviewed {
+	const ETYPE: ScreenReaderEventDiscriminants =
+		ScreenReaderEventDiscriminants::ActivateReviewed;
+}
+impl TryFrom<ScreenReaderEvent> for ActivateReviewed {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::ActivateReviewed => Ok(ActivateReviewed),
+			_ => Err(OdiliaError::Static(
+				"Only an ActivateReviewed event can be converted into ActivateReviewed",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::SearchHistory`].
+#[derive(Clone)]
+pub struct SearchHistory(pub String);
+
+impl std::fmt::Debug for SearchHistory {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("SearchHistory").field(&Redacted(&self.0)).finish()
+	}
+}
+
+impl EventType for SearchHistory {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::SearchHistory;
+}
+impl TryFrom<ScreenReaderEvent> for SearchHistory {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::SearchHistory(text) => Ok(SearchHistory(text)),
+			_ => Err(OdiliaError::Static(
+				"Only a SearchHistory event can be converted into SearchHistory",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::NavigateLink`].
+#[derive(Debug, Clone)]
+pub struct NavigateLink {
+	pub direction: Direction,
+	pub unvisited: bool,
+}
+
+impl EventType for NavigateLink {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::NavigateLink;
+}
+impl TryFrom<ScreenReaderEvent> for NavigateLink {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::NavigateLink { direction, unvisited } => {
+				Ok(NavigateLink { direction, unvisited })
+			}
+			_ => Err(OdiliaError::Static(
+				"Only a NavigateLink event can be converted into NavigateLink",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::SkipSayAll`].
+#[derive(Debug, Clone)]
+pub struct SkipSayAll {
+	pub unit: TextUnit,
+	pub direction: Direction,
+}
+
+impl EventType for SkipSayAll {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::SkipSayAll;
+}
+impl TryFrom<ScreenReaderEvent> for SkipSayAll {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::SkipSayAll { unit, direction } => {
+				Ok(SkipSayAll { unit, direction })
+			}
+			_ => Err(OdiliaError::Static(
+				"Only a SkipSayAll event can be converted into SkipSayAll",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::EnterText`].
+#[derive(Debug, Clone)]
+pub struct EnterText(pub TextPurpose);
+
+impl EventType for EnterText {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::EnterText;
+}
+impl TryFrom<ScreenReaderEvent> for EnterText {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::EnterText(purpose) => Ok(EnterText(purpose)),
+			_ => Err(OdiliaError::Static(
+				"Only an EnterText event can be converted into EnterText",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::TextEntered`].
+#[derive(Clone)]
+pub struct TextEntered(pub String);
+
+impl std::fmt::Debug for TextEntered {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("TextEntered").field(&Redacted(&self.0)).finish()
+	}
+}
+
+impl EventType for TextEntered {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::TextEntered;
+}
+impl TryFrom<ScreenReaderEvent> for TextEntered {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::TextEntered(text) => Ok(TextEntered(text)),
+			_ => Err(OdiliaError::Static(
+				"Only a TextEntered event can be converted into TextEntered",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::UserActivity`].
+#[derive(Debug, Clone, Copy)]
+pub struct UserActivity;
+
+impl EventType for UserActivity {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::UserActivity;
+}
+impl TryFrom<ScreenReaderEvent> for UserActivity {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::UserActivity => Ok(UserActivity),
+			_ => Err(OdiliaError::Static(
+				"Only a UserActivity event can be converted into UserActivity",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::Heartbeat`].
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+	pub server: String,
+	pub interval_ms: u64,
+}
+
+impl EventType for Heartbeat {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::Heartbeat;
+}
+impl TryFrom<ScreenReaderEvent> for Heartbeat {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::Heartbeat { server, interval_ms } => {
+				Ok(Heartbeat { server, interval_ms })
+			}
+			_ => Err(OdiliaError::Static(
+				"Only a Heartbeat event can be converted into Heartbeat",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::KeyEcho`].
+#[derive(Clone)]
+pub struct KeyEcho(pub String);
+
+impl std::fmt::Debug for KeyEcho {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("KeyEcho").field(&Redacted(&self.0)).finish()
+	}
+}
+
+impl EventType for KeyEcho {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::KeyEcho;
+}
+impl TryFrom<ScreenReaderEvent> for KeyEcho {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::KeyEcho(text) => Ok(KeyEcho(text)),
+			_ => Err(OdiliaError::Static(
+				"Only a KeyEcho event can be converted into KeyEcho",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::ChangeMode`].
+#[derive(Debug, Clone)]
+pub struct ChangeMode(pub ScreenReaderMode);
+
+impl EventType for ChangeMode {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::ChangeMode;
+}
+impl TryFrom<ScreenReaderEvent> for ChangeMode {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::ChangeMode(mode) => Ok(ChangeMode(mode)),
+			_ => Err(OdiliaError::Static(
+				"Only a ChangeMode event can be converted into ChangeMode",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::CycleMode`].
+#[derive(Debug, Clone)]
+pub struct CycleMode(pub Direction);
+
+impl EventType for CycleMode {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::CycleMode;
+}
+impl TryFrom<ScreenReaderEvent> for CycleMode {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::CycleMode(direction) => Ok(CycleMode(direction)),
+			_ => Err(OdiliaError::Static(
+				"Only a CycleMode event can be converted into CycleMode",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::SayMode`].
+#[derive(Debug, Clone, Copy)]
+pub struct SayMode;
+
+impl EventType for SayMode {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::SayMode;
+}
+impl TryFrom<ScreenReaderEvent> for SayMode {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::SayMode => Ok(SayMode),
+			_ => Err(OdiliaError::Static(
+				"Only a SayMode event can be converted into SayMode",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::ReadRow`].
+#[derive(Debug, Clone, Copy)]
+pub struct ReadRow;
+
+impl EventType for ReadRow {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::ReadRow;
+}
+impl TryFrom<ScreenReaderEvent> for ReadRow {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::ReadRow => Ok(ReadRow),
+			_ => Err(OdiliaError::Static(
+				"Only a ReadRow event can be converted into ReadRow",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::ReadColumn`].
+#[derive(Debug, Clone, Copy)]
+pub struct ReadColumn;
+
+impl EventType for ReadColumn {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::ReadColumn;
+}
+impl TryFrom<ScreenReaderEvent> for ReadColumn {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::ReadColumn => Ok(ReadColumn),
+			_ => Err(OdiliaError::Static(
+				"Only a ReadColumn event can be converted into ReadColumn",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::ReadContainer`].
+#[derive(Debug, Clone, Copy)]
+pub struct ReadContainer;
+
+impl EventType for ReadContainer {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::ReadContainer;
+}
+impl TryFrom<ScreenReaderEvent> for ReadContainer {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::ReadContainer => Ok(ReadContainer),
+			_ => Err(OdiliaError::Static(
+				"Only a ReadContainer event can be converted into ReadContainer",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::Quit`].
+#[derive(Debug, Clone, Copy)]
+pub struct Quit;
+
+impl EventType for Quit {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::Quit;
+}
+impl TryFrom<ScreenReaderEvent> for Quit {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::Quit => Ok(Quit),
+			_ => Err(OdiliaError::Static(
+				"Only a Quit event can be converted into Quit",
+			)),
+		}
+	}
+}
+
+/// See [`ScreenReaderEvent::Custom`].
+#[derive(Clone)]
+pub struct Custom {
+	pub name: String,
+	pub args: Vec<String>,
+}
+
+impl std::fmt::Debug for Custom {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let args: Vec<Redacted<'_>> = self.args.iter().map(|arg| Redacted(arg)).collect();
+		f.debug_struct("Custom")
+			.field("name", &self.name)
+			.field("args", &args)
+			.finish()
+	}
+}
+
+impl EventType for Custom {
+	const ETYPE: ScreenReaderEventDiscriminants = ScreenReaderEventDiscriminants::Custom;
+}
+impl TryFrom<ScreenReaderEvent> for Custom {
+	type Error = OdiliaError;
+	fn try_from(ev: ScreenReaderEvent) -> Result<Self, Self::Error> {
+		match ev {
+			ScreenReaderEvent::Custom { name, args } => Ok(Custom { name, args }),
+			_ => Err(OdiliaError::Static(
+				"Only a Custom event can be converted into Custom",
+			)),
+		}
+	}
 }
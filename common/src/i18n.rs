@@ -0,0 +1,281 @@
+//! Translations of everything Odilia says by itself.
+//!
+//! Messages are looked up by id in catalogs compiled into Odilia, one per language, written in a subset of the [Fluent](https://projectfluent.org/) syntax: `id = text`, with `{ $name }` for arguments, and `{ $name -> [one] … *[other] … }` to pick a variant by the plural category of a number (or by its exact value, like `[0]`).
+//! Each lookup tries the user's languages in order, then English, so a missing translation falls back to English rather than to nothing.
+//!
+//! Roles and states are named in the same catalogs, as `role-<name>` and `state-<name>`, with a `.short` and a `.braille` attribute for each, so that speech and braille always agree; see [`Names`].
+
+#![allow(clippy::module_name_repetitions)]
+
+use std::{collections::HashMap, env, sync::Arc};
+
+use atspi_common::{Role, State};
+
+use crate::settings::speech::SpeechSettings;
+
+/// The catalogs compiled into Odilia, by language.
+const CATALOGS: &[(&str, &str)] = &[
+	("en", include_str!("../locales/en/odilia.ftl")),
+	("de", include_str!("../locales/de/odilia.ftl")),
+	("es", include_str!("../locales/es/odilia.ftl")),
+];
+
+/// The language every lookup ends with; its catalog has every message.
+const FALLBACK: &str = "en";
+
+/// Looks up translated messages; cheap to clone.
+#[derive(Debug, Clone)]
+pub struct Localizer {
+	/// The catalogs to try, in order.
+	chain: Arc<Vec<HashMap<String, String>>>,
+}
+
+/// How a role or state is called: in full when it is spoken, shorter when it is repeated often, and abbreviated on a braille display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Names {
+	pub long: String,
+	pub short: String,
+	pub braille: String,
+}
+
+impl Default for Localizer {
+	fn default() -> Self {
+		Localizer::new::<&str>(&[])
+	}
+}
+
+impl Localizer {
+	/// A localizer for the first of `locales` (like `de-AT`, or `de_AT.UTF-8`) Odilia has a catalog for, falling back to the next ones, and finally to English.
+	/// A locale with a region also falls back to its language without one.
+	#[must_use]
+	pub fn new<S: AsRef<str>>(locales: &[S]) -> Self {
+		let mut languages: Vec<String> = Vec::new();
+		for locale in locales {
+			let tag = normalize(locale.as_ref());
+			let language = tag.split('-').next().unwrap_or_default().to_string();
+			for candidate in [tag, language] {
+				if !candidate.is_empty() && !languages.contains(&candidate) {
+					languages.push(candidate);
+				}
+			}
+		}
+		languages.push(FALLBACK.to_string());
+		let chain = languages
+			.iter()
+			.filter_map(|language| {
+				CATALOGS.iter()
+					.find(|(name, _)| name == language)
+					.map(|(_, ftl)| parse(ftl))
+			})
+			.collect();
+		Localizer { chain: Arc::new(chain) }
+	}
+	/// A localizer for `configured` if it is set, otherwise for the locale in the environment (`LC_ALL`, `LC_MESSAGES`, then `LANG`), then `fallback`.
+	#[must_use]
+	pub fn from_env(configured: Option<&str>, fallback: &str) -> Self {
+		let mut locales: Vec<String> = configured.into_iter().map(str::to_string).collect();
+		locales.extend(["LC_ALL", "LC_MESSAGES", "LANG"]
+			.into_iter()
+			.filter_map(|var| env::var(var).ok())
+			.filter(|locale| !locale.is_empty() && locale != "C" && locale != "POSIX"));
+		locales.push(fallback.to_string());
+		Localizer::new(&locales)
+	}
+	/// A localizer for the languages given in `speech`, see [`Localizer::from_env`].
+	#[must_use]
+	pub fn from_settings(speech: &SpeechSettings) -> Self {
+		Localizer::from_env(speech.locale.as_deref(), &speech.language)
+	}
+	/// The message `id`, or the id itself if no catalog has it.
+	#[must_use]
+	pub fn get(&self, id: &str) -> String {
+		self.format(id, &[])
+	}
+	/// The names of `role`; roles missing from every catalog use the name AT-SPI gives them.
+	#[must_use]
+	pub fn role(&self, role: Role) -> Names {
+		self.names(&format!("role-{}", role.name().replace(' ', "-")), role.name())
+	}
+	/// The names of `state`; states missing from every catalog use their English name.
+	#[must_use]
+	pub fn state(&self, state: State) -> Names {
+		let name = kebab_case(&format!("{state:?}"));
+		self.names(&format!("state-{name}"), &name.replace('-', " "))
+	}
+	/// The names given by the message `id`, and its `.short` and `.braille` attributes, all from the same catalog, so that a translated name never gets an English abbreviation.
+	/// A missing short name is the long one, and a missing braille abbreviation is the short name.
+	#[must_use]
+	pub fn names(&self, id: &str, fallback: &str) -> Names {
+		let Some(catalog) = self.chain.iter().find(|catalog| catalog.contains_key(id))
+		else {
+			return Names {
+				long: fallback.to_string(),
+				short: fallback.to_string(),
+				braille: fallback.to_string(),
+			};
+		};
+		let long = catalog[id].clone();
+		let short = catalog
+			.get(&format!("{id}.short"))
+			.map_or(long.as_str(), String::as_str);
+		let braille = catalog.get(&format!("{id}.braille")).map_or(short, String::as_str);
+		Names { short: short.to_string(), braille: braille.to_string(), long: long.clone() }
+	}
+	fn lookup(&self, id: &str) -> Option<&str> {
+		self.chain
+			.iter()
+			.find_map(|catalog| catalog.get(id).map(String::as_str))
+	}
+	/// The message `id`, with each `{ $name }` replaced by its value in `args`, and each `{ $name -> … }` by the variant its value selects.
+	#[must_use]
+	pub fn format(&self, id: &str, args: &[(&str, &str)]) -> String {
+		let Some(message) = self.lookup(id) else {
+			return id.to_string();
+		};
+		let mut formatted = String::with_capacity(message.len());
+		format_pattern(message, args, &mut formatted);
+		formatted
+	}
+	/// The languages Odilia has a catalog for.
+	pub fn languages() -> impl Iterator<Item = &'static str> {
+		CATALOGS.iter().map(|(language, _)| *language)
+	}
+	/// The ids of the messages in the catalog for `language`, attributes included (as `id.attribute`); nothing if Odilia has no catalog for it.
+	#[must_use]
+	pub fn ids(language: &str) -> Vec<String> {
+		CATALOGS.iter()
+			.find(|(name, _)| *name == language)
+			.map(|(_, ftl)| parse(ftl).into_keys().collect())
+			.unwrap_or_default()
+	}
+}
+
+/// Appends `pattern` to `out`, with its placeables filled in from `args`; a placeable naming a missing argument is left as it is.
+fn format_pattern(pattern: &str, args: &[(&str, &str)], out: &mut String) {
+	let mut rest = pattern;
+	while let Some(start) = rest.find('{') {
+		out.push_str(&rest[..start]);
+		let Some(len) = placeable_len(&rest[start..]) else {
+			// an unclosed brace is only text
+			out.push_str(&rest[start..]);
+			return;
+		};
+		let placeable = &rest[start..start + len];
+		let inner = placeable[1..len - 1].trim();
+		let arg = |name: &str| {
+			args.iter()
+				.find(|(arg, _)| name.strip_prefix('$') == Some(*arg))
+				.map(|(_, value)| *value)
+		};
+		match inner.split_once("->") {
+			Some((selector, variants)) => match arg(selector.trim()) {
+				Some(value) => format_pattern(select(variants, value), args, out),
+				None => out.push_str(placeable),
+			},
+			None => match arg(inner) {
+				Some(value) => out.push_str(value),
+				None => out.push_str(placeable),
+			},
+		}
+		rest = &rest[start + len..];
+	}
+	out.push_str(rest);
+}
+
+/// The length of the placeable `text` starts with, up to and including its closing brace; `None` if it is never closed.
+fn placeable_len(text: &str) -> Option<usize> {
+	let mut depth = 0;
+	for (i, c) in text.char_indices() {
+		match c {
+			'{' => depth += 1,
+			'}' => {
+				depth -= 1;
+				if depth == 0 {
+					return Some(i + 1);
+				}
+			}
+			_ => {}
+		}
+	}
+	None
+}
+
+/// The variant of `variants` (lines like `[one] text`, with `*` before the default) which `value` selects: the one named by its exact value, else by its plural category, else the default.
+fn select<'a>(variants: &'a str, value: &str) -> &'a str {
+	let variants: Vec<(bool, &str, &str)> = variants
+		.lines()
+		.filter_map(|line| {
+			let line = line.trim();
+			let (default, line) = match line.strip_prefix('*') {
+				Some(line) => (true, line),
+				None => (false, line),
+			};
+			let (key, text) = line.strip_prefix('[')?.split_once(']')?;
+			Some((default, key.trim(), text.trim()))
+		})
+		.collect();
+	let category = plural_category(value);
+	let find = |key: &str| variants.iter().find(|(_, name, _)| *name == key);
+	find(value)
+		.or_else(|| category.and_then(find))
+		.or_else(|| variants.iter().find(|(default, _, _)| *default))
+		.map_or("", |(_, _, text)| text)
+}
+
+/// The plural category of `value`, if it is a number: `one` for 1, `other` for every other number.
+/// This is the rule of every language Odilia has a catalog for; a language with more categories, like `few`, needs a rule of its own.
+fn plural_category(value: &str) -> Option<&'static str> {
+	let number: f64 = value.parse().ok()?;
+	#[allow(clippy::float_cmp)]
+	Some(if number == 1.0 { "one" } else { "other" })
+}
+
+/// Turns a POSIX locale like `de_AT.UTF-8@euro` into a tag like `de-AT`.
+fn normalize(locale: &str) -> String {
+	locale.split(['.', '@']).next().unwrap_or_default().replace('_', "-")
+}
+
+/// `ReadOnly` to `read-only`.
+fn kebab_case(name: &str) -> String {
+	let mut kebab = String::with_capacity(name.len() + 4);
+	for (i, c) in name.chars().enumerate() {
+		if c.is_uppercase() && i > 0 {
+			kebab.push('-');
+		}
+		kebab.extend(c.to_lowercase());
+	}
+	kebab
+}
+
+/// Reads messages, and their attributes (indented lines like `.short = text`, stored as `id.short`).
+/// Other indented lines continue the message or attribute above them, like the variants of a selector.
+fn parse(ftl: &str) -> HashMap<String, String> {
+	let mut messages: HashMap<String, String> = HashMap::new();
+	let mut current = "";
+	let mut last: Option<String> = None;
+	for line in ftl.lines() {
+		let trimmed = line.trim();
+		if trimmed.is_empty() || trimmed.starts_with('#') {
+			continue;
+		}
+		if !line.starts_with(char::is_whitespace) {
+			let Some((id, message)) = line.split_once('=') else {
+				continue;
+			};
+			current = id.trim();
+			messages.insert(current.to_string(), message.trim().to_string());
+			last = Some(current.to_string());
+		} else if let Some((attribute, message)) = trimmed
+			.strip_prefix('.')
+			.and_then(|attribute| attribute.split_once('='))
+		{
+			let id = format!("{current}.{}", attribute.trim());
+			messages.insert(id.clone(), message.trim().to_string());
+			last = Some(id);
+		} else if let Some(message) = last.as_ref().and_then(|id| messages.get_mut(id)) {
+			message.push('\n');
+			message.push_str(trimmed);
+		}
+	}
+	messages
+}
@@ -0,0 +1,144 @@
+//! Interned strings, for the bus names and object paths which make up every cache key.
+//!
+//! The same few senders and paths are cloned over and over by the cache, events and handlers.
+//! An [`Atom`] shares a single allocation between every copy of an equal string, so cloning one never allocates, and comparing or hashing one is as cheap as for a pointer.
+//! Atoms are not `Copy`, since the table has to know when the last one of a string is dropped: applications like Firefox mint new object paths for as long as they run, and those must not pile up.
+
+#![allow(clippy::module_name_repetitions)]
+
+use std::{
+	collections::{hash_map::DefaultHasher, HashSet},
+	fmt,
+	hash::{Hash, Hasher},
+	ops::Deref,
+	sync::{Arc, LazyLock, PoisonError, RwLock},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// How many parts the table is split into, each with its own lock, so that interning on several threads rarely waits.
+const SHARDS: usize = 16;
+
+/// Every string currently interned, split into [`SHARDS`] by the hash of the string.
+/// Almost every string is interned again and again, so looking one up only takes a read lock.
+static TABLE: LazyLock<[RwLock<Shard>; SHARDS]> =
+	LazyLock::new(|| std::array::from_fn(|_| RwLock::default()));
+
+/// A part of the table.
+/// Entries are only removed once no [`Atom`] refers to them anymore, so that paths of accessibles which have long gone away do not pile up over a long session.
+#[derive(Default)]
+struct Shard {
+	atoms: HashSet<Arc<str>>,
+	/// The shard is pruned whenever it has grown to twice this size.
+	pruned_at: usize,
+}
+
+impl Shard {
+	const MIN_PRUNE_SIZE: usize = 64;
+
+	fn insert(&mut self, s: &str) -> Arc<str> {
+		// another thread may have interned it while no lock was held
+		if let Some(atom) = self.atoms.get(s) {
+			return Arc::clone(atom);
+		}
+		if self.atoms.len() >= (self.pruned_at * 2).max(Self::MIN_PRUNE_SIZE) {
+			// only the table itself holds these
+			self.atoms.retain(|atom| Arc::strong_count(atom) > 1);
+			self.pruned_at = self.atoms.len();
+		}
+		let atom: Arc<str> = Arc::from(s);
+		self.atoms.insert(Arc::clone(&atom));
+		atom
+	}
+}
+
+fn shard(s: &str) -> &'static RwLock<Shard> {
+	let mut hasher = DefaultHasher::new();
+	s.hash(&mut hasher);
+	// the remainder is below `SHARDS`
+	#[allow(clippy::cast_possible_truncation)]
+	let index = (hasher.finish() % SHARDS as u64) as usize;
+	&TABLE[index]
+}
+
+fn intern(s: &str) -> Arc<str> {
+	let shard = shard(s);
+	if let Some(atom) = shard.read().unwrap_or_else(PoisonError::into_inner).atoms.get(s) {
+		return Arc::clone(atom);
+	}
+	shard.write().unwrap_or_else(PoisonError::into_inner).insert(s)
+}
+
+/// An interned, immutable string.
+/// Two atoms are equal exactly when they point to the same allocation, since equal strings are always interned to the same one.
+#[derive(Clone)]
+pub struct Atom(Arc<str>);
+
+impl Atom {
+	/// Interns `s`, reusing the existing allocation if an equal string is already interned.
+	#[must_use]
+	pub fn new(s: &str) -> Self {
+		Atom(intern(s))
+	}
+	#[must_use]
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+}
+
+impl PartialEq for Atom {
+	fn eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.0, &other.0)
+	}
+}
+impl Eq for Atom {}
+impl Hash for Atom {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		std::ptr::hash(Arc::as_ptr(&self.0).cast::<u8>(), state);
+	}
+}
+
+impl Deref for Atom {
+	type Target = str;
+	fn deref(&self) -> &str {
+		&self.0
+	}
+}
+impl AsRef<str> for Atom {
+	fn as_ref(&self) -> &str {
+		&self.0
+	}
+}
+impl fmt::Debug for Atom {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Debug::fmt(&*self.0, f)
+	}
+}
+impl fmt::Display for Atom {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&*self.0, f)
+	}
+}
+
+impl From<&str> for Atom {
+	fn from(s: &str) -> Self {
+		Atom::new(s)
+	}
+}
+impl From<String> for Atom {
+	fn from(s: String) -> Self {
+		Atom::new(&s)
+	}
+}
+
+impl Serialize for Atom {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.0)
+	}
+}
+impl<'de> Deserialize<'de> for Atom {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+		Ok(Atom::new(&s))
+	}
+}
@@ -14,7 +14,10 @@ pub mod command;
 pub mod elements;
 pub mod errors;
 pub mod events;
+pub mod i18n;
+pub mod intern;
 pub mod modes;
+pub mod privacy;
 pub mod result;
 pub mod settings;
 pub mod types;
@@ -0,0 +1,44 @@
+//! Keeping what the user reads and types out of logs, speech captures and crash reports.
+//!
+//! Whether text is redacted is decided once at startup, from `log.redact_text`; every place which logs user text wraps it in [`Redacted`].
+
+use std::{
+	fmt,
+	sync::atomic::{AtomicBool, Ordering},
+};
+
+static REDACTING: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether user text is redacted from now on.
+pub fn set_redacting(redacting: bool) {
+	REDACTING.store(redacting, Ordering::Relaxed);
+}
+
+/// Whether user text is redacted.
+pub fn redacting() -> bool {
+	REDACTING.load(Ordering::Relaxed)
+}
+
+/// Text the user read or typed, which is shown as only its length while redacting, like `<12 characters>`.
+#[derive(Clone, Copy)]
+pub struct Redacted<'a>(pub &'a str);
+
+impl fmt::Debug for Redacted<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if redacting() {
+			write!(f, "<{} characters>", self.0.chars().count())
+		} else {
+			fmt::Debug::fmt(self.0, f)
+		}
+	}
+}
+
+impl fmt::Display for Redacted<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if redacting() {
+			write!(f, "<{} characters>", self.0.chars().count())
+		} else {
+			f.write_str(self.0)
+		}
+	}
+}
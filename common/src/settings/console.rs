@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+///structure for the options of console mode, in which odilia reads a linux virtual terminal (like the one on ctrl+alt+f3) instead of applications through at-spi
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct ConsoleSettings {
+	///when to read the console instead of the desktop
+	pub mode: ConsoleMode,
+	///the virtual console screen odilia reads; `/dev/vcsa` is always the one currently shown
+	/// reading it usually needs membership in the `tty` group
+	pub device: String,
+	///how often the console is checked for new output, in milliseconds
+	pub poll_ms: u64,
+}
+impl Default for ConsoleSettings {
+	fn default() -> Self {
+		Self { mode: ConsoleMode::Auto, device: "/dev/vcsa".to_string(), poll_ms: 100 }
+	}
+}
+
+///when odilia reads the console
+#[allow(clippy::module_name_repetitions)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConsoleMode {
+	///when odilia is started on a virtual console outside of a graphical session (neither `WAYLAND_DISPLAY` nor `DISPLAY` is set), or at-spi is not available
+	#[default]
+	Auto,
+	///always, even in a graphical session
+	Always,
+	///never; odilia then needs at-spi to start
+	Never,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+///structure for the options controlling what odilia does when it crashes
+/// a crash report is always written to the xdg state directory, and odilia always tries to announce the crash
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct CrashSettings {
+	///whether odilia should start itself again after a crash
+	/// odilia gives up after a few crashes in a row, so that a crash on startup can not loop forever
+	pub relaunch: bool,
+}
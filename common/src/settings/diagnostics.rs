@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+///structure for the options of event diagnostics, which find out which applications send odilia the most events
+/// diagnostics are off until turned on with the `eventDiagnostics` input event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct DiagnosticsSettings {
+	///how many seconds of events are counted; the busiest applications are also reported this often
+	pub window_secs: u64,
+	///how many of the busiest applications and interfaces are reported
+	pub top: usize,
+}
+impl Default for DiagnosticsSettings {
+	fn default() -> Self {
+		Self { window_secs: 10, top: 3 }
+	}
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+///structure for the options controlling earcons, the short tones odilia plays instead of (or as well as) speaking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct EarconSettings {
+	pub enabled: bool,
+	///the command used to play a sound file, followed by its arguments; the path of the file is appended
+	pub player: Vec<String>,
+	///from 0.0 (silent) to 1.0
+	pub volume: f32,
+	///how often, in milliseconds, the busy earcon repeats while the document in the focused application loads
+	pub busy_interval_ms: u64,
+}
+impl Default for EarconSettings {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			player: vec!["paplay".into()],
+			volume: 0.5,
+			busy_interval_ms: 2000,
+		}
+	}
+}
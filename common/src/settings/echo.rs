@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+///structure for the options controlling how odilia echoes what the user types, as reported by the input server
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct EchoSettings {
+	///what is echoed, unless the focused application has its own entry in `apps`
+	pub mode: EchoMode,
+	///the echo mode for particular applications, by the name they report, for example `{ "gnome-terminal-server" = "None" }`
+	pub apps: HashMap<String, EchoMode>,
+}
+
+///what is spoken as the user types
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub enum EchoMode {
+	///every character, as it is typed
+	#[default]
+	Character,
+	///every word, once it is finished with a space or punctuation
+	Word,
+	///both every character, and every finished word
+	CharacterAndWord,
+	///nothing
+	None,
+}
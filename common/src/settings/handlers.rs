@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+///structure for the options controlling which of odilia's event handlers run
+/// handlers are named after the function implementing them, for example `caret_moved`; the running instance lists them over dbus
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct HandlerSettings {
+	///the handlers which should not run at all, for example `["caret_moved"]` to stop announcing caret movements
+	pub disabled: Vec<String>,
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+///structure for the options controlling what happens while the user is away from the computer
+/// odilia considers the user away once the session is marked idle (usually when the screen is blanked) for long enough; announcements are then held back, and summarised when they return
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct IdleSettings {
+	///whether announcements are held back while the user is away at all
+	pub enabled: bool,
+	///how long the session has to be idle before the user counts as away, in seconds
+	pub away_after_secs: u64,
+	///how many announcements are kept while away; any more are only counted in the summary
+	pub max_held: usize,
+	///whether the held announcements are read out after the summary, rather than only counted
+	pub read_held: bool,
+	///whether the computer is kept from going idle, and so from suspending or locking the screen, while odilia reads continuously
+	pub inhibit_while_reading: bool,
+}
+impl Default for IdleSettings {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			away_after_secs: 30,
+			max_held: 20,
+			read_held: true,
+			inhibit_while_reading: true,
+		}
+	}
+}
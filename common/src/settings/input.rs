@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::{Direction, ScreenReaderEvent};
+
+///structure for all the input related configuration options available in odilia
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct InputSettings {
+	///options for driving odilia from another machine, see [`RemoteSettings`]
+	pub remote: RemoteSettings,
+	///whether quitting has to be confirmed by asking for it again, so that odilia is not stopped by an accidental key press
+	pub confirm_quit: bool,
+	///how long after the first request to quit the second one still counts, in milliseconds
+	pub confirm_quit_ms: u64,
+	///how many heartbeats in a row an input server can miss before it is reported as no longer responding
+	pub heartbeat_missed: u32,
+	///a command run when an input server stops responding, followed by its arguments; the name of the server is appended
+	/// for example `["systemctl", "--user", "restart"]`, if servers are named after their service units; empty to only announce it
+	pub restart_unresponsive: Vec<String>,
+	///programs which may send events to the input socket even though they run as another user, by absolute path, like `["/usr/libexec/odilia-braille-helper"]`
+	/// processes running as the same user as odilia are always allowed; any other connection is refused, and announced
+	/// this is best-effort: the program of another user's process can often not be found out (for example, without `CAP_SYS_PTRACE`), and such a process is refused
+	pub allowed_programs: Vec<String>,
+	///where odilia gets key presses from, besides input servers, see [`InputMethod`]
+	pub method: InputMethod,
+	///the shortcuts registered with the desktop when `method` is `Portal`, by id
+	pub shortcuts: HashMap<String, Shortcut>,
+}
+impl Default for InputSettings {
+	fn default() -> Self {
+		Self {
+			remote: RemoteSettings::default(),
+			confirm_quit: true,
+			confirm_quit_ms: 3000,
+			heartbeat_missed: 3,
+			restart_unresponsive: Vec::new(),
+			allowed_programs: Vec::new(),
+			method: InputMethod::default(),
+			shortcuts: default_shortcuts(),
+		}
+	}
+}
+
+///where odilia gets key presses from; events from input servers are always accepted
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub enum InputMethod {
+	///only from input servers, like one grabbing the keyboard
+	#[default]
+	Socket,
+	///also from the global shortcuts portal of the desktop, which needs no special permissions, but only knows whole shortcuts rather than every key press
+	/// this needs a desktop with a portal implementing `org.freedesktop.portal.GlobalShortcuts`, which may ask the user to confirm the shortcuts first
+	Portal,
+}
+
+///a shortcut registered with the global shortcuts portal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shortcut {
+	///what the shortcut does, as the desktop shows it to the user
+	pub description: String,
+	///the keys asked for, like `LOGO+ALT+s`; the desktop may assign others, or let the user change them
+	pub trigger: String,
+	///the event sent when the shortcut is pressed
+	pub event: ScreenReaderEvent,
+	///the modes the shortcut does something in, like `["Browse"]`; in every mode if empty
+	/// every shortcut is bound once, in every mode, since the desktop may ask the user to confirm each bind; it does not pass bound keys on to the focused application, even in modes the shortcut does nothing in, so keys applications need, like single letters, should not be used
+	#[serde(default)]
+	pub modes: Vec<String>,
+}
+
+fn default_shortcuts() -> HashMap<String, Shortcut> {
+	let shortcut = |description: &str, trigger: &str, event| Shortcut {
+		description: description.to_string(),
+		trigger: trigger.to_string(),
+		event,
+		modes: Vec::new(),
+	};
+	// single letters, like input servers use while browsing, would be kept from applications in every mode
+	HashMap::from([
+		(
+			"stop-speech".to_string(),
+			shortcut("Stop speech", "LOGO+ALT+x", ScreenReaderEvent::StopSpeech),
+		),
+		(
+			"next-mode".to_string(),
+			shortcut(
+				"Switch to the next mode",
+				"LOGO+ALT+m",
+				ScreenReaderEvent::CycleMode(Direction::Forward),
+			),
+		),
+		(
+			"read-container".to_string(),
+			shortcut(
+				"Read to the end of the container",
+				"LOGO+ALT+r",
+				ScreenReaderEvent::ReadContainer,
+			),
+		),
+		(
+			"say-location".to_string(),
+			shortcut(
+				"Say where the focus is",
+				"LOGO+ALT+l",
+				ScreenReaderEvent::SayLocation,
+			),
+		),
+		(
+			"quit".to_string(),
+			shortcut("Quit Odilia", "LOGO+ALT+q", ScreenReaderEvent::Quit),
+		),
+		(
+			"next-link".to_string(),
+			shortcut(
+				"Move to the next link",
+				"LOGO+ALT+k",
+				ScreenReaderEvent::NavigateLink {
+					direction: Direction::Forward,
+					unvisited: false,
+				},
+			),
+		),
+		(
+			"previous-link".to_string(),
+			shortcut(
+				"Move to the previous link",
+				"LOGO+ALT+SHIFT+k",
+				ScreenReaderEvent::NavigateLink {
+					direction: Direction::Backward,
+					unvisited: false,
+				},
+			),
+		),
+		(
+			"next-unvisited-link".to_string(),
+			shortcut(
+				"Move to the next unvisited link",
+				"LOGO+ALT+u",
+				ScreenReaderEvent::NavigateLink {
+					direction: Direction::Forward,
+					unvisited: true,
+				},
+			),
+		),
+	])
+}
+
+///configuration of the TCP listener, which accepts the same events as the local input socket
+/// this is meant for testers, remote assistants and kiosk setups, where the person controlling odilia is not sitting at the machine running it
+/// it is disabled by default, and will refuse to start without a shared secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct RemoteSettings {
+	///whether the TCP listener should be started at all
+	pub enabled: bool,
+	///the address and port to listen on
+	/// this defaults to the loopback interface, so that the port can be forwarded over ssh without exposing it to the network
+	pub address: String,
+	///the secret every client must send as the first line of a connection, before the event itself
+	pub secret: String,
+}
+impl Default for RemoteSettings {
+	fn default() -> Self {
+		Self { enabled: false, address: "127.0.0.1:7379".into(), secret: String::new() }
+	}
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+///structure for the options controlling announcements about the keyboard itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct KeyboardSettings {
+	///say when caps lock, num lock or scroll lock is turned on or off
+	/// this matters especially since caps lock is usually also the odilia key, which makes its real state easy to lose track of
+	pub announce_locks: bool,
+	///how often the lock keys are checked, in milliseconds
+	pub locks_poll_ms: u64,
+	///say the name of the keyboard layout when it is switched
+	pub announce_layout: bool,
+	///say the volume of the default sound output when it changes, like with the volume keys, and when it is muted; this needs `pactl`, and says so on startup if it is missing
+	pub announce_volume: bool,
+}
+impl Default for KeyboardSettings {
+	fn default() -> Self {
+		Self {
+			announce_locks: true,
+			locks_poll_ms: 250,
+			announce_layout: true,
+			announce_volume: true,
+		}
+	}
+}
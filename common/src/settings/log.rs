@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 ///structure used for all the configurable options related to logging
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(clippy::module_name_repetitions)]
 pub struct LogSettings {
 	///the logging level this session should output at
@@ -13,6 +13,18 @@ pub struct LogSettings {
 	///the place where odilia should output its logs
 	/// the values possible include tty, file and syslog
 	pub logger: LoggingKind,
+	///leave out the text the user reads and types from logs, speech captures and crash reports, keeping only what kind of object or event it came from
+	/// this is on by default in release builds; turn it off (or start odilia with `--log-text`) when debugging something that depends on the text itself
+	pub redact_text: bool,
+	///when the log file is moved aside and a new one is started; only used when logging to a file
+	pub rotation: LogRotation,
+	///with size based rotation, how large the log file can get before it is rotated, in megabytes
+	pub max_file_mb: u64,
+	///how large the rotated log files can get altogether, in megabytes; the oldest are removed beyond that
+	pub max_total_mb: u64,
+	///where spans are exported to with OTLP over gRPC, like `http://localhost:4317`, to follow the latency of an event through the whole pipeline in a tool like Jaeger
+	/// this is as well as the logger, not instead of it, and only works when odilia is built with the `otlp` feature
+	pub otlp_endpoint: Option<String>,
 }
 impl Default for LogSettings {
 	fn default() -> Self {
@@ -23,12 +35,20 @@ impl Default for LogSettings {
 			.place_state_file("odilia.log")
 			.expect("unable to place log file");
 
-		Self { level: "info".to_owned(), logger: LoggingKind::File(log_path) }
+		Self {
+			level: "info".to_owned(),
+			logger: LoggingKind::File(log_path),
+			redact_text: !cfg!(debug_assertions),
+			rotation: LogRotation::Size,
+			max_file_mb: 10,
+			max_total_mb: 50,
+			otlp_endpoint: None,
+		}
 	}
 }
 
 ///the place where odilia should output its logs
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub enum LoggingKind {
 	///a file where the log messages should be written
 	/// the path can be both absolute and relative to the current working directory
@@ -38,5 +58,20 @@ pub enum LoggingKind {
 	Tty,
 	///the logs are sent to systemd-journald, as long as the target architecture supports it
 	/// if that's not the case, this option does nothing
+	/// every field of a message, and of the spans it happened in, is its own journal field, prefixed with `ODILIA_`
+	/// for example, `journalctl --user -u odilia ODILIA_TASK=ssip` shows only what the speech task logged
 	Syslog,
 }
+
+///when the log file is rotated: moved aside, with the time it was rotated appended to its name, like `odilia.log.1700000000`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub enum LogRotation {
+	///never; the log file is started over every time odilia starts
+	Never,
+	///at midnight (UTC)
+	Daily,
+	///once it is larger than `max_file_mb`
+	#[default]
+	Size,
+}
@@ -1,16 +1,63 @@
+pub mod console;
+pub mod crash;
+pub mod diagnostics;
+pub mod earcons;
+pub mod echo;
+pub mod handlers;
+pub mod idle;
+pub mod input;
+pub mod keyboard;
 pub mod log;
+pub mod modes;
+pub mod navigation;
 pub mod speech;
+pub mod tables;
+pub mod tray;
 
+use console::ConsoleSettings;
+use crash::CrashSettings;
+use diagnostics::DiagnosticsSettings;
+use earcons::EarconSettings;
+use echo::EchoSettings;
+use handlers::HandlerSettings;
+use idle::IdleSettings;
+use input::InputSettings;
+use keyboard::KeyboardSettings;
 use log::LogSettings;
+use modes::ModeSettings;
+use navigation::NavigationSettings;
 use speech::SpeechSettings;
+use tables::TableSettings;
+use tray::TraySettings;
+
+use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+///settings for particular applications, bundled with odilia: terminals do not echo keys, browsers start in browse mode
+/// this is toml for the same sections as the configuration file, to be merged beneath it, so that users can replace any entry
+pub const APP_DEFAULTS: &str = include_str!("../../data/app-defaults.toml");
+
 ///type representing a *read-only* view of the odilia screenreader configuration
 /// this type should only be obtained as a result of parsing odilia's configuration files, as it containes types for each section responsible for controlling various parts of the screenreader
 /// the only way this config should change is if the configuration file changes, in which case the entire view will be replaced to reflect the fact
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ApplicationConfig {
 	pub speech: SpeechSettings,
 	pub log: LogSettings,
+	pub input: InputSettings,
+	pub keyboard: KeyboardSettings,
+	pub crash: CrashSettings,
+	pub diagnostics: DiagnosticsSettings,
+	pub earcons: EarconSettings,
+	pub echo: EchoSettings,
+	pub handlers: HandlerSettings,
+	pub idle: IdleSettings,
+	pub modes: ModeSettings,
+	pub navigation: NavigationSettings,
+	pub tables: TableSettings,
+	pub console: ConsoleSettings,
+	pub tray: TraySettings,
+	///how to say the names of applications, by the name they report (for example `"org.example.Editor" = "Editor"`); these are added to, or replace, the names bundled with odilia
+	pub app_names: HashMap<String, String>,
 }
@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+///structure for the modes odilia can be in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct ModeSettings {
+	///the modes which can be switched to, in the order they are cycled through; odilia starts in the first
+	/// besides `Focus`, `Browse` and `Review`, any name can be added (for example `Numpad`), for input servers to bind keys in
+	pub names: Vec<String>,
+	///the mode to switch to when focus moves into particular applications, by the name they report, for example `{ "firefox" = "Browse" }`
+	pub apps: HashMap<String, String>,
+}
+impl Default for ModeSettings {
+	fn default() -> Self {
+		Self {
+			names: vec![
+				"Focus".to_string(),
+				"Browse".to_string(),
+				"Review".to_string(),
+			],
+			apps: HashMap::new(),
+		}
+	}
+}
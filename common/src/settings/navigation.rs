@@ -0,0 +1,35 @@
+use atspi_common::ScrollType;
+use serde::{Deserialize, Serialize};
+
+///structure for the options of moving through objects the user can not see on screen
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct NavigationSettings {
+	///where an object out of view is scrolled to, when odilia moves to it
+	pub scroll_anchor: ScrollAnchor,
+}
+
+///where in its window an object is scrolled to, to bring it into view
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScrollAnchor {
+	///its top left corner to the top left of the window
+	#[default]
+	TopLeft,
+	///its top edge to the top of the window
+	Top,
+	///its bottom edge to the bottom of the window
+	Bottom,
+	///wherever the application chooses, usually the least scrolling which brings it into view
+	Anywhere,
+}
+
+impl From<ScrollAnchor> for ScrollType {
+	fn from(anchor: ScrollAnchor) -> Self {
+		match anchor {
+			ScrollAnchor::TopLeft => ScrollType::TopLeft,
+			ScrollAnchor::Top => ScrollType::TopEdge,
+			ScrollAnchor::Bottom => ScrollType::BottomEdge,
+			ScrollAnchor::Anywhere => ScrollType::Anywhere,
+		}
+	}
+}
@@ -1,6 +1,8 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 ///structure for all the speech related configuration options available in odilia
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(clippy::module_name_repetitions)]
 pub struct SpeechSettings {
 	pub rate: i8,
@@ -8,8 +10,44 @@ pub struct SpeechSettings {
 	pub volume: i8,
 	pub module: String,
 	pub language: String,
+	/// The languages switched between with the `CycleLanguage` command, like `["en-US", "de-DE"]`, for reading text in several languages which are not told apart automatically.
+	pub languages: Vec<String>,
+	/// The language Odilia's own messages are spoken in, like `de-AT`; if unset, the one from the environment (`LANG`) is used, then `language`.
+	pub locale: Option<String>,
 	pub person: String,
 	pub punctuation: PunctuationSpellingMode,
+	/// Caret movements on the same object this many milliseconds apart are spoken once, for the final position; 0 speaks every one.
+	pub caret_coalesce_ms: u64,
+	/// When the value of a slider, spin button or scroll bar changes again within this many milliseconds, like while a key is held, the previous value is cut off rather than finished.
+	pub value_repeat_ms: u64,
+	/// What is said when a page (or other document) finishes loading in the application being used.
+	pub page_load: PageLoad,
+	/// Stop reading when the user presses any key; important messages are never interrupted.
+	pub interrupt_on_activity: bool,
+	/// Speak the name of the new mode when it changes; when this is off, only an earcon is played (unless earcons are disabled too).
+	pub announce_mode_changes: bool,
+	/// Speak each stage of startup (speech, the accessibility bus, the input socket) as it becomes ready; when this is off, only an earcon is played (unless earcons are disabled too). A stage which fails is always spoken.
+	pub announce_startup: bool,
+	/// How much is said about an accessible besides its name, role and states.
+	pub verbosity: Verbosity,
+	/// What is said about an accessible, by role name (or `default`), for example `{ "push button" = "{name}, button, {states}" }`.
+	/// Placeholders are `{name}`, `{role}`, `{states}`, `{value}`, `{position}` and `{shortcut}`.
+	pub templates: HashMap<String, String>,
+	/// Which fields are said about an accessible, in order, by the name of the mode Odilia is in, for example `{ Browse = ["Role", "Name"], Focus = ["Name", "Value", "Role", "States", "Position"] }`.
+	/// This takes the place of the built-in templates and the `default` one, but not of templates for a particular role.
+	pub fields: HashMap<String, Vec<SpokenField>>,
+	/// How long focus has to stay on an object, in milliseconds, before its description (and at high verbosity, its help text) is spoken, so that tabbing quickly is not slowed down.
+	pub description_delay_ms: u64,
+	/// Key presses closer together than this many milliseconds count as typing rapidly; notifications and other speech unrelated to what the user is doing then wait until they pause.
+	pub typing_gap_ms: u64,
+	/// How long typing has to stop, in milliseconds, before speech held back by it is spoken.
+	pub typing_pause_ms: u64,
+	/// The longest speech is held back while typing, in milliseconds, even if the user never pauses.
+	pub typing_max_hold_ms: u64,
+	/// How many of the last utterances are kept, to go back through with the review commands; 0 keeps none.
+	pub history_size: usize,
+	/// Where Odilia's messages go: spoken, shown on a braille display, both, or neither; `CycleOutput` switches between the first three while Odilia runs.
+	pub output: Output,
 }
 impl Default for SpeechSettings {
 	fn default() -> Self {
@@ -19,13 +57,113 @@ impl Default for SpeechSettings {
 			volume: 100,
 			module: "espeak-ng".into(),
 			language: "en-US".into(),
+			languages: Vec::new(),
+			locale: None,
 			person: "English (America)+Max".into(),
 			punctuation: PunctuationSpellingMode::Some,
+			caret_coalesce_ms: 50,
+			value_repeat_ms: 300,
+			page_load: PageLoad::default(),
+			interrupt_on_activity: true,
+			announce_mode_changes: true,
+			announce_startup: false,
+			verbosity: Verbosity::default(),
+			templates: HashMap::new(),
+			fields: HashMap::new(),
+			description_delay_ms: 1000,
+			typing_gap_ms: 300,
+			typing_pause_ms: 700,
+			typing_max_hold_ms: 5000,
+			history_size: 500,
+			output: Output::default(),
 		}
 	}
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+///how much is said about an accessible besides its name, role and states
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Verbosity {
+	///nothing more
+	Low,
+	///its keyboard shortcut, like "Control plus S", and its description
+	#[default]
+	Medium,
+	///its keyboard shortcut, the key which activates it from its menu, its description and its help text
+	High,
+}
+
+///where Odilia's messages go
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Output {
+	///they are spoken
+	#[default]
+	Speech,
+	///they are shown on a braille display, and not spoken; this needs `odilia-braille-input` running, which shows them
+	Braille,
+	///they are spoken, and shown on a braille display
+	Both,
+	///they are neither spoken nor shown
+	Neither,
+}
+
+impl Output {
+	///whether messages are spoken
+	#[must_use]
+	pub fn speaks(self) -> bool {
+		matches!(self, Output::Speech | Output::Both)
+	}
+	///whether messages are shown in braille
+	#[must_use]
+	pub fn brailles(self) -> bool {
+		matches!(self, Output::Braille | Output::Both)
+	}
+}
+
+///something said about an accessible, as listed in `speech.fields`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpokenField {
+	///its name, or its label
+	Name,
+	///what kind of object it is, like "push button"
+	Role,
+	///the states worth mentioning, like "checked"
+	States,
+	///the text of an editable object
+	Value,
+	///like "3 of 5", in lists, menus, tabs and trees
+	Position,
+	///its keyboard shortcut, as much of it as `verbosity` asks for
+	Shortcut,
+}
+
+impl SpokenField {
+	///the placeholder for this field in templates, like `{name}`
+	#[must_use]
+	pub fn placeholder(self) -> &'static str {
+		match self {
+			SpokenField::Name => "{name}",
+			SpokenField::Role => "{role}",
+			SpokenField::States => "{states}",
+			SpokenField::Value => "{value}",
+			SpokenField::Position => "{position}",
+			SpokenField::Shortcut => "{shortcut}",
+		}
+	}
+}
+
+///what is said when a document finishes loading; reading stops as soon as the user presses a key
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PageLoad {
+	///only that it has loaded
+	#[default]
+	Announce,
+	///its title, and how many headings and links it has
+	Summary,
+	///the whole document, from the top
+	Read,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PunctuationSpellingMode {
 	Some,
 	Most,
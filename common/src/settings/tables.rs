@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+///structure for the options controlling how tables and grids are read
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct TableSettings {
+	///read the whole row when moving into another row of a table, rather than only the cell moved to
+	pub read_whole_row: bool,
+}
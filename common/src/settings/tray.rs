@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+///structure for the options of odilia's icon in the panel's tray
+/// the icon shows the mode odilia is in, and has a menu of common commands; it needs a panel supporting `StatusNotifierItem`s
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct TraySettings {
+	///whether to show the icon at all; off by default
+	pub enabled: bool,
+}
@@ -0,0 +1,55 @@
+//! Every catalog translates every message, and messages which depend on a number pick the variant for its plural category, in every language.
+
+use odilia_common::i18n::Localizer;
+
+fn summary(language: &str, headings: &str, links: &str) -> String {
+	Localizer::new(&[language]).format(
+		"page-summary",
+		&[("title", "Inbox"), ("headings", headings), ("links", links)],
+	)
+}
+
+#[test]
+fn one_of_something_is_singular() {
+	assert_eq!(summary("en", "1", "1"), "Inbox: 1 heading, 1 link");
+	assert_eq!(summary("de", "1", "1"), "Inbox: 1 Überschrift, 1 Link");
+	assert_eq!(summary("es", "1", "1"), "Inbox: 1 encabezado, 1 enlace");
+}
+
+#[test]
+fn none_or_several_are_plural() {
+	assert_eq!(summary("en", "0", "12"), "Inbox: 0 headings, 12 links");
+	assert_eq!(summary("de", "0", "12"), "Inbox: 0 Überschriften, 12 Links");
+	assert_eq!(summary("es", "0", "12"), "Inbox: 0 encabezados, 12 enlaces");
+}
+
+#[test]
+fn every_variable_is_filled_in_once() {
+	let l10n = Localizer::new(&["en"]);
+	let loaded = l10n.format("finished-loading", &[("headings", "2"), ("links", "1")]);
+	assert_eq!(loaded, "Finished loading, 2 headings, 1 link");
+	// arguments are not read as placeables themselves
+	let frame = l10n.format("frame", &[("title", "{ $title }")]);
+	assert_eq!(frame, "frame: { $title }");
+}
+
+#[test]
+fn a_missing_argument_is_left_as_it_is() {
+	let l10n = Localizer::new(&["en"]);
+	assert_eq!(l10n.format("frame", &[]), "frame: { $title }");
+}
+
+#[test]
+fn every_catalog_has_every_message() {
+	let english = Localizer::ids("en");
+	for language in Localizer::languages() {
+		let ids = Localizer::ids(language);
+		// attributes are optional: a missing abbreviation falls back to the translated name
+		let mut missing: Vec<_> = english
+			.iter()
+			.filter(|id| !id.contains('.') && !ids.contains(id))
+			.collect();
+		missing.sort();
+		assert!(missing.is_empty(), "the {language} catalog lacks {missing:?}");
+	}
+}
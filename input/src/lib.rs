@@ -8,17 +8,29 @@
 )]
 #![allow(clippy::multiple_crate_versions)]
 
-use eyre::Context;
+use eyre::{bail, Context};
+use listenfd::ListenFd;
 use nix::unistd::Uid;
-use odilia_common::events::ScreenReaderEvent;
+use odilia_common::{
+	events::ScreenReaderEvent, privacy::Redacted, settings::input::RemoteSettings,
+};
+use serde::{Deserialize, Serialize};
 use std::{
 	env,
-	path::Path,
-	process::{exit, id},
-	time::{SystemTime, UNIX_EPOCH},
+	os::unix::fs::MetadataExt,
+	path::{Path, PathBuf},
+	process::exit,
+	sync::Arc,
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+	fs,
+	io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader},
+	net::{TcpListener, TcpStream, UnixListener, UnixStream},
+	sync::mpsc::{self, Sender},
+	task::JoinSet,
+	time::timeout,
 };
-use sysinfo::{ProcessExt, System, SystemExt};
-use tokio::{fs, io::AsyncReadExt, net::UnixListener, sync::mpsc::Sender};
 use tokio_util::sync::CancellationToken;
 
 #[tracing::instrument(ret)]
@@ -50,18 +62,148 @@ fn get_log_file_name() -> String {
 	}
 }
 
-/// Receives [`odilia_common::events::ScreenReaderEvent`] structs, then sends them over the `event_sender` socket.
+/// A process which connected to the input socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Peer {
+	pub uid: u32,
+	pub pid: Option<i32>,
+	/// The program the process runs, if it could be found out.
+	pub exe: Option<PathBuf>,
+}
+
+impl Peer {
+	fn of(socket: &UnixStream) -> std::io::Result<Self> {
+		let cred = socket.peer_cred()?;
+		let exe = cred.pid().and_then(|pid| exe_of(pid, cred.uid()));
+		Ok(Peer { uid: cred.uid(), pid: cred.pid(), exe })
+	}
+	/// Whether this process may send events: it has to run as the same user as Odilia, or be one of the `allowed` programs.
+	/// The user is known for certain, from the socket itself; the program only as far as [`exe_of`] can tell, so `allowed` is best-effort, and a process whose program is not known is refused.
+	fn is_allowed(&self, allowed: &[String]) -> bool {
+		self.uid == Uid::current().as_raw()
+			|| self.exe.as_ref().is_some_and(|exe| {
+				allowed.iter().any(|path| exe == Path::new(path))
+			})
+	}
+}
+
+/// The program process `pid` runs, as long as it runs as `uid`.
+/// This is `None` whenever in doubt: `/proc/<pid>/exe` can usually not be read for processes of other users, and the process may exit and its pid be reused by another one, between connecting and this being read.
+fn exe_of(pid: i32, uid: u32) -> Option<PathBuf> {
+	let dir = PathBuf::from(format!("/proc/{pid}"));
+	let owner = |dir: &Path| std::fs::metadata(dir).ok().map(|meta| meta.uid());
+	if owner(&dir) != Some(uid) {
+		return None;
+	}
+	let exe = std::fs::read_link(dir.join("exe")).ok()?;
+	// a reused pid most likely belongs to a process of another user
+	(owner(&dir) == Some(uid)).then_some(exe)
+}
+
+/// What Odilia asks of an input server, in reply to each event it sends over the local socket.
+/// Input servers send [`ScreenReaderEvent::UserActivity`] before handling each key, so the reply to it tells them how to treat that key.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reply {
+	/// Collect a line of typed text, editing and echoing it locally, and send it as one [`ScreenReaderEvent::TextEntered`] once Enter is pressed (or an empty one, if Escape is), rather than treating keys as commands.
+	#[serde(default)]
+	pub capture_text: bool,
+	/// The mode Odilia is in, like `Browse`, so that servers grabbing the keyboard know which keys to keep from the focused application, like the letters moving between headings and links while browsing.
+	#[serde(default)]
+	pub mode: String,
+}
+
+/// Where [`sr_event_receiver`] reports what happens on the input socket, for Odilia to tell the user.
+/// Reports are sent only if there is room, so a full channel never holds up input.
+#[derive(Debug, Clone)]
+pub struct Reports {
+	/// Connections from processes which may not send events.
+	pub rejected: Sender<Peer>,
+	/// Connections which are let in; the first one shows that an input server is there.
+	pub accepted: Sender<()>,
+	/// Events which had to wait for room, since Odilia's handlers are behind.
+	pub backlogged: Sender<()>,
+}
+
+/// Opens Odilia's input socket, for [`sr_event_receiver`] to accept connections on.
+/// If Odilia was socket-activated (for example, by a systemd `.socket` unit), the socket passed in is used; otherwise, the socket file is created by Odilia itself.
+/// # Errors
+/// This function will return an error if the socket can not be created.
+/// If an old socket file exists and can not be removed, then this function will call `exit(1)`; TODO: should probably return a result instead.
+pub async fn listen() -> eyre::Result<UnixListener> {
+	let listener = match activated_listener()? {
+		Some(listener) => listener,
+		None => claim_socket().await?,
+	};
+	tracing::debug!("Listener activated!");
+	Ok(listener)
+}
+
+/// Receives [`odilia_common::events::ScreenReaderEvent`] structs on `listener` (see [`listen`]), then sends them over the `event_sender` socket.
+/// Each connection is answered with the [`Reply`] from `reply`, as JSON, once the event has been read.
+/// Only processes running as the same user as Odilia, or running one of the `allowed` programs (by absolute path), may send events; any other connection is dropped.
+/// Rejected and accepted connections, and events which have to wait for room in `event_sender`, are sent over `reports`.
 /// This function will exit upon the expiry of the cancellation token passed in.
 /// # Errors
-/// This function will return an error type if the same function is already running.
-/// This is checked by looking for a file on disk. If the file exists, this program is probably already running.
-/// If there is no way to get access to the directory, then this function will call `exit(1)`; TODO: should probably return a result instead.
+/// This function will return an error if a reply can not be serialized.
 #[tracing::instrument(skip_all)]
 pub async fn sr_event_receiver(
+	listener: UnixListener,
 	event_sender: Sender<ScreenReaderEvent>,
+	reply: impl Fn() -> Reply + Send,
+	allowed: Vec<String>,
+	reports: Reports,
 	shutdown: CancellationToken,
 ) -> eyre::Result<()> {
-	let (pid_file_path, sock_file_path) = get_file_paths();
+	loop {
+		tokio::select! {
+		    msg = listener.accept() => {
+			match msg {
+			    Ok((mut socket, address)) => {
+				tracing::debug!("Ok from socket");
+				match Peer::of(&socket) {
+				    Ok(peer) if peer.is_allowed(&allowed) => {
+					// a full channel means an earlier connection is not yet seen, which says the same
+					let _ = reports.accepted.try_send(());
+				    }
+				    Ok(peer) => {
+					tracing::warn!(?peer, "Rejected input from a process of another user");
+					if let Err(e) = reports.rejected.try_send(peer) {
+					    tracing::debug!("Could not report the rejected connection: {e}");
+					}
+					continue;
+				    }
+				    Err(e) => {
+					tracing::warn!("Rejected input from a process whose credentials can not be read: {e}");
+					continue;
+				    }
+				}
+				let response = read_event(&mut socket).await;
+				forward_event(&response, &event_sender, &reports.backlogged).await;
+				tracing::debug!("Socket: {:?} Address: {:?} Response: {}", socket, address, Redacted(&response));
+				let reply = serde_json::to_vec(&reply())?;
+				// older input servers close the socket without waiting for a reply
+				if let Err(e) = socket.write_all(&reply).await {
+				    tracing::debug!("Could not reply to an input server: {e}");
+				}
+			    },
+			    Err(e) => tracing::error!("accept function failed: {:?}", e),
+			}
+			continue;
+		    }
+		    () = shutdown.cancelled() => {
+			tracing::debug!("Shutting down input socket due to cancellation token");
+			break;
+		    }
+		}
+	}
+	Ok(())
+}
+
+/// Creates the socket file, replacing any stale one.
+/// Only one instance of Odilia runs at a time (this is enforced by the main binary), so any existing socket file is either stale or belongs to an instance which is being replaced.
+async fn claim_socket() -> eyre::Result<UnixListener> {
+	let sock_file_path = get_socket_path();
 	let log_file_name = get_log_file_name();
 
 	let log_path = Path::new(&log_file_name);
@@ -75,34 +217,6 @@ pub async fn sr_event_receiver(
 		}
 	}
 
-	tracing::debug!("checking for already running program");
-	if Path::new(&pid_file_path).exists() {
-		tracing::debug!(
-			%pid_file_path, "Reading pid file  and checking for running instances"
-
-		);
-		let odilias_pid = match fs::read_to_string(&pid_file_path).await {
-			Ok(odilias_pid) => odilias_pid,
-			Err(e) => {
-				tracing::error!(
-					"Unable to read {} to check all running instances",
-					e
-				);
-				exit(1);
-			}
-		};
-		tracing::debug!("Previous PID: {}", odilias_pid);
-
-		let mut sys = System::new_all();
-		sys.refresh_all();
-		for (pid, process) in sys.processes() {
-			if pid.to_string() == odilias_pid && process.exe() == env::current_exe()? {
-				tracing::error!("Server is already running!");
-				exit(1);
-			}
-		}
-	}
-
 	if Path::new(&sock_file_path).exists() {
 		tracing::debug!("Sockfile exists, attempting to remove it.");
 		match fs::remove_file(&sock_file_path).await {
@@ -119,77 +233,297 @@ pub async fn sr_event_receiver(
 			}
 		};
 	}
-	tracing::debug!(%pid_file_path, "writing current ID to pid file");
+	UnixListener::bind(sock_file_path).context("Could not open socket")
+}
 
-	match fs::write(&pid_file_path, id().to_string()).await {
-		Ok(()) => {}
-		Err(e) => {
-			tracing::error!("Unable to write to {}: {}", pid_file_path, e);
-			exit(1);
-		}
-	}
+/// Returns the socket passed in by the service manager, if Odilia was socket-activated.
+fn activated_listener() -> eyre::Result<Option<UnixListener>> {
+	let Some(listener) = ListenFd::from_env()
+		.take_unix_listener(0)
+		.context("Could not use the socket passed in by the service manager")?
+	else {
+		return Ok(None);
+	};
+	tracing::debug!("Using socket passed in by the service manager");
+	listener.set_nonblocking(true)?;
+	Ok(Some(UnixListener::from_std(listener)?))
+}
 
-	let listener = UnixListener::bind(sock_file_path).context("Could not open socket")?;
-	tracing::debug!("Listener activated!");
+/// The longest event read from any connection, in bytes; anything after it is ignored.
+const MAX_EVENT_LEN: u64 = 64 * 1024;
+/// The longest handshake line a remote client may send, in bytes.
+const MAX_SECRET_LEN: u64 = 1024;
+/// How long a remote client has to send its secret, and then its event.
+pub const REMOTE_READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// At most this many remote connections are handled at once; more are dropped until one of them is done.
+const MAX_REMOTE_CONNECTIONS: usize = 16;
+
+/// Receives [`odilia_common::events::ScreenReaderEvent`] structs from remote clients over TCP, then sends them over the `event_sender` socket.
+/// Every connection must begin with a line containing the shared secret from [`RemoteSettings`], followed by the event itself, exactly as it would be sent to the local socket.
+/// Each connection is handled by a task of its own, and has [`REMOTE_READ_TIMEOUT`] for each of the two, so that a slow or idle client can not hold up the others; at most [`MAX_REMOTE_CONNECTIONS`] are handled at once, and the ones still going on are stopped with the listener.
+/// Events waiting for room in `event_sender` are reported over `backlogged`, like by [`sr_event_receiver`].
+/// This function will exit upon the expiry of the cancellation token passed in.
+/// # Errors
+/// This function will return an error if no secret has been configured, or if the listener can not be bound to the configured address.
+#[tracing::instrument(skip_all, fields(address = %settings.address))]
+pub async fn tcp_event_receiver(
+	event_sender: Sender<ScreenReaderEvent>,
+	settings: RemoteSettings,
+	backlogged: Sender<()>,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	if settings.secret.is_empty() {
+		bail!("Remote control is enabled, but no secret is set; refusing to accept unauthenticated connections.");
+	}
+	let listener = TcpListener::bind(&settings.address)
+		.await
+		.with_context(|| format!("Could not listen on {}", settings.address))?;
+	tracing::debug!("Remote listener activated!");
+	let secret: Arc<str> = settings.secret.into();
+	let mut connections = JoinSet::new();
 	loop {
 		tokio::select! {
 			msg = listener.accept() => {
-			    match msg {
-				Ok((mut socket, address)) => {
-				    tracing::debug!("Ok from socket");
-				    let mut response = String::new();
-				    match socket.read_to_string(&mut response).await {
-				      Ok(_) => {},
-				      Err(e) => {
-					tracing::error!("Error reading from socket {:#?}", e);
-				      }
-				    }
-				    // if valid screen reader event
-				    match serde_json::from_str::<ScreenReaderEvent>(&response) {
-				      Ok(sre) => {
-					if let Err(e) = event_sender.send(sre).await {
-					  tracing::error!("Error sending ScreenReaderEvent over socket: {}", e);
-		} else {
-					  tracing::debug!("Sent SR event");
-		}
-				      },
-				      Err(e) => tracing::debug!("Invalid odilia event. {:#?}", e),
-				    }
-				    tracing::debug!("Socket: {:?} Address: {:?} Response: {}", socket, address, response);
-				},
-				Err(e) => tracing::error!("accept function failed: {:?}", e),
-			    }
-			    continue;
+				match msg {
+					Ok((_, address)) if connections.len() >= MAX_REMOTE_CONNECTIONS => {
+						tracing::warn!(%address, "Too many remote clients are connected; dropping connection");
+					}
+					Ok((socket, address)) => {
+						connections.spawn(remote_connection(
+							socket,
+							address,
+							Arc::clone(&secret),
+							event_sender.clone(),
+							backlogged.clone(),
+						));
+					}
+					Err(e) => tracing::error!("accept function failed: {:?}", e),
+				}
 			}
+			Some(_) = connections.join_next() => {}
 			() = shutdown.cancelled() => {
-			    tracing::debug!("Shutting down input socket due to cancellation token");
-			    break;
+				tracing::debug!("Shutting down remote listener due to cancellation token");
+				break;
 			}
-		    }
+		}
 	}
+	connections.shutdown().await;
 	Ok(())
 }
 
+/// Handles one remote connection for [`tcp_event_receiver`]: checks its secret, then forwards its event.
+async fn remote_connection(
+	socket: TcpStream,
+	address: std::net::SocketAddr,
+	secret: Arc<str>,
+	event_sender: Sender<ScreenReaderEvent>,
+	backlogged: Sender<()>,
+) {
+	let mut socket = BufReader::new(socket);
+	let mut handshake = String::new();
+	let mut limited = (&mut socket).take(MAX_SECRET_LEN);
+	match timeout(REMOTE_READ_TIMEOUT, limited.read_line(&mut handshake)).await {
+		Ok(Ok(_)) => {}
+		Ok(Err(e)) => {
+			tracing::error!(%address, "Error reading handshake from remote client {:#?}", e);
+			return;
+		}
+		Err(_) => {
+			tracing::warn!(%address, "Remote client did not send a secret in time; dropping connection");
+			return;
+		}
+	}
+	if !secrets_match(handshake.trim_end().as_bytes(), secret.as_bytes()) {
+		tracing::warn!(%address, "Remote client sent an invalid secret; dropping connection");
+		return;
+	}
+	let Ok(response) = timeout(REMOTE_READ_TIMEOUT, read_event(&mut socket)).await else {
+		tracing::warn!(%address, "Remote client did not send an event in time; dropping connection");
+		return;
+	};
+	forward_event(&response, &event_sender, &backlogged).await;
+	tracing::debug!("Address: {:?} Response: {}", address, Redacted(&response));
+}
+
+/// Reads the rest of the stream, up to [`MAX_EVENT_LEN`] bytes; this is the framing used by every listener: one event per connection.
+async fn read_event<S>(socket: &mut S) -> String
+where
+	S: AsyncRead + Unpin,
+{
+	let mut response = String::new();
+	if let Err(e) = socket.take(MAX_EVENT_LEN).read_to_string(&mut response).await {
+		tracing::error!("Error reading from socket {:#?}", e);
+	}
+	response
+}
+
+/// Forwards `response`, read by [`read_event`], over `event_sender` if it is a valid JSON-encoded [`ScreenReaderEvent`].
+/// If `event_sender` is full, this waits for room, and reports it over `backlogged`, rather than dropping the event.
+async fn forward_event(
+	response: &str,
+	event_sender: &Sender<ScreenReaderEvent>,
+	backlogged: &Sender<()>,
+) {
+	// if valid screen reader event
+	match serde_json::from_str::<ScreenReaderEvent>(response) {
+		Ok(sre) => {
+			if event_sender.capacity() == 0 {
+				tracing::debug!(
+					"Input handlers are behind, waiting to pass on an event"
+				);
+				if let Err(e) = backlogged.try_send(()) {
+					tracing::debug!("Could not report the input backlog: {e}");
+				}
+			}
+			if let Err(e) = event_sender.send(sre).await {
+				tracing::error!(
+					"Error sending ScreenReaderEvent over socket: {}",
+					e
+				);
+			} else {
+				tracing::debug!("Sent SR event");
+			}
+		}
+		Err(e) => tracing::debug!("Invalid odilia event. {:#?}", e),
+	}
+}
+
+/// Sends `event` to the running instance of Odilia, over its input socket; this is what input servers do for every event.
+/// Returns what Odilia asks of the input server in reply.
+/// # Errors
+/// If Odilia is not running, or the event could not be written to the socket.
+pub async fn send_event(event: &ScreenReaderEvent) -> eyre::Result<Reply> {
+	send_event_to(&get_socket_path(), event).await
+}
+
+async fn send_event_to(socket_path: &str, event: &ScreenReaderEvent) -> eyre::Result<Reply> {
+	let mut socket = UnixStream::connect(socket_path)
+		.await
+		.context("Could not connect to Odilia's input socket")?;
+	socket.write_all(&serde_json::to_vec(event)?).await?;
+	socket.shutdown().await?;
+	let mut reply = String::new();
+	socket.read_to_string(&mut reply).await?;
+	// versions of Odilia which do not reply ask for nothing
+	if reply.is_empty() {
+		return Ok(Reply::default());
+	}
+	Ok(serde_json::from_str(&reply)?)
+}
+
+/// Sends a [`ScreenReaderEvent::Heartbeat`] for `server` every `interval` until `shutdown` is cancelled, so that Odilia notices if this input server stops responding.
+/// Heartbeats which can not be sent are only logged, since Odilia may just be restarting.
+pub async fn send_heartbeats(server: String, interval: Duration, shutdown: CancellationToken) {
+	let socket_path = get_socket_path();
+	let interval_ms = u64::try_from(interval.as_millis()).unwrap_or(u64::MAX);
+	let heartbeat = ScreenReaderEvent::Heartbeat { server, interval_ms };
+	loop {
+		if let Err(e) = send_event_to(&socket_path, &heartbeat).await {
+			tracing::debug!("Could not send heartbeat: {e:?}");
+		}
+		if shutdown
+			.run_until_cancelled(tokio::time::sleep(interval))
+			.await
+			.is_none()
+		{
+			break;
+		}
+	}
+}
+
+/// How long to wait before first trying again to reach Odilia; this doubles with every attempt, up to [`MAX_RETRY_INTERVAL`].
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The status input servers exit with when Odilia can not be reached at all, `EX_UNAVAILABLE` from `sysexits.h`, so that service managers can tell it apart from a crash.
+pub const EXIT_UNAVAILABLE: i32 = 69;
+
+/// Waits until Odilia's input socket exists, for at most `timeout`, trying again less and less often; input servers started with Odilia may otherwise start before it has created it.
+/// # Errors
+/// If the socket still does not exist once `timeout` has passed.
+pub async fn wait_for_socket(timeout: Duration) -> eyre::Result<()> {
+	let socket_path = get_socket_path();
+	let started = Instant::now();
+	let mut delay = RETRY_INTERVAL;
+	while !fs::try_exists(&socket_path).await.unwrap_or(false) {
+		if started.elapsed() >= timeout {
+			bail!(
+				"Odilia's input socket did not appear at {socket_path} within {} seconds; is Odilia running?",
+				timeout.as_secs()
+			);
+		}
+		tracing::debug!("Waiting for Odilia's input socket");
+		tokio::time::sleep(delay).await;
+		delay = (delay * 2).min(MAX_RETRY_INTERVAL);
+	}
+	Ok(())
+}
+
+/// Sends events to Odilia one after another from a task of its own, holding on to them while its input socket can not be reached, like while it restarts; cheap to clone.
+/// Replies are not returned, so servers which need them, to know whether text is asked for, should use [`send_event`].
+#[derive(Debug, Clone)]
+pub struct EventQueue {
+	events: mpsc::Sender<(ScreenReaderEvent, Instant)>,
+}
+
+impl EventQueue {
+	/// Starts sending queued events, until `shutdown` is cancelled.
+	/// At most `capacity` events are held; ones held longer than `max_age` are dropped, since keys pressed that long ago are likely no longer meant.
+	#[must_use]
+	pub fn spawn(capacity: usize, max_age: Duration, shutdown: CancellationToken) -> Self {
+		let (events, mut queued) = mpsc::channel::<(ScreenReaderEvent, Instant)>(capacity);
+		let socket_path = get_socket_path();
+		tokio::spawn(async move {
+			while let Some(Some((event, queued_at))) =
+				shutdown.run_until_cancelled(queued.recv()).await
+			{
+				let mut delay = RETRY_INTERVAL;
+				while let Err(e) = send_event_to(&socket_path, &event).await {
+					if queued_at.elapsed() > max_age {
+						tracing::debug!(?event, "Dropped an event Odilia could not be reached for: {e:?}");
+						break;
+					}
+					tracing::trace!(
+						"Could not send an event, trying again: {e:?}"
+					);
+					let retry = tokio::time::sleep(delay);
+					if shutdown.run_until_cancelled(retry).await.is_none() {
+						return;
+					}
+					delay = (delay * 2).min(MAX_RETRY_INTERVAL);
+				}
+			}
+		});
+		EventQueue { events }
+	}
+	/// Queues `event` to be sent; if too many events are waiting for Odilia already, it is dropped.
+	pub fn send(&self, event: ScreenReaderEvent) {
+		if let Err(e) = self.events.try_send((event, Instant::now())) {
+			tracing::warn!("Could not queue an event for Odilia: {e}");
+		}
+	}
+}
+
+/// Compares two secrets without returning early, so that the time taken does not leak how much of the secret was guessed correctly.
+fn secrets_match(given: &[u8], expected: &[u8]) -> bool {
+	given.len() == expected.len()
+		&& given.iter().zip(expected).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
 #[tracing::instrument(ret)]
-fn get_file_paths() -> (String, String) {
+fn get_socket_path() -> String {
 	match env::var("XDG_RUNTIME_DIR") {
 		Ok(val) => {
 			tracing::info!(
                 "XDG_RUNTIME_DIR Variable is present, using it's value as default file path."
             );
 
-			let pid_file_path = format!("{val}/odilias.pid");
-			let sock_file_path = format!("{val}/odilia.sock");
-
-			(pid_file_path, sock_file_path)
+			format!("{val}/odilia.sock")
 		}
 		Err(e) => {
 			tracing::warn!(error=%e, "XDG_RUNTIME_DIR Variable is not set, falling back to hardcoded path");
 
-			let pid_file_path = format!("/run/user/{}/odilias.pid", Uid::current());
-			let sock_file_path = format!("/run/user/{}/odilia.sock", Uid::current());
-
-			(pid_file_path, sock_file_path)
+			format!("/run/user/{}/odilia.sock", Uid::current())
 		}
 	}
 }
@@ -0,0 +1,72 @@
+//! How long one focus event takes to go through its listeners, when they run one after the other and when they run concurrently.
+//!
+//! Each listener waits as long as a round trip to an application would, which is what most of them spend their time on.
+#![feature(impl_trait_in_assoc_type)]
+
+use std::{
+	sync::{atomic::AtomicBool, Arc},
+	time::Duration,
+};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use odilia_common::errors::OdiliaError;
+use tower::{service_fn, util::BoxCloneService, Service};
+
+#[allow(dead_code)]
+#[path = "../src/tower/service_set.rs"]
+mod service_set;
+use service_set::{FailurePolicy, Order, ServiceInfo, ServiceSet};
+
+/// A slow call to an application over D-Bus; tokio's timer does not go below a millisecond anyway.
+const ROUND_TRIP: Duration = Duration::from_millis(1);
+
+type Listener = BoxCloneService<(), (), OdiliaError>;
+
+/// Adds `listeners` to `set` which each wait for [`ROUND_TRIP`], like the listeners to a focus event.
+fn listeners(mut set: ServiceSet<Listener>, listeners: usize) -> ServiceSet<Listener> {
+	for _ in 0..listeners {
+		let info = ServiceInfo {
+			name: "listener",
+			order: Order::default(),
+			enabled: Arc::new(AtomicBool::new(true)),
+		};
+		set.push(
+			info,
+			BoxCloneService::new(service_fn(|()| async {
+				tokio::time::sleep(ROUND_TRIP).await;
+				Ok::<_, OdiliaError>(())
+			})),
+		);
+	}
+	set
+}
+
+fn dispatch(c: &mut Criterion) {
+	let rt = tokio::runtime::Builder::new_multi_thread()
+		.enable_time()
+		.build()
+		.unwrap();
+	let mut group = c.benchmark_group("focus event");
+	for count in [1, 4, 16] {
+		let serial =
+			listeners(ServiceSet::default().policy(FailurePolicy::IgnoreAndLog), count);
+		let concurrent = listeners(
+			ServiceSet::concurrent().policy(FailurePolicy::IgnoreAndLog),
+			count,
+		);
+		group.bench_with_input(BenchmarkId::new("serial", count), &serial, |b, set| {
+			b.to_async(&rt).iter(|| set.clone().call(()));
+		});
+		group.bench_with_input(
+			BenchmarkId::new("concurrent", count),
+			&concurrent,
+			|b, set| {
+				b.to_async(&rt).iter(|| set.clone().call(()));
+			},
+		);
+	}
+	group.finish();
+}
+
+criterion_group!(benches, dispatch);
+criterion_main!(benches);
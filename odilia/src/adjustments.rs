@@ -0,0 +1,240 @@
+//! Settings the user changes while Odilia runs, like the punctuation level, which take the place of the configured ones.
+//!
+//! They are kept in `adjustments.json` in the XDG state directory, rather than in the user's configuration file, so that they survive restarts until reset with [`ResetAdjustments`](odilia_common::events::ResetAdjustments).
+//! Every change is returned as a [`SettingChange`], which handlers turn into a single utterance like "Punctuation: most" with [`SpeechFormatter::setting`](crate::speech_format::SpeechFormatter::setting).
+
+use std::{
+	path::PathBuf,
+	sync::{Arc, Mutex, PoisonError},
+};
+
+use futures::future::{ok, Ready};
+use odilia_common::{
+	errors::OdiliaError,
+	settings::{
+		echo::EchoMode,
+		speech::{Output, PunctuationSpellingMode},
+	},
+};
+use serde::{Deserialize, Serialize};
+use ssip_client_async::PunctuationMode;
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+/// A setting which was changed, and its new value, both as message ids, so that they can be spoken in any language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SettingChange {
+	pub setting: &'static str,
+	pub value: &'static str,
+}
+
+const FILE: &str = "adjustments.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Adjusted {
+	punctuation: Option<PunctuationSpellingMode>,
+	echo: Option<EchoMode>,
+	/// The name of the mode Odilia was last in.
+	mode: Option<String>,
+	language: Option<String>,
+	output: Option<Output>,
+}
+
+/// The names of common languages in themselves, by their code without a region, so that a language can be named while switching to it.
+const LANGUAGE_NAMES: [(&str, &str); 20] = [
+	("ar", "العربية"),
+	("cs", "čeština"),
+	("da", "dansk"),
+	("de", "Deutsch"),
+	("el", "Ελληνικά"),
+	("en", "English"),
+	("es", "español"),
+	("fi", "suomi"),
+	("fr", "français"),
+	("hu", "magyar"),
+	("it", "italiano"),
+	("ja", "日本語"),
+	("nl", "Nederlands"),
+	("no", "norsk"),
+	("pl", "polski"),
+	("pt", "português"),
+	("ru", "русский"),
+	("sv", "svenska"),
+	("uk", "українська"),
+	("zh", "中文"),
+];
+
+/// The name of `language` (a code like `de-AT`) in itself, or the code if it is not known.
+pub fn language_name(language: &str) -> &str {
+	let code = language.split(['-', '_']).next().unwrap_or(language);
+	LANGUAGE_NAMES
+		.iter()
+		.find(|(known, _)| known.eq_ignore_ascii_case(code))
+		.map_or(language, |(_, name)| name)
+}
+
+/// The settings changed at runtime; `None` for the ones which were not; cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct Adjustments(Arc<Mutex<Adjusted>>);
+
+fn path() -> eyre::Result<PathBuf> {
+	Ok(xdg::BaseDirectories::with_prefix("odilia")?.place_state_file(FILE)?)
+}
+
+impl Adjustments {
+	/// The adjustments saved by the last run; none if there are none, or they can not be read.
+	pub fn load() -> Self {
+		let Some(path) = xdg::BaseDirectories::with_prefix("odilia")
+			.ok()
+			.and_then(|dirs| dirs.find_state_file(FILE))
+		else {
+			return Self::default();
+		};
+		let adjusted = std::fs::read(&path)
+			.map_err(eyre::Report::from)
+			.and_then(|json| Ok(serde_json::from_slice(&json)?));
+		match adjusted {
+			Ok(adjusted) => Adjustments(Arc::new(Mutex::new(adjusted))),
+			Err(e) => {
+				tracing::warn!(
+					"Ignoring the settings saved in {}: {e}",
+					path.display()
+				);
+				Self::default()
+			}
+		}
+	}
+	fn adjusted(&self) -> std::sync::MutexGuard<'_, Adjusted> {
+		self.0.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+	/// Writes `adjusted` to the state directory; failing to is only logged, since the change still applies until Odilia exits.
+	fn save(adjusted: &Adjusted) {
+		let saved = path()
+			.and_then(|path| Ok(std::fs::write(path, serde_json::to_vec(adjusted)?)?));
+		if let Err(e) = saved {
+			tracing::warn!("Could not save the changed settings: {e}");
+		}
+	}
+	/// Forgets every adjustment, so that the configured settings apply again.
+	pub fn reset(&self) {
+		*self.adjusted() = Adjusted::default();
+		if let Err(e) = path().and_then(|path| Ok(std::fs::remove_file(path)?)) {
+			tracing::debug!("No saved settings to remove: {e}");
+		}
+	}
+	pub fn mode(&self) -> Option<String> {
+		self.adjusted().mode.clone()
+	}
+	pub fn set_mode(&self, mode: &str) {
+		let mut adjusted = self.adjusted();
+		adjusted.mode = Some(mode.to_string());
+		Self::save(&adjusted);
+	}
+	pub fn punctuation(&self) -> Option<PunctuationSpellingMode> {
+		self.adjusted().punctuation
+	}
+	pub fn echo(&self) -> Option<EchoMode> {
+		self.adjusted().echo
+	}
+	pub fn output(&self) -> Option<Output> {
+		self.adjusted().output
+	}
+	pub fn language(&self) -> Option<String> {
+		self.adjusted().language.clone()
+	}
+	/// Moves on to the language after the current one in `languages`, or to the first of them if the current one (`configured` unless it was changed before) is not among them; `None` if there are none.
+	pub fn cycle_language(&self, configured: &str, languages: &[String]) -> Option<String> {
+		let mut adjusted = self.adjusted();
+		let current = adjusted.language.as_deref().unwrap_or(configured);
+		let next = match languages
+			.iter()
+			.position(|language| language.eq_ignore_ascii_case(current))
+		{
+			Some(i) => languages.get((i + 1) % languages.len()),
+			None => languages.first(),
+		}?
+		.clone();
+		adjusted.language = Some(next.clone());
+		Self::save(&adjusted);
+		Some(next)
+	}
+	/// Moves on to the next punctuation level after the current one, which is `configured` unless it was changed before.
+	pub fn cycle_punctuation(
+		&self,
+		configured: PunctuationSpellingMode,
+	) -> (PunctuationSpellingMode, SettingChange) {
+		let mut adjusted = self.adjusted();
+		let next = match adjusted.punctuation.unwrap_or(configured) {
+			PunctuationSpellingMode::None => PunctuationSpellingMode::Some,
+			PunctuationSpellingMode::Some => PunctuationSpellingMode::Most,
+			PunctuationSpellingMode::Most => PunctuationSpellingMode::All,
+			PunctuationSpellingMode::All => PunctuationSpellingMode::None,
+		};
+		adjusted.punctuation = Some(next);
+		Self::save(&adjusted);
+		let value = match next {
+			PunctuationSpellingMode::None => "punctuation-none",
+			PunctuationSpellingMode::Some => "punctuation-some",
+			PunctuationSpellingMode::Most => "punctuation-most",
+			PunctuationSpellingMode::All => "punctuation-all",
+		};
+		(next, SettingChange { setting: "setting-punctuation", value })
+	}
+	/// Moves on to the next output after the current one, which is `configured` unless it was changed before.
+	/// Having neither speech nor braille can only be configured, since there would be no way to tell what happens after it.
+	pub fn cycle_output(&self, configured: Output) -> (Output, SettingChange) {
+		let mut adjusted = self.adjusted();
+		let next = match adjusted.output.unwrap_or(configured) {
+			Output::Speech => Output::Braille,
+			Output::Braille => Output::Both,
+			Output::Both | Output::Neither => Output::Speech,
+		};
+		adjusted.output = Some(next);
+		Self::save(&adjusted);
+		let value = match next {
+			Output::Speech => "output-speech",
+			Output::Braille => "output-braille",
+			Output::Both => "output-both",
+			Output::Neither => "output-neither",
+		};
+		(next, SettingChange { setting: "setting-output", value })
+	}
+	/// Moves on to the next echo mode after the current one, which is `configured` unless it was changed before.
+	pub fn cycle_echo(&self, configured: EchoMode) -> SettingChange {
+		let mut adjusted = self.adjusted();
+		let next = match adjusted.echo.unwrap_or(configured) {
+			EchoMode::Character => EchoMode::Word,
+			EchoMode::Word => EchoMode::CharacterAndWord,
+			EchoMode::CharacterAndWord => EchoMode::None,
+			EchoMode::None => EchoMode::Character,
+		};
+		adjusted.echo = Some(next);
+		Self::save(&adjusted);
+		let value = match next {
+			EchoMode::Character => "echo-character",
+			EchoMode::Word => "echo-word",
+			EchoMode::CharacterAndWord => "echo-character-and-word",
+			EchoMode::None => "echo-none",
+		};
+		SettingChange { setting: "setting-echo", value }
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for Adjustments {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.adjustments.clone())
+	}
+}
+
+/// What speech-dispatcher calls `mode`.
+pub fn punctuation_mode(mode: PunctuationSpellingMode) -> PunctuationMode {
+	match mode {
+		PunctuationSpellingMode::Some => PunctuationMode::Some,
+		PunctuationSpellingMode::Most => PunctuationMode::Most,
+		PunctuationSpellingMode::None => PunctuationMode::None,
+		PunctuationSpellingMode::All => PunctuationMode::All,
+	}
+}
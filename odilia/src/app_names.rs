@@ -0,0 +1,54 @@
+//! Speakable names for applications, for example "Firefox" rather than "org.mozilla.firefox".
+//!
+//! The defaults are bundled from `data/app-names.toml`, which anyone can extend with a pull request; users can add or replace entries in the `app_names` setting.
+
+use std::{collections::HashMap, sync::Arc};
+
+use futures::future::{ok, Ready};
+use odilia_common::errors::OdiliaError;
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+const BUNDLED: &str = include_str!("../data/app-names.toml");
+
+/// Looks up how to say an application's name; cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct AppNames {
+	/// By lowercase name.
+	names: Arc<HashMap<String, String>>,
+}
+
+impl AppNames {
+	/// The bundled names, with `overrides` taking precedence.
+	pub fn new(overrides: &HashMap<String, String>) -> Self {
+		let bundled: HashMap<String, String> =
+			toml::from_str(BUNDLED).unwrap_or_else(|e| {
+				tracing::error!("The bundled application names are invalid: {e}");
+				HashMap::new()
+			});
+		let names = bundled
+			.into_iter()
+			.chain(overrides.iter().map(|(id, name)| (id.clone(), name.clone())))
+			.map(|(id, name)| (id.to_lowercase(), name))
+			.collect();
+		AppNames { names: Arc::new(names) }
+	}
+	/// How to say `app`: its entry if it has one, the last part of an application id like `org.example.Editor`, or otherwise `app` itself.
+	pub fn speakable<'a>(&'a self, app: &'a str) -> &'a str {
+		if let Some(name) = self.names.get(&app.to_lowercase()) {
+			return name;
+		}
+		if app.contains('.') && !app.contains(char::is_whitespace) {
+			return app.rsplit('.').next().unwrap_or(app);
+		}
+		app
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for AppNames {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.app_names.clone())
+	}
+}
@@ -0,0 +1,142 @@
+//! Capturing of everything Odilia says, as JSON lines.
+//!
+//! This is meant for automated accessibility testing: a CI system (or an application developer) can
+//! start Odilia with `--capture-speech`, drive an application, and assert on what would have been
+//! spoken, without needing any audio.
+//! Every line written is one [`SpokenRecord`].
+
+use std::{
+	os::unix::fs::FileTypeExt,
+	path::{Path, PathBuf},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use eyre::Context;
+use odilia_common::{
+	command::{Announcement, Category},
+	privacy::Redacted,
+};
+use serde::Serialize;
+use tokio::{
+	fs::OpenOptions,
+	io::{AsyncWrite, AsyncWriteExt},
+	net::UnixStream,
+	sync::mpsc::{Receiver, Sender},
+};
+use tokio_util::sync::CancellationToken;
+
+tokio::task_local! {
+	/// The event currently being handled on this task, if any.
+	/// Set by the handler loops, so that speech can be attributed to whatever caused it.
+	pub static CURRENT_SOURCE: SpeechSource;
+}
+
+/// What caused a piece of speech to be generated.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SpeechSource {
+	/// An event from the accessibility bus.
+	Atspi { interface: &'static str, member: &'static str, sender: String, path: String },
+	/// An event received over one of the input sockets.
+	Input { event: String },
+	/// A desktop notification.
+	Notification,
+}
+
+/// A single utterance, as written to the capture stream.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpokenRecord {
+	/// Milliseconds since the unix epoch.
+	pub timestamp_ms: u128,
+	pub priority: String,
+	/// What it was about, like `focus` or `system`.
+	pub category: Category,
+	pub text: String,
+	/// `null` when the speech was not caused by an event; for example, the welcome message.
+	pub source: Option<SpeechSource>,
+}
+
+/// A handle used to record speech; this does nothing unless capturing was requested on the command line.
+#[derive(Debug, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct SpeechCapture(Option<Sender<SpokenRecord>>);
+
+impl SpeechCapture {
+	pub fn new(records: Sender<SpokenRecord>) -> Self {
+		SpeechCapture(Some(records))
+	}
+	/// Records `announcement` as spoken, attributing it to the event currently being handled on this task.
+	/// This never waits: if the writer falls behind, the record is dropped with a warning rather than delaying speech.
+	pub fn record(&self, announcement: &Announcement) {
+		let Some(records) = &self.0 else {
+			return;
+		};
+		let timestamp_ms = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_millis())
+			.unwrap_or_default();
+		let record = SpokenRecord {
+			timestamp_ms,
+			priority: announcement.priority.to_string(),
+			category: announcement.category,
+			text: Redacted(&announcement.text).to_string(),
+			source: CURRENT_SOURCE.try_with(Clone::clone).ok(),
+		};
+		if let Err(e) = records.try_send(record) {
+			tracing::warn!("Could not capture speech: {e}");
+		}
+	}
+}
+
+/// Writes every [`SpokenRecord`] received to `target`, one JSON object per line.
+/// `target` may be `-` for stdout, the path of a listening unix socket, or any other path, which will be appended to.
+/// # Errors
+/// This function will return an error if the target can not be opened, or if writing to it fails.
+#[tracing::instrument(skip(records, shutdown), err)]
+pub async fn write_captured_speech(
+	mut records: Receiver<SpokenRecord>,
+	target: PathBuf,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	let mut output = open_target(&target).await?;
+	loop {
+		tokio::select! {
+			record = records.recv() => {
+				let Some(record) = record else {
+					break;
+				};
+				let mut line = serde_json::to_string(&record)?;
+				line.push('\n');
+				output.write_all(line.as_bytes()).await?;
+				output.flush().await?;
+			}
+			() = shutdown.cancelled() => {
+				tracing::debug!("Shutting down speech capture due to cancellation token");
+				break;
+			}
+		}
+	}
+	Ok(())
+}
+
+async fn open_target(target: &Path) -> eyre::Result<Box<dyn AsyncWrite + Send + Unpin>> {
+	if target == Path::new("-") {
+		return Ok(Box::new(tokio::io::stdout()));
+	}
+	let is_socket = tokio::fs::metadata(target)
+		.await
+		.is_ok_and(|meta| meta.file_type().is_socket());
+	if is_socket {
+		let stream = UnixStream::connect(target).await.with_context(|| {
+			format!("Could not connect to capture socket {}", target.display())
+		})?;
+		return Ok(Box::new(stream));
+	}
+	let file = OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(target)
+		.await
+		.with_context(|| format!("Could not open capture file {}", target.display()))?;
+	Ok(Box::new(file))
+}
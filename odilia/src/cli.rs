@@ -7,4 +7,16 @@ pub struct Args {
 	/// Specify a custom Odilia configuration path
 	#[arg(short, long, value_name = "FILE")]
 	pub config: Option<PathBuf>,
+	/// Also write everything that is spoken to this file or unix socket as JSON lines; use `-` for stdout
+	#[arg(long, value_name = "PATH")]
+	pub capture_speech: Option<PathBuf>,
+	/// Log the text the user reads and types, even if `log.redact_text` is set; for debugging sessions
+	#[arg(long)]
+	pub log_text: bool,
+	/// Replace the instance of Odilia which is already running, if any
+	#[arg(long)]
+	pub replace: bool,
+	/// Restore the cache and settings saved by a previous instance before it restarted; only used by Odilia itself
+	#[arg(long, value_name = "FILE", hide = true)]
+	pub restore_from: Option<PathBuf>,
 }
@@ -0,0 +1,281 @@
+//! Console mode: reading a Linux virtual terminal, like the one on Ctrl+Alt+F3, where there is no AT-SPI.
+//!
+//! The screen is read from `console.device` every `console.poll_ms` and compared with the screen read before it; lines scrolled in, and text added to a line, are spoken.
+//! The last screen read is also kept as a review buffer, which [`ReviewLine`](odilia_common::events::ReviewLine) moves through a line at a time.
+//! Only speech, stopping it, reviewing and quitting work in this mode; everything else needs AT-SPI.
+
+use std::{
+	sync::{Arc, Mutex, PoisonError},
+	time::Duration,
+};
+
+use eyre::WrapErr;
+use futures::FutureExt;
+use odilia_common::{
+	events::{Direction, ScreenReaderEvent},
+	i18n::Localizer,
+	settings::{
+		console::{ConsoleMode, ConsoleSettings},
+		ApplicationConfig,
+	},
+};
+use odilia_tts::SpeechSender;
+use ssip::Priority;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+	adjustments::Adjustments,
+	announce_input_backlog, announce_rejected_input,
+	shutdown::{Shutdown, Stage},
+	state::configure_speech,
+};
+
+/// Whether Odilia runs on a virtual console, like `/dev/tty3`, rather than in a terminal emulator or as a service.
+fn on_virtual_console() -> bool {
+	std::fs::read_link("/proc/self/fd/0").is_ok_and(|tty| {
+		tty.to_str()
+			.and_then(|tty| tty.strip_prefix("/dev/tty"))
+			.is_some_and(|number| number.parse::<u8>().is_ok())
+	})
+}
+
+/// Whether Odilia should read the console rather than the desktop, before even trying to connect to AT-SPI.
+/// With `console.mode` set to `Auto`, that is when it runs on a virtual console outside of a graphical session; it also falls back to the console if AT-SPI is not available.
+pub fn wanted(settings: &ConsoleSettings) -> bool {
+	match settings.mode {
+		ConsoleMode::Always => true,
+		ConsoleMode::Never => false,
+		ConsoleMode::Auto => {
+			std::env::var_os("WAYLAND_DISPLAY").is_none()
+				&& std::env::var_os("DISPLAY").is_none()
+				&& on_virtual_console()
+		}
+	}
+}
+
+/// The text on a console screen, and the line the cursor is on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Screen {
+	lines: Vec<String>,
+	cursor_line: usize,
+}
+
+/// Parses the contents of a `vcsa` device: the number of lines, the number of columns and the cursor position, one byte each, followed by a character and an attribute byte for each cell.
+fn parse(bytes: &[u8]) -> Option<Screen> {
+	let [rows, columns, _cursor_column, cursor_line, cells @ ..] = bytes else {
+		return None;
+	};
+	let columns = usize::from(*columns);
+	if columns == 0 || cells.len() < usize::from(*rows) * columns * 2 {
+		return None;
+	}
+	let lines = cells
+		.chunks(columns * 2)
+		.take(usize::from(*rows))
+		.map(|row| {
+			// the console font's code page is close enough to Latin-1 for text
+			let line: String = row.chunks(2).map(|cell| char::from(cell[0])).collect();
+			line.trim_end().to_string()
+		})
+		.collect();
+	Some(Screen { lines, cursor_line: usize::from(*cursor_line) })
+}
+
+/// The text which is new on `new`, compared to `old`: the lines scrolled in at the bottom, or otherwise each changed line (only what was added to it, if that is all).
+fn new_text(old: &Screen, new: &Screen) -> Vec<String> {
+	let (old, new) = (&old.lines, &new.lines);
+	if old == new {
+		return Vec::new();
+	}
+	let changed: Vec<&str> = if old.len() == new.len() {
+		let n = new.len();
+		let scrolled = (1..n).find(|&k| old[k..] == new[..n - k]);
+		match scrolled {
+			Some(k) => new[n - k..].iter().map(String::as_str).collect(),
+			None => old
+				.iter()
+				.zip(new)
+				.filter(|(old, new)| old != new)
+				.map(|(old, new)| new.strip_prefix(old.as_str()).unwrap_or(new))
+				.collect(),
+		}
+	} else {
+		// the console was resized
+		new.iter().map(String::as_str).collect()
+	};
+	changed.into_iter()
+		.map(str::trim)
+		.filter(|text| !text.is_empty())
+		.map(str::to_string)
+		.collect()
+}
+
+/// The screen read last, and which line of it the review cursor is on; cheap to clone.
+#[derive(Debug, Clone, Default)]
+struct Review(Arc<Mutex<(Screen, usize)>>);
+
+impl Review {
+	/// Replaces the screen, moving the review cursor to the line the cursor is on; returns the text which is new on it.
+	fn update(&self, screen: Screen) -> Vec<String> {
+		let mut review = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+		let text = new_text(&review.0, &screen);
+		if !text.is_empty() || review.0.cursor_line != screen.cursor_line {
+			review.1 = screen.cursor_line;
+		}
+		review.0 = screen;
+		text
+	}
+	/// Moves the review cursor a line in `direction`; returns the line it is then on, or `None` if it is already on the first (or last) one.
+	fn step(&self, direction: &Direction) -> Option<String> {
+		let mut review = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+		let (screen, line) = &mut *review;
+		let next = match direction {
+			Direction::Forward => {
+				line.checked_add(1).filter(|&next| next < screen.lines.len())?
+			}
+			Direction::Backward => line.checked_sub(1)?,
+		};
+		*line = next;
+		screen.lines.get(next).cloned()
+	}
+}
+
+/// Reads the console every `settings.poll_ms` until `shutdown` is cancelled, speaking new output.
+#[tracing::instrument(skip_all)]
+async fn watch(
+	settings: ConsoleSettings,
+	review: Review,
+	ssip: SpeechSender,
+	l10n: Localizer,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	let mut interval = tokio::time::interval(Duration::from_millis(settings.poll_ms.max(10)));
+	let mut reported = false;
+	while shutdown.run_until_cancelled(interval.tick()).await.is_some() {
+		let screen = match tokio::fs::read(&settings.device).await {
+			Ok(bytes) => parse(&bytes),
+			Err(e) => {
+				// most likely, the user is not in the `tty` group; this is only said once, but checked again in case that changes
+				if !reported {
+					tracing::error!("Could not read {}: {e}", settings.device);
+					let message = l10n.format(
+						"console-unreadable",
+						&[("device", &settings.device)],
+					);
+					ssip.speak(Priority::Important, message).await?;
+					reported = true;
+				}
+				continue;
+			}
+		};
+		let Some(screen) = screen else {
+			tracing::debug!("{} does not look like a vcsa device", settings.device);
+			continue;
+		};
+		reported = false;
+		let text = review.update(screen);
+		if !text.is_empty() {
+			ssip.speak(Priority::Text, text.join("\n")).await?;
+		}
+	}
+	Ok(())
+}
+
+/// Handles the input events which work on the console, until the input socket closes.
+#[tracing::instrument(skip_all)]
+async fn handle_input(
+	mut events: mpsc::Receiver<ScreenReaderEvent>,
+	review: Review,
+	ssip: SpeechSender,
+	config: ApplicationConfig,
+	l10n: Localizer,
+	requested: CancellationToken,
+) -> eyre::Result<()> {
+	while let Some(event) = events.recv().await {
+		match event {
+			ScreenReaderEvent::StopSpeech => ssip.stop().await?,
+			ScreenReaderEvent::UserActivity => {
+				ssip.key_pressed();
+				if config.speech.interrupt_on_activity {
+					ssip.interrupt().await?;
+				}
+			}
+			ScreenReaderEvent::ReviewLine(direction) => {
+				let text = match review.step(&direction) {
+					Some(line) if line.is_empty() => l10n.get("blank-line"),
+					Some(line) => line,
+					None if direction == Direction::Forward => {
+						l10n.get("screen-bottom")
+					}
+					None => l10n.get("screen-top"),
+				};
+				ssip.stop().await?;
+				ssip.speak(Priority::Text, text).await?;
+			}
+			ScreenReaderEvent::Quit => {
+				tracing::info!("Quitting, as asked by the user");
+				requested.cancel();
+			}
+			event => tracing::debug!(?event, "Not available in console mode"),
+		}
+	}
+	Ok(())
+}
+
+/// Starts console mode, as tasks of `shutdown`: reading the console, and handling input from the input socket.
+/// # Errors
+/// If speech can not be set up.
+pub async fn start(
+	config: ApplicationConfig,
+	ssip: SpeechSender,
+	shutdown: &mut Shutdown,
+) -> eyre::Result<()> {
+	tracing::info!(device = config.console.device, "Reading the console");
+	configure_speech(&ssip, &config.speech, &Adjustments::load()).await?;
+	let l10n = Localizer::from_settings(&config.speech);
+	ssip.speak(Priority::Message, l10n.get("welcome")).await?;
+	ssip.speak(Priority::Message, l10n.get("console-mode")).await?;
+	let review = Review::default();
+	let reader = watch(
+		config.console.clone(),
+		review.clone(),
+		ssip.clone(),
+		l10n.clone(),
+		shutdown.token(Stage::Input),
+	);
+	shutdown.spawn(Stage::Input, "console reader", reader);
+	let (sr_event_tx, sr_event_rx) = mpsc::channel::<ScreenReaderEvent>(128);
+	let (rejected_tx, rejected_rx) = mpsc::channel::<odilia_input::Peer>(8);
+	let (backlogged_tx, backlogged_rx) = mpsc::channel::<()>(64);
+	let allowed = config.input.allowed_programs.clone();
+	let input_token = shutdown.token(Stage::Input);
+	let input_task = async move {
+		let listener = odilia_input::listen().await?;
+		odilia_input::sr_event_receiver(
+			listener,
+			sr_event_tx,
+			// the console can not ask for text, and has no modes
+			odilia_input::Reply::default,
+			allowed,
+			odilia_input::Reports {
+				rejected: rejected_tx,
+				// the console announces nothing on startup
+				accepted: mpsc::channel(1).0,
+				backlogged: backlogged_tx,
+			},
+			input_token,
+		)
+		.await
+	}
+	.map(|r| r.wrap_err("Could not process input socket"));
+	shutdown.spawn(Stage::Input, "input socket", input_task);
+	let rejection_announcer = announce_rejected_input(rejected_rx, ssip.clone(), l10n.clone());
+	shutdown.spawn(Stage::Input, "rejected input announcer", rejection_announcer);
+	let backlog_announcer = announce_input_backlog(backlogged_rx, ssip.clone(), l10n.clone());
+	shutdown.spawn(Stage::Input, "input backlog announcer", backlog_announcer);
+	let requested = shutdown.requested();
+	let input_handler = handle_input(sr_event_rx, review, ssip, config, l10n, requested);
+	shutdown.spawn(Stage::Handlers, "console input handler", input_handler);
+	Ok(())
+}
@@ -0,0 +1,137 @@
+//! What happens when Odilia panics.
+//!
+//! A crashed screen reader leaves its user with no feedback at all, so before exiting we try, in order, to:
+//!
+//! 1. write a crash report, with a backtrace, to the XDG state directory,
+//! 2. tell the user that Odilia has crashed, over a fresh speech-dispatcher connection,
+//! 3. start Odilia again, if that was enabled in the configuration.
+//!
+//! Everything here is synchronous and uses only the standard library, since the panic may have happened inside the async runtime itself.
+
+use std::{
+	backtrace::Backtrace,
+	env,
+	io::{Read, Write},
+	os::unix::net::UnixStream,
+	panic::{self, PanicHookInfo},
+	path::PathBuf,
+	process::{self, Command},
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use odilia_common::{i18n::Localizer, privacy};
+
+use crate::restart;
+
+/// Passed on to relaunched instances, so that a crash loop gives up eventually.
+const CRASH_COUNT_VAR: &str = "ODILIA_CRASH_COUNT";
+/// How many times in a row Odilia will relaunch itself.
+const MAX_RELAUNCHES: u32 = 3;
+/// A crash after running for this long is not counted as part of a crash loop.
+const CRASH_LOOP_WINDOW: Duration = Duration::from_secs(60);
+
+/// Replaces the default panic hook with one that reports the crash, then exits the whole process.
+/// The default hook is still run first, so the panic is printed as usual, unless user text is being redacted.
+pub fn install_panic_hook(relaunch: bool, l10n: &Localizer) {
+	let started = Instant::now();
+	// translated up front, since a panic may have left anything in a broken state
+	let goodbye = l10n.get("crashed");
+	let default_hook = panic::take_hook();
+	panic::set_hook(Box::new(move |info| {
+		// the default hook prints the panic message, which may include user text
+		if !privacy::redacting() {
+			default_hook(info);
+		}
+		tracing::error!("Odilia has crashed: {}", describe(info));
+		match write_crash_report(info) {
+			Ok(path) => tracing::error!("Crash report written to {}", path.display()),
+			Err(e) => tracing::error!("Could not write crash report: {e}"),
+		}
+		if let Err(e) = say_goodbye(&goodbye) {
+			tracing::error!("Could not announce the crash: {e}");
+		}
+		if relaunch {
+			relaunch_self(started.elapsed() < CRASH_LOOP_WINDOW);
+		}
+		// a panic on any task leaves Odilia half-working; it is better to exit, and let the user (or the relaunch) start over
+		process::exit(101);
+	}));
+}
+
+/// The panic, with its message left out while redacting user text, since it may include some.
+fn describe(info: &PanicHookInfo<'_>) -> String {
+	if !privacy::redacting() {
+		return info.to_string();
+	}
+	match info.location() {
+		Some(location) => format!("panicked at {location} (message redacted)"),
+		None => "panicked (message redacted)".to_string(),
+	}
+}
+
+fn write_crash_report(info: &PanicHookInfo<'_>) -> std::io::Result<PathBuf> {
+	let time = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.unwrap_or_default()
+		.as_secs();
+	let path = xdg::BaseDirectories::with_prefix("odilia")?
+		.place_state_file(format!("crash-{time}.txt"))?;
+	let thread = std::thread::current();
+	let report = format!(
+		"Odilia {} crashed at {time} (seconds since the unix epoch).\nThread: {}\n{}\n\nBacktrace:\n{}\n",
+		env!("CARGO_PKG_VERSION"),
+		thread.name().unwrap_or("<unnamed>"),
+		describe(info),
+		Backtrace::force_capture(),
+	);
+	std::fs::write(&path, report)?;
+	Ok(path)
+}
+
+/// Speaks a final message, using its own connection, since the usual one may belong to the task that panicked.
+fn say_goodbye(message: &str) -> std::io::Result<()> {
+	let runtime_dir = env::var_os("XDG_RUNTIME_DIR")
+		.map(PathBuf::from)
+		.ok_or_else(|| std::io::Error::other("XDG_RUNTIME_DIR is not set"))?;
+	let mut speechd = UnixStream::connect(runtime_dir.join("speech-dispatcher/speechd.sock"))?;
+	speechd.set_read_timeout(Some(Duration::from_secs(1)))?;
+	speechd.write_all(
+		format!("SET self CLIENT_NAME odilia:crash:main\r\n\
+			SET self PRIORITY important\r\n\
+			SPEAK\r\n\
+			{message}\r\n\
+			.\r\n\
+			QUIT\r\n")
+		.as_bytes(),
+	)?;
+	// wait for the server to hang up, so that the message is queued before we exit
+	let mut replies = Vec::new();
+	let _ = speechd.read_to_end(&mut replies);
+	Ok(())
+}
+
+fn relaunch_self(crashed_quickly: bool) {
+	let previous_crashes = if crashed_quickly {
+		env::var(CRASH_COUNT_VAR)
+			.ok()
+			.and_then(|count| count.parse::<u32>().ok())
+			.unwrap_or(0)
+	} else {
+		0
+	};
+	let crashes = previous_crashes + 1;
+	if crashes > MAX_RELAUNCHES {
+		tracing::error!("Odilia has crashed {crashes} times in a row; not relaunching");
+		return;
+	}
+	// we are still running (and own the instance name) until just after this, so the new instance has to replace us
+	let relaunched = Command::new(restart::current_binary())
+		.args(env::args_os().skip(1).filter(|arg| arg != "--replace"))
+		.arg("--replace")
+		.env(CRASH_COUNT_VAR, crashes.to_string())
+		.spawn();
+	match relaunched {
+		Ok(child) => tracing::info!(pid = child.id(), "Relaunched Odilia"),
+		Err(e) => tracing::error!("Could not relaunch Odilia: {e}"),
+	}
+}
@@ -0,0 +1,135 @@
+//! Odilia's own D-Bus interface, served on the session bus under [`BUS_NAME`](crate::instance::BUS_NAME).
+
+use std::sync::Arc;
+
+use odilia_common::errors::OdiliaError;
+use odilia_tts::SpeechSender;
+use ssip::Priority;
+use tokio_util::sync::CancellationToken;
+use zbus::{fdo, interface};
+
+use crate::{modes::Modes, state::ScreenReaderState, tower::registry::HandlerRegistry};
+
+/// The object path every Odilia interface is served at.
+pub const PATH: &str = "/app/odilia/Screenreader";
+
+/// Lists Odilia's event handlers, and turns individual ones on and off.
+pub struct HandlersInterface(pub HandlerRegistry);
+
+#[interface(name = "app.odilia.Screenreader.Handlers")]
+impl HandlersInterface {
+	/// Every handler, as `(name, kind, enabled)`; `kind` is one of `atspi`, `command`, `input` or `custom`.
+	fn list(&self) -> Vec<(String, String, bool)> {
+		self.0.list()
+			.into_iter()
+			.map(|(name, kind, enabled)| (name, kind.as_str().to_string(), enabled))
+			.collect()
+	}
+	/// Enables or disables the handler called `name`, until Odilia exits.
+	fn set_enabled(&self, name: &str, enabled: bool) -> fdo::Result<()> {
+		if self.0.set_enabled(name, enabled) {
+			Ok(())
+		} else {
+			Err(fdo::Error::InvalidArgs(format!("There is no handler called {name}")))
+		}
+	}
+}
+
+/// What Odilia is doing, and speaking through it.
+pub struct StatusInterface(Arc<ScreenReaderState>);
+
+#[interface(name = "app.odilia.Screenreader.Status")]
+impl StatusInterface {
+	/// The version of Odilia running.
+	#[zbus(property)]
+	#[allow(clippy::unused_self)]
+	fn version(&self) -> String {
+		env!("CARGO_PKG_VERSION").to_string()
+	}
+	/// The name of the mode Odilia is in; changes are signalled.
+	#[zbus(property)]
+	fn mode(&self) -> String {
+		self.0.modes.current().name
+	}
+	/// The last message shown in braille, for whatever drives the braille display; changes are signalled.
+	/// It only changes while `speech.output` (or `CycleOutput`) sends messages to braille.
+	#[zbus(property)]
+	fn braille(&self) -> String {
+		self.0.ssip.braille().borrow().clone()
+	}
+	/// Everything a panel applet shows at once: `(mode, speaking, app, role, name, uptime)`.
+	/// `speaking` is an estimate; `app`, `role` and `name` describe the focused object, and are empty if nothing was focused yet; `uptime` is in seconds.
+	async fn get_status(&self) -> (String, bool, String, String, String, u64) {
+		let state = &self.0;
+		let (app, role, name) =
+			match state.history_item(0).and_then(|focused| state.cache.get(&focused)) {
+				Some(item) => {
+					let app = match state.cache.get(&item.app) {
+						Some(app) => app.name().await.unwrap_or_default(),
+						None => String::new(),
+					};
+					let name = item.name().await.unwrap_or_default();
+					(app, item.role.name().to_string(), name)
+				}
+				None => Default::default(),
+			};
+		(
+			state.modes.current().name,
+			state.ssip.is_speaking(),
+			app,
+			role,
+			name,
+			state.started.elapsed().as_secs(),
+		)
+	}
+	/// Speaks `text`, like a message from Odilia itself.
+	async fn speak(&self, text: String) -> fdo::Result<()> {
+		self.0.ssip
+			.speak(Priority::Message, text)
+			.await
+			.map_err(|e| OdiliaError::from(e).into())
+	}
+}
+
+/// Serves every interface on `session`.
+/// # Errors
+/// If an interface can not be registered with the connection.
+pub async fn serve(session: &zbus::Connection, state: &Arc<ScreenReaderState>) -> zbus::Result<()> {
+	let server = session.object_server();
+	server.at(PATH, HandlersInterface(state.handler_registry.clone()))
+		.await?;
+	server.at(PATH, StatusInterface(Arc::clone(state))).await?;
+	Ok(())
+}
+
+/// Signals every change of mode on [`StatusInterface`], until `shutdown` is cancelled.
+/// # Errors
+/// If the interface is not being served, or the signal can not be sent.
+pub async fn signal_mode_changes(
+	session: zbus::Connection,
+	modes: Modes,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	let status = session.object_server().interface::<_, StatusInterface>(PATH).await?;
+	let mut changes = modes.subscribe();
+	while let Some(Ok(())) = shutdown.run_until_cancelled(changes.changed()).await {
+		status.get().await.mode_changed(status.signal_emitter()).await?;
+	}
+	Ok(())
+}
+
+/// Signals every message shown in braille on [`StatusInterface`], until `shutdown` is cancelled.
+/// # Errors
+/// If the interface is not being served, or the signal can not be sent.
+pub async fn signal_braille_changes(
+	session: zbus::Connection,
+	ssip: SpeechSender,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	let status = session.object_server().interface::<_, StatusInterface>(PATH).await?;
+	let mut changes = ssip.braille();
+	while let Some(Ok(())) = shutdown.run_until_cancelled(changes.changed()).await {
+		status.get().await.braille_changed(status.signal_emitter()).await?;
+	}
+	Ok(())
+}
@@ -0,0 +1,117 @@
+//! Speech which waits: things worth saying only once the user has stayed on an object a moment, like its description.
+//!
+//! Handlers run one event at a time, so they can not wait themselves without holding up every event after theirs; they schedule a job here instead, which runs on its own task.
+//! Each job is scheduled in a [`Slot`], and only one job waits per slot: scheduling another cancels it, so that moving on before it runs keeps it from being said, without cancelling the jobs of other features.
+
+use std::{
+	collections::HashMap,
+	future::Future,
+	sync::{Arc, Mutex, PoisonError},
+	time::Duration,
+};
+
+use futures::future::{ok, Ready};
+use odilia_common::{
+	command::{Announcement, Category},
+	errors::OdiliaError,
+};
+use odilia_tts::SpeechSender;
+use ssip_client_async::Priority;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+	capture::SpeechCapture, state::ScreenReaderState, tower::from_state::TryFromState,
+	transcript::SpeechHistory,
+};
+
+/// What a delayed job announces; a job only supersedes the one waiting in the same slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slot {
+	/// The description and help text of the focused object.
+	FocusDetails,
+	/// The selected item of a window which was just activated.
+	WindowSelection,
+}
+
+/// Schedules speech for later; cheap to clone.
+#[derive(Debug, Clone)]
+pub struct DelayedSpeech {
+	ssip: SpeechSender,
+	capture: SpeechCapture,
+	history: SpeechHistory,
+	/// Cancels the job waiting in each slot.
+	waiting: Arc<Mutex<HashMap<Slot, CancellationToken>>>,
+	/// Cancels every job when Odilia shuts down.
+	shutdown: CancellationToken,
+}
+
+impl DelayedSpeech {
+	pub fn new(
+		ssip: SpeechSender,
+		capture: SpeechCapture,
+		history: SpeechHistory,
+		shutdown: CancellationToken,
+	) -> Self {
+		DelayedSpeech { ssip, capture, history, waiting: Arc::default(), shutdown }
+	}
+	fn waiting(&self) -> std::sync::MutexGuard<'_, HashMap<Slot, CancellationToken>> {
+		self.waiting.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+	/// Runs `job` after `delay`, in place of any job still waiting in `slot`, and speaks what it returns, if anything.
+	/// The job itself runs only once the delay is over, so it sees the state of things then.
+	pub fn schedule<F>(&self, slot: Slot, delay: Duration, job: F)
+	where
+		F: Future<Output = Result<Option<String>, OdiliaError>> + Send + 'static,
+	{
+		let token = self.shutdown.child_token();
+		if let Some(previous) = self.waiting().insert(slot, token.clone()) {
+			previous.cancel();
+		}
+		let (ssip, capture, history) =
+			(self.ssip.clone(), self.capture.clone(), self.history.clone());
+		tokio::spawn(async move {
+			let text = token
+				.run_until_cancelled(async {
+					tokio::time::sleep(delay).await;
+					job.await
+				})
+				.await;
+			match text {
+				Some(Ok(Some(text))) => {
+					let announcement = Announcement::new(
+						Category::Focus,
+						Priority::Text,
+						text,
+					);
+					capture.record(&announcement);
+					history.push(&announcement.text);
+					if let Err(e) =
+						ssip.speak(Priority::Text, announcement.text).await
+					{
+						tracing::error!(
+							"Could not speak delayed speech: {e}"
+						);
+					}
+				}
+				Some(Err(e)) => {
+					tracing::debug!(?slot, "Delayed speech failed: {e}");
+				}
+				Some(Ok(None)) | None => {}
+			}
+		});
+	}
+	/// Cancels the jobs waiting in every slot, as when the user stops speech.
+	pub fn cancel_all(&self) {
+		for (_, waiting) in self.waiting().drain() {
+			waiting.cancel();
+		}
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for DelayedSpeech {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.delayed.clone())
+	}
+}
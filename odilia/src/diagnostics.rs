@@ -0,0 +1,143 @@
+//! Finding out which applications flood Odilia with AT-SPI events, which is the usual reason for it to become slow.
+//!
+//! While turned on with the [`EventDiagnostics`](odilia_common::events::EventDiagnostics) input event, every event received is counted by the application sending it and its interface.
+//! Every `diagnostics.window_secs`, the busiest of the last `diagnostics.window_secs` are logged and spoken.
+
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Mutex, PoisonError,
+	},
+	time::{Duration, Instant},
+};
+
+use futures::future::{ok, Ready};
+use odilia_common::{
+	cache::AccessiblePrimitive, command::Category, errors::OdiliaError, intern::Atom,
+};
+use ssip::Priority;
+use tokio_util::sync::CancellationToken;
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+/// How long each bucket of counts covers; the window is rounded up to a whole number of these.
+const BUCKET: Duration = Duration::from_secs(1);
+const ROOT: &str = "/org/a11y/atspi/accessible/root";
+
+/// The number of events by sender and interface.
+type Counts = HashMap<(Atom, &'static str), usize>;
+
+#[derive(Debug, Default)]
+struct Inner {
+	enabled: AtomicBool,
+	/// The counts for each [`BUCKET`], oldest first.
+	buckets: Mutex<VecDeque<(Instant, Counts)>>,
+}
+
+/// Counts AT-SPI events while diagnostics are on; cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct EventCounts(Arc<Inner>);
+
+impl EventCounts {
+	pub fn enabled(&self) -> bool {
+		self.0.enabled.load(Ordering::Relaxed)
+	}
+	/// Turns counting on or off, and returns whether it is now on; the counts so far are dropped either way.
+	pub fn toggle(&self) -> bool {
+		self.buckets().clear();
+		!self.0.enabled.fetch_xor(true, Ordering::Relaxed)
+	}
+	/// Counts an event from `sender` on `interface`, if diagnostics are on.
+	pub fn record(&self, sender: &str, interface: &'static str) {
+		if !self.enabled() {
+			return;
+		}
+		let mut buckets = self.buckets();
+		let now = Instant::now();
+		if !buckets
+			.back()
+			.is_some_and(|(start, _)| now.duration_since(*start) < BUCKET)
+		{
+			buckets.push_back((now, Counts::new()));
+		}
+		if let Some((_, counts)) = buckets.back_mut() {
+			*counts.entry((Atom::from(sender), interface)).or_default() += 1;
+		}
+	}
+	/// The `n` senders and interfaces with the most events in the last `window`, busiest first.
+	fn busiest(&self, window: Duration, n: usize) -> Vec<(Atom, &'static str, usize)> {
+		let mut buckets = self.buckets();
+		while buckets.front().is_some_and(|(start, _)| start.elapsed() > window) {
+			buckets.pop_front();
+		}
+		let mut total = Counts::new();
+		for (key, count) in buckets.iter().flat_map(|(_, counts)| counts) {
+			*total.entry(key.clone()).or_default() += count;
+		}
+		let mut busiest: Vec<_> = total
+			.into_iter()
+			.map(|((sender, interface), count)| (sender, interface, count))
+			.collect();
+		busiest.sort_unstable_by(|a, b| b.2.cmp(&a.2));
+		busiest.truncate(n);
+		busiest
+	}
+	fn buckets(&self) -> std::sync::MutexGuard<'_, VecDeque<(Instant, Counts)>> {
+		self.0.buckets.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for EventCounts {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.event_counts.clone())
+	}
+}
+
+/// The name of the application behind the bus name `sender`, or `sender` itself if it can not be found out.
+async fn app_name(state: &ScreenReaderState, sender: &Atom) -> String {
+	let root = AccessiblePrimitive { id: ROOT.into(), sender: sender.clone() };
+	let name = match root.into_accessible(state.connection()).await {
+		Ok(root) => root.name().await.ok(),
+		Err(_) => None,
+	};
+	match name {
+		Some(name) if !name.is_empty() => state.app_names.speakable(&name).to_string(),
+		_ => sender.to_string(),
+	}
+}
+
+/// Reports the busiest applications every `diagnostics.window_secs` while diagnostics are on, until `shutdown` is cancelled.
+#[tracing::instrument(skip_all)]
+pub async fn watch(state: Arc<ScreenReaderState>, shutdown: CancellationToken) -> eyre::Result<()> {
+	let settings = &state.config.diagnostics;
+	let window = Duration::from_secs(settings.window_secs.max(1));
+	while shutdown
+		.run_until_cancelled(tokio::time::sleep(window))
+		.await
+		.is_some()
+	{
+		if !state.event_counts.enabled() {
+			continue;
+		}
+		let mut report = Vec::new();
+		for (sender, interface, count) in state.event_counts.busiest(window, settings.top) {
+			let app = app_name(&state, &sender).await;
+			// like `Object` for `org.a11y.atspi.Event.Object`
+			let kind = interface.rsplit('.').next().unwrap_or(interface);
+			tracing::info!(%app, %sender, interface, count, ?window, "Busy event sender");
+			report.push(state.l10n.format(
+				"event-diagnostics-busiest",
+				&[("app", &app), ("kind", kind), ("count", &count.to_string())],
+			));
+		}
+		if report.is_empty() {
+			continue;
+		}
+		state.say(Category::System, Priority::Notification, report.join(", "))
+			.await;
+	}
+	Ok(())
+}
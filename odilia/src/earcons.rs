@@ -0,0 +1,197 @@
+//! Earcons: short tones which tell the user something happened, faster than speech could.
+//!
+//! The tones are synthesized once, written as WAV files to the runtime directory, and played with an external command (`paplay` by default, see [`EarconSettings`]), so that Odilia does not need to talk to the sound server itself.
+
+use std::{
+	f32::consts::TAU,
+	io,
+	path::{Path, PathBuf},
+	process::Stdio,
+	sync::Arc,
+	time::Duration,
+};
+
+use futures::future::{ok, Ready};
+use odilia_common::{errors::OdiliaError, settings::earcons::EarconSettings};
+use tokio::process::Command;
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+const SAMPLE_RATE: u32 = 22_050;
+
+/// Every sound Odilia can play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Earcon {
+	/// A rising tone: browse mode.
+	BrowseMode,
+	/// A falling tone: focus mode.
+	FocusMode,
+	/// A short, quiet pair of tones, repeated while a document loads.
+	Busy,
+	/// A short, high blip: one stage of startup is ready.
+	StageReady,
+	/// A low, falling pair of tones: a stage of startup failed.
+	StageFailed,
+}
+
+/// A tone sliding from one frequency to another.
+struct Sweep {
+	from_hz: f32,
+	to_hz: f32,
+	millis: u32,
+}
+
+impl Earcon {
+	fn sweeps(self) -> &'static [Sweep] {
+		match self {
+			Earcon::BrowseMode => {
+				&[Sweep { from_hz: 440.0, to_hz: 880.0, millis: 140 }]
+			}
+			Earcon::FocusMode => &[Sweep { from_hz: 880.0, to_hz: 440.0, millis: 140 }],
+			Earcon::Busy => &[
+				Sweep { from_hz: 330.0, to_hz: 330.0, millis: 50 },
+				Sweep { from_hz: 392.0, to_hz: 392.0, millis: 50 },
+			],
+			Earcon::StageReady => &[Sweep { from_hz: 660.0, to_hz: 990.0, millis: 60 }],
+			Earcon::StageFailed => &[
+				Sweep { from_hz: 330.0, to_hz: 294.0, millis: 150 },
+				Sweep { from_hz: 247.0, to_hz: 196.0, millis: 250 },
+			],
+		}
+	}
+	fn name(self) -> &'static str {
+		match self {
+			Earcon::BrowseMode => "browse-mode",
+			Earcon::FocusMode => "focus-mode",
+			Earcon::Busy => "busy",
+			Earcon::StageReady => "stage-ready",
+			Earcon::StageFailed => "stage-failed",
+		}
+	}
+}
+
+/// Plays earcons; cheap to clone.
+#[derive(Debug, Clone)]
+pub struct Earcons {
+	settings: Arc<EarconSettings>,
+	dir: PathBuf,
+}
+
+impl Earcons {
+	pub fn new(settings: EarconSettings) -> Self {
+		let dir = xdg::BaseDirectories::with_prefix("odilia")
+			.ok()
+			.and_then(|dirs| {
+				dirs.get_runtime_directory().ok().map(|dir| dir.join("odilia"))
+			})
+			.unwrap_or_else(std::env::temp_dir)
+			.join("earcons");
+		Earcons { settings: Arc::new(settings), dir }
+	}
+	/// Whether earcons are played at all; handlers which would otherwise stay silent should fall back to speech when they are not.
+	#[must_use]
+	pub fn enabled(&self) -> bool {
+		self.settings.enabled
+	}
+	/// How long to wait between plays of [`Earcon::Busy`].
+	#[must_use]
+	pub fn busy_interval(&self) -> Duration {
+		Duration::from_millis(self.settings.busy_interval_ms)
+	}
+	/// Plays `earcon` in the background, if earcons are enabled; failures are only logged, since an earcon is never essential.
+	pub fn play(&self, earcon: Earcon) {
+		if !self.enabled() {
+			return;
+		}
+		let Some((program, args)) = self.settings.player.split_first() else {
+			tracing::warn!("No earcon player is configured");
+			return;
+		};
+		let path = match self.file(earcon) {
+			Ok(path) => path,
+			Err(e) => {
+				tracing::warn!(?earcon, "Could not write earcon: {e}");
+				return;
+			}
+		};
+		let child = Command::new(program)
+			.args(args)
+			.arg(&path)
+			.stdin(Stdio::null())
+			.stdout(Stdio::null())
+			.stderr(Stdio::null())
+			.spawn();
+		match child {
+			Ok(mut child) => {
+				tokio::spawn(async move { child.wait().await });
+			}
+			Err(e) => tracing::warn!(?earcon, program, "Could not play earcon: {e}"),
+		}
+	}
+	/// The sound file for `earcon`, written on first use.
+	fn file(&self, earcon: Earcon) -> io::Result<PathBuf> {
+		// the volume is part of the name, so that changing it in the configuration takes effect
+		#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+		let percent = (self.settings.volume.clamp(0.0, 1.0) * 100.0) as u8;
+		let path = self.dir.join(format!("{}-{percent}.wav", earcon.name()));
+		if !path.exists() {
+			std::fs::create_dir_all(&self.dir)?;
+			write_wav(&path, &synthesize(earcon.sweeps(), self.settings.volume))?;
+		}
+		Ok(path)
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for Earcons {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.earcons.clone())
+	}
+}
+
+/// Renders `sweeps` one after the other, as 16-bit samples, fading each in and out to avoid clicks.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn synthesize(sweeps: &[Sweep], volume: f32) -> Vec<i16> {
+	let amplitude = volume.clamp(0.0, 1.0) * f32::from(i16::MAX);
+	let mut samples = Vec::new();
+	for sweep in sweeps {
+		let len = (SAMPLE_RATE * sweep.millis / 1000) as usize;
+		let fade = (len / 10).max(1);
+		let mut phase = 0.0_f32;
+		for i in 0..len {
+			let progress = i as f32 / len as f32;
+			let hz = sweep.from_hz + (sweep.to_hz - sweep.from_hz) * progress;
+			phase = (phase + TAU * hz / SAMPLE_RATE as f32) % TAU;
+			let envelope = (i.min(len - i) as f32 / fade as f32).min(1.0);
+			samples.push((phase.sin() * envelope * amplitude) as i16);
+		}
+	}
+	samples
+}
+
+/// Writes mono 16-bit PCM `samples` as a WAV file.
+fn write_wav(path: &Path, samples: &[i16]) -> io::Result<()> {
+	let data_len = u32::try_from(samples.len() * 2)
+		.map_err(|_| io::Error::other("earcon is too long"))?;
+	let mut wav = Vec::with_capacity(44 + samples.len() * 2);
+	wav.extend_from_slice(b"RIFF");
+	wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+	wav.extend_from_slice(b"WAVEfmt ");
+	wav.extend_from_slice(&16_u32.to_le_bytes()); // size of the format chunk
+	wav.extend_from_slice(&1_u16.to_le_bytes()); // PCM
+	wav.extend_from_slice(&1_u16.to_le_bytes()); // mono
+	wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+	wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // bytes per second
+	wav.extend_from_slice(&2_u16.to_le_bytes()); // bytes per frame
+	wav.extend_from_slice(&16_u16.to_le_bytes()); // bits per sample
+	wav.extend_from_slice(b"data");
+	wav.extend_from_slice(&data_len.to_le_bytes());
+	for sample in samples {
+		wav.extend_from_slice(&sample.to_le_bytes());
+	}
+	// write to a temporary file first, so that a player never sees a half-written file
+	let partial = path.with_extension("wav.partial");
+	std::fs::write(&partial, wav)?;
+	std::fs::rename(partial, path)
+}
@@ -0,0 +1,118 @@
+//! Echoing what the user types, by character, by word, or both.
+//!
+//! Input servers send the text each key produced as a [`KeyEcho`](odilia_common::events::KeyEcho) event; the word being typed is buffered here until a space or punctuation finishes it.
+//! Which of these are spoken depends on `echo.mode`, or on the entry in `echo.apps` for the focused application, so that for example terminals, which echo typed text themselves, can be silenced.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use futures::future::{ok, Ready};
+use odilia_common::{
+	cache::AccessiblePrimitive,
+	errors::OdiliaError,
+	settings::echo::{EchoMode, EchoSettings},
+};
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+const BACKSPACE: char = '\u{8}';
+
+#[derive(Debug, Default)]
+struct Buffer {
+	/// The accessible the word is being typed in; the word is started afresh when focus moves.
+	object: Option<AccessiblePrimitive>,
+	word: String,
+	/// The focused application, and its name, as the last echo looked it up.
+	app: Option<(AccessiblePrimitive, String)>,
+}
+
+/// What should be spoken for a piece of typed text.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Utterance {
+	/// Characters to speak one by one.
+	pub chars: Vec<char>,
+	/// Words which were finished.
+	pub words: Vec<String>,
+}
+
+/// The word currently being typed; cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct Echo(Arc<Mutex<Buffer>>);
+
+impl Echo {
+	fn buffer(&self) -> std::sync::MutexGuard<'_, Buffer> {
+		self.0.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+	/// The name `app` reports; it is only asked for when it is not the application the last echo was typed into.
+	pub async fn app_name(
+		&self,
+		app: &AccessiblePrimitive,
+		connection: &zbus::Connection,
+	) -> Result<String, OdiliaError> {
+		let cached = match &self.buffer().app {
+			Some((cached, name)) if cached == app => Some(name.clone()),
+			_ => None,
+		};
+		if let Some(name) = cached {
+			return Ok(name);
+		}
+		let name = app.clone().into_accessible(connection).await?.name().await?;
+		self.buffer().app = Some((app.clone(), name.clone()));
+		Ok(name)
+	}
+	/// Adds `text`, typed into `object`, to the word being typed, and returns what should be spoken for it in `mode`.
+	/// Whitespace is never spoken as a character, since speech-dispatcher cannot be asked to name it.
+	pub fn push(
+		&self,
+		object: Option<AccessiblePrimitive>,
+		text: &str,
+		mode: EchoMode,
+	) -> Utterance {
+		let mut buffer = self.buffer();
+		if buffer.object != object {
+			buffer.word.clear();
+			buffer.object = object;
+		}
+		let mut utterance = Utterance::default();
+		for ch in text.chars() {
+			if ch == BACKSPACE {
+				buffer.word.pop();
+				continue;
+			}
+			if matches!(mode, EchoMode::Character | EchoMode::CharacterAndWord)
+				&& !ch.is_whitespace()
+			{
+				utterance.chars.push(ch);
+			}
+			if ch.is_alphanumeric() {
+				buffer.word.push(ch);
+				continue;
+			}
+			let word = std::mem::take(&mut buffer.word);
+			if !word.is_empty()
+				&& matches!(mode, EchoMode::Word | EchoMode::CharacterAndWord)
+			{
+				utterance.words.push(word);
+			}
+		}
+		utterance
+	}
+}
+
+/// The echo mode for the application called `app`, if known.
+pub fn mode_for(settings: &EchoSettings, app: Option<&str>) -> EchoMode {
+	app.and_then(|app| {
+		settings.apps
+			.iter()
+			.find(|(name, _)| name.eq_ignore_ascii_case(app))
+			.map(|(_, mode)| *mode)
+	})
+	.unwrap_or(settings.mode)
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for Echo {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.echo.clone())
+	}
+}
@@ -0,0 +1,57 @@
+//! The stream of AT-SPI events, with a fast path for the events which make up most of the traffic.
+//!
+//! The general conversion deserializes every event body into an owned `EventBodyOwned` (the `kind` string, the `any_data` variant and the properties map) before building the event, and then throws most of it away.
+//! Caret movements and state changes only need `kind` and `detail1`, so for those the body is deserialized borrowing from the message, and the event is built directly.
+//! Anything the fast path does not recognize (including Qt's differently-shaped bodies) falls back to the general conversion.
+
+use std::{collections::HashMap, future::ready};
+
+use atspi::{
+	events::object::{StateChangedEvent, TextCaretMovedEvent},
+	AtspiError, BusProperties, Event, ObjectRef,
+};
+use atspi_connection::AccessibilityConnection;
+use futures::{Stream, StreamExt};
+use zbus::{message::Type as MessageType, zvariant::Value, Message, MessageStream};
+
+/// The body of an AT-SPI event signal, borrowed from the message: `(kind, detail1, detail2, any_data, properties)`.
+type BorrowedEventBody<'m> = (&'m str, i32, i32, Value<'m>, HashMap<&'m str, Value<'m>>);
+
+/// A drop-in replacement for [`AccessibilityConnection::event_stream`].
+pub fn event_stream(
+	atspi: &AccessibilityConnection,
+) -> impl Stream<Item = Result<Event, AtspiError>> {
+	MessageStream::from(atspi.connection()).filter_map(|res| {
+		ready(match res {
+			Err(e) => Some(Err(e.into())),
+			Ok(msg) if msg.message_type() == MessageType::Signal => {
+				Some(fast_path(&msg).unwrap_or_else(|| Event::try_from(&msg)))
+			}
+			Ok(_) => None,
+		})
+	})
+}
+
+/// Converts caret-moved and state-changed events, or returns `None` for anything else.
+fn fast_path(msg: &Message) -> Option<Result<Event, AtspiError>> {
+	let header = msg.header();
+	if header.interface()?.as_str() != TextCaretMovedEvent::DBUS_INTERFACE {
+		return None;
+	}
+	let member = header.member()?;
+	let caret_moved = member.as_str() == TextCaretMovedEvent::DBUS_MEMBER;
+	if !caret_moved && member.as_str() != StateChangedEvent::DBUS_MEMBER {
+		return None;
+	}
+	let body = msg.body();
+	let (kind, detail1, ..) = body.deserialize::<BorrowedEventBody<'_>>().ok()?;
+	let item = match ObjectRef::try_from(msg) {
+		Ok(item) => item,
+		Err(e) => return Some(Err(e)),
+	};
+	Some(Ok(if caret_moved {
+		TextCaretMovedEvent { item, position: detail1 }.into()
+	} else {
+		StateChangedEvent { item, state: kind.into(), enabled: detail1 > 0 }.into()
+	}))
+}
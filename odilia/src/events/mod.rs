@@ -1,2 +1 @@
-mod cache;
 mod document;
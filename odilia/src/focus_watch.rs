@@ -0,0 +1,79 @@
+//! Keeping the braille display up to date with the focused object: when its name or text changes while it has focus, the display shows it as it is now, rather than as it was when focus moved to it.
+//!
+//! Only the focused object is watched; following a new one stops watching the one before.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use futures::future::{ok, Ready};
+use odilia_cache::{Cache, Change};
+use odilia_common::{cache::AccessiblePrimitive, errors::OdiliaError};
+use odilia_tts::SpeechSender;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+	speech_format::SpeechFormatter, state::ScreenReaderState, tower::from_state::TryFromState,
+};
+
+/// Watches the focused object; cheap to clone.
+#[derive(Debug, Clone)]
+pub struct FocusWatch {
+	cache: Arc<Cache>,
+	ssip: SpeechSender,
+	formatter: SpeechFormatter,
+	/// Stops watching the object focused last.
+	watching: Arc<Mutex<Option<CancellationToken>>>,
+	/// Stops watching when Odilia shuts down.
+	shutdown: CancellationToken,
+}
+
+impl FocusWatch {
+	pub fn new(
+		cache: Arc<Cache>,
+		ssip: SpeechSender,
+		formatter: SpeechFormatter,
+		shutdown: CancellationToken,
+	) -> Self {
+		FocusWatch { cache, ssip, formatter, watching: Arc::default(), shutdown }
+	}
+	/// Watches `focused` from now on, in place of the object focused before, until it is removed.
+	pub fn follow(&self, focused: AccessiblePrimitive) {
+		let token = self.shutdown.child_token();
+		if let Some(previous) = self
+			.watching
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.replace(token.clone())
+		{
+			previous.cancel();
+		}
+		let mut watch = self.cache.watch(focused.clone());
+		let (cache, ssip, formatter) =
+			(Arc::clone(&self.cache), self.ssip.clone(), self.formatter.clone());
+		tokio::spawn(async move {
+			while let Some(Some(change)) = token.run_until_cancelled(watch.next()).await
+			{
+				if !matches!(change, Change::Text | Change::Lagged) {
+					continue;
+				}
+				let Some(item) = cache.get(&focused) else {
+					break;
+				};
+				// named like the `focused` handler names it
+				let label = if item.text.is_empty() {
+					item.name().await.unwrap_or_default()
+				} else {
+					item.text.clone()
+				};
+				ssip.refresh_braille(&formatter.format(&item, &label));
+			}
+		});
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for FocusWatch {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.focus_watch.clone())
+	}
+}
@@ -0,0 +1,97 @@
+//! Announcing when focus moves into or out of a frame, like an iframe embedding another page into a web page.
+//!
+//! A frame is a document inside another document; which one focus is in is found by walking up the cached tree from the focused object.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use atspi::Role;
+use futures::future::{ok, Ready};
+use odilia_cache::CacheItem;
+use odilia_common::{cache::AccessiblePrimitive, errors::OdiliaError, i18n::Localizer};
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+const DOCUMENTS: [Role; 4] =
+	[Role::DocumentWeb, Role::DocumentFrame, Role::DocumentEmail, Role::DocumentText];
+/// Roles which are never inside a document, so that looking for one can stop.
+const BOUNDARIES: [Role; 2] = [Role::Frame, Role::Application];
+
+/// The documents `item` is in (including itself, if it is one), innermost first, as far as they are cached.
+fn documents(item: &CacheItem) -> Vec<CacheItem> {
+	let mut documents = Vec::new();
+	let mut current = item.clone();
+	loop {
+		if DOCUMENTS.contains(&current.role) {
+			documents.push(current.clone());
+		}
+		let Ok(parent) = current.parent() else {
+			break;
+		};
+		if BOUNDARIES.contains(&parent.role) || parent.object == current.object {
+			break;
+		}
+		current = parent;
+	}
+	documents
+}
+
+/// The innermost frame `item` is in, if it is in one.
+fn frame_of(item: &CacheItem) -> Option<CacheItem> {
+	let mut documents = documents(item);
+	if documents.len() < 2 {
+		return None;
+	}
+	Some(documents.swap_remove(0))
+}
+
+/// What a frame is called: the title of its document, or otherwise the name of the object embedding it.
+async fn title(frame: &CacheItem) -> Result<String, OdiliaError> {
+	let title = frame.name().await?;
+	if !title.is_empty() {
+		return Ok(title);
+	}
+	match frame.parent() {
+		Ok(parent) => parent.name().await,
+		Err(_) => Ok(title),
+	}
+}
+
+/// The frame focus was last in; cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct Frames(Arc<Mutex<Option<AccessiblePrimitive>>>);
+
+impl Frames {
+	/// What to say about moving focus to `item`, if it is in another frame than the last focused object (or no longer in one).
+	/// # Errors
+	/// If the title of the new frame can not be fetched.
+	pub async fn focus_moved(
+		&self,
+		item: &CacheItem,
+		l10n: &Localizer,
+	) -> Result<Option<String>, OdiliaError> {
+		let frame = frame_of(item);
+		let current = frame.as_ref().map(|frame| frame.object.clone());
+		let previous = std::mem::replace(
+			&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner),
+			current.clone(),
+		);
+		if previous == current {
+			return Ok(None);
+		}
+		match frame {
+			Some(frame) => {
+				let title = title(&frame).await?;
+				Ok(Some(l10n.format("frame", &[("title", &title)])))
+			}
+			None => Ok(Some(l10n.get("out-of-frame"))),
+		}
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for Frames {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.frames.clone())
+	}
+}
@@ -0,0 +1,209 @@
+//! Where things are on screen, per monitor, like "top left of HDMI-1".
+//!
+//! On GNOME, the monitors are read from Mutter's `DisplayConfig` D-Bus interface, and read again whenever it says they changed.
+//! Other desktops have no common interface which lists them, so there the whole screen, as the extents of the accessibility registry's desktop, counts as one monitor.
+
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex, PoisonError},
+};
+
+use atspi::CoordType;
+use futures::{
+	future::{ok, Ready},
+	StreamExt,
+};
+use odilia_cache::Convertable;
+use odilia_common::{errors::OdiliaError, i18n::Localizer};
+use tokio_util::sync::CancellationToken;
+use zbus::zvariant::OwnedValue;
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState, warm_start::registry_root};
+/// A monitor, in the coordinates of the whole screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monitor {
+	pub name: String,
+	pub x: i32,
+	pub y: i32,
+	pub width: i32,
+	pub height: i32,
+}
+
+impl Monitor {
+	fn contains(&self, x: i32, y: i32) -> bool {
+		(self.x..self.x + self.width).contains(&x)
+			&& (self.y..self.y + self.height).contains(&y)
+	}
+	/// Which third of the monitor, vertically and horizontally, the point `(x, y)` is in, as message ids.
+	fn region(&self, x: i32, y: i32) -> (&'static str, &'static str) {
+		let third = |offset: i32, size: i32| {
+			(3 * i64::from(offset) / i64::from(size.max(1))).clamp(0, 2)
+		};
+		let vertical = match third(y - self.y, self.height) {
+			0 => "screen-top",
+			1 => "screen-middle",
+			_ => "screen-bottom",
+		};
+		let horizontal = match third(x - self.x, self.width) {
+			0 => "screen-left",
+			1 => "screen-center",
+			_ => "screen-right",
+		};
+		(vertical, horizontal)
+	}
+}
+
+/// Properties of a mode, monitor, or the whole configuration, by name.
+type Properties = HashMap<String, OwnedValue>;
+/// A physical monitor's connector (like `HDMI-1`), vendor, product, and serial number.
+type MonitorSpec = (String, String, String, String);
+/// A mode's id, width and height in pixels, refresh rate, preferred and supported scales, and properties, like `is-current`.
+type Mode = (String, i32, i32, f64, f64, Vec<f64>, Properties);
+/// A physical monitor, with the modes it supports.
+type PhysicalMonitor = (MonitorSpec, Vec<Mode>, Properties);
+/// An area of the desktop: its position, scale, transform (rotation), whether it is primary, and the physical monitors showing it.
+type LogicalMonitor = (i32, i32, f64, u32, bool, Vec<MonitorSpec>, Properties);
+
+#[zbus::proxy(
+	interface = "org.gnome.Mutter.DisplayConfig",
+	default_service = "org.gnome.Mutter.DisplayConfig",
+	default_path = "/org/gnome/Mutter/DisplayConfig"
+)]
+trait DisplayConfig {
+	fn get_current_state(
+		&self,
+	) -> zbus::Result<(u32, Vec<PhysicalMonitor>, Vec<LogicalMonitor>, Properties)>;
+	#[zbus(signal)]
+	fn monitors_changed(&self) -> zbus::Result<()>;
+}
+
+/// The monitors in Mutter's current state, named after the connector of their first physical monitor.
+/// In the logical layout mode, positions are in logical pixels, so the sizes of modes are divided by the scale; rotated monitors are as high as their modes are wide.
+fn monitors(
+	physical: &[PhysicalMonitor],
+	logical: &[LogicalMonitor],
+	properties: &Properties,
+) -> Vec<Monitor> {
+	// 1 is logical, 2 is physical; older versions of Mutter only had the physical layout
+	let layout_logical = properties
+		.get("layout-mode")
+		.and_then(|mode| u32::try_from(mode).ok())
+		== Some(1);
+	logical.iter()
+		.filter_map(|(x, y, scale, transform, _primary, specs, _)| {
+			let spec = specs.first()?;
+			let (_, modes, _) = physical.iter().find(|(other, _, _)| other == spec)?;
+			let (_, width, height, ..) = modes.iter().find(|(.., properties)| {
+				properties
+					.get("is-current")
+					.and_then(|current| bool::try_from(current).ok())
+					.unwrap_or_default()
+			})?;
+			let (mut width, mut height) = (*width, *height);
+			if layout_logical && *scale > 0.0 {
+				#[allow(clippy::cast_possible_truncation)]
+				let unscale = |size: i32| (f64::from(size) / scale).round() as i32;
+				(width, height) = (unscale(width), unscale(height));
+			}
+			// odd transforms are turned by 90 or 270 degrees
+			if transform % 2 == 1 {
+				(width, height) = (height, width);
+			}
+			Some(Monitor { name: spec.0.clone(), x: *x, y: *y, width, height })
+		})
+		.collect()
+}
+
+/// The whole screen as one monitor, from the extents of the registry's desktop; `None` if it has none.
+async fn desktop(connection: &zbus::Connection) -> Option<Monitor> {
+	let desktop = registry_root().into_accessible(connection).await.ok()?;
+	let (x, y, width, height) = desktop
+		.to_component()
+		.await
+		.ok()?
+		.get_extents(CoordType::Screen)
+		.await
+		.ok()?;
+	(width > 0 && height > 0).then(|| Monitor { name: String::new(), x, y, width, height })
+}
+
+/// Follows Mutter's monitors until `shutdown` is cancelled, keeping them in `state.geometry`.
+/// Returns at once if Mutter is not running, since there is nothing to follow; the desktop's extents are used then.
+#[tracing::instrument(skip_all)]
+pub async fn watch(
+	state: Arc<ScreenReaderState>,
+	session: zbus::Connection,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	let config = DisplayConfigProxy::new(&session).await?;
+	let mut changes = config.receive_monitors_changed().await?;
+	loop {
+		match config.get_current_state().await {
+			Ok((_, physical, logical, properties)) => {
+				state.geometry.set(monitors(&physical, &logical, &properties));
+			}
+			Err(e) => {
+				tracing::info!(
+					"Not listing monitors, since Mutter is not available: {e}"
+				);
+				return Ok(());
+			}
+		}
+		if shutdown.run_until_cancelled(changes.next()).await.flatten().is_none() {
+			return Ok(());
+		}
+	}
+}
+
+/// The monitors Mutter listed last, if any; cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct Geometry(Arc<Mutex<Vec<Monitor>>>);
+
+impl Geometry {
+	fn set(&self, monitors: Vec<Monitor>) {
+		tracing::debug!(?monitors, "Monitors changed");
+		*self.0.lock().unwrap_or_else(PoisonError::into_inner) = monitors;
+	}
+	/// Where the point `(x, y)`, in screen coordinates, is: like "top left", followed by the monitor's name if there is more than one.
+	/// Without a list of monitors, the desktop's extents, read over the accessibility bus `connection`, are used instead.
+	/// `None` if it is on none of them, or neither is known.
+	pub async fn describe(
+		&self,
+		x: i32,
+		y: i32,
+		connection: &zbus::Connection,
+		l10n: &Localizer,
+	) -> Option<String> {
+		let listed = self.0.lock().unwrap_or_else(PoisonError::into_inner).clone();
+		let monitors =
+			if listed.is_empty() { vec![desktop(connection).await?] } else { listed };
+		let monitor = monitors.iter().find(|monitor| monitor.contains(x, y))?;
+		let (vertical, horizontal) = monitor.region(x, y);
+		let region = if (vertical, horizontal) == ("screen-middle", "screen-center") {
+			l10n.get("screen-center")
+		} else {
+			l10n.format(
+				"screen-region",
+				&[
+					("vertical", &l10n.get(vertical)),
+					("horizontal", &l10n.get(horizontal)),
+				],
+			)
+		};
+		if monitors.len() < 2 {
+			return Some(region);
+		}
+		Some(l10n.format(
+			"screen-region-on-monitor",
+			&[("region", &region), ("monitor", &monitor.name)],
+		))
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for Geometry {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.geometry.clone())
+	}
+}
@@ -0,0 +1,103 @@
+//! Noticing when an input server stops responding.
+//!
+//! Input servers which want to be watched send a [`Heartbeat`](odilia_common::events::Heartbeat) every so often; once one of them misses `input.heartbeat_missed` in a row, this is announced and logged, and `input.restart_unresponsive` is run for it.
+//! Servers which never send a heartbeat are never reported.
+
+use std::{
+	collections::HashMap,
+	process::Stdio,
+	sync::{Arc, Mutex, PoisonError},
+	time::{Duration, Instant},
+};
+
+use futures::future::{ok, Ready};
+use odilia_common::{command::Category, errors::OdiliaError};
+use ssip::Priority;
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+/// How often the input servers are checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// When each input server last sent a heartbeat, and how often it sends them; cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct InputServers(Arc<Mutex<HashMap<String, (Instant, Duration)>>>);
+
+impl InputServers {
+	/// Records a heartbeat from `server`; returns `true` if it was not being watched before.
+	pub fn beat(&self, server: String, interval: Duration) -> bool {
+		self.0.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.insert(server, (Instant::now(), interval))
+			.is_none()
+	}
+	/// The servers which have missed `missed` heartbeats in a row; they are no longer watched, until they send another.
+	fn overdue(&self, missed: u32) -> Vec<String> {
+		let mut servers = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+		let overdue: Vec<String> = servers
+			.iter()
+			.filter(|(_, (last, interval))| last.elapsed() > *interval * missed)
+			.map(|(server, _)| server.clone())
+			.collect();
+		for server in &overdue {
+			servers.remove(server);
+		}
+		overdue
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for InputServers {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.input_servers.clone())
+	}
+}
+
+/// Checks the input servers until `shutdown` is cancelled, reporting (and possibly restarting) any which stopped sending heartbeats.
+#[tracing::instrument(skip_all)]
+pub async fn watch(state: Arc<ScreenReaderState>, shutdown: CancellationToken) -> eyre::Result<()> {
+	let settings = &state.config.input;
+	while shutdown
+		.run_until_cancelled(tokio::time::sleep(CHECK_INTERVAL))
+		.await
+		.is_some()
+	{
+		for server in state.input_servers.overdue(settings.heartbeat_missed) {
+			tracing::warn!(%server, "Input server has stopped sending heartbeats");
+			state.say(
+				Category::System,
+				Priority::Important,
+				state.l10n.format(
+					"input-server-unresponsive",
+					&[("server", &server)],
+				),
+			)
+			.await;
+			let Some((program, args)) = settings.restart_unresponsive.split_first()
+			else {
+				continue;
+			};
+			let status = Command::new(program)
+				.args(args)
+				.arg(&server)
+				.stdin(Stdio::null())
+				.status()
+				.await;
+			match status {
+				Ok(status) if status.success() => {
+					tracing::info!(%server, "Asked for the input server to be restarted");
+				}
+				Ok(status) => {
+					tracing::error!(%server, %status, "Could not restart the input server");
+				}
+				Err(e) => {
+					tracing::error!(%server, "Could not restart the input server: {e}");
+				}
+			}
+		}
+	}
+	Ok(())
+}
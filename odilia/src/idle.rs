@@ -0,0 +1,145 @@
+//! Noticing when the user is away from the computer, so that announcements they would miss are held back until they return.
+//!
+//! The session's idle hint comes from logind; the desktop sets it once there has been no input for a while, usually when it blanks the screen.
+//! Only once it has been set for `idle.away_after_secs` does the user count as away, so that a short pause does not hide anything.
+
+use std::{
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use futures::StreamExt;
+use odilia_common::command::Category;
+use ssip::Priority;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+	capture::{SpeechSource, CURRENT_SOURCE},
+	state::ScreenReaderState,
+};
+
+#[zbus::proxy(
+	interface = "org.freedesktop.login1.Session",
+	default_service = "org.freedesktop.login1",
+	default_path = "/org/freedesktop/login1/session/auto"
+)]
+trait Session {
+	#[zbus(property)]
+	fn idle_hint(&self) -> zbus::Result<bool>;
+}
+
+#[derive(Debug, Default)]
+struct Held {
+	away: bool,
+	messages: Vec<String>,
+	/// How many announcements were not kept, because `max_held` had been reached.
+	dropped: usize,
+}
+
+/// Whether the user is away, and what they have missed since; cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct Away(Arc<Mutex<Held>>);
+
+impl Away {
+	fn held(&self) -> std::sync::MutexGuard<'_, Held> {
+		self.0.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+	}
+	/// Whether the user currently counts as away.
+	pub fn is_away(&self) -> bool {
+		self.held().away
+	}
+	/// If the user is away, keeps `message` (or only counts it, once `max` are kept) and returns `true`; it should then not be spoken.
+	pub fn hold(&self, message: String, max: usize) -> bool {
+		let mut held = self.held();
+		if !held.away {
+			return false;
+		}
+		if held.messages.len() < max {
+			held.messages.push(message);
+		} else {
+			held.dropped += 1;
+		}
+		true
+	}
+	fn leave(&self) {
+		self.held().away = true;
+	}
+	/// Marks the user as back, and returns what they missed: the kept announcements, and the total number of them.
+	fn back(&self) -> (Vec<String>, usize) {
+		let mut held = self.held();
+		let Held { messages, dropped, .. } = std::mem::take(&mut *held);
+		let count = messages.len() + dropped;
+		(messages, count)
+	}
+}
+
+/// Tells the user what they missed, once they are back.
+async fn summarise(state: &ScreenReaderState, messages: Vec<String>, count: usize) {
+	if count == 0 {
+		return;
+	}
+	let summary = if count == 1 {
+		state.l10n.get("while-you-were-away-one")
+	} else {
+		state.l10n
+			.format("while-you-were-away", &[("count", &count.to_string())])
+	};
+	CURRENT_SOURCE
+		.scope(SpeechSource::Notification, async {
+			state.say(Category::Notification, Priority::Important, summary).await;
+			if state.config.idle.read_held {
+				for message in messages {
+					state.say(
+						Category::Notification,
+						Priority::Notification,
+						message,
+					)
+					.await;
+				}
+			}
+		})
+		.await;
+}
+
+/// Follows the session's idle hint until `shutdown` is cancelled, keeping `state.away` up to date.
+/// Does nothing if disabled, or if logind is not available.
+#[tracing::instrument(skip_all)]
+pub async fn watch(state: Arc<ScreenReaderState>, shutdown: CancellationToken) -> eyre::Result<()> {
+	if !state.config.idle.enabled {
+		return Ok(());
+	}
+	let session = match zbus::Connection::system().await {
+		Ok(system) => SessionProxy::new(&system).await?,
+		Err(e) => {
+			tracing::info!("Not watching for the user being away, since the system bus is not available: {e}");
+			return Ok(());
+		}
+	};
+	let away_after = Duration::from_secs(state.config.idle.away_after_secs);
+	let mut changes = session.receive_idle_hint_changed().await;
+	// when the user counts as away, if the session is idle but they do not yet
+	let mut away_at: Option<Instant> = None;
+	loop {
+		tokio::select! {
+			Some(change) = changes.next() => {
+				if change.get().await? {
+					if away_at.is_none() && !state.away.is_away() {
+						away_at = Some(Instant::now() + away_after);
+					}
+				} else {
+					away_at = None;
+					let (messages, count) = state.away.back();
+					summarise(&state, messages, count).await;
+				}
+			},
+			() = tokio::time::sleep_until(away_at.unwrap_or_else(Instant::now)), if away_at.is_some() => {
+				away_at = None;
+				tracing::debug!("The user is away; holding back announcements");
+				state.away.leave();
+			},
+			() = shutdown.cancelled() => break,
+		}
+	}
+	Ok(())
+}
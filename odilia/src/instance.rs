@@ -0,0 +1,61 @@
+//! Making sure only one Odilia runs per session.
+//!
+//! Every instance owns the well-known name [`BUS_NAME`] on the session bus; the bus daemon guarantees that only one connection can own it at a time, so there is no race between two instances starting at once.
+//! An instance started with `--replace` takes the name over, and the old instance shuts down cleanly when it notices it has lost the name.
+
+use eyre::bail;
+use futures::StreamExt;
+use tokio_util::sync::CancellationToken;
+use zbus::fdo::{DBusProxy, RequestNameFlags, RequestNameReply};
+
+/// The name owned by the running instance of Odilia.
+pub const BUS_NAME: &str = "app.odilia.Screenreader";
+
+/// Claims [`BUS_NAME`] on the session bus.
+/// The returned connection must be kept alive for as long as Odilia is running, since the name is released when it is dropped.
+/// # Errors
+/// If Odilia is already running and `replace` is false, or the session bus is not available.
+#[tracing::instrument(err)]
+pub async fn claim(replace: bool) -> eyre::Result<zbus::Connection> {
+	let session = zbus::Connection::session().await?;
+	let mut flags = RequestNameFlags::AllowReplacement | RequestNameFlags::DoNotQueue;
+	if replace {
+		flags |= RequestNameFlags::ReplaceExisting;
+	}
+	match session.request_name_with_flags(BUS_NAME, flags).await? {
+		RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner => Ok(session),
+		RequestNameReply::Exists | RequestNameReply::InQueue if replace => {
+			bail!("Odilia is already running, and could not be replaced.")
+		}
+		RequestNameReply::Exists | RequestNameReply::InQueue => {
+			bail!("Odilia is already running; use --replace to replace it.")
+		}
+	}
+}
+
+/// Cancels `shutdown` when another instance takes over [`BUS_NAME`].
+/// # Errors
+/// If the name ownership can not be monitored.
+#[tracing::instrument(skip_all, err)]
+pub async fn watch_for_replacement(
+	session: zbus::Connection,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	let dbus = DBusProxy::new(&session).await?;
+	let mut name_lost = dbus.receive_name_lost().await?;
+	loop {
+		tokio::select! {
+			Some(lost) = name_lost.next() => {
+				if lost.args()?.name() == BUS_NAME {
+					tracing::info!("Another instance of Odilia has replaced this one; shutting down.");
+					shutdown.cancel();
+					break;
+				}
+			}
+			() = shutdown.cancelled() => {
+				break;
+			}
+		}
+	}
+	Ok(())
+}
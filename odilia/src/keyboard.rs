@@ -0,0 +1,203 @@
+//! Announcing changes to the lock keys (caps lock, num lock and scroll lock) and to the keyboard layout.
+//!
+//! The locks are read from the keyboards' LEDs in sysfs, which any user can read, so this works on any desktop (and on the console) without access to the input devices.
+//! Layout switches are followed through GNOME's input sources setting with `gsettings monitor`; the name of a layout comes from the XKB rules.
+
+use std::{process::Stdio, sync::Arc, time::Duration};
+
+use odilia_common::command::Category;
+use ssip::Priority;
+use tokio::{
+	io::{AsyncBufReadExt, BufReader},
+	process::Command,
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::state::ScreenReaderState;
+
+const LEDS: &str = "/sys/class/leds";
+const XKB_RULES: &str = "/usr/share/X11/xkb/rules/evdev.lst";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Lock {
+	Caps,
+	Num,
+	Scroll,
+}
+
+impl Lock {
+	const ALL: [Lock; 3] = [Lock::Caps, Lock::Num, Lock::Scroll];
+	/// The suffix of the LEDs for this lock, like `input3::capslock`.
+	fn led(self) -> &'static str {
+		match self {
+			Lock::Caps => "::capslock",
+			Lock::Num => "::numlock",
+			Lock::Scroll => "::scrolllock",
+		}
+	}
+	fn message(self, on: bool) -> &'static str {
+		match (self, on) {
+			(Lock::Caps, true) => "caps-lock-on",
+			(Lock::Caps, false) => "caps-lock-off",
+			(Lock::Num, true) => "num-lock-on",
+			(Lock::Num, false) => "num-lock-off",
+			(Lock::Scroll, true) => "scroll-lock-on",
+			(Lock::Scroll, false) => "scroll-lock-off",
+		}
+	}
+}
+
+/// Whether each of [`Lock::ALL`] is on, or `None` if no keyboard has an LED for it.
+/// A lock counts as on if it is on for any keyboard.
+async fn locks() -> std::io::Result<[Option<bool>; 3]> {
+	let mut states = [None; 3];
+	let mut leds = tokio::fs::read_dir(LEDS).await?;
+	while let Some(led) = leds.next_entry().await? {
+		let name = led.file_name();
+		let name = name.to_string_lossy();
+		let Some(i) = Lock::ALL.iter().position(|lock| name.ends_with(lock.led())) else {
+			continue;
+		};
+		let Ok(brightness) = tokio::fs::read_to_string(led.path().join("brightness")).await
+		else {
+			continue;
+		};
+		let on = brightness.trim() != "0";
+		states[i] = Some(states[i].unwrap_or(false) || on);
+	}
+	Ok(states)
+}
+
+/// Checks the lock keys every `keyboard.locks_poll_ms` until `shutdown` is cancelled, saying whenever one of them changes.
+/// Does nothing if disabled, or if there are no LEDs to read.
+#[tracing::instrument(skip_all)]
+pub async fn watch_locks(
+	state: Arc<ScreenReaderState>,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	if !state.config.keyboard.announce_locks {
+		return Ok(());
+	}
+	let mut previous =
+		match locks().await {
+			Ok(states) => states,
+			Err(e) => {
+				tracing::info!("Not announcing lock keys, since their LEDs can not be read: {e}");
+				return Ok(());
+			}
+		};
+	let interval = Duration::from_millis(state.config.keyboard.locks_poll_ms);
+	while shutdown
+		.run_until_cancelled(tokio::time::sleep(interval))
+		.await
+		.is_some()
+	{
+		let current = locks().await?;
+		for ((lock, before), now) in Lock::ALL.iter().zip(previous).zip(current) {
+			if let (Some(before), Some(now)) = (before, now) {
+				if before != now {
+					state.say(
+						Category::System,
+						Priority::Message,
+						state.l10n.get(lock.message(now)),
+					)
+					.await;
+				}
+			}
+		}
+		previous = current;
+	}
+	Ok(())
+}
+
+/// The current input source in a line printed by `gsettings monitor org.gnome.desktop.input-sources mru-sources`, like `mru-sources: [('xkb', 'de+neo'), ('xkb', 'us')]`.
+fn current_source(line: &str) -> Option<&str> {
+	let (_, sources) = line.split_once("[(")?;
+	let (_, id) = sources.split_once(", '")?;
+	id.split_once('\'').map(|(id, _)| id)
+}
+
+/// The description of the XKB layout `id` (like `de`, or `de+neo` with a variant) in `rules`, the contents of an `evdev.lst` file.
+fn layout_name(rules: &str, id: &str) -> Option<String> {
+	let (layout, variant) = match id.split_once('+') {
+		Some((layout, variant)) => (layout, Some(variant)),
+		None => (id, None),
+	};
+	let mut section = "";
+	for line in rules.lines() {
+		if let Some(name) = line.strip_prefix("! ") {
+			section = name.trim();
+			continue;
+		}
+		let Some((name, description)) = line.trim().split_once(char::is_whitespace) else {
+			continue;
+		};
+		let description = description.trim();
+		match (section, variant) {
+			("layout", None) if name == layout => return Some(description.to_string()),
+			("variant", Some(variant)) if name == variant => {
+				if let Some(description) = description
+					.strip_prefix(layout)
+					.and_then(|rest| rest.strip_prefix(": "))
+				{
+					return Some(description.to_string());
+				}
+			}
+			_ => {}
+		}
+	}
+	None
+}
+
+/// Follows GNOME's current input source until `shutdown` is cancelled, saying the name of the layout whenever it is switched.
+/// Does nothing if disabled, or if `gsettings` is not available.
+#[tracing::instrument(skip_all)]
+pub async fn watch_layout(
+	state: Arc<ScreenReaderState>,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	if !state.config.keyboard.announce_layout {
+		return Ok(());
+	}
+	// killed when this returns
+	let mut monitor = match Command::new("gsettings")
+		.args(["monitor", "org.gnome.desktop.input-sources", "mru-sources"])
+		.stdout(Stdio::piped())
+		.stderr(Stdio::null())
+		.kill_on_drop(true)
+		.spawn()
+	{
+		Ok(monitor) => monitor,
+		Err(e) => {
+			tracing::info!("Not announcing keyboard layouts, since gsettings is not available: {e}");
+			return Ok(());
+		}
+	};
+	let stdout = monitor
+		.stdout
+		.take()
+		.ok_or_else(|| eyre::eyre!("gsettings has no stdout"))?;
+	let rules = tokio::fs::read_to_string(XKB_RULES).await.unwrap_or_default();
+	let mut lines = BufReader::new(stdout).lines();
+	let mut previous: Option<String> = None;
+	while let Some(line) = shutdown.run_until_cancelled(lines.next_line()).await {
+		let Some(line) = line? else {
+			break;
+		};
+		let Some(id) = current_source(&line) else {
+			continue;
+		};
+		if previous.as_deref() == Some(id) {
+			continue;
+		}
+		let name = layout_name(&rules, id).unwrap_or_else(|| id.to_string());
+		state.say(
+			Category::System,
+			Priority::Message,
+			state.l10n.format("keyboard-layout", &[("name", &name)]),
+		)
+		.await;
+		previous = Some(id.to_string());
+	}
+	Ok(())
+}
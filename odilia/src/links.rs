@@ -0,0 +1,367 @@
+//! Moving from link to link in a document, however its application exposes them.
+//!
+//! Most applications give each link an object of its own, which is a child of the text it is in; some only mark a range of that text as a link.
+//! Either way, the object the text belongs to lists its links through the `Hypertext` interface, with where each starts and ends in its text, so links are found through it, in reading order.
+//! Only what is in the cache is searched, like in [`reading`](crate::reading); the links of each object are fetched from its application as the search reaches it.
+
+use std::{future::Future, sync::Arc};
+
+use atspi_common::{Interface, Role, State};
+use odilia_cache::{Cache, CacheItem, Convertable};
+use odilia_common::{cache::AccessiblePrimitive, errors::OdiliaError, events::Direction};
+
+use crate::reading::MAX_OBJECTS;
+
+/// Roles of the object a search for links stays within.
+const DOCUMENTS: [Role; 5] = [
+	Role::DocumentWeb,
+	Role::DocumentFrame,
+	Role::DocumentText,
+	Role::DocumentEmail,
+	Role::Frame,
+];
+
+/// The character which stands for an object embedded in the text of its parent, like a link of its own.
+const EMBEDDED_OBJECT: char = '\u{fffc}';
+
+/// A link, as a range of the text of the object it is in.
+#[derive(Debug, Clone)]
+pub struct Link {
+	/// The object whose text the link is in.
+	pub container: CacheItem,
+	/// The link's own object, as the `Hypertext` interface lists it; for a range of text, this may not be an accessible of its own.
+	pub key: AccessiblePrimitive,
+	/// The link's own object, if it is a cached child of the container.
+	pub object: Option<CacheItem>,
+	/// Where the link starts in the container's text, in characters.
+	pub start: usize,
+	/// Where the link ends in the container's text, in characters.
+	pub end: usize,
+	pub visited: bool,
+}
+
+impl Link {
+	/// The link's range of its container's text.
+	fn range(&self) -> String {
+		self.container
+			.text
+			.chars()
+			.skip(self.start)
+			.take(self.end - self.start)
+			.collect()
+	}
+	/// The text of the link.
+	/// A link of its own is only an embedded object character in its container's text, so its own text is read instead, or its name if it has none, like a linked image.
+	/// # Errors
+	/// If the name of the link has to be fetched from its application, and can not be.
+	pub async fn text(&self, connection: &zbus::Connection) -> Result<String, OdiliaError> {
+		if let Some(text) = self.cached_text() {
+			return Ok(text);
+		}
+		Ok(self.key.clone().into_accessible(connection).await?.name().await?)
+	}
+	/// The text of the link, if it is known without asking its application for its name.
+	fn cached_text(&self) -> Option<String> {
+		let range = self.range();
+		if !range.contains(EMBEDDED_OBJECT) {
+			return Some(range);
+		}
+		self.object
+			.as_ref()
+			.filter(|object| !object.text.is_empty())
+			.map(|object| object.text.clone())
+	}
+}
+
+/// The document `item` is in, or the outermost cached object above it if it is in none.
+pub fn document(item: &CacheItem) -> CacheItem {
+	let mut current = item.clone();
+	while !DOCUMENTS.contains(&current.role) {
+		match current.parent() {
+			Ok(parent) if parent.object != current.object => current = parent,
+			_ => break,
+		}
+	}
+	current
+}
+
+/// `root` and every cached object in it, in reading order.
+pub fn in_order(root: CacheItem) -> Vec<CacheItem> {
+	let mut items = Vec::new();
+	let mut stack = vec![root];
+	while let Some(item) = stack.pop() {
+		if items.len() == MAX_OBJECTS {
+			tracing::debug!("Stopped searching for links after {MAX_OBJECTS} objects");
+			break;
+		}
+		// pushed in reverse, so that they are popped in reading order
+		stack.extend(item.get_children().unwrap_or_default().into_iter().rev());
+		items.push(item);
+	}
+	items
+}
+
+/// The links in the text of `container`, in the order of their text.
+/// Children which are not links, like images embedded in a paragraph, are left out.
+async fn links_in(container: &CacheItem, cache: &Arc<Cache>) -> Result<Vec<Link>, OdiliaError> {
+	if !container.interfaces.contains(Interface::Hypertext) {
+		return Ok(Vec::new());
+	}
+	let connection = &cache.connection;
+	let accessible = container.object.clone().into_accessible(connection).await?;
+	let hypertext = accessible.to_hypertext().await?;
+	let mut links = Vec::new();
+	for index in 0..hypertext.get_nlinks().await? {
+		let key = AccessiblePrimitive::from(hypertext.get_link(index).await?);
+		let object = container
+			.children
+			.iter()
+			.any(|child| child.key == key)
+			.then(|| cache.get(&key))
+			.flatten();
+		if object.as_ref().is_some_and(|object| object.role != Role::Link) {
+			continue;
+		}
+		let link = key.clone().into_accessible(connection).await?;
+		let hyperlink = link.to_hyperlink().await?;
+		let (start, end) = (hyperlink.start_index().await?, hyperlink.end_index().await?);
+		let visited = match &object {
+			Some(object) => object.states.contains(State::Visited),
+			// a range of text may not be an accessible of its own
+			None => link
+				.get_state()
+				.await
+				.is_ok_and(|states| states.contains(State::Visited)),
+		};
+		links.push(Link {
+			container: container.clone(),
+			key,
+			object,
+			start: start.try_into()?,
+			end: end.try_into()?,
+			visited,
+		});
+	}
+	links.sort_by_key(|link| link.start);
+	Ok(links)
+}
+
+/// The next link after (or the last one before) `offset` in the text of `from`, or after (before) `from` if no offset is given, in the document `from` is in; only unvisited ones if `unvisited` is set.
+/// # Errors
+/// If the links of an object can not be fetched from its application.
+pub async fn find(
+	from: &CacheItem,
+	offset: Option<usize>,
+	direction: &Direction,
+	unvisited: bool,
+	cache: &Arc<Cache>,
+) -> Result<Option<Link>, OdiliaError> {
+	let items = in_order(document(from));
+	search(&items, from, offset, direction, unvisited, |container| {
+		let cache = Arc::clone(cache);
+		async move { links_in(&container, &cache).await }
+	})
+	.await
+}
+
+/// Like [`find`], over `items` in reading order, getting the links of each object searched from `links_of`.
+async fn search<F, Fut>(
+	items: &[CacheItem],
+	from: &CacheItem,
+	offset: Option<usize>,
+	direction: &Direction,
+	unvisited: bool,
+	mut links_of: F,
+) -> Result<Option<Link>, OdiliaError>
+where
+	F: FnMut(CacheItem) -> Fut,
+	Fut: Future<Output = Result<Vec<Link>, OdiliaError>>,
+{
+	let (mut origin, mut offset) = (from.object.clone(), offset);
+	// a link of its own comes after the text it is in, which holds the links around it, so the search starts where it is in that text
+	if from.role == Role::Link {
+		if let Some(parent) = items.iter().find(|item| item.object == from.parent.key) {
+			let links = links_of(parent.clone()).await?;
+			if let Some(link) = links.iter().find(|link| link.key == from.object) {
+				(origin, offset) = (parent.object.clone(), Some(link.start));
+			}
+		}
+	}
+	let Some(position) = items.iter().position(|item| item.object == origin) else {
+		return Ok(None);
+	};
+	let candidates: Vec<&CacheItem> = match direction {
+		Direction::Forward => items[position..].iter().collect(),
+		Direction::Backward => items[..=position].iter().rev().collect(),
+	};
+	for container in candidates {
+		let mut links = links_of(container.clone()).await?;
+		links.retain(|link| !(unvisited && link.visited));
+		if container.object == origin {
+			match (direction, offset) {
+				(Direction::Forward, Some(offset)) => {
+					links.retain(|link| link.start > offset);
+				}
+				(Direction::Backward, Some(offset)) => {
+					links.retain(|link| link.start < offset);
+				}
+				// without an offset, only the links in the objects around `from` count
+				(_, None) => continue,
+			}
+		}
+		let link = match direction {
+			Direction::Forward => links.into_iter().next(),
+			Direction::Backward => links.pop(),
+		};
+		if link.is_some() {
+			return Ok(link);
+		}
+	}
+	Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{collections::HashMap, sync::Weak};
+
+	use atspi_common::{InterfaceSet, StateSet};
+	use futures::{executor::block_on, future::ready};
+	use odilia_cache::CacheRef;
+
+	use super::*;
+
+	fn key(id: &str) -> AccessiblePrimitive {
+		AccessiblePrimitive {
+			id: format!("/org/a11y/atspi/accessible/{id}").into(),
+			sender: ":1.2".into(),
+		}
+	}
+
+	fn item(id: &str, parent: &str, role: Role, text: &str, children: &[&str]) -> CacheItem {
+		CacheItem {
+			object: key(id),
+			app: key("root"),
+			parent: CacheRef::new(key(parent)),
+			index: None,
+			children_num: Some(children.len()),
+			interfaces: InterfaceSet::new(
+				Interface::Accessible | Interface::Text | Interface::Hypertext,
+			),
+			role,
+			states: StateSet::empty(),
+			text: text.to_string(),
+			children: children.iter().map(|child| CacheRef::new(key(child))).collect(),
+			shortcut: None,
+			value: None,
+			cache: Weak::new(),
+		}
+	}
+
+	/// A document with two paragraphs: the first has three links of their own, the second one, which is only a range of its text.
+	fn document() -> Vec<CacheItem> {
+		vec![
+			item("doc", "root", Role::DocumentWeb, "\u{fffc}\u{fffc}", &["p1", "p2"]),
+			item(
+				"p1",
+				"doc",
+				Role::Paragraph,
+				"a \u{fffc} b \u{fffc} c \u{fffc}",
+				&["l1", "l2", "l3"],
+			),
+			item("l1", "p1", Role::Link, "first", &[]),
+			item("l2", "p1", Role::Link, "second", &[]),
+			item("l3", "p1", Role::Link, "", &[]),
+			item("p2", "doc", Role::Paragraph, "see the fourth", &[]),
+		]
+	}
+
+	/// The links of each object in `items`, as their applications would list them.
+	fn links(items: &[CacheItem]) -> HashMap<AccessiblePrimitive, Vec<Link>> {
+		let by_key: HashMap<_, _> =
+			items.iter().map(|item| (item.object.clone(), item.clone())).collect();
+		let link = |container: &str, id: &str, start: usize, end: usize| Link {
+			container: by_key[&key(container)].clone(),
+			key: key(id),
+			object: by_key.get(&key(id)).cloned(),
+			start,
+			end,
+			visited: false,
+		};
+		HashMap::from([
+			(
+				key("p1"),
+				vec![
+					link("p1", "l1", 2, 3),
+					link("p1", "l2", 6, 7),
+					link("p1", "l3", 10, 11),
+				],
+			),
+			(key("p2"), vec![link("p2", "l4", 8, 14)]),
+		])
+	}
+
+	/// The id of the link found from `from`, at `offset` in its text.
+	fn next(from: &str, offset: Option<usize>, direction: &Direction) -> Option<String> {
+		let items = document();
+		let links = links(&items);
+		let from = items
+			.iter()
+			.find(|item| item.object == key(from))
+			.cloned()
+			.expect("the item is in the document");
+		let found =
+			block_on(search(&items, &from, offset, direction, false, |container| {
+				ready(Ok(links.get(&container.object).cloned().unwrap_or_default()))
+			}))
+			.expect("links are listed");
+		found.map(|link| link.key.id.rsplit('/').next().unwrap_or_default().to_string())
+	}
+
+	#[test]
+	fn from_a_link_the_next_one_is_in_the_same_paragraph() {
+		assert_eq!(next("l2", None, &Direction::Forward).as_deref(), Some("l3"));
+	}
+
+	#[test]
+	fn from_a_link_the_previous_one_is_before_it() {
+		assert_eq!(next("l2", None, &Direction::Backward).as_deref(), Some("l1"));
+	}
+
+	#[test]
+	fn from_the_last_link_of_a_paragraph_the_search_goes_on_after_it() {
+		assert_eq!(next("l3", None, &Direction::Forward).as_deref(), Some("l4"));
+	}
+
+	#[test]
+	fn from_the_first_link_there_is_none_before() {
+		assert_eq!(next("l1", None, &Direction::Backward), None);
+	}
+
+	#[test]
+	fn from_a_caret_the_links_around_it_are_found() {
+		assert_eq!(next("p1", Some(4), &Direction::Forward).as_deref(), Some("l2"));
+		assert_eq!(next("p1", Some(4), &Direction::Backward).as_deref(), Some("l1"));
+	}
+
+	#[test]
+	fn a_range_of_text_is_its_own_text() {
+		let items = document();
+		let link = &links(&items)[&key("p2")][0];
+		assert_eq!(link.cached_text().as_deref(), Some("fourth"));
+	}
+
+	#[test]
+	fn a_link_of_its_own_is_read_from_its_object() {
+		let items = document();
+		let link = &links(&items)[&key("p1")][1];
+		assert_eq!(link.range(), "\u{fffc}");
+		assert_eq!(link.cached_text().as_deref(), Some("second"));
+	}
+
+	#[test]
+	fn a_link_without_text_needs_its_name() {
+		let items = document();
+		let link = &links(&items)[&key("p1")][2];
+		assert_eq!(link.cached_text(), None);
+	}
+}
@@ -0,0 +1,78 @@
+//! Feedback while a document loads: the busy earcon repeats from when the document in the focused application sets its `Busy` state until it clears it.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use futures::future::{ok, Ready};
+use odilia_common::{cache::AccessiblePrimitive, errors::OdiliaError};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+	earcons::{Earcon, Earcons},
+	state::ScreenReaderState,
+	tower::from_state::TryFromState,
+};
+
+/// The document which is loading, and the token stopping its earcon.
+type Current = Option<(AccessiblePrimitive, CancellationToken)>;
+
+/// Which document is loading, if any; cheap to clone.
+#[derive(Debug, Clone)]
+pub struct Loading {
+	current: Arc<Mutex<Current>>,
+	/// Stops the earcon when Odilia shuts down.
+	shutdown: CancellationToken,
+}
+
+impl Loading {
+	pub fn new(shutdown: CancellationToken) -> Self {
+		Loading { current: Arc::default(), shutdown }
+	}
+	fn current(&self) -> std::sync::MutexGuard<'_, Current> {
+		self.current.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+	/// Starts repeating the busy earcon for `document`, replacing the one of any other document, since only one can be focused.
+	/// Returns `false` if `document` was already loading.
+	pub fn start(&self, document: AccessiblePrimitive, earcons: &Earcons) -> bool {
+		let mut current = self.current();
+		if current.as_ref().is_some_and(|(loading, _)| *loading == document) {
+			return false;
+		}
+		if let Some((_, stop)) = current.take() {
+			stop.cancel();
+		}
+		let stop = self.shutdown.child_token();
+		let earcons = earcons.clone();
+		let interval = earcons.busy_interval();
+		let stopped = stop.clone();
+		tokio::spawn(async move {
+			stopped.run_until_cancelled(async {
+				loop {
+					earcons.play(Earcon::Busy);
+					tokio::time::sleep(interval).await;
+				}
+			})
+			.await
+		});
+		*current = Some((document, stop));
+		true
+	}
+	/// Stops the earcon of `document`; returns whether it was loading.
+	pub fn finish(&self, document: &AccessiblePrimitive) -> bool {
+		let mut current = self.current();
+		if !current.as_ref().is_some_and(|(loading, _)| loading == document) {
+			return false;
+		}
+		if let Some((_, stop)) = current.take() {
+			stop.cancel();
+		}
+		true
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for Loading {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.loading.clone())
+	}
+}
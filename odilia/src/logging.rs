@@ -1,17 +1,176 @@
 //!Logging with the [`tracing`] crate.
 //!
-//! Not much here yet, but this will get more complex if we decide to add other layers for error
-//! reporting, tokio-console, etc.
+//! Log files are rotated according to `log.rotation`, and the oldest rotated files are removed once they take up more than `log.max_total_mb`.
+//! The log level can be changed while Odilia runs, with [`set_level`].
+//! Built with the `otlp` feature, spans are also exported to `log.otlp_endpoint`, if it is set.
 
-use std::{env, io};
+use std::{
+	env,
+	fs::{self, File},
+	io::{self, Write},
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex, OnceLock, PoisonError},
+	time::{SystemTime, UNIX_EPOCH},
+};
 
 use eyre::Context;
-use odilia_common::settings::{log::LoggingKind, ApplicationConfig};
+use odilia_common::settings::{
+	log::{LogRotation, LogSettings, LoggingKind},
+	ApplicationConfig,
+};
 use tracing_error::ErrorLayer;
-use tracing_subscriber::{prelude::*, EnvFilter};
+use tracing_subscriber::{prelude::*, reload, EnvFilter};
 use tracing_tree::time::Uptime;
 use tracing_tree::HierarchicalLayer;
 
+const BYTES_PER_MB: u64 = 1024 * 1024;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.unwrap_or_default()
+		.as_secs()
+}
+
+struct OpenLog {
+	file: File,
+	/// How much has been written to the file so far.
+	written: u64,
+	/// The day (since the unix epoch) the file was started on.
+	day: u64,
+}
+
+/// A log file which is rotated according to [`LogSettings`].
+struct RollingFile {
+	path: PathBuf,
+	rotation: LogRotation,
+	max_file: u64,
+	max_total: u64,
+	log: Mutex<OpenLog>,
+}
+
+impl RollingFile {
+	/// Starts a new log file at `path`; the one left by the last run is rotated, unless rotation is off.
+	fn create(path: PathBuf, settings: &LogSettings) -> io::Result<Self> {
+		let file = if settings.rotation == LogRotation::Never {
+			File::create(&path)?
+		} else {
+			File::options().append(true).create(true).open(&path)?
+		};
+		let written = file.metadata()?.len();
+		let rolling = RollingFile {
+			rotation: settings.rotation,
+			max_file: settings.max_file_mb.saturating_mul(BYTES_PER_MB),
+			max_total: settings.max_total_mb.saturating_mul(BYTES_PER_MB),
+			log: Mutex::new(OpenLog {
+				file,
+				written,
+				day: now_secs() / SECONDS_PER_DAY,
+			}),
+			path,
+		};
+		if written > 0 {
+			rolling.rotate(&mut rolling
+				.log
+				.lock()
+				.unwrap_or_else(PoisonError::into_inner))?;
+		}
+		Ok(rolling)
+	}
+	/// Moves the current file aside, with the time appended to its name, and starts a new one; then removes the oldest rotated files beyond `max_total`.
+	fn rotate(&self, log: &mut OpenLog) -> io::Result<()> {
+		let now = now_secs();
+		let mut rotated = self.path.clone().into_os_string();
+		rotated.push(format!(".{now}"));
+		fs::rename(&self.path, rotated)?;
+		*log = OpenLog {
+			file: File::create(&self.path)?,
+			written: 0,
+			day: now / SECONDS_PER_DAY,
+		};
+		self.prune()
+	}
+	fn rotated_files(&self) -> io::Result<Vec<(PathBuf, u64)>> {
+		let (Some(dir), Some(name)) = (self.path.parent(), self.path.file_name()) else {
+			return Ok(Vec::new());
+		};
+		let prefix = format!("{}.", name.to_string_lossy());
+		let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+		let mut files = Vec::new();
+		for entry in fs::read_dir(dir)? {
+			let entry = entry?;
+			let file_name = entry.file_name();
+			let Some(time) = file_name
+				.to_string_lossy()
+				.strip_prefix(&prefix)
+				.map(str::to_string)
+			else {
+				continue;
+			};
+			if time.parse::<u64>().is_ok() {
+				files.push((entry.path(), entry.metadata()?.len()));
+			}
+		}
+		// oldest first, by the time in their names
+		files.sort();
+		Ok(files)
+	}
+	fn prune(&self) -> io::Result<()> {
+		let files = self.rotated_files()?;
+		let mut total: u64 = files.iter().map(|(_, len)| len).sum();
+		for (path, len) in files {
+			if total <= self.max_total {
+				break;
+			}
+			fs::remove_file(path)?;
+			total -= len;
+		}
+		Ok(())
+	}
+	fn due(&self, log: &OpenLog) -> bool {
+		match self.rotation {
+			LogRotation::Never => false,
+			LogRotation::Daily => now_secs() / SECONDS_PER_DAY != log.day,
+			LogRotation::Size => log.written >= self.max_file,
+		}
+	}
+}
+
+impl Write for &RollingFile {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let mut log = self.log.lock().unwrap_or_else(PoisonError::into_inner);
+		// a failure to rotate should not lose the message; it is written to the current file instead
+		if self.due(&log) && self.rotate(&mut log).is_err() {
+			log.written = 0;
+			log.day = now_secs() / SECONDS_PER_DAY;
+		}
+		let written = log.file.write(buf)?;
+		log.written += written as u64;
+		Ok(written)
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		self.log.lock().unwrap_or_else(PoisonError::into_inner).file.flush()
+	}
+}
+
+/// Replaces the log filter; set once logging is initialised.
+type Reload = Box<dyn Fn(EnvFilter) -> Result<(), reload::Error> + Send + Sync>;
+static RELOAD: OnceLock<Reload> = OnceLock::new();
+
+/// Changes which messages are logged, like `debug` or `odilia=trace,zbus=warn`, until Odilia exits.
+/// # Errors
+/// If `level` is not a valid filter, or logging was not initialised.
+pub fn set_level(level: &str) -> eyre::Result<()> {
+	let filter = EnvFilter::try_new(level).wrap_err("Invalid log level")?;
+	let reload = RELOAD
+		.get()
+		.ok_or_else(|| eyre::eyre!("Logging is not initialised"))?;
+	reload(filter)?;
+	tracing::info!(level, "Changed the log level");
+	Ok(())
+}
+
 /// Initialise the logging stack
 /// this requires an application configuration structure, so configuration must be initialized before logging is
 pub fn init(config: &ApplicationConfig) -> eyre::Result<()> {
@@ -31,16 +190,27 @@ pub fn init(config: &ApplicationConfig) -> eyre::Result<()> {
 	//this requires boxing because the types returned by this match block would be incompatible otherwise, since we return different layers, or modifications to a layer depending on what we get from the configuration. It is possible to do it otherwise, hopefully, but for now this  would do
 	let final_layer = match &config.log.logger {
 		LoggingKind::File(path) => {
-			let file = std::fs::File::create(path).with_context(|| {
-				format!("creating log file '{}'", path.display())
-			})?;
-			tree.with_writer(file).boxed()
+			let file = RollingFile::create(path.clone(), &config.log).with_context(
+				|| format!("creating log file '{}'", path.display()),
+			)?;
+			tree.with_writer(Arc::new(file)).boxed()
 		}
 		LoggingKind::Tty => tree.with_writer(io::stdout).with_ansi(true).boxed(),
 		LoggingKind::Syslog => tracing_journald::Layer::new()?
 			.with_syslog_identifier("odilia".to_owned())
+			.with_field_prefix(Some("ODILIA".to_owned()))
 			.boxed(),
 	};
+	#[cfg(feature = "otlp")]
+	let otlp = match &config.log.otlp_endpoint {
+		Some(endpoint) => Some(tracing_opentelemetry::layer().with_tracer(
+			otlp_tracer(endpoint)
+				.with_context(|| format!("exporting spans to '{endpoint}'"))?,
+		)),
+		None => None,
+	};
+	#[cfg(not(feature = "otlp"))]
+	let otlp = None::<tracing_subscriber::layer::Identity>;
 	#[cfg(feature = "tokio-console")]
 	let trace_sub = {
 		let console_layer = console_subscriber::spawn();
@@ -50,10 +220,43 @@ pub fn init(config: &ApplicationConfig) -> eyre::Result<()> {
 	};
 	#[cfg(not(feature = "tokio-console"))]
 	let trace_sub = { tracing_subscriber::Registry::default() };
+	let (env_filter, handle) = reload::Layer::new(env_filter);
+	let _ = RELOAD.set(Box::new(move |filter| handle.reload(filter)));
 	trace_sub
 		.with(env_filter)
 		.with(ErrorLayer::default())
 		.with(final_layer)
+		.with(otlp)
 		.init();
+	#[cfg(not(feature = "otlp"))]
+	if config.log.otlp_endpoint.is_some() {
+		tracing::warn!("log.otlp_endpoint is set, but Odilia was built without the otlp feature; no spans are exported");
+	}
 	Ok(())
 }
+
+/// A tracer sending spans to the OTLP collector at `endpoint`, in batches, on the tokio runtime; it is also made the global tracer provider, so that [`shutdown`] can flush it.
+#[cfg(feature = "otlp")]
+fn otlp_tracer(endpoint: &str) -> eyre::Result<opentelemetry_sdk::trace::Tracer> {
+	use opentelemetry::{trace::TracerProvider as _, KeyValue};
+	use opentelemetry_otlp::WithExportConfig;
+	use opentelemetry_sdk::{runtime, trace::TracerProvider, Resource};
+
+	let exporter = opentelemetry_otlp::SpanExporter::builder()
+		.with_tonic()
+		.with_endpoint(endpoint)
+		.build()?;
+	let provider = TracerProvider::builder()
+		.with_batch_exporter(exporter, runtime::Tokio)
+		.with_resource(Resource::new([KeyValue::new("service.name", "odilia")]))
+		.build();
+	let tracer = provider.tracer("odilia");
+	opentelemetry::global::set_tracer_provider(provider);
+	Ok(tracer)
+}
+
+/// Sends the spans which are still waiting to be exported, if any are; call it last thing before exiting.
+pub fn shutdown() {
+	#[cfg(feature = "otlp")]
+	opentelemetry::global::shutdown_tracer_provider();
+}
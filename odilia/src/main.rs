@@ -9,25 +9,99 @@
 #![allow(clippy::multiple_crate_versions)]
 #![feature(impl_trait_in_assoc_type)]
 
+mod adjustments;
+mod app_names;
+mod capture;
 mod cli;
+mod console;
+mod crash;
+mod dbus;
+mod delay;
+mod diagnostics;
+mod earcons;
+mod echo;
+mod event_stream;
 mod events;
+mod focus_watch;
+mod frames;
+mod geometry;
+mod heartbeat;
+mod idle;
+mod instance;
+mod keyboard;
+mod links;
+mod loading;
 mod logging;
+mod modes;
+mod navigator;
+mod pending;
+mod portal;
+mod power;
+mod prompt;
+mod reading;
+mod reconnect;
+mod restart;
+mod scroll;
+mod shutdown;
+mod speech_format;
+mod startup;
 mod state;
+mod systemd;
+mod tables;
 mod tower;
+mod transcript;
+mod tray;
+mod values;
+mod volume;
+mod warm_start;
 
-use std::{fs, path::PathBuf, process::exit, sync::Arc, time::Duration};
+use std::{
+	fs,
+	path::PathBuf,
+	process::exit,
+	sync::Arc,
+	time::{Duration, Instant},
+};
 
+use crate::adjustments::{Adjustments, SettingChange};
+use crate::capture::{SpeechCapture, SpeechSource, CURRENT_SOURCE};
 use crate::cli::Args;
+use crate::delay::{DelayedSpeech, Slot};
+use crate::diagnostics::EventCounts;
+use crate::earcons::{Earcon, Earcons};
+use crate::echo::Echo;
+use crate::focus_watch::FocusWatch;
+use crate::frames::Frames;
+use crate::geometry::Geometry;
+use crate::heartbeat::InputServers;
+use crate::loading::Loading;
+use crate::modes::Modes;
+use crate::navigator::Navigator;
+use crate::pending::PendingAction;
+use crate::prompt::TextEntry;
+use crate::reading::SayAll;
+use crate::restart::Snapshot;
+use crate::shutdown::{Shutdown, Stage};
+use crate::speech_format::SpeechFormatter;
 use crate::state::AccessibleHistory;
+use crate::state::AtspiCache;
+use crate::state::AtspiEvent;
 use crate::state::Command;
+use crate::state::Configured;
 use crate::state::CurrentCaretPos;
+use crate::state::InputEvent;
 use crate::state::LastCaretPos;
-use crate::state::LastFocused;
 use crate::state::ScreenReaderState;
+use crate::state::ShutdownRequest;
 use crate::state::Speech;
-use crate::tower::Handlers;
+use crate::state::CACHE_LOOKUP_TIMEOUT;
+use crate::state::{CurrentMode, LastFocused};
+use crate::tables::{Line, Tables};
 use crate::tower::{cache_event::ActiveAppEvent, CacheEvent};
-use atspi::RelationType;
+use crate::tower::{service_set::Order, Handlers};
+use crate::transcript::SpeechHistory;
+use crate::values::{Change, ValueChanges};
+use atspi::{CoordType, Interface, RelationType, Role, State};
 use clap::Parser;
 use eyre::WrapErr;
 use figment::{
@@ -35,23 +109,46 @@ use figment::{
 	Figment,
 };
 use futures::{future::FutureExt, StreamExt};
+use odilia_cache::{repair, Cache, CacheExt, CacheItem, Convertable};
 use odilia_common::{
-	command::{CaretPos, Focus, IntoCommands, OdiliaCommand, Speak, TryIntoCommands},
-	errors::OdiliaError,
-	settings::ApplicationConfig,
+	cache::AccessiblePrimitive,
+	command::{
+		Activate, Announcement, CaretPos, Category, Focus, IntoCommands, OdiliaCommand,
+		Route, ScrollIntoView, SetCaret, Silence, Speak, SpeakChar, SpeechControl,
+		TryIntoCommands,
+	},
+	errors::{CacheError, OdiliaError},
+	events::{
+		ActivateReviewed, ChangeMode, Custom, CycleEcho, CycleLanguage, CycleMode,
+		CycleOutput, CyclePunctuation, Direction, EnterText, EventDiagnostics, EventType,
+		Heartbeat, Inspect, KeyEcho, NavigateLink, NavigateObject, ObjectDirection, Quit,
+		ReadColumn, ReadContainer, ReadRow, ResetAdjustments, Restart, ReviewLine,
+		SayLocation, SayMode, ScreenReaderEvent, SearchHistory, SetLogLevel, SkipSayAll,
+		StopSpeech, StructuralNavigation, UserActivity,
+	},
+	i18n::Localizer,
+	modes::ScreenReaderMode,
+	privacy,
+	settings::{
+		console::ConsoleMode,
+		echo::EchoSettings,
+		input::InputSettings,
+		speech::{PageLoad, SpeechSettings, Verbosity},
+		tables::TableSettings,
+		ApplicationConfig, APP_DEFAULTS,
+	},
 };
 
 use odilia_notify::listen_to_dbus_notifications;
 use ssip::Priority;
-use ssip::Request as SSIPRequest;
+use ssip_client_async::{ClientScope, MessageScope, Request as SSIPRequest};
 use tokio::{
 	signal::unix::{signal, SignalKind},
 	sync::mpsc,
-	time::timeout,
 };
-use tokio_util::{sync::CancellationToken, task::TaskTracker};
+use tokio_util::sync::CancellationToken;
 
-use atspi_common::events::{document, object};
+use atspi_common::events::{document, object, window};
 use tracing::Instrument;
 #[tracing::instrument(skip(state, shutdown))]
 async fn notifications_monitor(
@@ -64,9 +161,17 @@ async fn notifications_monitor(
 	loop {
 		tokio::select! {
 		    Some(notification) = stream.next() => {
-		      let notification_message =
-			format!("new notification: {}, {}, {}.", notification.app_name, notification.title, notification.body);
-		      state.say(Priority::Important, notification_message).await;
+		      let notification_message = state.l10n.format("new-notification", &[
+			("app", state.app_names.speakable(&notification.app_name)),
+			("title", &notification.title),
+			("body", &notification.body),
+		      ]);
+		      if state.away.hold(notification_message.clone(), state.config.idle.max_held) {
+			continue;
+		      }
+		      CURRENT_SOURCE
+			.scope(SpeechSource::Notification, state.say(Category::Notification, Priority::Important, notification_message))
+			.await;
 		    },
 		    () = shutdown.cancelled() => {
 		      tracing::debug!("Shutting down notification task.");
@@ -76,49 +181,950 @@ async fn notifications_monitor(
 	}
 	Ok(())
 }
-#[tracing::instrument]
-async fn sigterm_signal_watcher(
-	token: CancellationToken,
-	tracker: TaskTracker,
+
+/// Announces each process which the input socket refused, naming its program if that is known, until the input socket closes.
+/// Without this, an input server started as the wrong user (or not in `input.allowed_programs`) would just seem to do nothing.
+async fn announce_rejected_input(
+	mut rejected: mpsc::Receiver<odilia_input::Peer>,
+	ssip: odilia_tts::SpeechSender,
+	l10n: Localizer,
+) -> eyre::Result<()> {
+	while let Some(peer) = rejected.recv().await {
+		let program = peer.exe.as_deref().and_then(std::path::Path::file_name).map_or_else(
+			|| l10n.get("unknown-program"),
+			|name| name.to_string_lossy().into_owned(),
+		);
+		ssip.speak(
+			Priority::Important,
+			l10n.format("input-rejected", &[("program", &program)]),
+		)
+		.await?;
+	}
+	Ok(())
+}
+
+/// How often an input backlog is announced at most, so that announcing it does not add to it.
+const BACKLOG_ALERT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Counts the events which had to wait for the input handlers, logging each and announcing them now and then, until the input sockets close.
+/// Without this, keys would just seem to be ignored while Odilia is busy.
+async fn announce_input_backlog(
+	mut backlogged: mpsc::Receiver<()>,
+	ssip: odilia_tts::SpeechSender,
+	l10n: Localizer,
 ) -> eyre::Result<()> {
-	let timeout_duration = Duration::from_millis(500); //todo: perhaps take this from the configuration file at some point
+	let mut delayed: u64 = 0;
+	let mut announced: Option<Instant> = None;
+	while backlogged.recv().await.is_some() {
+		delayed += 1;
+		tracing::warn!(delayed, "Input handlers are behind, so events are delayed");
+		if announced.is_some_and(|at| at.elapsed() < BACKLOG_ALERT_INTERVAL) {
+			continue;
+		}
+		announced = Some(Instant::now());
+		ssip.speak(Priority::Important, l10n.get("input-backlog")).await?;
+	}
+	Ok(())
+}
+
+/// Waits for Ctrl+C, a request from the service manager to stop, or any other part of Odilia asking for a shutdown, then stops every task in order.
+#[tracing::instrument(skip_all)]
+async fn sigterm_signal_watcher(shutdown: Shutdown) -> eyre::Result<()> {
+	let requested = shutdown.requested();
 	let mut c = signal(SignalKind::interrupt())?;
-	c.recv().instrument(tracing::debug_span!("Watching for Ctrl+C")).await;
+	// the service manager asks us to stop with SIGTERM, rather than Ctrl+C
+	let mut term = signal(SignalKind::terminate())?;
+	async {
+		tokio::select! {
+			_ = c.recv() => {},
+			_ = term.recv() => {},
+			// for example, when replaced by another instance
+			() = requested.cancelled() => {},
+		}
+	}
+	.instrument(tracing::debug_span!("Watching for Ctrl+C"))
+	.await;
+	systemd::notify_stopping();
 	tracing::debug!("Asking all processes to stop.");
-	tracing::debug!("cancelling all tokens");
-	token.cancel();
-	tracing::debug!(?timeout_duration, "waiting for all tasks to finish");
-	timeout(timeout_duration, tracker.wait()).await?;
-	tracing::debug!("All listeners have stopped.");
+	requested.cancel();
+	let aborted = shutdown.run().await;
+	if aborted.is_empty() {
+		tracing::debug!("All listeners have stopped.");
+	} else {
+		tracing::warn!(?aborted, "Some tasks had to be aborted; they delayed exiting.");
+	}
 	tracing::debug!("Goodbye, Odilia!");
 	Ok(())
 }
 
-use atspi::events::document::LoadCompleteEvent;
-use atspi::events::object::TextCaretMovedEvent;
+use atspi::events::cache::{AddAccessibleEvent, RemoveAccessibleEvent};
+use atspi::events::document::{LoadCompleteEvent, PageChangedEvent, ReloadEvent};
+use atspi::events::object::{PropertyChangeEvent, SelectionChangedEvent, TextCaretMovedEvent};
 use atspi::Granularity;
 use std::cmp::{max, min};
 
 #[tracing::instrument(ret, err)]
 async fn speak(
-	Command(Speak(text, priority)): Command<Speak>,
+	Command(Speak(announcement)): Command<Speak>,
 	Speech(ssip): Speech,
+	capture: SpeechCapture,
+	history: SpeechHistory,
 ) -> Result<(), odilia_common::errors::OdiliaError> {
-	ssip.send(SSIPRequest::SetPriority(priority)).await?;
-	ssip.send(SSIPRequest::Speak).await?;
-	ssip.send(SSIPRequest::SendLines(Vec::from([text]))).await?;
+	announce(announcement, &ssip, &capture, &history).await
+}
+
+/// Speaks `announcement`, recording it in the capture and the history; what the [`Speak`] command does.
+async fn announce(
+	announcement: Announcement,
+	ssip: &odilia_tts::SpeechSender,
+	capture: &SpeechCapture,
+	history: &SpeechHistory,
+) -> Result<(), OdiliaError> {
+	capture.record(&announcement);
+	if announcement.remembered {
+		history.push(&announcement.text);
+	}
+	if announcement.marks.is_empty() {
+		ssip.speak(announcement.priority, announcement.text).await?;
+	} else {
+		ssip.speak_marked(announcement.priority, announcement.text, &announcement.marks)
+			.await?;
+	}
+	Ok(())
+}
+
+#[tracing::instrument(ret, err)]
+async fn speak_char(
+	Command(SpeakChar { ch, priority }): Command<SpeakChar>,
+	Speech(ssip): Speech,
+) -> Result<(), OdiliaError> {
+	ssip.speak_char(priority, ch).await?;
 	Ok(())
 }
 
+#[tracing::instrument(ret, err)]
+async fn control_speech(
+	Command(SpeechControl(request)): Command<SpeechControl>,
+	Speech(ssip): Speech,
+) -> Result<(), OdiliaError> {
+	ssip.send(request).await?;
+	Ok(())
+}
+
+#[tracing::instrument(ret, err)]
+async fn silence(
+	Command(silence): Command<Silence>,
+	Speech(ssip): Speech,
+) -> Result<(), OdiliaError> {
+	match silence {
+		Silence::All => ssip.stop().await?,
+		Silence::Interruptible => {
+			ssip.interrupt().await?;
+		}
+	}
+	Ok(())
+}
+
+#[tracing::instrument(ret, err)]
+async fn set_caret(
+	Command(SetCaret { item, offset }): Command<SetCaret>,
+	AtspiCache(cache): AtspiCache,
+) -> Result<(), OdiliaError> {
+	let item = cache.get_ipc(&item).await?;
+	item.set_caret_offset(offset.try_into()?).await?;
+	Ok(())
+}
+
+#[tracing::instrument(ret, err)]
+async fn route(
+	Command(Route { speech, braille }): Command<Route>,
+	Speech(ssip): Speech,
+) -> Result<(), OdiliaError> {
+	ssip.route(speech, braille);
+	Ok(())
+}
+
+#[tracing::instrument(ret, err)]
+async fn scroll_into_view(
+	Command(ScrollIntoView { item, anchor }): Command<ScrollIntoView>,
+	AtspiCache(cache): AtspiCache,
+) -> Result<(), OdiliaError> {
+	let item = cache.get_ipc(&item).await?;
+	if let Err(e) = scroll::ensure_visible(&item, &cache, anchor).await {
+		tracing::debug!("Could not scroll {:?} into view: {e}", item.object);
+	}
+	Ok(())
+}
+
+#[tracing::instrument(err)]
+async fn activate(
+	Command(Activate { item, otherwise }): Command<Activate>,
+	AtspiCache(cache): AtspiCache,
+	Speech(ssip): Speech,
+	capture: SpeechCapture,
+	history: SpeechHistory,
+) -> Result<(), OdiliaError> {
+	let item = cache.get_ipc(&item).await?;
+	if navigator::activate(&item, &cache.connection).await? {
+		return Ok(());
+	}
+	announce(otherwise, &ssip, &capture, &history).await
+}
+
+#[tracing::instrument(ret)]
+async fn stop_speech(
+	InputEvent(StopSpeech): InputEvent<StopSpeech>,
+	delayed: DelayedSpeech,
+) -> impl TryIntoCommands {
+	delayed.cancel_all();
+	Silence::All
+}
+
+#[tracing::instrument(ret)]
+async fn user_activity(
+	InputEvent(UserActivity): InputEvent<UserActivity>,
+	Speech(ssip): Speech,
+	settings: SpeechSettings,
+	say_all: SayAll,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	ssip.key_pressed();
+	if say_all.cancel() {
+		Ok(Silence::All.into_commands())
+	} else if settings.interrupt_on_activity {
+		Ok(Silence::Interruptible.into_commands())
+	} else {
+		Ok(vec![])
+	}
+}
+
+// the echoed text is not logged, only errors
+#[tracing::instrument(err)]
+async fn key_echo(
+	InputEvent(KeyEcho(text)): InputEvent<KeyEcho>,
+	echo: Echo,
+	settings: EchoSettings,
+	AccessibleHistory(history): AccessibleHistory,
+	AtspiCache(cache): AtspiCache,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let focused = history.lock().ok().and_then(|history| history.iter().next().cloned());
+	let app = focused
+		.as_ref()
+		.and_then(|focused| cache.get(focused))
+		.map(|item| item.app);
+	let app_name = match &app {
+		Some(app) => Some(echo.app_name(app, &cache.connection).await?),
+		None => None,
+	};
+	let mode = echo::mode_for(&settings, app_name.as_deref());
+	let utterance = echo.push(focused, &text, mode);
+	let chars = utterance
+		.chars
+		.into_iter()
+		.map(|ch| SpeakChar { ch, priority: Priority::Text });
+	// typing is not worth reviewing later
+	let words = utterance.words.into_iter().map(|word| {
+		Speak(Announcement::new(Category::Echo, Priority::Text, word).forgotten())
+	});
+	Ok(chars.map(OdiliaCommand::from)
+		.chain(words.map(OdiliaCommand::from))
+		.collect())
+}
+
+#[tracing::instrument(ret, err)]
+async fn heartbeat(
+	InputEvent(Heartbeat { server, interval_ms }): InputEvent<Heartbeat>,
+	servers: InputServers,
+) -> Result<(), OdiliaError> {
+	if servers.beat(server.clone(), Duration::from_millis(interval_ms)) {
+		tracing::info!(%server, "Watching input server for heartbeats");
+	}
+	Ok(())
+}
+
+#[tracing::instrument(ret)]
+async fn event_diagnostics(
+	InputEvent(EventDiagnostics): InputEvent<EventDiagnostics>,
+	counts: EventCounts,
+	formatter: SpeechFormatter,
+) -> impl TryIntoCommands {
+	let value = if counts.toggle() { "on" } else { "off" };
+	let change = SettingChange { setting: "setting-event-diagnostics", value };
+	(Category::System, Priority::Message, formatter.setting(change))
+}
+
+#[tracing::instrument(ret)]
+async fn cycle_punctuation(
+	InputEvent(CyclePunctuation): InputEvent<CyclePunctuation>,
+	adjustments: Adjustments,
+	settings: SpeechSettings,
+	formatter: SpeechFormatter,
+) -> impl TryIntoCommands {
+	let (punctuation, change) = adjustments.cycle_punctuation(settings.punctuation);
+	let mode = SpeechControl(SSIPRequest::SetPunctuationMode(
+		ClientScope::Current,
+		adjustments::punctuation_mode(punctuation),
+	));
+	(mode, (Category::System, Priority::Message, formatter.setting(change)))
+}
+
+/// Switches the voice to the next language in `speech.languages`, and names it in itself, so that it is said in the new language.
+#[tracing::instrument(ret, err)]
+async fn cycle_language(
+	InputEvent(CycleLanguage): InputEvent<CycleLanguage>,
+	adjustments: Adjustments,
+	settings: SpeechSettings,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let Some(language) = adjustments.cycle_language(&settings.language, &settings.languages)
+	else {
+		return Ok((Category::System, Priority::Message, l10n.get("no-languages"))
+			.into_commands());
+	};
+	let name = adjustments::language_name(&language).to_string();
+	let switch = SpeechControl(SSIPRequest::SetLanguage(ClientScope::Current, language));
+	Ok((switch, (Category::System, Priority::Message, name)).into_commands())
+}
+
+#[tracing::instrument(ret, err)]
+async fn reset_adjustments(
+	InputEvent(ResetAdjustments): InputEvent<ResetAdjustments>,
+	adjustments: Adjustments,
+	modes: Modes,
+	Configured(config): Configured,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	adjustments.reset();
+	let route = Route {
+		speech: config.speech.output.speaks(),
+		braille: config.speech.output.brailles(),
+	};
+	modes.reset();
+	let punctuation = SpeechControl(SSIPRequest::SetPunctuationMode(
+		ClientScope::Current,
+		adjustments::punctuation_mode(config.speech.punctuation),
+	));
+	let language = SpeechControl(SSIPRequest::SetLanguage(
+		ClientScope::Current,
+		config.speech.language,
+	));
+	Ok((
+		route,
+		punctuation,
+		language,
+		(Category::System, Priority::Message, l10n.get("adjustments-reset")),
+	)
+		.into_commands())
+}
+
+#[tracing::instrument(ret)]
+async fn cycle_echo(
+	InputEvent(CycleEcho): InputEvent<CycleEcho>,
+	adjustments: Adjustments,
+	settings: EchoSettings,
+	formatter: SpeechFormatter,
+) -> impl TryIntoCommands {
+	let change = adjustments.cycle_echo(settings.mode);
+	(Category::System, Priority::Message, formatter.setting(change))
+}
+
+/// Switches where messages go, then announces it there.
+#[tracing::instrument(ret)]
+async fn cycle_output(
+	InputEvent(CycleOutput): InputEvent<CycleOutput>,
+	adjustments: Adjustments,
+	settings: SpeechSettings,
+	formatter: SpeechFormatter,
+) -> impl TryIntoCommands {
+	let (output, change) = adjustments.cycle_output(settings.output);
+	let route = Route { speech: output.speaks(), braille: output.brailles() };
+	(route, (Category::System, Priority::Message, formatter.setting(change)))
+}
+
+/// Plays the earcon for `mode`, if it has one, and says its name unless the earcon is enough.
+fn announce_mode(
+	mode: ScreenReaderMode,
+	earcons: &Earcons,
+	settings: &SpeechSettings,
+) -> Vec<OdiliaCommand> {
+	let name = mode.name.to_lowercase();
+	let earcon = if name.contains("browse") {
+		Some(Earcon::BrowseMode)
+	} else if name.contains("focus") {
+		Some(Earcon::FocusMode)
+	} else {
+		None
+	};
+	if let Some(earcon) = earcon {
+		earcons.play(earcon);
+	}
+	// the user has to be told about the change one way or another
+	let silent = earcon.is_some() && earcons.enabled() && !settings.announce_mode_changes;
+	if silent {
+		vec![]
+	} else {
+		(Category::System, Priority::Message, mode.name).into_commands()
+	}
+}
+
+#[tracing::instrument(ret)]
+async fn change_mode(
+	InputEvent(ChangeMode(mode)): InputEvent<ChangeMode>,
+	modes: Modes,
+	adjustments: Adjustments,
+	earcons: Earcons,
+	settings: SpeechSettings,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let Some(mode) = modes.set(&mode.name) else {
+		tracing::warn!(
+			mode = mode.name,
+			"Not changing to a mode which is not in modes.names"
+		);
+		return Ok((
+			Category::System,
+			Priority::Message,
+			l10n.format("unknown-mode", &[("name", &mode.name)]),
+		)
+			.into_commands());
+	};
+	adjustments.set_mode(&mode.name);
+	Ok(announce_mode(mode, &earcons, &settings))
+}
+
+/// Switches to the mode configured in `modes.apps` for an application when focus moves into it.
+#[tracing::instrument(ret, err)]
+async fn app_mode(
+	state_changed: CacheEvent<Focused>,
+	modes: Modes,
+	echo: Echo,
+	AtspiCache(cache): AtspiCache,
+	earcons: Earcons,
+	settings: SpeechSettings,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let app = &state_changed.item.app;
+	if !modes.entered(app) {
+		return Ok(vec![]);
+	}
+	let name = echo.app_name(app, &cache.connection).await?;
+	let Some(mode) = modes.for_app(&name).map(str::to_string) else {
+		return Ok(vec![]);
+	};
+	let Some(mode) = modes.set(&mode) else {
+		tracing::warn!(
+			mode,
+			app = name,
+			"Not changing to a mode which is not in modes.names"
+		);
+		return Ok(vec![]);
+	};
+	Ok(announce_mode(mode, &earcons, &settings))
+}
+
+#[tracing::instrument(ret)]
+async fn cycle_mode(
+	InputEvent(CycleMode(direction)): InputEvent<CycleMode>,
+	modes: Modes,
+	adjustments: Adjustments,
+	earcons: Earcons,
+	settings: SpeechSettings,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let mode = modes.cycle(&direction);
+	adjustments.set_mode(&mode.name);
+	Ok(announce_mode(mode, &earcons, &settings))
+}
+
+#[tracing::instrument(ret)]
+async fn say_mode(
+	InputEvent(SayMode): InputEvent<SayMode>,
+	CurrentMode(mode): CurrentMode,
+) -> impl TryIntoCommands {
+	(Category::System, Priority::Message, mode.name)
+}
+
+/// Reads the row or column of the focused table cell.
+async fn read_table_line(
+	line: Line,
+	focused: &AccessiblePrimitive,
+	cache: &Arc<Cache>,
+	l10n: &Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let text = tables::read(focused, line, cache)
+		.await?
+		.unwrap_or_else(|| l10n.get("not-in-table"));
+	Ok((Category::Navigation, Priority::Message, text).into_commands())
+}
+
+#[tracing::instrument(ret, err)]
+async fn read_row(
+	InputEvent(ReadRow): InputEvent<ReadRow>,
+	LastFocused(focused): LastFocused,
+	AtspiCache(cache): AtspiCache,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	read_table_line(Line::Row, &focused, &cache, &l10n).await
+}
+
+#[tracing::instrument(ret, err)]
+async fn read_column(
+	InputEvent(ReadColumn): InputEvent<ReadColumn>,
+	LastFocused(focused): LastFocused,
+	AtspiCache(cache): AtspiCache,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	read_table_line(Line::Column, &focused, &cache, &l10n).await
+}
+
+#[tracing::instrument(ret, err)]
+async fn read_container(
+	InputEvent(ReadContainer): InputEvent<ReadContainer>,
+	LastFocused(focused): LastFocused,
+	AtspiCache(cache): AtspiCache,
+	say_all: SayAll,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let item = cache.get(&focused).ok_or(CacheError::NoItem)?;
+	let texts = reading::text(reading::container(&item), Some(&item.object)).await?;
+	Ok(say_all.start(texts))
+}
+
+/// Skips through what is being read continuously, and reads on from there.
+#[tracing::instrument(ret, err)]
+async fn skip_say_all(
+	InputEvent(SkipSayAll { unit, direction }): InputEvent<SkipSayAll>,
+	say_all: SayAll,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let Some(commands) = say_all.skip(unit, &direction) else {
+		return Ok(vec![]);
+	};
+	Ok(std::iter::once(Silence::All.into()).chain(commands).collect())
+}
+
+#[tracing::instrument(ret, err)]
+async fn say_location(
+	InputEvent(SayLocation): InputEvent<SayLocation>,
+	LastFocused(focused): LastFocused,
+	AtspiCache(cache): AtspiCache,
+	geometry: Geometry,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let accessible = focused.into_accessible(&cache.connection).await?;
+	let (x, y, width, height) = accessible
+		.to_component()
+		.await?
+		.get_extents(CoordType::Screen)
+		.await?;
+	let location = geometry
+		.describe(x + width / 2, y + height / 2, &cache.connection, &l10n)
+		.await
+		.unwrap_or_else(|| l10n.get("location-unknown"));
+	Ok((Category::Navigation, Priority::Message, location).into_commands())
+}
+
+#[tracing::instrument(ret)]
+async fn set_log_level(
+	InputEvent(SetLogLevel(level)): InputEvent<SetLogLevel>,
+	l10n: Localizer,
+) -> impl TryIntoCommands {
+	let message = match logging::set_level(&level) {
+		Ok(()) => l10n.format("log-level", &[("level", &level)]),
+		Err(e) => {
+			tracing::warn!("Could not change the log level: {e:?}");
+			l10n.format("invalid-log-level", &[("level", &level)])
+		}
+	};
+	(Category::System, Priority::Message, message)
+}
+
+/// Speaks and logs everything about the object under the object navigator (the focused object, unless it was moved), for developers checking what their application exposes.
+#[tracing::instrument(err)]
+async fn inspect(
+	InputEvent(Inspect): InputEvent<Inspect>,
+	LastFocused(focused): LastFocused,
+	AtspiCache(cache): AtspiCache,
+	navigator: Navigator,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let item = cache.get_ipc(&navigator.current(&focused)).await?;
+	let name = item.name().await.unwrap_or_default();
+	let join = |names: Vec<String>| {
+		if names.is_empty() {
+			"-".to_string()
+		} else {
+			names.join(", ")
+		}
+	};
+	let interfaces = join(item
+		.interfaces
+		.iter()
+		.map(|interface| format!("{interface:?}"))
+		.collect());
+	let states = join(item
+		.states
+		.iter()
+		.map(|state| state.to_static_str().to_string())
+		.collect());
+	let mut attributes: Vec<String> = item
+		.get_attributes()
+		.await
+		.unwrap_or_default()
+		.into_iter()
+		.map(|(key, value)| format!("{key}: {value}"))
+		.collect();
+	attributes.sort();
+	let attributes = join(attributes);
+	tracing::info!(
+		role = item.role.name(),
+		name = %privacy::Redacted(&name),
+		interfaces,
+		states,
+		attributes,
+		path = item.object.id.as_str(),
+		app = item.object.sender.as_str(),
+		shortcut = item.shortcut,
+		"Inspected"
+	);
+	let details = l10n.format(
+		"inspector",
+		&[
+			("role", item.role.name()),
+			("name", &name),
+			("interfaces", &interfaces),
+			("states", &states),
+			("attributes", &attributes),
+			("path", item.object.id.as_str()),
+			("app", item.object.sender.as_str()),
+		],
+	);
+	Ok((Category::System, Priority::Message, details).into_commands())
+}
+
+/// Moves the object navigator, and speaks the object it lands on.
+#[tracing::instrument(err)]
+async fn navigate_object(
+	InputEvent(NavigateObject(direction)): InputEvent<NavigateObject>,
+	LastFocused(focused): LastFocused,
+	AtspiCache(cache): AtspiCache,
+	navigator: Navigator,
+	formatter: SpeechFormatter,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let Some(item) = navigator.step(direction, &focused, &cache).await? else {
+		let edge = match direction {
+			ObjectDirection::Parent => "navigator-no-parent",
+			ObjectDirection::FirstChild => "navigator-no-children",
+			ObjectDirection::NextSibling => "navigator-last",
+			ObjectDirection::PreviousSibling => "navigator-first",
+		};
+		return Ok(
+			(Category::Navigation, Priority::Message, l10n.get(edge)).into_commands()
+		);
+	};
+	let name = if item.text.is_empty() {
+		item.name().await.unwrap_or_default()
+	} else {
+		item.text.clone()
+	};
+	let scroll = ScrollIntoView { item: item.object.clone(), anchor: navigator.anchor() };
+	// spoken first, so that failing to scroll can not keep the user from hearing where they are
+	Ok(((Category::Navigation, Priority::Text, formatter.format(&item, &name)), scroll)
+		.into_commands())
+}
+
+/// Moves the object navigator to the next (or previous) object with a role, like a heading, and reads it.
+#[tracing::instrument(ret, err)]
+async fn structural_navigation(
+	InputEvent(StructuralNavigation(direction, role)): InputEvent<StructuralNavigation>,
+	LastFocused(focused): LastFocused,
+	AtspiCache(cache): AtspiCache,
+	navigator: Navigator,
+	formatter: SpeechFormatter,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let Some(item) = navigator.find(role, &direction, &focused, &cache).await? else {
+		let none = l10n.format("no-more-of-role", &[("role", &l10n.role(role).long)]);
+		return Ok((Category::Navigation, Priority::Message, none).into_commands());
+	};
+	let name = if item.text.is_empty() {
+		item.name().await.unwrap_or_default()
+	} else {
+		item.text.clone()
+	};
+	let scroll = ScrollIntoView { item: item.object.clone(), anchor: navigator.anchor() };
+	Ok(((Category::Navigation, Priority::Text, formatter.format(&item, &name)), scroll)
+		.into_commands())
+}
+
+/// Activates the object under the object navigator, as if it was clicked; only says something if that is not possible.
+#[tracing::instrument(err)]
+async fn activate_reviewed(
+	InputEvent(ActivateReviewed): InputEvent<ActivateReviewed>,
+	LastFocused(focused): LastFocused,
+	AtspiCache(cache): AtspiCache,
+	navigator: Navigator,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let otherwise =
+		Announcement::new(Category::System, Priority::Message, l10n.get("cannot-activate"));
+	Ok(Activate { item: navigator.current(&focused), otherwise }.into_commands())
+}
+
+/// The `braille-route` custom event, sent for the routing keys of a braille display: activates the object under the object navigator, like [`ActivateReviewed`].
+/// Its argument, where the key is in the message shown, is not needed for that.
+#[tracing::instrument(err)]
+async fn braille_route(
+	InputEvent(Custom { .. }): InputEvent<Custom>,
+	LastFocused(focused): LastFocused,
+	navigator: Navigator,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let otherwise =
+		Announcement::new(Category::System, Priority::Message, l10n.get("cannot-activate"));
+	Ok(Activate { item: navigator.current(&focused), otherwise }.into_commands())
+}
+
+/// Reads the utterance after (or before) the one being reviewed in the speech history.
+/// It is kept out of the history, so that reviewing the history does not add to it.
 #[tracing::instrument(ret)]
-async fn doc_loaded(loaded: ActiveAppEvent<LoadCompleteEvent>) -> impl TryIntoCommands {
-	(Priority::Text, "Doc loaded")
+async fn review_history(
+	InputEvent(ReviewLine(direction)): InputEvent<ReviewLine>,
+	history: SpeechHistory,
+	l10n: Localizer,
+) -> impl TryIntoCommands {
+	let text = history.step(&direction).unwrap_or_else(|| match direction {
+		Direction::Forward => l10n.get("history-bottom"),
+		Direction::Backward => l10n.get("history-top"),
+	});
+	(Silence::All, Announcement::new(Category::Navigation, Priority::Text, text).forgotten())
 }
 
-use crate::tower::state_changed::{Focused, Unfocused};
+/// Goes back to the last utterance in the speech history containing the given text, and reads it; like [`review_history`], it is not added to the history.
+#[tracing::instrument(skip_all)]
+async fn search_history(
+	InputEvent(SearchHistory(text)): InputEvent<SearchHistory>,
+	history: SpeechHistory,
+	l10n: Localizer,
+) -> impl TryIntoCommands {
+	let text = history
+		.search(&text)
+		.unwrap_or_else(|| l10n.format("history-not-found", &[("text", &text)]));
+	(Silence::All, Announcement::new(Category::Navigation, Priority::Text, text).forgotten())
+}
 
+/// Moves the caret to the next or previous (unvisited) link in the document, starting from the caret, and reads the link.
+#[tracing::instrument(ret, err)]
+async fn navigate_link(
+	InputEvent(NavigateLink { direction, unvisited }): InputEvent<NavigateLink>,
+	LastFocused(focused): LastFocused,
+	AtspiCache(cache): AtspiCache,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let item = cache.get(&focused).ok_or(CacheError::NoItem)?;
+	let offset = if item.interfaces.contains(Interface::Text) {
+		Some(item.caret_offset().await?.try_into()?)
+	} else {
+		None
+	};
+	let Some(link) = links::find(&item, offset, &direction, unvisited, &cache).await? else {
+		return Ok((Category::Navigation, Priority::Message, l10n.get("no-more-links"))
+			.into_commands());
+	};
+	let mut parts = vec![link.text(&cache.connection).await?, l10n.role(Role::Link).long];
+	if link.visited {
+		parts.push(l10n.state(State::Visited).long);
+	}
+	let caret = SetCaret { item: link.container.object.clone(), offset: link.start };
+	Ok((caret, (Category::Navigation, Priority::Text, parts.join(", "))).into_commands())
+}
+
+/// Asks the input server for a line of text, telling the user to type it.
 #[tracing::instrument(ret)]
-async fn focused(state_changed: CacheEvent<Focused>) -> impl TryIntoCommands {
+async fn enter_text(
+	InputEvent(EnterText(purpose)): InputEvent<EnterText>,
+	text_entry: TextEntry,
+	l10n: Localizer,
+) -> impl TryIntoCommands {
+	text_entry.request(purpose);
+	(Category::System, Priority::Message, l10n.get("enter-text"))
+}
+
+/// The `say` custom event: speaks its arguments, for scripts and plugins which need to tell the user something.
+#[tracing::instrument]
+async fn say(InputEvent(Custom { args, .. }): InputEvent<Custom>) -> impl TryIntoCommands {
+	(Category::System, Priority::Message, args.join(" "))
+}
+
+#[tracing::instrument(ret, err)]
+async fn quit(
+	InputEvent(Quit): InputEvent<Quit>,
+	pending: PendingAction,
+	settings: InputSettings,
+	ShutdownRequest(shutdown): ShutdownRequest,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let window = Duration::from_millis(settings.confirm_quit_ms);
+	if settings.confirm_quit && !pending.confirm(Quit::ETYPE, window) {
+		return Ok((Category::System, Priority::Message, l10n.get("press-again-to-quit"))
+			.into_commands());
+	}
+	tracing::info!("Quitting, as asked by the user");
+	shutdown.cancel();
+	Ok(vec![])
+}
+
+#[tracing::instrument(skip(snapshot), err)]
+async fn restart(
+	InputEvent(Restart): InputEvent<Restart>,
+	snapshot: Snapshot,
+) -> Result<(), OdiliaError> {
+	let path = snapshot.save().map_err(|e| OdiliaError::Generic(e.to_string()))?;
+	tracing::info!(items = snapshot.cache.len(), path = %path.display(), "Saved state; restarting");
+	let e = restart::exec_self(&path);
+	let _ = std::fs::remove_file(&path);
+	Err(OdiliaError::Generic(format!("Could not restart Odilia: {e}")))
+}
+
+#[tracing::instrument(ret, err)]
+async fn add_accessible(
+	AtspiEvent(added): AtspiEvent<AddAccessibleEvent>,
+	AtspiCache(cache): AtspiCache,
+) -> Result<(), OdiliaError> {
+	cache.add_or_update_atspi_item(added.node_added).await?;
+	Ok(())
+}
+
+#[tracing::instrument(ret, err)]
+async fn remove_accessible(
+	AtspiEvent(removed): AtspiEvent<RemoveAccessibleEvent>,
+	AtspiCache(cache): AtspiCache,
+) -> Result<(), OdiliaError> {
+	cache.remove(&removed.node_removed.into());
+	Ok(())
+}
+
+/// Repairs the cached tree of a document which finished loading, if its toolkit is known to expose a broken one; runs before [`doc_loaded`], so that it reads the repaired tree.
+#[tracing::instrument(ret, err)]
+async fn repair_document(
+	loaded: CacheEvent<LoadCompleteEvent>,
+	AtspiCache(cache): AtspiCache,
+) -> Result<(), OdiliaError> {
+	let toolkit = loaded
+		.item
+		.app
+		.clone()
+		.into_accessible(&cache.connection)
+		.await?
+		.to_application()
+		.await?
+		.toolkit_name()
+		.await?;
+	let rules = repair::Rules::for_toolkit(&toolkit);
+	if rules != repair::Rules::default() {
+		cache.repair(&loaded.item.object, rules)?;
+	}
+	Ok(())
+}
+
+/// Forgets the cached tree of a document being reloaded, none of which will be there once it has, and reads the document itself again, so that its new tree is found as it loads.
+#[tracing::instrument(ret, err)]
+async fn doc_reloaded(
+	AtspiEvent(reloaded): AtspiEvent<ReloadEvent>,
+	AtspiCache(cache): AtspiCache,
+) -> Result<(), OdiliaError> {
+	let document = AccessiblePrimitive::from(reloaded.item);
+	cache.invalidate(&document);
+	let accessible = document.into_accessible(&cache.connection).await?;
+	cache.get_or_create(&accessible, Arc::clone(&cache), CACHE_LOOKUP_TIMEOUT)
+		.await?;
+	Ok(())
+}
+
+/// Says which page a document in the focused application is on when it changes, like "page 5 of 120", for PDFs and other documents with pages.
+#[tracing::instrument(ret, err)]
+async fn page_changed(
+	changed: CacheEvent<PageChangedEvent>,
+	LastFocused(focused): LastFocused,
+	AtspiCache(cache): AtspiCache,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	if changed.item.object.sender != focused.sender {
+		return Ok(vec![]);
+	}
+	let accessible = changed.item.object.clone().into_accessible(&cache.connection).await?;
+	let document = accessible.to_document().await?;
+	let (page, count) =
+		tokio::try_join!(document.current_page_number(), document.page_count())?;
+	// applications which do not number their pages say -1
+	if page < 1 {
+		return Ok(vec![]);
+	}
+	let text = if count >= page {
+		l10n.format(
+			"page-of",
+			&[("page", &page.to_string()), ("count", &count.to_string())],
+		)
+	} else {
+		l10n.format("page", &[("page", &page.to_string())])
+	};
+	Ok((Category::Navigation, Priority::Text, text).into_commands())
+}
+
+#[tracing::instrument(ret, err)]
+async fn doc_loaded(
+	loaded: ActiveAppEvent<LoadCompleteEvent>,
+	settings: SpeechSettings,
+	say_all: SayAll,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	match settings.page_load {
+		PageLoad::Announce => {
+			Ok((Category::Navigation, Priority::Text, l10n.get("document-loaded"))
+				.into_commands())
+		}
+		PageLoad::Summary => {
+			let (headings, links) = reading::count_headings_and_links(&loaded.item);
+			let title = loaded.item.name().await?;
+			let summary = l10n.format(
+				"page-summary",
+				&[
+					("title", &title),
+					("headings", &headings.to_string()),
+					("links", &links.to_string()),
+				],
+			);
+			Ok((Category::Navigation, Priority::Text, summary).into_commands())
+		}
+		PageLoad::Read => {
+			let texts = reading::text(loaded.item.clone(), None).await?;
+			Ok(say_all.start(texts))
+		}
+	}
+}
+
+use crate::tower::state_changed::{Busy, Focused, NotBusy, Unfocused};
+
+#[tracing::instrument]
+async fn focused(
+	state_changed: CacheEvent<Focused>,
+	formatter: SpeechFormatter,
+	tables: Tables,
+	settings: TableSettings,
+	AtspiCache(cache): AtspiCache,
+	l10n: Localizer,
+) -> impl TryIntoCommands {
+	if let Some(day) = tables.calendar_day(&state_changed.item.object, &cache, &l10n).await? {
+		return Ok(vec![
+			Focus(state_changed.item.object).into(),
+			Speak(Announcement::new(Category::Focus, Priority::Text, day)).into(),
+		]);
+	}
+	if settings.read_whole_row {
+		if let Some(row) = tables.entered_row(&state_changed.item.object, &cache).await? {
+			return Ok(vec![
+				Focus(state_changed.item.object).into(),
+				Speak(Announcement::new(Category::Focus, Priority::Text, row))
+					.into(),
+			]);
+		}
+	}
 	//because the current command implementation doesn't allow for multiple speak commands without interrupting the previous utterance, this is more or less an accumulating buffer for that utterance
 	let mut utterance_buffer = String::new();
 	//does this have a text or a name?
@@ -155,28 +1161,250 @@ async fn focused(state_changed: CacheEvent<Focused>) -> impl TryIntoCommands {
 		//then just append to the buffer and be done with it
 		utterance_buffer += text;
 	}
-	let role = state_changed.item.role;
-	//there has to be a space between the accessible name of an object and its role, so insert it now
-	utterance_buffer += &format!(" {}", role.name().to_owned());
+	let utterance = formatter.format(&state_changed.item, &utterance_buffer);
 	Ok(vec![
 		Focus(state_changed.item.object).into(),
-		Speak(utterance_buffer, Priority::Text).into(),
+		Speak(Announcement::new(Category::Focus, Priority::Text, utterance)).into(),
 	])
 }
 
-#[tracing::instrument(ret)]
+/// Reads the day selected in a focused calendar, since arrowing through one moves its selection rather than focus in some toolkits.
+#[tracing::instrument(err)]
+async fn calendar_selection(
+	changed: ActiveAppEvent<SelectionChangedEvent>,
+	tables: Tables,
+	AtspiCache(cache): AtspiCache,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	if !changed.item.interfaces.contains(Interface::Selection)
+		|| !changed.item.interfaces.contains(Interface::Table)
+	{
+		return Ok(vec![]);
+	}
+	let selected = changed
+		.item
+		.object
+		.clone()
+		.into_accessible(&cache.connection)
+		.await?
+		.to_selection()
+		.await?
+		.get_selected_child(0)
+		.await?;
+	let Some(day) = tables.calendar_day(&selected.into(), &cache, &l10n).await? else {
+		return Ok(vec![]);
+	};
+	Ok((Category::Focus, Priority::Text, day).into_commands())
+}
+
+/// Speaks the description of the focused object, and at high verbosity its help text, once focus has stayed on it for `speech.description_delay_ms`; focus moving on before then cancels it.
+#[tracing::instrument(err)]
+async fn focus_details(
+	state_changed: CacheEvent<Focused>,
+	AccessibleHistory(history): AccessibleHistory,
+	settings: SpeechSettings,
+	delayed: DelayedSpeech,
+) -> Result<(), OdiliaError> {
+	let item = state_changed.item;
+	let delay = Duration::from_millis(settings.description_delay_ms);
+	delayed.schedule(Slot::FocusDetails, delay, async move {
+		if settings.verbosity == Verbosity::Low
+			|| history.lock()?.iter().next() != Some(&item.object)
+		{
+			return Ok(None);
+		}
+		let mut details = vec![item.description().await?];
+		if settings.verbosity == Verbosity::High {
+			details.push(item.help_text().await.unwrap_or_default());
+		}
+		let mut details: Vec<String> = details
+			.into_iter()
+			.map(|detail| detail.trim().to_string())
+			.filter(|detail| !detail.is_empty() && *detail != item.text)
+			.collect();
+		details.dedup();
+		Ok((!details.is_empty()).then(|| details.join(". ")))
+	});
+	Ok(())
+}
+
+/// Says when focus moves into or out of a frame; runs before [`focused`], so that this is said first.
+#[tracing::instrument(ret, err)]
+async fn frame_boundary(
+	state_changed: CacheEvent<Focused>,
+	frames: Frames,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	let Some(text) = frames.focus_moved(&state_changed.item, &l10n).await? else {
+		return Ok(vec![]);
+	};
+	Ok((Category::Navigation, Priority::Text, text).into_commands())
+}
+
+#[tracing::instrument]
 async fn unfocused(state_changed: CacheEvent<Unfocused>) -> impl TryIntoCommands {
 	Ok(vec![
 		Focus(state_changed.item.object).into(),
-		Speak(state_changed.item.text, Priority::Text).into(),
+		Speak(Announcement::new(Category::Focus, Priority::Text, state_changed.item.text))
+			.into(),
 	])
 }
 
+/// Whether `item` is a document in the same application as `focused`; only those give feedback while they load.
+fn in_focused_app(item: &CacheItem, focused: &AccessiblePrimitive) -> bool {
+	matches!(item.role, Role::DocumentWeb | Role::DocumentFrame)
+		&& item.object.sender == focused.sender
+}
+
+/// Starts the busy earcon when a document in the focused application starts loading; says so instead if earcons are off.
+#[tracing::instrument(ret, err)]
+async fn busy(
+	state_changed: CacheEvent<Busy>,
+	LastFocused(focused): LastFocused,
+	loading: Loading,
+	earcons: Earcons,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	if !in_focused_app(&state_changed.item, &focused)
+		|| !loading.start(state_changed.item.object.clone(), &earcons)
+		|| earcons.enabled()
+	{
+		return Ok(vec![]);
+	}
+	Ok((Category::System, Priority::Message, l10n.get("loading")).into_commands())
+}
+
+/// Stops the busy earcon when the document which was loading is done, and says how many headings and links it has.
+#[tracing::instrument(ret, err)]
+async fn not_busy(
+	state_changed: CacheEvent<NotBusy>,
+	loading: Loading,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	if !loading.finish(&state_changed.item.object) {
+		return Ok(vec![]);
+	}
+	let (headings, links) = reading::count_headings_and_links(&state_changed.item);
+	let text = l10n.format(
+		"finished-loading",
+		&[("headings", &headings.to_string()), ("links", &links.to_string())],
+	);
+	Ok((Category::System, Priority::Message, text).into_commands())
+}
+
+/// How long a window's toolkit gets to send a focus event after the window is activated, before [`window_activated`] reads its selected item itself.
+const ACTIVATION_GRACE: Duration = Duration::from_millis(250);
+
+/// Reads the item which is both focused and selected in a window which was just activated, since some toolkits never send a focus event for it.
+/// Waits for [`ACTIVATION_GRACE`] first, and says nothing if focus moved in the meantime, since then the toolkit did send one.
+#[tracing::instrument(err)]
+async fn window_activated(
+	activated: CacheEvent<window::ActivateEvent>,
+	AccessibleHistory(history): AccessibleHistory,
+	AtspiCache(cache): AtspiCache,
+	formatter: SpeechFormatter,
+	l10n: Localizer,
+	delayed: DelayedSpeech,
+) -> Result<(), OdiliaError> {
+	let before = history.lock()?.iter().next().cloned();
+	delayed.schedule(Slot::WindowSelection, ACTIVATION_GRACE, async move {
+		if history.lock()?.iter().next() != before.as_ref() {
+			return Ok(None);
+		}
+		let Some(selected) =
+			cache.subtree(&activated.item.object).into_iter().find(|item| {
+				item.states.contains(State::Focused)
+					&& item.states.contains(State::Selected)
+			})
+		else {
+			return Ok(None);
+		};
+		if before.as_ref() == Some(&selected.object) {
+			return Ok(None);
+		}
+		history.lock()?.push(selected.object.clone());
+		let name = if selected.text.is_empty() {
+			selected.name().await.unwrap_or_default()
+		} else {
+			selected.text.clone()
+		};
+		let window = activated.item.name().await.unwrap_or_default();
+		Ok(Some(l10n.format(
+			"window-selection",
+			&[("window", &window), ("item", &formatter.format(&selected, &name))],
+		)))
+	});
+	Ok(())
+}
+
+/// Keeps the cached value of a slider, progress bar or similar up to date while it does not have focus, so that it is right once it does; [`value_changed`] reads the focused one.
+// the new value of a property, like a name, can be text the user reads, so only which property changed is recorded
+#[tracing::instrument(skip(changed), fields(property = %changed.property), ret, err)]
+async fn refresh_value(
+	AtspiEvent(changed): AtspiEvent<PropertyChangeEvent>,
+	LastFocused(focused): LastFocused,
+	AtspiCache(cache): AtspiCache,
+) -> Result<(), OdiliaError> {
+	if changed.property != "accessible-value" {
+		return Ok(());
+	}
+	let key = AccessiblePrimitive::from(changed.item);
+	// only items which were already read are kept up to date, rather than reading every one which changes
+	match cache.get(&key) {
+		Some(item) if item.value.is_some() && key != focused => {
+			item.read_value().await?;
+		}
+		_ => {}
+	}
+	Ok(())
+}
+
+#[tracing::instrument(skip(changed), fields(property = %changed.inner.property, item = ?changed.item), ret, err)]
+async fn value_changed(
+	changed: ActiveAppEvent<PropertyChangeEvent>,
+	last_change: ValueChanges,
+	settings: SpeechSettings,
+	l10n: Localizer,
+) -> Result<Vec<OdiliaCommand>, OdiliaError> {
+	if changed.inner.property != "accessible-value"
+		|| !matches!(
+			changed.item.role,
+			Role::Slider | Role::SpinButton | Role::ScrollBar | Role::ProgressBar
+		) {
+		return Ok(vec![]);
+	}
+	let value = changed.item.read_value().await?;
+	let window = Duration::from_millis(settings.value_repeat_ms);
+	let mut commands: Vec<OdiliaCommand> = Vec::new();
+	let text = match last_change.record(&changed.item.object, window) {
+		Change::First => l10n.format(
+			"value-range",
+			&[
+				("value", &values::describe(&value, &l10n)),
+				("min", &values::format(value.minimum)),
+				("max", &values::format(value.maximum)),
+			],
+		),
+		Change::Again { rapid } => {
+			// while a key is held, only the latest value matters
+			if rapid {
+				let cancel = SpeechControl(SSIPRequest::Cancel(MessageScope::Last));
+				commands.push(cancel.into());
+			}
+			values::describe(&value, &l10n)
+		}
+	};
+	commands.extend((Category::Focus, Priority::Text, text).into_commands());
+	Ok(commands)
+}
+
 #[tracing::instrument(ret, err)]
 async fn new_focused_item(
 	Command(Focus(new_focus)): Command<Focus>,
 	AccessibleHistory(old_focus): AccessibleHistory,
+	focus_watch: FocusWatch,
 ) -> Result<(), OdiliaError> {
+	focus_watch.follow(new_focus.clone());
 	let _ = old_focus.lock()?.push(new_focus);
 	Ok(())
 }
@@ -203,11 +1431,14 @@ async fn caret_moved(
 		let start = min(caret_moved.inner.position.try_into()?, last_pos);
 		let end = max(caret_moved.inner.position.try_into()?, last_pos);
 		if let Some(text) = caret_moved.item.text.get(start..end) {
-			commands.extend((Priority::Text, text.to_string()).into_commands());
+			commands.extend((Category::Navigation, Priority::Text, text.to_string())
+				.into_commands());
 		} else {
 			return Err(OdiliaError::Generic(format!(
 				"Slide {}..{} could not be created from {}",
-				start, end, caret_moved.item.text
+				start,
+				end,
+				privacy::Redacted(&caret_moved.item.text)
 			)));
 		}
 	} else {
@@ -218,25 +1449,34 @@ async fn caret_moved(
 				Granularity::Line,
 			)
 			.await?;
-		commands.extend((Priority::Text, text).into_commands());
+		commands.extend((Category::Navigation, Priority::Text, text).into_commands());
 	}
 	Ok(commands)
 }
 
+#[allow(clippy::too_many_lines)]
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
 	let args = Args::parse();
 
-	//initialize the primary token for task cancelation
-	let token = CancellationToken::new();
-
-	//initialize a task tracker, which will allow us to wait for all tasks to finish
-	let tracker = TaskTracker::new();
+	//initialize the shutdown coordinator, which owns every task and stops them in order
+	let mut shutdown = Shutdown::new();
 
-	//initializing configuration
-	let config = load_configuration(args.config)?;
+	//initializing configuration; after a restart, the settings in effect before it are kept
+	let restored = args.restore_from.as_deref().map(Snapshot::load);
+	let (config, restored_cache, restore_error) = match restored {
+		Some(Ok(snapshot)) => (snapshot.config, Some(snapshot.cache), None),
+		Some(Err(e)) => (load_configuration(args.config)?, None, Some(e)),
+		None => (load_configuration(args.config)?, None, None),
+	};
 	//initialize logging, with the provided config
 	logging::init(&config)?;
+	privacy::set_redacting(config.log.redact_text && !args.log_text);
+	if let Some(e) = restore_error {
+		tracing::error!("Could not restore the state saved before restarting: {e:?}");
+	}
+	crash::install_panic_hook(config.crash.relaunch, &Localizer::from_settings(&config.speech));
+	let session = instance::claim(args.replace).await?;
 
 	tracing::info!(?config, "this configuration was used to prepair odilia");
 
@@ -250,47 +1490,190 @@ async fn main() -> eyre::Result<()> {
 	// this is the channel which handles all SSIP commands. If SSIP is not allowed to operate on a separate task, then waiting for the receiving message can block other long-running operations like structural navigation.
 	// Although in the future, this may possibly be resolved through a proper cache, I think it still makes sense to separate SSIP's IO operations to a separate task.
 	//  it is very important that this is *never* full, since it can cause deadlocking if the other task sending the request is working with zbus.
-	let (ssip_req_tx, ssip_req_rx) = mpsc::channel::<ssip_client_async::Request>(128);
+	let typing_timing = odilia_tts::TypingTiming {
+		gap: Duration::from_millis(config.speech.typing_gap_ms),
+		pause: Duration::from_millis(config.speech.typing_pause_ms),
+		max_hold: Duration::from_millis(config.speech.typing_max_hold_ms),
+	};
+	let (ssip_req_tx, ssip_req_rx) = odilia_tts::speech_channel(128, typing_timing);
+	// on a virtual console, there is no AT-SPI to read; the console is read instead
+	if console::wanted(&config.console) {
+		return run_console(config, ssip_req_tx, ssip_req_rx, session, shutdown).await;
+	}
 	let (mut ev_tx, ev_rx) =
 		futures::channel::mpsc::channel::<Result<atspi::Event, atspi::AtspiError>>(10_000);
+	// events from the input socket(s) are buffered here, until the input handlers are free to process them.
+	let (sr_event_tx, sr_event_rx) = mpsc::channel::<ScreenReaderEvent>(128);
+	let remote_settings = config.input.remote.clone();
+	// everything spoken is also sent here, if the user asked for it to be captured.
+	let (capture, capture_task) = match args.capture_speech {
+		Some(path) => {
+			let (capture_tx, capture_rx) = mpsc::channel::<capture::SpokenRecord>(128);
+			let task = capture::write_captured_speech(
+				capture_rx,
+				path,
+				shutdown.token(Stage::Speech),
+			);
+			(SpeechCapture::new(capture_tx), Some(task))
+		}
+		None => (SpeechCapture::default(), None),
+	};
 	// Initialize state
-	let state = Arc::new(ScreenReaderState::new(ssip_req_tx, config).await?);
-	let ssip = odilia_tts::create_ssip_client().await?;
+	let state = match ScreenReaderState::new(
+		ssip_req_tx.clone(),
+		config.clone(),
+		capture,
+		shutdown.requested(),
+	)
+	.await
+	{
+		Ok(state) => Arc::new(state),
+		Err(e) if config.console.mode == ConsoleMode::Auto => {
+			tracing::warn!(
+				"Reading the console instead, since AT-SPI is not available: {e:?}"
+			);
+			return run_console(config, ssip_req_tx, ssip_req_rx, session, shutdown)
+				.await;
+		}
+		Err(e) => {
+			// there is no speech yet to say why
+			Earcons::new(config.earcons.clone()).play(Earcon::StageFailed);
+			return Err(e);
+		}
+	};
+	if let Some(items) = restored_cache {
+		tracing::debug!(items = items.len(), "Restoring cache");
+		if let Err(e) = state.cache.restore(items) {
+			tracing::error!("Could not restore the cache: {e}");
+		}
+	}
+	let ssip = match odilia_tts::create_ssip_client().await {
+		Ok(ssip) => ssip,
+		Err(e) => {
+			startup::failed(&state, startup::Step::Speech).await;
+			return Err(e);
+		}
+	};
+	// started now, rather than with the other tasks, so that a later stage which fails can still be spoken
+	let ssip_event_receiver = odilia_tts::handle_ssip_commands(
+		ssip,
+		ssip_req_rx,
+		privacy::redacting(),
+		state.l10n.get("quitting"),
+		shutdown.token(Stage::Speech),
+	)
+	.map(|r| r.wrap_err("Could no process SSIP request"));
+	shutdown.spawn(Stage::Speech, "ssip", ssip_event_receiver);
 
-	if state.say(Priority::Message, "Welcome to Odilia!".to_string()).await {
+	if state.say(Category::System, Priority::Message, state.l10n.get("welcome"))
+		.await
+	{
 		tracing::debug!("Welcome message spoken.");
+		startup::ready(&state, startup::Step::Speech).await;
 	} else {
 		tracing::error!("Welcome message failed. Odilia is not able to continue in this state. Exiting now.");
+		startup::failed(&state, startup::Step::Speech).await;
 		state.close_speech().await;
 		exit(1);
 	}
 
 	// Register events
-	tokio::try_join!(
+	let registered = tokio::try_join!(
 		state.register_event::<object::StateChangedEvent>(),
 		state.register_event::<object::TextCaretMovedEvent>(),
 		state.register_event::<object::ChildrenChangedEvent>(),
 		state.register_event::<object::TextChangedEvent>(),
+		state.register_event::<object::PropertyChangeEvent>(),
+		state.register_event::<object::SelectionChangedEvent>(),
 		state.register_event::<document::LoadCompleteEvent>(),
+		state.register_event::<document::ReloadEvent>(),
+		state.register_event::<document::PageChangedEvent>(),
+		state.register_event::<window::ActivateEvent>(),
 		state.add_cache_match_rule(),
-	)?;
+	);
+	if let Err(e) = registered {
+		startup::failed(&state, startup::Step::Bus).await;
+		// stopping the speech stage lets the failure be spoken before exiting
+		shutdown.requested().cancel();
+		shutdown.run().await;
+		return Err(e.into());
+	}
+	startup::ready(&state, startup::Step::Bus).await;
 
 	// load handlers
 	let handlers = Handlers::new(state.clone())
 		.command_listener(speak)
+		.command_listener(speak_char)
+		.command_listener(control_speech)
+		.command_listener(silence)
+		.command_listener(set_caret)
+		.command_listener(route)
+		.command_listener(scroll_into_view)
+		.command_listener(activate)
 		.command_listener(new_focused_item)
 		.command_listener(new_caret_pos)
+		.atspi_listener_with(repair_document, Order { priority: 1, ..Order::default() })
 		.atspi_listener(doc_loaded)
+		.atspi_listener(doc_reloaded)
+		.atspi_listener(page_changed)
 		.atspi_listener(caret_moved)
+		.atspi_listener_with(frame_boundary, Order { priority: 1, ..Order::default() })
+		.atspi_listener_with(app_mode, Order { priority: 1, ..Order::default() })
 		.atspi_listener(focused)
-		.atspi_listener(unfocused);
-
-	let ssip_event_receiver =
-		odilia_tts::handle_ssip_commands(ssip, ssip_req_rx, token.clone())
-			.map(|r| r.wrap_err("Could no process SSIP request"));
-	let notification_task = notifications_monitor(Arc::clone(&state), token.clone())
-		.map(|r| r.wrap_err("Could not process signal shutdown."));
-	let mut stream = state.atspi.event_stream();
+		.atspi_listener(focus_details)
+		.atspi_listener(calendar_selection)
+		.atspi_listener(unfocused)
+		.atspi_listener(window_activated)
+		.atspi_listener(busy)
+		.atspi_listener(not_busy)
+		.atspi_listener(refresh_value)
+		.atspi_listener(value_changed)
+		.atspi_listener(add_accessible)
+		.atspi_listener(remove_accessible)
+		.input_listener(stop_speech)
+		.input_listener(user_activity)
+		.input_listener(key_echo)
+		.input_listener(heartbeat)
+		.input_listener(event_diagnostics)
+		.input_listener(cycle_punctuation)
+		.input_listener(cycle_echo)
+		.input_listener(cycle_language)
+		.input_listener(cycle_output)
+		.input_listener(reset_adjustments)
+		.input_listener(say_location)
+		.input_listener(set_log_level)
+		.input_listener(inspect)
+		.input_listener(navigate_object)
+		.input_listener(activate_reviewed)
+		.input_listener(review_history)
+		.input_listener(search_history)
+		.input_listener(navigate_link)
+		.input_listener(structural_navigation)
+		.input_listener(enter_text)
+		.input_listener(skip_say_all)
+		.input_listener(change_mode)
+		.input_listener(cycle_mode)
+		.input_listener(say_mode)
+		.input_listener(read_row)
+		.input_listener(read_column)
+		.input_listener(read_container)
+		.input_listener(quit)
+		.custom_listener("say", say)
+		.custom_listener("braille-route", braille_route)
+		.input_listener(restart)
+		.check()?;
+	let unknown = state.handler_registry.unknown_disabled();
+	if !unknown.is_empty() {
+		tracing::warn!(?unknown, "Some handlers in handlers.disabled do not exist");
+	}
+	dbus::serve(&session, &state)
+		.await
+		.wrap_err("Could not serve Odilia's D-Bus interface")?;
+
+	let notification_task =
+		notifications_monitor(Arc::clone(&state), shutdown.token(Stage::Input))
+			.map(|r| r.wrap_err("Could not process signal shutdown."));
+	let mut stream = event_stream::event_stream(&state.atspi);
 	// There is a reason we are not reading from the event stream directly.
 	// This `MessageStream` can only store 64 events in its buffer.
 	// And, even if it could store more (it can via options), `zbus` specifically states that:
@@ -298,32 +1681,213 @@ async fn main() -> eyre::Result<()> {
 	// So, we continually poll it here, then receive it on the other end.
 	// Additioanlly, since sending is not async, but simply errors when there is an issue, this will
 	// help us avoid hangs.
-	let event_send_task = async move {
-		std::pin::pin!(&mut stream);
-		while let Some(ev) = stream.next().await {
-			if let Err(e) = ev_tx.try_send(ev) {
-				tracing::error!("Error sending event across channel! {e:?}");
+	// The stream only ends on its own when the connection is lost; stopping it (and dropping the sender) is what lets the atspi handlers finish.
+	let event_stream_token = shutdown.token(Stage::Input);
+	let reconnect_state = Arc::clone(&state);
+	let startup_events = ev_tx.clone();
+	let event_send_task =
+		async move {
+			std::pin::pin!(&mut stream);
+			loop {
+				match event_stream_token.run_until_cancelled(stream.next()).await {
+					None => return Ok(()),
+					Some(Some(ev)) => {
+						if let Err(e) = ev_tx.try_send(ev) {
+							tracing::error!("Error sending event across channel! {e:?}");
+						}
+					}
+					Some(None) => {
+						return reconnect::reconnect(
+							reconnect_state,
+							event_stream_token,
+						)
+						.await;
+					}
+				}
 			}
+		};
+	if state.config.tray.enabled {
+		let tray = tray::show(
+			session.clone(),
+			Arc::clone(&state),
+			sr_event_tx.clone(),
+			shutdown.token(Stage::Input),
+		)
+		.map(|r| r.wrap_err("Could not show the tray icon"));
+		shutdown.spawn(Stage::Input, "tray icon", tray);
+	}
+	let shortcuts = portal::listen(
+		session.clone(),
+		Arc::clone(&state),
+		sr_event_tx.clone(),
+		shutdown.token(Stage::Input),
+	)
+	.map(|r| r.wrap_err("Could not use global shortcuts"));
+	shutdown.spawn(Stage::Input, "global shortcuts", shortcuts);
+	let (rejected_tx, rejected_rx) = mpsc::channel::<odilia_input::Peer>(8);
+	let (backlogged_tx, backlogged_rx) = mpsc::channel::<()>(64);
+	let (accepted_tx, accepted_rx) = mpsc::channel::<()>(1);
+	let listener = match odilia_input::listen().await {
+		Ok(listener) => {
+			let input_server_waiter = startup::wait_for_input_server(
+				Arc::clone(&state),
+				accepted_rx,
+				shutdown.token(Stage::Input),
+			);
+			shutdown.spawn(Stage::Input, "input server waiter", input_server_waiter);
+			Some(listener)
+		}
+		Err(e) => {
+			tracing::error!("Could not open the input socket: {e:?}");
+			startup::failed(&state, startup::Step::Keyboard).await;
+			None
 		}
 	};
+	let input_task = listener.map(|listener| {
+		odilia_input::sr_event_receiver(
+			listener,
+			sr_event_tx.clone(),
+			{
+				let state = Arc::clone(&state);
+				move || odilia_input::Reply {
+					capture_text: state.text_entry.is_requested(),
+					mode: state.modes.current().name,
+				}
+			},
+			state.config.input.allowed_programs.clone(),
+			odilia_input::Reports {
+				rejected: rejected_tx,
+				accepted: accepted_tx,
+				backlogged: backlogged_tx.clone(),
+			},
+			shutdown.token(Stage::Input),
+		)
+		.map(|r| r.wrap_err("Could not process input socket"))
+	});
+	let rejection_announcer =
+		announce_rejected_input(rejected_rx, state.ssip.clone(), state.l10n.clone());
+	let backlog_announcer =
+		announce_input_backlog(backlogged_rx, state.ssip.clone(), state.l10n.clone());
+	let input_handlers_task = handlers.clone().input_handler(sr_event_rx);
 	let atspi_handlers_task = handlers.atspi_handler(ev_rx);
 
-	tracker.spawn(ssip_event_receiver);
-	tracker.spawn(notification_task);
-	tracker.spawn(atspi_handlers_task);
-	tracker.spawn(event_send_task);
-	tracker.close();
-	let _ = sigterm_signal_watcher(token, tracker)
+	shutdown.spawn(Stage::Input, "notifications", notification_task);
+	let idle_watcher = idle::watch(Arc::clone(&state), shutdown.token(Stage::Input));
+	shutdown.spawn(Stage::Input, "idle watcher", idle_watcher);
+	let heartbeat_watcher = heartbeat::watch(Arc::clone(&state), shutdown.token(Stage::Input));
+	shutdown.spawn(Stage::Input, "input server watchdog", heartbeat_watcher);
+	let diagnostics_reporter =
+		diagnostics::watch(Arc::clone(&state), shutdown.token(Stage::Input));
+	shutdown.spawn(Stage::Input, "event diagnostics", diagnostics_reporter);
+	let lock_watcher = keyboard::watch_locks(Arc::clone(&state), shutdown.token(Stage::Input));
+	shutdown.spawn(Stage::Input, "lock key watcher", lock_watcher);
+	let layout_watcher =
+		keyboard::watch_layout(Arc::clone(&state), shutdown.token(Stage::Input));
+	shutdown.spawn(Stage::Input, "keyboard layout watcher", layout_watcher);
+	let volume_watcher = volume::watch(Arc::clone(&state), shutdown.token(Stage::Input));
+	shutdown.spawn(Stage::Input, "volume watcher", volume_watcher);
+	let running_apps =
+		warm_start::prime(Arc::clone(&state), startup_events, shutdown.token(Stage::Input))
+			.map(|r| r.wrap_err("Could not read the applications already running"));
+	shutdown.spawn(Stage::Input, "running applications", running_apps);
+	let inhibitor =
+		power::inhibit_while_reading(Arc::clone(&state), shutdown.token(Stage::Input));
+	shutdown.spawn(Stage::Input, "idle inhibitor", inhibitor);
+	let marks = reading::follow_marks(Arc::clone(&state), shutdown.token(Stage::Input));
+	shutdown.spawn(Stage::Input, "say all index marks", marks);
+	if let Some(input_task) = input_task {
+		shutdown.spawn(Stage::Input, "input socket", input_task);
+	}
+	shutdown.spawn(Stage::Input, "rejected input announcer", rejection_announcer);
+	shutdown.spawn(Stage::Input, "input backlog announcer", backlog_announcer);
+	if remote_settings.enabled {
+		let remote_task = odilia_input::tcp_event_receiver(
+			sr_event_tx,
+			remote_settings,
+			backlogged_tx,
+			shutdown.token(Stage::Input),
+		)
+		.map(|r| r.wrap_err("Could not process remote input"));
+		shutdown.spawn(Stage::Input, "remote input", remote_task);
+	} else {
+		drop(sr_event_tx);
+	}
+	if let Some(capture_task) = capture_task {
+		shutdown.spawn(
+			Stage::Speech,
+			"speech capture",
+			capture_task.map(|r| r.wrap_err("Could not capture speech")),
+		);
+	}
+	shutdown.spawn(Stage::Handlers, "input handlers", input_handlers_task.map(Ok));
+	shutdown.spawn(Stage::Handlers, "atspi handlers", atspi_handlers_task.map(Ok));
+	shutdown.spawn(Stage::Input, "atspi event stream", event_send_task);
+	let watchdog = systemd::watchdog(shutdown.token(Stage::Speech));
+	shutdown.spawn(Stage::Speech, "watchdog", watchdog.map(Ok));
+	let mode_signaller = dbus::signal_mode_changes(
+		session.clone(),
+		state.modes.clone(),
+		shutdown.token(Stage::Input),
+	)
+	.map(|r| r.wrap_err("Could not signal mode changes"));
+	shutdown.spawn(Stage::Input, "mode change signaller", mode_signaller);
+	let braille_signaller = dbus::signal_braille_changes(
+		session.clone(),
+		state.ssip.clone(),
+		shutdown.token(Stage::Input),
+	)
+	.map(|r| r.wrap_err("Could not signal braille changes"));
+	shutdown.spawn(Stage::Input, "braille signaller", braille_signaller);
+	let monitor_watcher =
+		geometry::watch(Arc::clone(&state), session.clone(), shutdown.token(Stage::Input))
+			.map(|r| r.wrap_err("Could not follow the monitors"));
+	shutdown.spawn(Stage::Input, "monitor watcher", monitor_watcher);
+	let replacement_watcher = instance::watch_for_replacement(session, shutdown.requested())
+		.map(|r| r.wrap_err("Could not watch for replacement"));
+	shutdown.spawn(Stage::Input, "instance watcher", replacement_watcher);
+	systemd::notify_ready();
+	let _ = sigterm_signal_watcher(shutdown)
 		.await
 		.wrap_err("can not process interrupt signal");
+	logging::shutdown();
 	Ok(())
 }
 
+/// Runs in console mode (see [`console`]) until asked to stop, instead of reading the desktop.
+async fn run_console(
+	config: ApplicationConfig,
+	ssip_req_tx: odilia_tts::SpeechSender,
+	ssip_req_rx: odilia_tts::SpeechReceiver,
+	session: zbus::Connection,
+	mut shutdown: Shutdown,
+) -> eyre::Result<()> {
+	let ssip = odilia_tts::create_ssip_client().await?;
+	let ssip_event_receiver = odilia_tts::handle_ssip_commands(
+		ssip,
+		ssip_req_rx,
+		privacy::redacting(),
+		Localizer::from_settings(&config.speech).get("quitting"),
+		shutdown.token(Stage::Speech),
+	)
+	.map(|r| r.wrap_err("Could no process SSIP request"));
+	shutdown.spawn(Stage::Speech, "ssip", ssip_event_receiver);
+	console::start(config, ssip_req_tx, &mut shutdown).await?;
+	let replacement_watcher = instance::watch_for_replacement(session, shutdown.requested())
+		.map(|r| r.wrap_err("Could not watch for replacement"));
+	shutdown.spawn(Stage::Input, "instance watcher", replacement_watcher);
+	systemd::notify_ready();
+	sigterm_signal_watcher(shutdown)
+		.await
+		.wrap_err("can not process interrupt signal")
+}
+
 fn load_configuration(cli_overide: Option<PathBuf>) -> Result<ApplicationConfig, eyre::Report> {
 	// In order, do  a configuration file specified via cli, XDG_CONFIG_HOME, the usual location for system wide configuration(/etc/odilia/config.toml)
 	// If XDG_CONFIG_HOME based configuration wasn't found, create one by combining default values with the system provided ones, if available, for the user to alter, for the next run of odilia
 	//default configuration first, because that doesn't affect the priority outlined above
-	let figment = Figment::from(Serialized::defaults(ApplicationConfig::default()));
+	let figment = Figment::from(Serialized::defaults(ApplicationConfig::default()))
+		//then the settings bundled for particular applications, which every later file can replace
+		.admerge(Toml::string(APP_DEFAULTS));
 	//cli override, if applicable
 	let figment =
 		if let Some(path) = cli_overide { figment.join(Toml::file(path)) } else { figment };
@@ -344,7 +1908,12 @@ fn load_configuration(cli_overide: Option<PathBuf>) -> Result<ApplicationConfig,
 	//realise the configuration and freeze it into place
 	let config: ApplicationConfig = figment.extract()?;
 	if !config_path.exists() {
-		let toml = toml::to_string(&config)?;
+		// without the settings bundled for applications, so that the ones shipped with later versions still apply
+		let defaults: ApplicationConfig =
+			Figment::from(Serialized::defaults(ApplicationConfig::default()))
+				.admerge(Toml::file("/etc/odilia/config.toml"))
+				.extract()?;
+		let toml = toml::to_string(&defaults)?;
 		fs::write(&config_path, toml).expect("Unable to create default config file.");
 	}
 	Ok(config)
@@ -0,0 +1,110 @@
+//! Which mode Odilia is in, out of the ones configured in `modes.names`.
+//!
+//! Focus moving into an application with an entry in `modes.apps` switches to its mode; it stays on when focus moves on, until something else changes it.
+
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex, PoisonError},
+};
+
+use futures::future::{ok, Ready};
+use odilia_common::{
+	cache::AccessiblePrimitive, errors::OdiliaError, events::Direction,
+	modes::ScreenReaderMode, settings::modes::ModeSettings,
+};
+
+use tokio::sync::watch;
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+/// The configured modes, and which of them is current; cheap to clone.
+#[derive(Debug, Clone)]
+pub struct Modes {
+	names: Arc<[String]>,
+	current: Arc<Mutex<usize>>,
+	/// The name of the current mode, for those following mode changes.
+	changes: Arc<watch::Sender<String>>,
+	/// The mode for particular applications, by lowercase name.
+	apps: Arc<HashMap<String, String>>,
+	/// The application focus was last in, to tell when it moves into another one.
+	app: Arc<Mutex<Option<AccessiblePrimitive>>>,
+}
+
+impl Modes {
+	/// Starts in the first of `settings.names`; if there are none, the only mode is `Focus`.
+	pub fn new(settings: &ModeSettings) -> Self {
+		let names: Arc<[String]> = if settings.names.is_empty() {
+			Arc::new(["Focus".to_string()])
+		} else {
+			settings.names.as_slice().into()
+		};
+		let changes = Arc::new(watch::Sender::new(names[0].clone()));
+		let apps = settings
+			.apps
+			.iter()
+			.map(|(app, mode)| (app.to_lowercase(), mode.clone()))
+			.collect();
+		Modes {
+			names,
+			current: Arc::new(Mutex::new(0)),
+			changes,
+			apps: Arc::new(apps),
+			app: Arc::default(),
+		}
+	}
+	/// Notes that focus is in `app`; returns whether it moved there from another application, and any application has a mode of its own.
+	pub fn entered(&self, app: &AccessiblePrimitive) -> bool {
+		let mut last = self.app.lock().unwrap_or_else(PoisonError::into_inner);
+		if last.as_ref() == Some(app) {
+			return false;
+		}
+		*last = Some(app.clone());
+		!self.apps.is_empty()
+	}
+	/// The mode for the application called `app`, unless it has none or is in it already.
+	pub fn for_app(&self, app: &str) -> Option<&str> {
+		let mode = self.apps.get(&app.to_lowercase())?;
+		(!mode.eq_ignore_ascii_case(&self.current().name)).then_some(mode.as_str())
+	}
+	/// Follows the name of the current mode, as it changes.
+	pub fn subscribe(&self) -> watch::Receiver<String> {
+		self.changes.subscribe()
+	}
+	fn switch_to(&self, index: &mut usize, to: usize) -> ScreenReaderMode {
+		*index = to;
+		self.changes.send_replace(self.names[to].clone());
+		ScreenReaderMode::new(&self.names[to])
+	}
+	fn index(&self) -> std::sync::MutexGuard<'_, usize> {
+		self.current.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+	pub fn current(&self) -> ScreenReaderMode {
+		ScreenReaderMode::new(&self.names[*self.index()])
+	}
+	/// Switches to the mode called `name`, ignoring case; returns `None`, and stays in the current mode, if it is not configured.
+	pub fn set(&self, name: &str) -> Option<ScreenReaderMode> {
+		let i = self.names.iter().position(|mode| mode.eq_ignore_ascii_case(name))?;
+		Some(self.switch_to(&mut self.index(), i))
+	}
+	/// Switches back to the first mode, which Odilia starts in.
+	pub fn reset(&self) {
+		self.switch_to(&mut self.index(), 0);
+	}
+	/// Switches to the mode after (or before) the current one, wrapping around.
+	pub fn cycle(&self, direction: &Direction) -> ScreenReaderMode {
+		let mut i = self.index();
+		let next = match direction {
+			Direction::Forward => (*i + 1) % self.names.len(),
+			Direction::Backward => (*i + self.names.len() - 1) % self.names.len(),
+		};
+		self.switch_to(&mut i, next)
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for Modes {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.modes.clone())
+	}
+}
@@ -0,0 +1,195 @@
+//! The object navigator: a position in the tree of accessibles which the user moves by hand, to reach objects focus never lands on.
+//!
+//! It starts on the focused object, and goes back to it whenever focus moves, so that it never strays far from where the user is working.
+//! Objects which are not cached yet are fetched from their application on the way; scrolling each object it lands on into view is left to a [`ScrollIntoView`](odilia_common::command::ScrollIntoView) command.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use atspi_common::Role;
+use atspi_common::{CoordType, Interface};
+use atspi_proxies::device_event_controller::DeviceEventControllerProxy;
+use futures::future::{ok, Ready};
+use odilia_cache::{Cache, CacheExt, CacheItem, Convertable};
+use odilia_common::{
+	cache::AccessiblePrimitive,
+	errors::OdiliaError,
+	events::{Direction, ObjectDirection},
+	settings::navigation::ScrollAnchor,
+};
+
+use crate::{links, state::ScreenReaderState, tower::from_state::TryFromState};
+
+#[derive(Debug, Default)]
+struct Position {
+	/// The object the navigator is on.
+	object: Option<AccessiblePrimitive>,
+	/// The focused object when the navigator was last moved, to notice when focus moves.
+	focus: Option<AccessiblePrimitive>,
+}
+
+/// Where the object navigator is; cheap to clone.
+#[derive(Debug, Clone)]
+pub struct Navigator {
+	position: Arc<Mutex<Position>>,
+	/// Where objects the navigator lands on are scrolled to.
+	anchor: ScrollAnchor,
+}
+
+impl Navigator {
+	/// Starts on the focused object; objects out of view are scrolled to `anchor`.
+	pub fn new(anchor: ScrollAnchor) -> Self {
+		Navigator { position: Arc::default(), anchor }
+	}
+	/// Where objects the navigator lands on should be scrolled to.
+	pub fn anchor(&self) -> ScrollAnchor {
+		self.anchor
+	}
+	fn position(&self) -> std::sync::MutexGuard<'_, Position> {
+		self.position.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+	/// The object the navigator is on, given that `focused` has focus: the focused object itself, unless the navigator was moved since focus last moved.
+	pub fn current(&self, focused: &AccessiblePrimitive) -> AccessiblePrimitive {
+		let position = self.position();
+		match &position.object {
+			Some(object) if position.focus.as_ref() == Some(focused) => object.clone(),
+			_ => focused.clone(),
+		}
+	}
+	/// Moves the navigator from where it is in `direction`, and returns the object it is then on; `None` if there is nothing there, in which case it stays where it is.
+	/// # Errors
+	/// If an object on the way can not be fetched from its application.
+	pub async fn step(
+		&self,
+		direction: ObjectDirection,
+		focused: &AccessiblePrimitive,
+		cache: &Arc<Cache>,
+	) -> Result<Option<CacheItem>, OdiliaError> {
+		let current = cache.get_ipc(&self.current(focused)).await?;
+		let next = match direction {
+			ObjectDirection::Parent => {
+				if current.parent.key == current.object
+					|| current.object == current.app
+				{
+					None
+				} else {
+					Some(cache.get_ipc(&current.parent.key).await?)
+				}
+			}
+			ObjectDirection::FirstChild => match current.children.first() {
+				Some(child) => Some(cache.get_ipc(&child.key).await?),
+				None => None,
+			},
+			ObjectDirection::NextSibling | ObjectDirection::PreviousSibling => {
+				sibling(&current, direction, cache).await?
+			}
+		};
+		let Some(next) = next else {
+			return Ok(None);
+		};
+		self.move_to(&next, focused);
+		Ok(Some(next))
+	}
+	/// Moves the navigator to the next (or previous) object with `role` after where it is, in reading order within its document, and returns it; `None` if there is none, in which case it stays where it is.
+	/// Only what is in the cache is searched, like for links.
+	/// # Errors
+	/// If the object the navigator is on can not be fetched from its application.
+	pub async fn find(
+		&self,
+		role: Role,
+		direction: &Direction,
+		focused: &AccessiblePrimitive,
+		cache: &Arc<Cache>,
+	) -> Result<Option<CacheItem>, OdiliaError> {
+		let current = cache.get_ipc(&self.current(focused)).await?;
+		let items = links::in_order(links::document(&current));
+		let Some(position) = items.iter().position(|item| item.object == current.object)
+		else {
+			return Ok(None);
+		};
+		let found = match direction {
+			Direction::Forward => {
+				items[position + 1..].iter().find(|item| item.role == role)
+			}
+			Direction::Backward => {
+				items[..position].iter().rev().find(|item| item.role == role)
+			}
+		};
+		let Some(found) = found.cloned() else {
+			return Ok(None);
+		};
+		self.move_to(&found, focused);
+		Ok(Some(found))
+	}
+	fn move_to(&self, item: &CacheItem, focused: &AccessiblePrimitive) {
+		let mut position = self.position();
+		position.object = Some(item.object.clone());
+		position.focus = Some(focused.clone());
+	}
+}
+
+/// Activates `item`, as if it was clicked: its default action is done, or if it has none (or refuses), a click is made in its middle with the AT-SPI registry, which only works on X11.
+/// Returns whether either worked.
+/// # Errors
+/// If `item` can not be reached.
+pub async fn activate(
+	item: &CacheItem,
+	connection: &zbus::Connection,
+) -> Result<bool, OdiliaError> {
+	let accessible = item.object.clone().into_accessible(connection).await?;
+	if item.interfaces.contains(Interface::Action) {
+		let action = accessible.to_action().await?;
+		if action.nactions().await? > 0 && action.do_action(0).await? {
+			return Ok(true);
+		}
+	}
+	if !item.interfaces.contains(Interface::Component) {
+		return Ok(false);
+	}
+	let (x, y, width, height) = accessible
+		.to_component()
+		.await?
+		.get_extents(CoordType::Screen)
+		.await?;
+	let clicked = DeviceEventControllerProxy::new(connection)
+		.await?
+		.generate_mouse_event(x + width / 2, y + height / 2, "b1c")
+		.await;
+	if let Err(e) = &clicked {
+		tracing::debug!("Could not click {:?}: {e}", item.object);
+	}
+	Ok(clicked.is_ok())
+}
+
+/// The sibling after (or before) `item` in its parent.
+async fn sibling(
+	item: &CacheItem,
+	direction: ObjectDirection,
+	cache: &Arc<Cache>,
+) -> Result<Option<CacheItem>, OdiliaError> {
+	if item.object == item.app {
+		return Ok(None);
+	}
+	let parent = cache.get_ipc(&item.parent.key).await?;
+	let Some(index) = parent.children.iter().position(|child| child.key == item.object) else {
+		return Ok(None);
+	};
+	let index = match direction {
+		ObjectDirection::NextSibling => index + 1,
+		_ => match index.checked_sub(1) {
+			Some(index) => index,
+			None => return Ok(None),
+		},
+	};
+	match parent.children.get(index) {
+		Some(sibling) => Ok(Some(cache.get_ipc(&sibling.key).await?)),
+		None => Ok(None),
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for Navigator {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.navigator.clone())
+	}
+}
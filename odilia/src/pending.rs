@@ -0,0 +1,53 @@
+//! Actions which only happen once the user has asked for them twice in a row, like quitting.
+//!
+//! The first request only makes the action pending; asking for the same action again within the confirmation window carries it out.
+//! Any other input in between cancels it, so that an accidental key press followed by something else is harmless.
+
+use std::{
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+use futures::future::{ok, Ready};
+use odilia_common::{errors::OdiliaError, events::ScreenReaderEventDiscriminants};
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+/// The action waiting to be confirmed, if any; cheap to clone.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Default)]
+pub struct PendingAction(Arc<Mutex<Option<(ScreenReaderEventDiscriminants, Instant)>>>);
+
+impl PendingAction {
+	/// Returns `true` if `action` was already pending, and was asked for less than `within` ago; it is then no longer pending.
+	/// Otherwise, `action` becomes the pending one, and `false` is returned.
+	pub fn confirm(&self, action: ScreenReaderEventDiscriminants, within: Duration) -> bool {
+		let mut pending = self.0.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+		match pending.take() {
+			Some((pending_action, since))
+				if pending_action == action && since.elapsed() < within =>
+			{
+				true
+			}
+			_ => {
+				*pending = Some((action, Instant::now()));
+				false
+			}
+		}
+	}
+	/// Cancels the pending action, unless it is `action`.
+	pub fn cancel_unless(&self, action: ScreenReaderEventDiscriminants) {
+		let mut pending = self.0.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+		if pending.is_some_and(|(pending_action, _)| pending_action != action) {
+			*pending = None;
+		}
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for PendingAction {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.pending.clone())
+	}
+}
@@ -0,0 +1,211 @@
+//! Getting commands from the desktop's global shortcuts portal, rather than from an input server grabbing the keyboard.
+//!
+//! The shortcuts in `input.shortcuts` are bound in a session of `org.freedesktop.portal.GlobalShortcuts`, which the desktop may first ask the user to confirm.
+//! Every shortcut is bound once, whatever modes it is for, since the desktop may ask the user to confirm each bind; a shortcut limited to some modes, like `["Browse"]`, is ignored while none of those is current.
+//! The desktop keeps bound keys from the focused application in every mode, though, so the default shortcuts are all chords with `LOGO+ALT`, never the single letters input servers use while browsing.
+//! Every activation is sent into the channel the input socket feeds, after a [`ScreenReaderEvent::UserActivity`] like input servers send, so that it is handled the same way.
+//! Methods of the portal answer through a `Request` object, whose path is known in advance from the token passed in, so that its response can be listened for before it can arrive.
+
+use std::{collections::HashMap, sync::Arc};
+
+use eyre::{bail, eyre};
+use futures::StreamExt;
+use odilia_common::{
+	events::ScreenReaderEvent,
+	settings::input::{InputMethod, Shortcut},
+};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+
+use crate::state::ScreenReaderState;
+
+#[zbus::proxy(
+	interface = "org.freedesktop.portal.GlobalShortcuts",
+	default_service = "org.freedesktop.portal.Desktop",
+	default_path = "/org/freedesktop/portal/desktop"
+)]
+trait GlobalShortcuts {
+	fn create_session(
+		&self,
+		options: HashMap<&str, Value<'_>>,
+	) -> zbus::Result<OwnedObjectPath>;
+	fn bind_shortcuts(
+		&self,
+		session_handle: &ObjectPath<'_>,
+		shortcuts: Vec<(&str, HashMap<&str, Value<'_>>)>,
+		parent_window: &str,
+		options: HashMap<&str, Value<'_>>,
+	) -> zbus::Result<OwnedObjectPath>;
+	#[zbus(signal)]
+	fn activated(
+		&self,
+		session_handle: ObjectPath<'_>,
+		shortcut_id: &str,
+		timestamp: u64,
+		options: HashMap<String, OwnedValue>,
+	) -> zbus::Result<()>;
+}
+
+#[zbus::proxy(
+	interface = "org.freedesktop.portal.Request",
+	default_service = "org.freedesktop.portal.Desktop"
+)]
+trait Request {
+	#[zbus(signal)]
+	fn response(&self, response: u32, results: HashMap<String, OwnedValue>)
+		-> zbus::Result<()>;
+}
+
+#[zbus::proxy(
+	interface = "org.freedesktop.portal.Session",
+	default_service = "org.freedesktop.portal.Desktop"
+)]
+trait Session {
+	fn close(&self) -> zbus::Result<()>;
+}
+
+/// Calls a method of the portal, passing `token` as its `handle_token`, and waits for its response, returning its results.
+async fn call<F, Fut>(
+	session: &zbus::Connection,
+	token: &str,
+	method: F,
+) -> eyre::Result<HashMap<String, OwnedValue>>
+where
+	F: FnOnce(HashMap<&'static str, Value<'static>>) -> Fut,
+	Fut: std::future::Future<Output = zbus::Result<OwnedObjectPath>>,
+{
+	let sender = session
+		.unique_name()
+		.ok_or_else(|| eyre!("Not connected to the session bus"))?
+		.trim_start_matches(':')
+		.replace('.', "_");
+	let path = format!("/org/freedesktop/portal/desktop/request/{sender}/{token}");
+	let request = RequestProxy::builder(session).path(path)?.build().await?;
+	let mut responses = request.receive_response().await?;
+	method(HashMap::from([("handle_token", Value::from(token.to_string()))])).await?;
+	let response = responses
+		.next()
+		.await
+		.ok_or_else(|| eyre!("The portal did not respond"))?;
+	let args = response.args()?;
+	// 1 means the user cancelled, 2 that the portal failed
+	if args.response != 0 {
+		bail!("The portal refused the request ({})", args.response);
+	}
+	Ok(args.results)
+}
+
+/// The session created, from the results of `CreateSession`.
+fn session_handle(results: &HashMap<String, OwnedValue>) -> eyre::Result<OwnedObjectPath> {
+	match results.get("session_handle").map(|handle| &**handle) {
+		Some(Value::ObjectPath(path)) => Ok(path.clone().into()),
+		// some portals send the handle as a string, which the specification once said it was
+		Some(Value::Str(path)) => Ok(ObjectPath::try_from(path.as_str())?.into()),
+		_ => bail!("The portal created no session"),
+	}
+}
+
+/// Creates a session, and binds `shortcuts` in it, returning the session.
+async fn bind(
+	session: &zbus::Connection,
+	portal: &GlobalShortcutsProxy<'_>,
+	shortcuts: &HashMap<String, Shortcut>,
+) -> eyre::Result<OwnedObjectPath> {
+	let created = call(session, "odilia_session", |mut options| {
+		options.insert("session_handle_token", Value::from("odilia"));
+		portal.create_session(options)
+	})
+	.await?;
+	let handle = session_handle(&created)?;
+	let bindings = shortcuts
+		.iter()
+		.map(|(id, shortcut)| {
+			let properties = HashMap::from([
+				("description", Value::from(shortcut.description.as_str())),
+				("preferred_trigger", Value::from(shortcut.trigger.as_str())),
+			]);
+			(id.as_str(), properties)
+		})
+		.collect();
+	call(session, "odilia_bind", |options| {
+		portal.bind_shortcuts(&handle, bindings, "", options)
+	})
+	.await?;
+	tracing::debug!("Bound {} global shortcuts", shortcuts.len());
+	Ok(handle)
+}
+
+/// Closes a session, which unbinds its shortcuts.
+async fn close(session: &zbus::Connection, handle: &OwnedObjectPath) -> eyre::Result<()> {
+	let proxy = SessionProxy::builder(session).path(handle)?.build().await?;
+	proxy.close().await?;
+	Ok(())
+}
+
+/// Whether `shortcut` does anything in `mode`.
+fn applies(shortcut: &Shortcut, mode: &str) -> bool {
+	shortcut.modes.is_empty()
+		|| shortcut.modes.iter().any(|name| name.eq_ignore_ascii_case(mode))
+}
+
+/// Binds every shortcut with the global shortcuts portal, and sends the event of each shortcut into `events` whenever it is pressed in a mode it applies to, until `shutdown` is cancelled.
+/// Does nothing unless `input.method` is `Portal`; if the desktop has no such portal, that is only logged.
+#[tracing::instrument(skip_all)]
+pub async fn listen(
+	session: zbus::Connection,
+	state: Arc<ScreenReaderState>,
+	events: mpsc::Sender<ScreenReaderEvent>,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	if state.config.input.method != InputMethod::Portal {
+		return Ok(());
+	}
+	let shortcuts: &HashMap<String, Shortcut> = &state.config.input.shortcuts;
+	if shortcuts.is_empty() {
+		return Ok(());
+	}
+	let portal = GlobalShortcutsProxy::new(&session).await?;
+	let mut activations = portal.receive_activated().await?;
+	let handle = match bind(&session, &portal, shortcuts).await {
+		Ok(handle) => handle,
+		Err(e) => {
+			tracing::info!(
+				"Not using global shortcuts, since the desktop does not offer them: {e}"
+			);
+			return Ok(());
+		}
+	};
+	loop {
+		tokio::select! {
+			activated = activations.next() => {
+				let Some(activated) = activated else {
+					break;
+				};
+				let args = activated.args()?;
+				if *handle != args.session_handle {
+					continue;
+				}
+				let Some(shortcut) = shortcuts.get(args.shortcut_id) else {
+					tracing::debug!(id = args.shortcut_id, "Unknown global shortcut activated");
+					continue;
+				};
+				let mode = state.modes.current().name;
+				if !applies(shortcut, &mode) {
+					tracing::debug!(id = args.shortcut_id, mode, "Global shortcut does nothing in this mode");
+					continue;
+				}
+				for event in [ScreenReaderEvent::UserActivity, shortcut.event.clone()] {
+					if events.send(event).await.is_err() {
+						tracing::debug!(
+							"Not sending global shortcuts, input handlers are gone"
+						);
+						return Ok(());
+					}
+				}
+			}
+			() = shutdown.cancelled() => break,
+		}
+	}
+	close(&session, &handle).await
+}
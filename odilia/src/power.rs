@@ -0,0 +1,74 @@
+//! Keeping the computer awake while Odilia reads continuously.
+//!
+//! Listening to a long document involves no input, so the session would otherwise go idle, and lock the screen or suspend, in the middle of it.
+//! While reading, Odilia holds an idle inhibitor lock from logind, which lasts as long as the file descriptor logind hands out is open.
+
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+use zbus::zvariant::OwnedFd;
+
+use crate::state::ScreenReaderState;
+
+#[zbus::proxy(
+	interface = "org.freedesktop.login1.Manager",
+	default_service = "org.freedesktop.login1",
+	default_path = "/org/freedesktop/login1"
+)]
+trait Manager {
+	fn inhibit(&self, what: &str, who: &str, why: &str, mode: &str) -> zbus::Result<OwnedFd>;
+}
+
+/// Holds an idle inhibitor lock whenever something is read continuously, until `shutdown` is cancelled; the lock is released once reading stops, or is likely finished.
+/// Does nothing if disabled, or if logind is not available.
+#[tracing::instrument(skip_all)]
+pub async fn inhibit_while_reading(
+	state: Arc<ScreenReaderState>,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	if !state.config.idle.inhibit_while_reading {
+		return Ok(());
+	}
+	let manager = match zbus::Connection::system().await {
+		Ok(system) => ManagerProxy::new(&system).await?,
+		Err(e) => {
+			tracing::info!("Not keeping the computer awake while reading, since the system bus is not available: {e}");
+			return Ok(());
+		}
+	};
+	let mut until = state.say_all.until();
+	// the lock is held for as long as this is open
+	let mut lock: Option<OwnedFd> = None;
+	loop {
+		let reading_until = (*until.borrow_and_update())
+			.filter(|until| *until > std::time::Instant::now());
+		match (reading_until, &lock) {
+			(Some(_), None) => {
+				match manager
+					.inhibit("idle", "Odilia", "Reading continuously", "block")
+					.await
+				{
+					Ok(fd) => lock = Some(fd),
+					Err(e) => tracing::warn!("Could not keep the computer awake while reading: {e}"),
+				}
+			}
+			(None, Some(_)) => {
+				lock = None;
+				tracing::debug!(
+					"Released the idle inhibitor, since reading stopped"
+				);
+			}
+			_ => {}
+		}
+		tokio::select! {
+			changed = until.changed() => {
+				if changed.is_err() {
+					break;
+				}
+			}
+			() = tokio::time::sleep_until(reading_until.unwrap_or_else(std::time::Instant::now).into()), if reading_until.is_some() => {}
+			() = shutdown.cancelled() => break,
+		}
+	}
+	Ok(())
+}
@@ -0,0 +1,38 @@
+//! Asking the user for a line of text, like a search string.
+//!
+//! Odilia can not read the keyboard itself, so it asks input servers to collect the text, in its replies to the events they send (see [`odilia_input::Reply`]); they send it back as one [`ScreenReaderEvent::TextEntered`](odilia_common::events::ScreenReaderEvent::TextEntered).
+//! Until then, keys are text rather than commands, so only one line is asked for at a time.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use futures::future::{ok, Ready};
+use odilia_common::{errors::OdiliaError, events::TextPurpose};
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+/// What the line of text being typed is for, if one is; cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct TextEntry(Arc<Mutex<Option<TextPurpose>>>);
+
+impl TextEntry {
+	/// Asks input servers for a line of text, for `purpose`; this replaces any line asked for before.
+	pub fn request(&self, purpose: TextPurpose) {
+		*self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(purpose);
+	}
+	/// Whether a line of text is asked for, which input servers are told in reply to every event.
+	pub fn is_requested(&self) -> bool {
+		self.0.lock().unwrap_or_else(PoisonError::into_inner).is_some()
+	}
+	/// Stops asking for text, returning what the text asked for was for, if any was.
+	pub fn finish(&self) -> Option<TextPurpose> {
+		self.0.lock().unwrap_or_else(PoisonError::into_inner).take()
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for TextEntry {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.text_entry.clone())
+	}
+}
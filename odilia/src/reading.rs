@@ -0,0 +1,450 @@
+//! Reading continuously through the cached tree, like from the focused object to the end of the list, table or other container it is in.
+//!
+//! Text is read in document order: the text of each object, with each embedded object character replaced by what the child it stands for reads as.
+//! Only what is in the cache is read; children which were never cached are skipped.
+
+use std::{
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc, Mutex, PoisonError,
+	},
+	time::{Duration, Instant},
+};
+
+use atspi::Role;
+use futures::future::{ok, Ready};
+use odilia_cache::CacheItem;
+use odilia_common::{
+	cache::AccessiblePrimitive,
+	command::{Announcement, Category, OdiliaCommand, Speak},
+	errors::OdiliaError,
+	events::{Direction, TextUnit},
+};
+use odilia_tts::CHARACTERS_PER_SECOND;
+use ssip::Priority;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+/// The character an object's text has in place of each child, like a link in a paragraph.
+const EMBEDDED: char = '\u{fffc}';
+/// At most this many objects are read, so that reading a huge document does not hold up handlers for long.
+pub const MAX_OBJECTS: usize = 2000;
+
+/// Roles which group related content, which reading to the end of a container stops at the end of.
+const CONTAINERS: [Role; 13] = [
+	Role::List,
+	Role::Table,
+	Role::Tree,
+	Role::TreeTable,
+	Role::Article,
+	Role::Comment,
+	Role::Landmark,
+	Role::Section,
+	Role::Form,
+	Role::BlockQuote,
+	Role::DocumentWeb,
+	Role::DocumentEmail,
+	Role::DocumentText,
+];
+/// Roles which reading never goes past, even without a container around the object read from.
+const BOUNDARIES: [Role; 3] = [Role::DocumentFrame, Role::Frame, Role::Application];
+
+/// The closest ancestor of `item` which is a container; if it is in none, the outermost cached object below its window.
+pub fn container(item: &CacheItem) -> CacheItem {
+	let mut current = item.clone();
+	loop {
+		let Ok(parent) = current.parent() else {
+			return current;
+		};
+		if CONTAINERS.contains(&parent.role) {
+			return parent;
+		}
+		if BOUNDARIES.contains(&parent.role) || parent.object == current.object {
+			return current;
+		}
+		current = parent;
+	}
+}
+
+/// Where continuous reading is: in which of the texts read, and how many characters into it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Position {
+	text: usize,
+	offset: usize,
+}
+
+/// What is being read continuously.
+#[derive(Debug)]
+struct Reading {
+	texts: Vec<String>,
+	/// Where speech was last started from, or the last index mark it got to.
+	anchor: Position,
+	/// When speech was started from, or got to, `anchor`, or `None` while reading is paused.
+	since: Option<Instant>,
+	/// Which start or skip the speech going on is from, which the names of its index marks include, so that marks from speech stopped since are ignored.
+	run: u64,
+}
+
+impl Reading {
+	/// Where speech has likely got to: the last index mark it got to, which there is one of at the start of every sentence, and from there an estimate from the time since, like [`odilia_tts::SpeechSender::is_speaking`]; past the end, the position is one past the last text.
+	fn position(&self) -> Position {
+		let Some(since) = self.since else {
+			return self.anchor;
+		};
+		let spoken = since.elapsed().as_millis() * u128::from(CHARACTERS_PER_SECOND) / 1000;
+		let mut left = usize::try_from(spoken).unwrap_or(usize::MAX);
+		let mut position = self.anchor;
+		while let Some(text) = self.texts.get(position.text) {
+			let length = text.chars().count().saturating_sub(position.offset);
+			if left < length {
+				position.offset += left;
+				return position;
+			}
+			left -= length;
+			position = Position { text: position.text + 1, offset: 0 };
+		}
+		position
+	}
+	/// Where the `unit` after (or before) the one at `from` starts, or `None` past the last one.
+	/// Going back from the middle of a unit goes to the start of the one before it, since speech is usually a little further than estimated.
+	fn skip(&self, from: Position, unit: TextUnit, direction: &Direction) -> Option<Position> {
+		match direction {
+			Direction::Forward => {
+				let text = self.texts.get(from.text)?;
+				if let Some(&offset) = starts(text, unit)
+					.iter()
+					.find(|&&start| start > from.offset)
+				{
+					return Some(Position { text: from.text, offset });
+				}
+				(from.text + 1 < self.texts.len())
+					.then_some(Position { text: from.text + 1, offset: 0 })
+			}
+			Direction::Backward => {
+				let from = if from.text < self.texts.len() {
+					from
+				} else {
+					let text = self.texts.len().checked_sub(1)?;
+					Position { text, offset: self.texts[text].chars().count() }
+				};
+				let starts_here = starts(&self.texts[from.text], unit);
+				let current = starts_here
+					.iter()
+					.rev()
+					.find(|&&start| start <= from.offset)
+					.copied()
+					.unwrap_or_default();
+				if let Some(&offset) =
+					starts_here.iter().rev().find(|&&start| start < current)
+				{
+					return Some(Position { text: from.text, offset });
+				}
+				let Some(text) = from.text.checked_sub(1) else {
+					return Some(Position::default());
+				};
+				let offset = starts(&self.texts[text], unit)
+					.last()
+					.copied()
+					.unwrap_or_default();
+				Some(Position { text, offset })
+			}
+		}
+	}
+	/// When speech will likely have read everything from `anchor`, if it is not paused.
+	fn end(&self) -> Option<Instant> {
+		let characters: usize = self.rest().iter().map(|text| text.chars().count()).sum();
+		let characters = u64::try_from(characters).unwrap_or(u64::MAX);
+		self.since.map(|since| {
+			since + Duration::from_millis(
+				characters.saturating_mul(1000) / CHARACTERS_PER_SECOND,
+			)
+		})
+	}
+	/// The texts from `anchor` on, the first one only from its offset.
+	fn rest(&self) -> Vec<String> {
+		let mut texts = self.texts.get(self.anchor.text..).unwrap_or_default().to_vec();
+		if let Some(first) = texts.first_mut() {
+			*first = first.chars().skip(self.anchor.offset).collect();
+		}
+		texts
+	}
+	/// Speech reading each of the texts from `anchor` on in turn, with an index mark at the start of every sentence.
+	fn speak(&self) -> Vec<OdiliaCommand> {
+		self.rest()
+			.into_iter()
+			.enumerate()
+			.map(|(i, text)| {
+				let position = if i == 0 {
+					self.anchor
+				} else {
+					Position { text: self.anchor.text + i, offset: 0 }
+				};
+				let marks = starts(&text, TextUnit::Sentence)
+					.into_iter()
+					.map(|start| {
+						let offset = position.offset + start;
+						(
+							start,
+							mark(
+								self.run,
+								Position { offset, ..position },
+							),
+						)
+					})
+					.collect();
+				let announcement = Announcement::new(
+					Category::Navigation,
+					Priority::Text,
+					text,
+				)
+				.marked(marks);
+				Speak(announcement).into()
+			})
+			.collect()
+	}
+}
+
+/// The name of the index mark at `position` in speech from `run`.
+fn mark(run: u64, position: Position) -> String {
+	format!("say-all:{run}:{}:{}", position.text, position.offset)
+}
+
+/// The run and position an index mark named by [`mark`] is at, if it is one.
+fn parse_mark(name: &str) -> Option<(u64, Position)> {
+	let mut parts = name.strip_prefix("say-all:")?.split(':');
+	let run = parts.next()?.parse().ok()?;
+	let text = parts.next()?.parse().ok()?;
+	let offset = parts.next()?.parse().ok()?;
+	Some((run, Position { text, offset }))
+}
+
+/// Where each `unit` in `text` starts, in characters; the first one always starts at 0.
+/// Sentences end with a full stop, question or exclamation mark followed by a space, so that numbers like 3.5 do not end one.
+fn starts(text: &str, unit: TextUnit) -> Vec<usize> {
+	let mut starts = vec![0];
+	if unit == TextUnit::Paragraph {
+		return starts;
+	}
+	let (mut ended, mut space) = (false, false);
+	for (i, c) in text.chars().enumerate() {
+		if c.is_whitespace() {
+			space = true;
+			continue;
+		}
+		if space && i > 0 && (unit == TextUnit::Word || ended) {
+			starts.push(i);
+		}
+		ended = matches!(c, '.' | '!' | '?' | '…');
+		space = false;
+	}
+	starts
+}
+
+/// What is being read continuously, which the next key press stops even if `speech.interrupt_on_activity` is off; cheap to clone.
+/// Stopping only pauses it, so that the user can skip through it with [`SayAll::skip`] right after.
+#[derive(Debug, Clone)]
+pub struct SayAll {
+	reading: Arc<Mutex<Option<Reading>>>,
+	/// When the reading going on will likely be finished; see [`SayAll::until`].
+	until: Arc<watch::Sender<Option<Instant>>>,
+	/// How many times reading was started or skipped through, for [`Reading::run`].
+	runs: Arc<AtomicU64>,
+}
+
+impl Default for SayAll {
+	fn default() -> Self {
+		SayAll {
+			reading: Arc::default(),
+			until: Arc::new(watch::Sender::new(None)),
+			runs: Arc::default(),
+		}
+	}
+}
+
+impl SayAll {
+	/// Starts reading `texts`, one after the other, returning the speech to queue for them.
+	pub fn start(&self, texts: Vec<String>) -> Vec<OdiliaCommand> {
+		let reading = Reading {
+			texts,
+			anchor: Position::default(),
+			since: Some(Instant::now()),
+			run: self.runs.fetch_add(1, Ordering::Relaxed) + 1,
+		};
+		let commands = reading.speak();
+		self.until.send_replace(reading.end());
+		*self.reading.lock().unwrap_or_else(PoisonError::into_inner) = Some(reading);
+		commands
+	}
+	/// Pauses reading where it likely got to; returns whether something was being read, which should then be stopped.
+	pub fn cancel(&self) -> bool {
+		let mut reading = self.reading.lock().unwrap_or_else(PoisonError::into_inner);
+		let Some(reading) = reading.as_mut().filter(|reading| reading.since.is_some())
+		else {
+			return false;
+		};
+		reading.anchor = reading.position();
+		reading.since = None;
+		self.until.send_replace(None);
+		true
+	}
+	/// Forgets what was read, so that it can no longer be skipped through.
+	pub fn forget(&self) {
+		*self.reading.lock().unwrap_or_else(PoisonError::into_inner) = None;
+		self.until.send_replace(None);
+	}
+	/// When the reading going on will likely be finished, which changes whenever it is started, stopped or skipped through; `None` while nothing is read.
+	pub fn until(&self) -> watch::Receiver<Option<Instant>> {
+		self.until.subscribe()
+	}
+	/// Moves by one `unit` in `direction` from where reading is, returning the speech to queue to read on from there.
+	/// Returns `None` if nothing is being read, or the end of what was read is reached, which stops reading.
+	pub fn skip(&self, unit: TextUnit, direction: &Direction) -> Option<Vec<OdiliaCommand>> {
+		let mut guard = self.reading.lock().unwrap_or_else(PoisonError::into_inner);
+		let reading = guard.as_mut()?;
+		let Some(to) = reading.skip(reading.position(), unit, direction) else {
+			*guard = None;
+			self.until.send_replace(None);
+			return None;
+		};
+		reading.anchor = to;
+		reading.since = Some(Instant::now());
+		reading.run = self.runs.fetch_add(1, Ordering::Relaxed) + 1;
+		self.until.send_replace(reading.end());
+		Some(reading.speak())
+	}
+	/// Notes that speech got to the index mark named `name`, which is where reading is from now on, if it is one from the reading going on.
+	pub fn marked(&self, name: &str) {
+		let Some((run, position)) = parse_mark(name) else {
+			return;
+		};
+		let mut reading = self.reading.lock().unwrap_or_else(PoisonError::into_inner);
+		let Some(reading) = reading
+			.as_mut()
+			.filter(|reading| reading.run == run && reading.since.is_some())
+		else {
+			return;
+		};
+		reading.anchor = position;
+		reading.since = Some(Instant::now());
+		self.until.send_replace(reading.end());
+	}
+}
+
+/// Follows the index marks speech gets to, so that [`SayAll`] knows where reading is, until `shutdown` is cancelled.
+#[tracing::instrument(skip_all)]
+pub async fn follow_marks(
+	state: Arc<ScreenReaderState>,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	let mut marks = state.ssip.marks();
+	loop {
+		tokio::select! {
+			changed = marks.changed() => {
+				if changed.is_err() {
+					break;
+				}
+				let name = marks.borrow_and_update().clone();
+				state.say_all.marked(&name);
+			}
+			() = shutdown.cancelled() => break,
+		}
+	}
+	Ok(())
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for SayAll {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.say_all.clone())
+	}
+}
+
+enum Part {
+	Text(String),
+	Item(CacheItem),
+}
+
+/// What `item` is made up of, in reading order.
+fn parts(item: &CacheItem) -> Vec<Part> {
+	let mut children = item.get_children().unwrap_or_default().into_iter();
+	let segments: Vec<&str> = item.text.split(EMBEDDED).collect();
+	let mut parts = Vec::new();
+	if segments.len() == children.len() + 1 {
+		for segment in segments {
+			parts.push(Part::Text(segment.to_string()));
+			if let Some(child) = children.next() {
+				parts.push(Part::Item(child));
+			}
+		}
+	} else {
+		// the children do not match up with the text, so they are read after it
+		parts.push(Part::Text(item.text.replace(EMBEDDED, "")));
+		parts.extend(children.map(Part::Item));
+	}
+	parts
+}
+
+/// The text of `root` and everything in it, in reading order, one entry per piece of text; if `start` is given, only from that object on.
+/// Objects without text are read by their name.
+/// # Errors
+/// If the name of an object can not be fetched.
+pub async fn text(
+	root: CacheItem,
+	start: Option<&AccessiblePrimitive>,
+) -> Result<Vec<String>, OdiliaError> {
+	let mut started = start.is_none();
+	let mut texts = Vec::new();
+	let mut stack = vec![Part::Item(root)];
+	let mut visited = 0;
+	while let Some(part) = stack.pop() {
+		let item = match part {
+			Part::Text(text) if started && !text.trim().is_empty() => {
+				texts.push(text.trim().to_string());
+				continue;
+			}
+			Part::Text(_) => continue,
+			Part::Item(item) => item,
+		};
+		visited += 1;
+		if visited > MAX_OBJECTS {
+			tracing::debug!("Stopped reading after {MAX_OBJECTS} objects");
+			break;
+		}
+		started = started || start == Some(&item.object);
+		if item.text.is_empty() && item.children.is_empty() {
+			if started {
+				let name = item.name().await?;
+				if !name.trim().is_empty() {
+					texts.push(name.trim().to_string());
+				}
+			}
+			continue;
+		}
+		// pushed in reverse, so that they are popped in reading order
+		stack.extend(parts(&item).into_iter().rev());
+	}
+	Ok(texts)
+}
+
+/// How many headings and links there are in `root`, as far as it is cached.
+pub fn count_headings_and_links(root: &CacheItem) -> (usize, usize) {
+	let (mut headings, mut links) = (0, 0);
+	let mut stack = vec![root.clone()];
+	let mut visited = 0;
+	while let Some(item) = stack.pop() {
+		visited += 1;
+		if visited > MAX_OBJECTS {
+			break;
+		}
+		match item.role {
+			Role::Heading => headings += 1,
+			Role::Link => links += 1,
+			_ => {}
+		}
+		stack.extend(item.get_children().unwrap_or_default());
+	}
+	(headings, links)
+}
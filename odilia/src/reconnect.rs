@@ -0,0 +1,57 @@
+//! Getting the accessibility bus back after it went away, for example because the session's accessibility services were restarted.
+//!
+//! Everything Odilia knows about applications is tied to its connection to that bus: the cache is keyed by unique names on it, and every proxy and match rule lives on it.
+//! So rather than patching each of them, Odilia waits until the bus can be reached again, then restarts in place like [`crate::restart`], keeping its settings but not the cache.
+
+use std::{sync::Arc, time::Duration};
+
+use atspi_connection::AccessibilityConnection;
+use eyre::WrapErr;
+use odilia_common::command::Category;
+use ssip::Priority;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+	restart::{self, Snapshot},
+	state::ScreenReaderState,
+};
+
+/// How long to wait before trying the bus again, at first; this doubles after every attempt, up to [`MAX_RETRY_INTERVAL`].
+const RETRY_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Says that applications can no longer be heard from, waits until the accessibility bus can be reached again, and restarts Odilia on it.
+/// Returns once `shutdown` is cancelled; otherwise only if Odilia could not be restarted.
+#[tracing::instrument(skip_all)]
+pub async fn reconnect(
+	state: Arc<ScreenReaderState>,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	tracing::warn!("Lost the connection to the accessibility bus");
+	state.say(Category::System, Priority::Important, state.l10n.get("atspi-lost"))
+		.await;
+	let mut interval = RETRY_INTERVAL;
+	loop {
+		match shutdown.run_until_cancelled(AccessibilityConnection::new()).await {
+			None => return Ok(()),
+			Some(Ok(_)) => break,
+			Some(Err(e)) => {
+				tracing::debug!(
+					"The accessibility bus is still not available: {e}"
+				);
+			}
+		}
+		if shutdown.run_until_cancelled(sleep(interval)).await.is_none() {
+			return Ok(());
+		}
+		interval = (interval * 2).min(MAX_RETRY_INTERVAL);
+	}
+	// the cache refers to applications by their names on the old bus
+	let snapshot = Snapshot { config: state.config.clone(), cache: Vec::new() };
+	let path = snapshot.save()?;
+	tracing::info!("The accessibility bus is back; restarting on it");
+	let e = restart::exec_self(&path);
+	let _ = std::fs::remove_file(&path);
+	Err(e).wrap_err("Could not restart Odilia on the new accessibility bus")
+}
@@ -0,0 +1,138 @@
+//! Restarting Odilia in place, for example after it has been updated.
+//!
+//! Building the cache from scratch means asking every application for its whole tree again, which is slow on a busy desktop.
+//! So before restarting, the cache and the settings in effect are written to the XDG state directory, and the new process reads them back with `--restore-from`.
+
+use std::{
+	env,
+	ffi::OsString,
+	os::unix::process::CommandExt,
+	path::{Path, PathBuf},
+	process::Command,
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures::future::{ok, Ready};
+use odilia_cache::CacheItem;
+use odilia_common::{errors::OdiliaError, settings::ApplicationConfig};
+use serde::{Deserialize, Serialize};
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+/// Everything carried over from one process to the next.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+	pub config: ApplicationConfig,
+	pub cache: Vec<CacheItem>,
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for Snapshot {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(Snapshot { config: state.config.clone(), cache: state.cache.snapshot() })
+	}
+}
+
+impl Snapshot {
+	/// Writes the snapshot to the XDG state directory.
+	/// # Errors
+	/// If the state directory can not be created, or the file can not be written.
+	pub fn save(&self) -> eyre::Result<PathBuf> {
+		let time = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.unwrap_or_default()
+			.as_secs();
+		let path = xdg::BaseDirectories::with_prefix("odilia")?
+			.place_state_file(format!("restart-{time}.json"))?;
+		std::fs::write(&path, serde_json::to_vec(self)?)?;
+		Ok(path)
+	}
+	/// Reads a snapshot written by [`Snapshot::save`], and removes the file, so that it is only ever restored once.
+	/// # Errors
+	/// If the file can not be read, or was not written by a compatible version of Odilia.
+	pub fn load(path: &Path) -> eyre::Result<Self> {
+		let snapshot = serde_json::from_slice(&std::fs::read(path)?)?;
+		std::fs::remove_file(path)?;
+		Ok(snapshot)
+	}
+}
+
+/// What Linux appends to the path of a running binary which was deleted, as when a package upgrade replaces it.
+const DELETED: &str = " (deleted)";
+
+/// The binary to start in place of this one: the one running, unless it was deleted (see [`DELETED`]).
+/// A deleted binary is usually replaced by a new one at the same path; if there is none, `argv0` is started instead, which is looked up in `PATH` if it is only a name.
+fn binary(
+	current: Option<PathBuf>,
+	argv0: Option<OsString>,
+	exists: impl Fn(&Path) -> bool,
+) -> PathBuf {
+	let fallback = || argv0.map_or_else(|| PathBuf::from("odilia"), PathBuf::from);
+	let Some(current) = current else {
+		return fallback();
+	};
+	let Some(replaced) = current.to_str().and_then(|path| path.strip_suffix(DELETED)) else {
+		return current;
+	};
+	let replaced = PathBuf::from(replaced);
+	if exists(&replaced) {
+		replaced
+	} else {
+		fallback()
+	}
+}
+
+/// The Odilia binary to start in place of this process, like after an update; see [`binary`].
+pub fn current_binary() -> PathBuf {
+	binary(env::current_exe().ok(), env::args_os().next(), Path::exists)
+}
+
+/// Replaces the running process with the (possibly updated) Odilia binary, which will restore `snapshot`.
+/// This only returns if the new binary could not be started.
+pub fn exec_self(snapshot: &Path) -> eyre::Report {
+	let exe = current_binary();
+	// the bus name is released when our connection closes on exec, but the new process may get there first
+	let restore_from = format!("--restore-from={}", snapshot.display());
+	Command::new(exe)
+		.args(env::args_os().skip(1).filter(|arg| {
+			arg != "--replace" && !arg.to_string_lossy().starts_with("--restore-from")
+		}))
+		.arg("--replace")
+		.arg(restore_from)
+		.exec()
+		.into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn the_running_binary_is_started_again() {
+		let exe = binary(Some("/usr/bin/odilia".into()), Some("odilia".into()), |_| true);
+		assert_eq!(exe, Path::new("/usr/bin/odilia"));
+	}
+
+	#[test]
+	fn a_deleted_binary_is_replaced_by_the_one_at_its_path() {
+		let current = Some("/usr/bin/odilia (deleted)".into());
+		let exe = binary(current, Some("odilia".into()), |path| {
+			path == Path::new("/usr/bin/odilia")
+		});
+		assert_eq!(exe, Path::new("/usr/bin/odilia"));
+	}
+
+	#[test]
+	fn a_deleted_binary_which_moved_is_looked_up_by_its_name() {
+		let current = Some("/usr/bin/odilia (deleted)".into());
+		let exe = binary(current, Some("odilia".into()), |_| false);
+		assert_eq!(exe, Path::new("odilia"));
+	}
+
+	#[test]
+	fn without_a_path_to_the_binary_it_is_looked_up_by_its_name() {
+		assert_eq!(binary(None, None, |_| true), Path::new("odilia"));
+	}
+}
@@ -0,0 +1,39 @@
+//! Bringing objects into view: whenever Odilia moves to an object, it is scrolled to where `navigation.scroll_anchor` says, so that what is on screen matches what is spoken, and applications which only load what is in view load what is around it.
+
+use std::sync::Arc;
+
+use atspi_common::{Interface, ScrollType};
+use odilia_cache::{Cache, CacheExt, CacheItem, Convertable};
+use odilia_common::{errors::OdiliaError, settings::navigation::ScrollAnchor};
+
+/// Scrolls `item` into view, anchored at `anchor`.
+/// Objects without a `Component` interface are scrolled by their text; if they have neither, their closest ancestor which has one is scrolled instead.
+/// Returns whether anything was scrolled.
+/// # Errors
+/// If `item`, or one of its ancestors, can not be reached.
+pub async fn ensure_visible(
+	item: &CacheItem,
+	cache: &Arc<Cache>,
+	anchor: ScrollAnchor,
+) -> Result<bool, OdiliaError> {
+	let scroll = ScrollType::from(anchor);
+	let mut item = item.clone();
+	loop {
+		let accessible = item.object.clone().into_accessible(&cache.connection).await?;
+		if item.interfaces.contains(Interface::Component) {
+			return Ok(accessible.to_component().await?.scroll_to(scroll).await?);
+		}
+		if item.interfaces.contains(Interface::Text) {
+			let end = i32::try_from(item.character_count()).unwrap_or(i32::MAX);
+			return Ok(accessible
+				.to_text()
+				.await?
+				.scroll_substring_to(0, end, scroll as u32)
+				.await?);
+		}
+		if item.parent.key == item.object || item.object == item.app {
+			return Ok(false);
+		}
+		item = cache.get_ipc(&item.parent.key).await?;
+	}
+}
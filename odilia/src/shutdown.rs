@@ -0,0 +1,95 @@
+//! Stopping Odilia's tasks in order.
+//!
+//! Every long-running task belongs to a [`Stage`]. When shutdown is requested, the stages are stopped one after the other:
+//! first the sources of new events, then the handlers processing them, and finally speech, so that anything already queued (including the goodbye message) can still be spoken.
+//! Each stage gets its own grace period; a task which does not stop in time is aborted, and logged, so that one stuck task can never keep Odilia from exiting.
+
+use std::{collections::BTreeMap, future::Future, time::Duration};
+
+use tokio::{
+	task::JoinHandle,
+	time::{timeout_at, Instant},
+};
+use tokio_util::sync::CancellationToken;
+
+/// A group of tasks which is stopped together; stages are stopped in the order they are declared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+	/// Everything producing events: the input sockets, the AT-SPI event stream, notifications.
+	Input,
+	/// Handlers for the events; these stop on their own once their inputs have closed, after processing what was left.
+	Handlers,
+	/// Speech-dispatcher, speech capture and everything else which should outlive the handlers.
+	Speech,
+}
+
+impl Stage {
+	/// How long the tasks of this stage may take to stop, before they are aborted.
+	fn grace_period(self) -> Duration {
+		match self {
+			Stage::Input => Duration::from_millis(200),
+			Stage::Handlers => Duration::from_millis(500),
+			Stage::Speech => Duration::from_millis(1000),
+		}
+	}
+}
+
+#[derive(Default)]
+struct StageTasks {
+	token: CancellationToken,
+	tasks: Vec<(&'static str, JoinHandle<()>)>,
+}
+
+/// Keeps track of every task, so that they can be stopped in order.
+#[derive(Default)]
+pub struct Shutdown {
+	requested: CancellationToken,
+	stages: BTreeMap<Stage, StageTasks>,
+}
+
+impl Shutdown {
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// The token which starts the shutdown when cancelled, from anywhere.
+	pub fn requested(&self) -> CancellationToken {
+		self.requested.clone()
+	}
+	/// The token which is cancelled when `stage` is asked to stop.
+	pub fn token(&mut self, stage: Stage) -> CancellationToken {
+		self.stages.entry(stage).or_default().token.clone()
+	}
+	/// Spawns `task` as part of `stage`; errors are logged under `name`.
+	pub fn spawn<F>(&mut self, stage: Stage, name: &'static str, task: F)
+	where
+		F: Future<Output = eyre::Result<()>> + Send + 'static,
+	{
+		let handle = tokio::spawn(async move {
+			if let Err(e) = task.await {
+				tracing::error!(task = name, "{e:?}");
+			}
+		});
+		self.stages.entry(stage).or_default().tasks.push((name, handle));
+	}
+	/// Waits until shutdown is requested (see [`Self::requested`]), then stops every stage in order.
+	/// Returns the names of the tasks which had to be aborted.
+	#[tracing::instrument(skip_all)]
+	pub async fn run(self) -> Vec<&'static str> {
+		self.requested.cancelled().await;
+		let mut aborted = Vec::new();
+		for (stage, StageTasks { token, tasks }) in self.stages {
+			let grace_period = stage.grace_period();
+			tracing::debug!(?stage, ?grace_period, "Stopping tasks");
+			token.cancel();
+			let deadline = Instant::now() + grace_period;
+			for (name, mut handle) in tasks {
+				if timeout_at(deadline, &mut handle).await.is_err() {
+					tracing::warn!(?stage, task = name, "Task did not stop within {grace_period:?}; aborting it");
+					handle.abort();
+					aborted.push(name);
+				}
+			}
+		}
+		aborted
+	}
+}
@@ -0,0 +1,233 @@
+//! Turning an accessible into what is spoken about it, like "Save, push button, unavailable".
+//!
+//! What is said is given by a template for the item's role, with the placeholders `{name}`, `{role}`, `{states}`, `{value}`, `{position}` and `{shortcut}`.
+//! Templates are split into parts at commas; a part whose placeholders are all empty is left out, so that an item without states does not end in a dangling comma.
+//! Any role's template can be replaced in the `speech.templates` setting, keyed by the role's name (for example `"push button"`), or `"default"` for every role without its own.
+//! The fields said in each mode can be chosen and reordered in `speech.fields` instead, which are turned into a template for every role without its own.
+
+use std::{collections::HashMap, sync::Arc};
+
+use atspi_common::{Role, State};
+use futures::future::{ok, Ready};
+use odilia_cache::CacheItem;
+use odilia_common::{
+	errors::OdiliaError,
+	i18n::Localizer,
+	settings::speech::{SpokenField, Verbosity},
+};
+
+use crate::{
+	adjustments::SettingChange, modes::Modes, state::ScreenReaderState,
+	tower::from_state::TryFromState, values,
+};
+
+const DEFAULT_TEMPLATE: &str = "{name}, {role}, {states}, {shortcut}";
+/// For items where the user needs to know how many there are.
+const POSITION_TEMPLATE: &str = "{name}, {role}, {states}, {position}, {shortcut}";
+/// For sliders, progress bars and other items with a value.
+const VALUE_TEMPLATE: &str = "{name}, {role}, {value}, {states}, {shortcut}";
+
+/// The states worth mentioning.
+const SPOKEN_STATES: &[State] = &[
+	State::Checked,
+	State::Pressed,
+	State::Expanded,
+	State::Selected,
+	State::Required,
+	State::ReadOnly,
+];
+
+/// Formats accessibles for speech; cheap to clone.
+#[derive(Debug, Clone)]
+pub struct SpeechFormatter {
+	templates: Arc<HashMap<String, String>>,
+	/// The templates made from `speech.fields`, by lowercase mode name.
+	mode_templates: Arc<HashMap<String, String>>,
+	modes: Modes,
+	verbosity: Verbosity,
+	l10n: Localizer,
+}
+
+impl SpeechFormatter {
+	pub fn new(
+		templates: HashMap<String, String>,
+		fields: &HashMap<String, Vec<SpokenField>>,
+		modes: Modes,
+		verbosity: Verbosity,
+		l10n: Localizer,
+	) -> Self {
+		let mode_templates = fields
+			.iter()
+			.map(|(mode, fields)| {
+				let template: Vec<&str> =
+					fields.iter().map(|field| field.placeholder()).collect();
+				(mode.to_lowercase(), template.join(", "))
+			})
+			.collect();
+		SpeechFormatter {
+			templates: Arc::new(templates),
+			mode_templates: Arc::new(mode_templates),
+			modes,
+			verbosity,
+			l10n,
+		}
+	}
+	/// What to say about a setting being changed, like "Punctuation: most".
+	pub fn setting(&self, change: SettingChange) -> String {
+		let setting = self.l10n.get(change.setting);
+		let value = self.l10n.get(change.value);
+		self.l10n
+			.format("setting-changed", &[("setting", &setting), ("value", &value)])
+	}
+	/// What to say about `item`, called `name` (which may come from a label rather than the item itself).
+	pub fn format(&self, item: &CacheItem, name: &str) -> String {
+		let template = self.template(item.role);
+		let role = self.l10n.role(item.role).long;
+		let states = self.states(item);
+		let position = position(item).unwrap_or_default();
+		let shortcut = self.shortcut(item);
+		// the text of an editable item is its value, not its name
+		let value = match &item.value {
+			Some(value) => values::describe(value, &self.l10n),
+			None if item.states.contains(State::Editable) && item.text != name => {
+				item.text.clone()
+			}
+			None => String::new(),
+		};
+		let fields = [
+			("{name}", name),
+			("{role}", role.as_str()),
+			("{states}", states.as_str()),
+			("{value}", value.as_str()),
+			("{position}", position.as_str()),
+			("{shortcut}", shortcut.as_str()),
+		];
+		template.split(',')
+			.filter_map(|part| fill(part, &fields))
+			.collect::<Vec<_>>()
+			.join(",")
+			.trim()
+			.to_string()
+	}
+	fn states(&self, item: &CacheItem) -> String {
+		let mut spoken: Vec<String> = SPOKEN_STATES
+			.iter()
+			.filter(|state| item.states.contains(**state))
+			.map(|state| self.l10n.state(*state).long)
+			.collect();
+		// these are the absence of a state, so they have no `State` of their own
+		if item.states.contains(State::Expandable) && !item.states.contains(State::Expanded)
+		{
+			spoken.push(self.l10n.names("state-collapsed", "collapsed").long);
+		}
+		if !item.states.contains(State::Sensitive) {
+			spoken.push(self.l10n.names("state-unavailable", "unavailable").long);
+		}
+		spoken.join(", ")
+	}
+	/// The keyboard shortcut of `item`, as much of it as [`Verbosity`] asks for, like "Control plus S".
+	fn shortcut(&self, item: &CacheItem) -> String {
+		let Some(binding) = item.shortcut.as_deref() else {
+			return String::new();
+		};
+		let (mnemonic, shortcut) = binding_parts(binding);
+		let mut spoken = Vec::new();
+		if self.verbosity >= Verbosity::Medium {
+			spoken.extend(shortcut.map(|keys| self.keys(keys)));
+		}
+		if self.verbosity >= Verbosity::High {
+			spoken.extend(mnemonic.map(|keys| {
+				self.l10n.format("access-key", &[("keys", &self.keys(keys))])
+			}));
+		}
+		spoken.join(", ")
+	}
+	/// Speaks a key combination like `<Control><Shift>s` (GTK) or `Ctrl+Shift+S` (Qt and Chromium) as "Control plus Shift plus S".
+	fn keys(&self, combination: &str) -> String {
+		let mut keys = Vec::new();
+		let mut rest = combination.trim();
+		while let Some((modifier, after)) =
+			rest.strip_prefix('<').and_then(|rest| rest.split_once('>'))
+		{
+			keys.push(modifier);
+			rest = after;
+		}
+		keys.extend(rest.split('+').filter(|key| !key.is_empty()));
+		let keys: Vec<String> = keys
+			.into_iter()
+			.map(|key| match key.to_ascii_lowercase().as_str() {
+				"control" | "ctrl" | "primary" => self.l10n.get("key-control"),
+				"shift" => self.l10n.get("key-shift"),
+				"alt" | "mod1" => self.l10n.get("key-alt"),
+				"super" | "meta" | "mod4" => self.l10n.get("key-super"),
+				_ if key.chars().count() == 1 => key.to_uppercase(),
+				_ => key.to_string(),
+			})
+			.collect();
+		keys.join(&format!(" {} ", self.l10n.get("key-plus")))
+	}
+	fn template(&self, role: Role) -> &str {
+		if let Some(template) = self.templates.get(role.name()) {
+			return template;
+		}
+		if let Some(template) =
+			self.mode_templates.get(&self.modes.current().name.to_lowercase())
+		{
+			return template;
+		}
+		match role {
+			Role::ListItem | Role::MenuItem | Role::PageTab | Role::TreeItem => {
+				POSITION_TEMPLATE
+			}
+			Role::Slider | Role::SpinButton | Role::ScrollBar | Role::ProgressBar => {
+				VALUE_TEMPLATE
+			}
+			_ => self.templates.get("default").map_or(DEFAULT_TEMPLATE, String::as_str),
+		}
+	}
+}
+
+/// Fills in the placeholders in one part of a template, or returns `None` if the part had placeholders, but all of them were empty.
+fn fill(part: &str, fields: &[(&str, &str)]) -> Option<String> {
+	let mut filled = part.to_string();
+	let mut placeholders = 0;
+	let mut empty = 0;
+	for (placeholder, value) in fields {
+		if filled.contains(placeholder) {
+			placeholders += 1;
+			if value.is_empty() {
+				empty += 1;
+			}
+			filled = filled.replace(placeholder, value);
+		}
+	}
+	(placeholders == 0 || empty < placeholders).then_some(filled)
+}
+
+/// The mnemonic and the shortcut in a key binding from the `Action` interface, either of which may be missing.
+/// The binding is either `mnemonic;sequence;shortcut`, or only a shortcut.
+fn binding_parts(binding: &str) -> (Option<&str>, Option<&str>) {
+	fn given(keys: &str) -> Option<&str> {
+		Some(keys.trim()).filter(|keys| !keys.is_empty())
+	}
+	match binding.split(';').collect::<Vec<_>>().as_slice() {
+		[mnemonic, _, shortcut] => (given(mnemonic), given(shortcut)),
+		[shortcut] => (None, given(shortcut)),
+		_ => (None, None),
+	}
+}
+
+/// "3 of 5", from the item's index in its parent.
+fn position(item: &CacheItem) -> Option<String> {
+	let index = item.index?;
+	let count = item.parent().ok()?.children_num?;
+	Some(format!("{} of {count}", index + 1))
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for SpeechFormatter {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.formatter.clone())
+	}
+}
@@ -0,0 +1,96 @@
+//! Telling the user how far Odilia got while starting, so that "Odilia talks, but keys do nothing" can be told apart from a stage which failed.
+//!
+//! Each stage plays [`Earcon::StageReady`] once it is ready, and is spoken as well if `speech.announce_startup` is set, or earcons are disabled.
+//! A stage which fails plays [`Earcon::StageFailed`], and says which stage it was; failures of speech itself only have the earcon, of course.
+
+use std::{sync::Arc, time::Duration};
+
+use odilia_common::command::Category;
+use ssip::Priority;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::{earcons::Earcon, state::ScreenReaderState};
+
+/// How long input servers have to connect once the input socket is open, before the user is told that none did.
+pub const INPUT_SERVER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The stages of startup which are announced, in the order they happen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+	/// Speech dispatcher is connected, and the welcome message is queued.
+	Speech,
+	/// Events from applications on the accessibility bus are being listened to.
+	Bus,
+	/// An input server (like the keyboard) connected to the input socket, so keys reach Odilia.
+	Keyboard,
+}
+
+impl Step {
+	fn ready_key(self) -> &'static str {
+		match self {
+			Step::Speech => "startup-speech-ready",
+			Step::Bus => "startup-bus-ready",
+			Step::Keyboard => "startup-keyboard-ready",
+		}
+	}
+	/// What is said when this stage fails, if anything can be.
+	fn failed_key(self) -> Option<&'static str> {
+		match self {
+			Step::Speech => None,
+			Step::Bus => Some("startup-bus-failed"),
+			Step::Keyboard => Some("startup-keyboard-failed"),
+		}
+	}
+}
+
+/// Announces that `step` is ready.
+pub async fn ready(state: &ScreenReaderState, step: Step) {
+	tracing::debug!(?step, "Startup stage ready");
+	state.earcons.play(Earcon::StageReady);
+	if state.earcons.enabled() && !state.config.speech.announce_startup {
+		return;
+	}
+	state.say(Category::System, Priority::Message, state.l10n.get(step.ready_key()))
+		.await;
+}
+
+/// Announces that `step` failed; this is spoken whatever the settings, since it is why Odilia will not work as expected.
+pub async fn failed(state: &ScreenReaderState, step: Step) {
+	tracing::error!(?step, "Startup stage failed");
+	state.earcons.play(Earcon::StageFailed);
+	if let Some(key) = step.failed_key() {
+		state.say(Category::System, Priority::Important, state.l10n.get(key))
+			.await;
+	}
+}
+
+/// Announces [`Step::Keyboard`] once the first input server connects, as reported over `accepted`.
+/// If none connects within [`INPUT_SERVER_TIMEOUT`], that is said instead, and the keyboard is still announced if one connects later.
+pub async fn wait_for_input_server(
+	state: Arc<ScreenReaderState>,
+	mut accepted: mpsc::Receiver<()>,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	let first = tokio::time::timeout(INPUT_SERVER_TIMEOUT, accepted.recv());
+	match shutdown.run_until_cancelled(first).await {
+		Some(Ok(Some(()))) => ready(&state, Step::Keyboard).await,
+		// the input socket stopped, which reports its own error
+		None | Some(Ok(None)) => {}
+		Some(Err(_)) => {
+			tracing::warn!(timeout = ?INPUT_SERVER_TIMEOUT, "No input server connected");
+			state.earcons.play(Earcon::StageFailed);
+			state.say(
+				Category::System,
+				Priority::Important,
+				state.l10n.get("startup-keyboard-timeout"),
+			)
+			.await;
+			if let Some(Some(())) = shutdown.run_until_cancelled(accepted.recv()).await
+			{
+				ready(&state, Step::Keyboard).await;
+			}
+		}
+	}
+	Ok(())
+}
@@ -1,14 +1,42 @@
-use std::{fmt::Debug, sync::atomic::AtomicUsize};
+use std::{
+	fmt::Debug,
+	sync::atomic::AtomicUsize,
+	time::{Duration, Instant},
+};
 
+use crate::adjustments::{self, Adjustments};
+use crate::app_names::AppNames;
+use crate::capture::SpeechCapture;
+use crate::delay::DelayedSpeech;
+use crate::diagnostics::EventCounts;
+use crate::earcons::Earcons;
+use crate::echo::Echo;
+use crate::focus_watch::FocusWatch;
+use crate::frames::Frames;
+use crate::geometry::Geometry;
+use crate::heartbeat::InputServers;
+use crate::idle::Away;
+use crate::loading::Loading;
+use crate::modes::Modes;
+use crate::navigator::Navigator;
+use crate::pending::PendingAction;
+use crate::prompt::TextEntry;
+use crate::reading::SayAll;
+use crate::speech_format::SpeechFormatter;
+use crate::tables::Tables;
 use crate::tower::from_state::TryFromState;
+use crate::tower::registry::HandlerRegistry;
+use crate::transcript::SpeechHistory;
+use crate::values::ValueChanges;
 use circular_queue::CircularQueue;
 use eyre::WrapErr;
 use futures::future::err;
 use futures::future::ok;
 use futures::future::Ready;
-use ssip_client_async::{MessageScope, Priority, PunctuationMode, Request as SSIPRequest};
+use odilia_tts::SpeechSender;
+use ssip_client_async::{Priority, Request as SSIPRequest};
 use std::sync::Mutex;
-use tokio::sync::mpsc::Sender;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, Instrument, Level};
 use zbus::{
 	fdo::DBusProxy, message::Type as MessageType, names::BusName, proxy::CacheProperties,
@@ -25,23 +53,62 @@ use odilia_cache::Convertable;
 use odilia_cache::{AccessibleExt, Cache, CacheItem};
 use odilia_common::{
 	cache::AccessiblePrimitive,
-	command::CommandType,
+	command::{Announcement, Category, CommandType},
 	errors::{CacheError, OdiliaError},
-	settings::{speech::PunctuationSpellingMode, ApplicationConfig},
+	events::EventType,
+	i18n::Localizer,
+	modes::ScreenReaderMode,
+	settings::{
+		echo::EchoSettings, input::InputSettings, speech::SpeechSettings,
+		tables::TableSettings, ApplicationConfig,
+	},
 	types::TextSelectionArea,
 	Result as OdiliaResult,
 };
 use std::sync::Arc;
 
+/// How long handlers wait for an application to describe an accessible which is not cached yet, before giving up on the event.
+pub const CACHE_LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
 #[allow(clippy::module_name_repetitions)]
 pub(crate) struct ScreenReaderState {
 	pub atspi: AccessibilityConnection,
 	pub dbus: DBusProxy<'static>,
-	pub ssip: Sender<SSIPRequest>,
+	pub ssip: SpeechSender,
 	pub previous_caret_position: Arc<AtomicUsize>,
 	pub accessible_history: Arc<Mutex<CircularQueue<AccessiblePrimitive>>>,
 	pub event_history: Mutex<CircularQueue<Event>>,
 	pub cache: Arc<Cache>,
+	pub capture: SpeechCapture,
+	pub delayed: DelayedSpeech,
+	pub focus_watch: FocusWatch,
+	pub history: SpeechHistory,
+	/// The settings Odilia was started with.
+	pub config: ApplicationConfig,
+	pub earcons: Earcons,
+	pub pending: PendingAction,
+	pub text_entry: TextEntry,
+	/// Cancelled to ask every task to stop, and Odilia to exit.
+	pub shutdown: CancellationToken,
+	pub handler_registry: HandlerRegistry,
+	pub formatter: SpeechFormatter,
+	pub l10n: Localizer,
+	pub app_names: AppNames,
+	pub away: Away,
+	pub echo: Echo,
+	pub input_servers: InputServers,
+	pub event_counts: EventCounts,
+	pub modes: Modes,
+	pub value_changes: ValueChanges,
+	pub tables: Tables,
+	pub say_all: SayAll,
+	pub frames: Frames,
+	pub geometry: Geometry,
+	pub adjustments: Adjustments,
+	pub navigator: Navigator,
+	pub loading: Loading,
+	/// When Odilia started, for its uptime.
+	pub started: Instant,
 }
 #[derive(Debug, Clone)]
 pub struct AccessibleHistory(pub Arc<Mutex<CircularQueue<AccessiblePrimitive>>>);
@@ -67,7 +134,7 @@ pub struct LastFocused(pub AccessiblePrimitive);
 pub struct CurrentCaretPos(pub Arc<AtomicUsize>);
 #[derive(Debug, Clone)]
 pub struct LastCaretPos(pub usize);
-pub struct Speech(pub Sender<SSIPRequest>);
+pub struct Speech(pub SpeechSender);
 #[derive(Debug)]
 pub struct Command<T>(pub T)
 where
@@ -84,9 +151,134 @@ where
 	}
 }
 
+#[derive(Debug)]
+pub struct InputEvent<T>(pub T)
+where
+	T: EventType;
+
+impl<I> TryFromState<Arc<ScreenReaderState>, I> for InputEvent<I>
+where
+	I: EventType + Clone + Debug,
+{
+	type Error = OdiliaError;
+	type Future = Ready<Result<InputEvent<I>, Self::Error>>;
+	fn try_from_state(_state: Arc<ScreenReaderState>, ev: I) -> Self::Future {
+		ok(InputEvent(ev))
+	}
+}
+
+/// An AT-SPI event as it was received, for handlers which do not need the event's item to be looked up in the cache first.
+#[derive(Debug)]
+pub struct AtspiEvent<E>(pub E);
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for AtspiEvent<E>
+where
+	E: EventProperties + Debug,
+{
+	type Error = OdiliaError;
+	type Future = Ready<Result<AtspiEvent<E>, Self::Error>>;
+	fn try_from_state(_state: Arc<ScreenReaderState>, ev: E) -> Self::Future {
+		ok(AtspiEvent(ev))
+	}
+}
+
+#[derive(Debug)]
+pub struct AtspiCache(pub Arc<Cache>);
+
+impl<C> TryFromState<Arc<ScreenReaderState>, C> for AtspiCache {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _cmd: C) -> Self::Future {
+		ok(AtspiCache(Arc::clone(&state.cache)))
+	}
+}
+
+impl<C> TryFromState<Arc<ScreenReaderState>, C> for InputSettings {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _cmd: C) -> Self::Future {
+		ok(state.config.input.clone())
+	}
+}
+
+impl<C> TryFromState<Arc<ScreenReaderState>, C> for EchoSettings {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _cmd: C) -> Self::Future {
+		let mut echo = state.config.echo.clone();
+		if let Some(mode) = state.adjustments.echo() {
+			echo.mode = mode;
+		}
+		ok(echo)
+	}
+}
+
+/// The mode current when the event came in; a copy, so that handlers which only read it share nothing with those changing it.
+#[derive(Debug, Clone)]
+pub struct CurrentMode(pub ScreenReaderMode);
+
+impl<C> TryFromState<Arc<ScreenReaderState>, C> for CurrentMode {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _cmd: C) -> Self::Future {
+		ok(CurrentMode(state.modes.current()))
+	}
+}
+
+/// The settings as configured, without the changes the user made while Odilia runs, unlike the extractors for each section.
+pub struct Configured(pub ApplicationConfig);
+
+impl<C> TryFromState<Arc<ScreenReaderState>, C> for Configured {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _cmd: C) -> Self::Future {
+		ok(Configured(state.config.clone()))
+	}
+}
+
+impl<C> TryFromState<Arc<ScreenReaderState>, C> for TableSettings {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _cmd: C) -> Self::Future {
+		ok(state.config.tables.clone())
+	}
+}
+
+impl<C> TryFromState<Arc<ScreenReaderState>, C> for Localizer {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _cmd: C) -> Self::Future {
+		ok(state.l10n.clone())
+	}
+}
+
+/// Asks Odilia to exit, see [`ScreenReaderState::shutdown`].
+#[derive(Debug, Clone)]
+pub struct ShutdownRequest(pub CancellationToken);
+
+impl<C> TryFromState<Arc<ScreenReaderState>, C> for ShutdownRequest {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _cmd: C) -> Self::Future {
+		ok(ShutdownRequest(state.shutdown.clone()))
+	}
+}
+
+impl<C> TryFromState<Arc<ScreenReaderState>, C> for SpeechSettings {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _cmd: C) -> Self::Future {
+		let mut speech = state.config.speech.clone();
+		if let Some(punctuation) = state.adjustments.punctuation() {
+			speech.punctuation = punctuation;
+		}
+		ok(speech)
+	}
+}
+
 impl<C> TryFromState<Arc<ScreenReaderState>, C> for Speech
 where
-	C: CommandType + Debug,
+	C: Debug,
 {
 	type Error = OdiliaError;
 	type Future = Ready<Result<Speech, Self::Error>>;
@@ -95,6 +287,17 @@ where
 	}
 }
 
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for SpeechCapture
+where
+	E: Debug,
+{
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.capture.clone())
+	}
+}
+
 impl<E> TryFromState<Arc<ScreenReaderState>, E> for LastCaretPos
 where
 	E: Debug,
@@ -134,11 +337,55 @@ where
 	}
 }
 
+/// Sets up the voice as configured in `settings`, with the punctuation level from `adjustments` if the user changed it.
+/// # Errors
+/// If the task handling speech requests has stopped.
+pub async fn configure_speech(
+	ssip: &SpeechSender,
+	settings: &SpeechSettings,
+	adjustments: &Adjustments,
+) -> eyre::Result<()> {
+	ssip.send(SSIPRequest::SetPitch(ssip_client_async::ClientScope::Current, settings.pitch))
+		.await?;
+	ssip.send(SSIPRequest::SetVolume(ssip_client_async::ClientScope::Current, settings.volume))
+		.await?;
+	ssip.send(SSIPRequest::SetOutputModule(
+		ssip_client_async::ClientScope::Current,
+		settings.module.clone(),
+	))
+	.await?;
+	ssip.send(SSIPRequest::SetLanguage(
+		ssip_client_async::ClientScope::Current,
+		adjustments.language().unwrap_or_else(|| settings.language.clone()),
+	))
+	.await?;
+	ssip.send(SSIPRequest::SetSynthesisVoice(
+		ssip_client_async::ClientScope::Current,
+		settings.person.clone(),
+	))
+	.await?;
+	let punctuation_mode = adjustments::punctuation_mode(
+		adjustments.punctuation().unwrap_or(settings.punctuation),
+	);
+	ssip.send(SSIPRequest::SetPunctuationMode(
+		ssip_client_async::ClientScope::Current,
+		punctuation_mode,
+	))
+	.await?;
+	ssip.send(SSIPRequest::SetRate(ssip_client_async::ClientScope::Current, settings.rate))
+		.await?;
+	let output = adjustments.output().unwrap_or(settings.output);
+	ssip.route(output.speaks(), output.brailles());
+	Ok(())
+}
+
 impl ScreenReaderState {
 	#[tracing::instrument(skip_all)]
 	pub async fn new(
-		ssip: Sender<SSIPRequest>,
+		ssip: SpeechSender,
 		config: ApplicationConfig,
+		capture: SpeechCapture,
+		shutdown: CancellationToken,
 	) -> eyre::Result<ScreenReaderState> {
 		let atspi = AccessibilityConnection::new()
 			.instrument(tracing::info_span!("connecting to at-spi bus"))
@@ -157,48 +404,38 @@ impl ScreenReaderState {
 		let accessible_history = Arc::new(Mutex::new(CircularQueue::with_capacity(16)));
 		let event_history = Mutex::new(CircularQueue::with_capacity(16));
 		let cache = Arc::new(Cache::new(atspi.connection().clone()));
-		ssip.send(SSIPRequest::SetPitch(
-			ssip_client_async::ClientScope::Current,
-			config.speech.pitch,
-		))
-		.await?;
-		ssip.send(SSIPRequest::SetVolume(
-			ssip_client_async::ClientScope::Current,
-			config.speech.volume,
-		))
-		.await?;
-		ssip.send(SSIPRequest::SetOutputModule(
-			ssip_client_async::ClientScope::Current,
-			config.speech.module.clone(),
-		))
-		.await?;
-		ssip.send(SSIPRequest::SetLanguage(
-			ssip_client_async::ClientScope::Current,
-			config.speech.language.clone(),
-		))
-		.await?;
-		ssip.send(SSIPRequest::SetSynthesisVoice(
-			ssip_client_async::ClientScope::Current,
-			config.speech.person.clone(),
-		))
-		.await?;
-		//doing it this way for now. It could have been done with a From impl, but I don't want to make ssip_client_async a dependency of odilia_common, so this conversion is done directly inside state, especially since this enum isn't supposed to grow any further, in complexity or variants
-		let punctuation_mode = match config.speech.punctuation {
-			PunctuationSpellingMode::Some => PunctuationMode::Some,
-			PunctuationSpellingMode::Most => PunctuationMode::Most,
-			PunctuationSpellingMode::None => PunctuationMode::None,
-			PunctuationSpellingMode::All => PunctuationMode::All,
-		};
-		ssip.send(SSIPRequest::SetPunctuationMode(
-			ssip_client_async::ClientScope::Current,
-			punctuation_mode,
-		))
-		.await?;
-		ssip.send(SSIPRequest::SetRate(
-			ssip_client_async::ClientScope::Current,
-			config.speech.rate,
-		))
-		.await?;
+		let adjustments = Adjustments::load();
+		configure_speech(&ssip, &config.speech, &adjustments).await?;
+		let l10n = Localizer::from_settings(&config.speech);
+		let modes = Modes::new(&config.modes);
+		if let Some(mode) = adjustments.mode() {
+			if modes.set(&mode).is_none() {
+				tracing::info!(
+					mode,
+					"The mode Odilia was last in is no longer configured"
+				);
+			}
+		}
+		let history = SpeechHistory::new(config.speech.history_size);
+		let formatter = SpeechFormatter::new(
+			config.speech.templates.clone(),
+			&config.speech.fields,
+			modes.clone(),
+			config.speech.verbosity,
+			l10n.clone(),
+		);
+		let focus_watch = FocusWatch::new(
+			Arc::clone(&cache),
+			ssip.clone(),
+			formatter.clone(),
+			shutdown.clone(),
+		);
+		let delayed = DelayedSpeech::new(
+			ssip.clone(),
+			capture.clone(),
+			history.clone(),
+			shutdown.clone(),
+		);
 		Ok(Self {
 			atspi,
 			dbus,
@@ -207,6 +444,33 @@ impl ScreenReaderState {
 			accessible_history,
 			event_history,
 			cache,
+			capture,
+			delayed,
+			history,
+			earcons: Earcons::new(config.earcons.clone()),
+			handler_registry: HandlerRegistry::new(config.handlers.disabled.clone()),
+			focus_watch,
+			formatter,
+			l10n,
+			app_names: AppNames::new(&config.app_names),
+			away: Away::default(),
+			echo: Echo::default(),
+			input_servers: InputServers::default(),
+			event_counts: EventCounts::default(),
+			modes,
+			value_changes: ValueChanges::default(),
+			tables: Tables::default(),
+			say_all: SayAll::default(),
+			frames: Frames::default(),
+			geometry: Geometry::default(),
+			adjustments,
+			navigator: Navigator::new(config.navigation.scroll_anchor),
+			loading: Loading::new(shutdown.clone()),
+			config,
+			pending: PendingAction::default(),
+			text_entry: TextEntry::default(),
+			shutdown,
+			started: Instant::now(),
 		})
 	}
 	#[tracing::instrument(level = "debug", skip(self), err)]
@@ -326,32 +590,24 @@ impl ScreenReaderState {
 	}
 	#[tracing::instrument(skip(self))]
 	pub async fn stop_speech(&self) -> bool {
-		self.ssip.send(SSIPRequest::Cancel(MessageScope::All)).await.is_ok()
+		self.ssip.stop().await.is_ok()
 	}
 	#[tracing::instrument(name = "closing speech dispatcher connection", skip(self))]
 	pub async fn close_speech(&self) -> bool {
 		self.ssip.send(SSIPRequest::Quit).await.is_ok()
 	}
-	#[tracing::instrument(skip(self))]
-	pub async fn say(&self, priority: Priority, text: String) -> bool {
-		if self.ssip.send(SSIPRequest::SetPriority(priority)).await.is_err() {
-			return false;
-		}
-		if self.ssip.send(SSIPRequest::Speak).await.is_err() {
-			return false;
-		}
+	#[tracing::instrument(skip(self, text))]
+	pub async fn say(&self, category: Category, priority: Priority, text: String) -> bool {
 		// this crashed ssip-client because the connection is automatically stopped when invalid text is sent; since the period character on a line by itself is the stop character, there's not much we can do except filter it out explicitly.
 		if text == *"." {
 			return false;
 		}
-		if self.ssip
-			.send(SSIPRequest::SendLines(Vec::from([text])))
+		let announcement = Announcement::new(category, priority, text);
+		self.capture.record(&announcement);
+		self.ssip
+			.speak(announcement.priority, announcement.text)
 			.await
-			.is_err()
-		{
-			return false;
-		}
-		true
+			.is_ok()
 	}
 
 	#[allow(dead_code)]
@@ -401,7 +657,11 @@ impl ScreenReaderState {
 			.build()
 			.await?;
 		self.cache
-			.get_or_create(&accessible_proxy, Arc::clone(&self.cache))
+			.get_or_create(
+				&accessible_proxy,
+				Arc::clone(&self.cache),
+				CACHE_LOOKUP_TIMEOUT,
+			)
 			.await
 	}
 	#[tracing::instrument(skip_all, ret, err)]
@@ -0,0 +1,47 @@
+//! Integration with systemd, or any other service manager implementing the `sd_notify` protocol.
+//!
+//! All of this does nothing unless Odilia is run as a service with `Type=notify`; see the units in the `systemd/` directory.
+
+use std::time::Duration;
+
+use sd_notify::NotifyState;
+use tokio_util::sync::CancellationToken;
+
+/// Tells the service manager that Odilia has finished starting up, and is handling events.
+pub fn notify_ready() {
+	if let Err(e) = sd_notify::notify(false, &[NotifyState::Ready]) {
+		tracing::warn!("Could not notify the service manager that Odilia is ready: {e}");
+	}
+}
+
+/// Tells the service manager that Odilia is shutting down on purpose.
+pub fn notify_stopping() {
+	if let Err(e) = sd_notify::notify(false, &[NotifyState::Stopping]) {
+		tracing::warn!("Could not notify the service manager that Odilia is stopping: {e}");
+	}
+}
+
+/// Pings the service manager's watchdog at half the interval it asked for, until cancelled.
+/// Returns immediately if no watchdog was requested (`WatchdogSec=` in the unit).
+#[tracing::instrument(skip_all)]
+pub async fn watchdog(shutdown: CancellationToken) {
+	let mut usec = 0;
+	if !sd_notify::watchdog_enabled(false, &mut usec) {
+		return;
+	}
+	let interval = Duration::from_micros(usec) / 2;
+	tracing::debug!(?interval, "Service manager watchdog enabled");
+	loop {
+		tokio::select! {
+			() = tokio::time::sleep(interval) => {
+				if let Err(e) = sd_notify::notify(false, &[NotifyState::Watchdog]) {
+					tracing::warn!("Could not ping the service manager watchdog: {e}");
+				}
+			}
+			() = shutdown.cancelled() => {
+				tracing::debug!("Shutting down watchdog task.");
+				break;
+			}
+		}
+	}
+}
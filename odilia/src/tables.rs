@@ -0,0 +1,200 @@
+//! Reading tables and grids a row or a column at a time.
+//!
+//! This relies on cells implementing AT-SPI's `TableCell` interface, as they do in Firefox, Chromium and GTK 4; elsewhere, each cell is only read on its own.
+//! The grids of days in calendars and date pickers are tables too, whose cells are read as dates instead.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use atspi_common::Role;
+use futures::future::{ok, Ready};
+use odilia_cache::{Cache, CacheExt, CacheItem, Convertable};
+use odilia_common::{cache::AccessiblePrimitive, errors::OdiliaError, i18n::Localizer};
+
+use crate::{
+	state::{ScreenReaderState, CACHE_LOOKUP_TIMEOUT},
+	tower::from_state::TryFromState,
+};
+
+/// At most this many cells are read, so that reading a column of a huge table does not take minutes.
+const MAX_CELLS: i32 = 100;
+
+/// A row or a column of a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line {
+	Row,
+	Column,
+}
+
+/// What was read last in tables; cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct Tables {
+	/// The table and row of the cell focused last, to tell when a new row is entered.
+	row: Arc<Mutex<Option<(AccessiblePrimitive, i32)>>>,
+	/// The month of the calendar day read last, to only say it when it changes.
+	month: Arc<Mutex<String>>,
+}
+
+impl Tables {
+	/// The text of the row of `cell`, if it was just focused and is in another row than the cell focused before it.
+	/// # Errors
+	/// If the table can not be read.
+	pub async fn entered_row(
+		&self,
+		cell: &AccessiblePrimitive,
+		cache: &Arc<Cache>,
+	) -> Result<Option<String>, OdiliaError> {
+		let Some((table, row, _)) = position(cell, cache).await else {
+			*self.row.lock().unwrap_or_else(PoisonError::into_inner) = None;
+			return Ok(None);
+		};
+		let current = Some((table.clone(), row));
+		let previous = std::mem::replace(
+			&mut *self.row.lock().unwrap_or_else(PoisonError::into_inner),
+			current.clone(),
+		);
+		if previous == current {
+			return Ok(None);
+		}
+		read_line(&table, Line::Row, row, cache).await.map(Some)
+	}
+	/// What is said for `cell` if it is a day in a calendar: its weekday, its date and whether it is selected, after its month if that is not the month of the day read before; `None` if it is not in a calendar.
+	/// # Errors
+	/// If the calendar can not be read.
+	pub async fn calendar_day(
+		&self,
+		cell: &AccessiblePrimitive,
+		cache: &Arc<Cache>,
+		l10n: &Localizer,
+	) -> Result<Option<String>, OdiliaError> {
+		let Some((table, row, column)) = position(cell, cache).await else {
+			return Ok(None);
+		};
+		let Some(calendar) = calendar(&table, cache).await else {
+			return Ok(None);
+		};
+		let accessible = table.clone().into_accessible(&cache.connection).await?;
+		let grid = accessible.to_table().await?;
+		// the weekday is the column header, or at least its description
+		let mut weekday = match grid.get_column_header(column).await {
+			Ok(header) => cell_text(header.into(), cache).await.unwrap_or_default(),
+			Err(_) => String::new(),
+		};
+		if weekday.is_empty() {
+			weekday = grid.get_column_description(column).await.unwrap_or_default();
+		}
+		let day = cell_text(cell.clone(), cache).await?;
+		let message = if grid.is_selected(row, column).await.unwrap_or(false) {
+			"calendar-day-selected"
+		} else {
+			"calendar-day"
+		};
+		let text = l10n.format(message, &[("weekday", &weekday), ("day", &day)]);
+		// the month is the caption of the grid, or otherwise the name of the calendar
+		let mut month = match grid.caption().await {
+			Ok(caption) => cell_text(caption.into(), cache).await.unwrap_or_default(),
+			Err(_) => String::new(),
+		};
+		if month.is_empty() {
+			month = calendar.name().await.unwrap_or_default();
+		}
+		let previous = std::mem::replace(
+			&mut *self.month.lock().unwrap_or_else(PoisonError::into_inner),
+			month.clone(),
+		);
+		if month.is_empty() || month == previous {
+			return Ok(Some(text));
+		}
+		Ok(Some(format!("{month}, {text}")))
+	}
+}
+
+/// The calendar `table` is the grid of days of: `table` itself, or the calendar around it; `None` if it is an ordinary table.
+async fn calendar(table: &AccessiblePrimitive, cache: &Arc<Cache>) -> Option<CacheItem> {
+	let item = cache.get_ipc(table).await.ok()?;
+	if item.role == Role::Calendar {
+		return Some(item);
+	}
+	let parent = cache.get_ipc(&item.parent.key).await.ok()?;
+	(parent.role == Role::Calendar).then_some(parent)
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for Tables {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.tables.clone())
+	}
+}
+
+/// The table `cell` is in, with its row and column; `None` if it is not a table cell.
+async fn position(
+	cell: &AccessiblePrimitive,
+	cache: &Arc<Cache>,
+) -> Option<(AccessiblePrimitive, i32, i32)> {
+	let accessible = cell.clone().into_accessible(&cache.connection).await.ok()?;
+	let cell = accessible.to_table_cell().await.ok()?;
+	let (row, column) = cell.position().await.ok()?;
+	let table = cell.table().await.ok()?;
+	Some((table.into(), row, column))
+}
+
+/// What is said for `cell`: its text, or otherwise its name.
+async fn cell_text(cell: AccessiblePrimitive, cache: &Arc<Cache>) -> Result<String, OdiliaError> {
+	let accessible = cell.into_accessible(&cache.connection).await?;
+	let item: CacheItem = cache
+		.get_or_create(&accessible, Arc::clone(cache), CACHE_LOOKUP_TIMEOUT)
+		.await?;
+	if item.text.is_empty() {
+		item.name().await
+	} else {
+		Ok(item.text)
+	}
+}
+
+/// The text of every cell in row or column `index` of `table`, separated by commas; empty cells are skipped.
+async fn read_line(
+	table: &AccessiblePrimitive,
+	line: Line,
+	index: i32,
+	cache: &Arc<Cache>,
+) -> Result<String, OdiliaError> {
+	let accessible = table.clone().into_accessible(&cache.connection).await?;
+	let table = accessible.to_table().await?;
+	let count = match line {
+		Line::Row => table.ncolumns().await?,
+		Line::Column => table.nrows().await?,
+	}
+	.min(MAX_CELLS);
+	let mut texts = Vec::new();
+	for other in 0..count {
+		let (row, column) = match line {
+			Line::Row => (index, other),
+			Line::Column => (other, index),
+		};
+		let cell = table.get_accessible_at(row, column).await?;
+		let text = cell_text(cell.into(), cache).await?;
+		// a cell spanning several columns (or rows) is returned for each of them
+		if !text.is_empty() && texts.last() != Some(&text) {
+			texts.push(text);
+		}
+	}
+	Ok(texts.join(", "))
+}
+
+/// The text of the row or column `cell` is in; `None` if it is not a table cell.
+/// # Errors
+/// If the table can not be read.
+pub async fn read(
+	cell: &AccessiblePrimitive,
+	line: Line,
+	cache: &Arc<Cache>,
+) -> Result<Option<String>, OdiliaError> {
+	let Some((table, row, column)) = position(cell, cache).await else {
+		return Ok(None);
+	};
+	let index = match line {
+		Line::Row => row,
+		Line::Column => column,
+	};
+	read_line(&table, line, index, cache).await.map(Some)
+}
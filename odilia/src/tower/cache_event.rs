@@ -1,4 +1,7 @@
-use crate::{tower::from_state::TryFromState, OdiliaError, ScreenReaderState};
+use crate::{
+	state::CACHE_LOOKUP_TIMEOUT, tower::from_state::TryFromState, OdiliaError,
+	ScreenReaderState,
+};
 use atspi_common::EventProperties;
 use derived_deref::{Deref, DerefMut};
 use odilia_cache::CacheItem;
@@ -68,6 +71,27 @@ where
 	}
 }
 
+/// Gets the item `event` is about, from the cache or otherwise from its application.
+/// Once Odilia is shutting down, this gives up right away, rather than keeping the handler waiting on an application which may never answer.
+async fn lookup<E: EventProperties>(
+	state: &Arc<ScreenReaderState>,
+	event: &E,
+) -> Result<CacheItem, OdiliaError> {
+	let a11y = AccessiblePrimitive::from_event(event);
+	let lookup = async {
+		let proxy = a11y.into_accessible(state.connection()).await?;
+		state.cache
+			.get_or_create(&proxy, Arc::clone(&state.cache), CACHE_LOOKUP_TIMEOUT)
+			.await
+	};
+	// boxed, since describing an accessible takes many calls, which would make every handler's future as large
+	Box::pin(state.shutdown.run_until_cancelled(lookup))
+		.await
+		.unwrap_or_else(|| {
+			Err(OdiliaError::ServiceUnavailable("Odilia is shutting down".to_string()))
+		})
+}
+
 impl<E> TryFromState<Arc<ScreenReaderState>, E> for InnerEvent<E>
 where
 	E: EventProperties + Debug + Clone,
@@ -77,10 +101,7 @@ where
 	#[tracing::instrument(skip(state), ret)]
 	fn try_from_state(state: Arc<ScreenReaderState>, event: E) -> Self::Future {
 		async move {
-			let a11y = AccessiblePrimitive::from_event(&event);
-			let proxy = a11y.into_accessible(state.connection()).await?;
-			let cache_item =
-				state.cache.get_or_create(&proxy, Arc::clone(&state.cache)).await?;
+			let cache_item = lookup(&state, &event).await?;
 			Ok(InnerEvent::new(event, cache_item))
 		}
 	}
@@ -96,10 +117,7 @@ where
 	#[tracing::instrument(skip(state), ret)]
 	fn try_from_state(state: Arc<ScreenReaderState>, event: E) -> Self::Future {
 		async move {
-			let a11y = AccessiblePrimitive::from_event(&event);
-			let proxy = a11y.into_accessible(state.connection()).await?;
-			let cache_item =
-				state.cache.get_or_create(&proxy, Arc::clone(&state.cache)).await?;
+			let cache_item = lookup(&state, &event).await?;
 			let cache_event = InnerEvent::new(event.clone(), cache_item);
 			EventPredicate::from_cache_event(cache_event, state).ok_or(
 				OdiliaError::PredicateFailure(format!(
@@ -8,6 +8,10 @@ use odilia_common::{
 		OdiliaCommandDiscriminants as CommandDiscriminants,
 	},
 	errors::OdiliaError,
+	events::{
+		Custom, EventType, ScreenReaderEvent,
+		ScreenReaderEventDiscriminants as EventDiscriminants,
+	},
 };
 use std::collections::{btree_map::Entry, BTreeMap};
 use std::fmt::Debug;
@@ -58,6 +62,27 @@ where
 	{
 		self.services.entry(k)
 	}
+	pub fn iter(&self) -> impl Iterator<Item = (&K, &S)> {
+		self.services.iter()
+	}
+	/// The service `req` would be given to, ready to be called.
+	/// # Errors
+	/// [`OdiliaError::ServiceNotFound`], if there is no service for it.
+	pub fn choose(&mut self, req: &Req) -> Result<S, OdiliaError>
+	where
+		S: Clone,
+		K: Ord + Debug,
+	{
+		let k = req.identifier();
+		let Some(orig_svc) = self.services.get_mut(&k) else {
+			return Err(OdiliaError::ServiceNotFound(format!(
+				"A service with key {k:?} could not be found in a list with keys of {:?}",
+				self.services.keys()
+			)));
+		};
+		let clone = orig_svc.clone();
+		Ok(std::mem::replace(orig_svc, clone))
+	}
 }
 
 impl<K, S, Req> Service<Req> for ChoiceService<K, S, Req>
@@ -77,17 +102,10 @@ where
 		Poll::Ready(Ok(()))
 	}
 	fn call(&mut self, req: Req) -> Self::Future {
-		let k = req.identifier();
-
-		let mut svc = if let Some(orig_svc) = self.services.get_mut(&k) {
-			let clone = orig_svc.clone();
-			std::mem::replace(orig_svc, clone)
-		} else {
-			return Either::Left(err(OdiliaError::ServiceNotFound(
-                format!("A service with key {k:?} could not be found in a list with keys of {:?}", self.services.keys())
-            )));
-		};
-		Either::Right(svc.call(req).err_into())
+		match self.choose(&req) {
+			Ok(mut svc) => Either::Right(svc.call(req).err_into()),
+			Err(e) => Either::Left(err(e)),
+		}
 	}
 }
 
@@ -107,6 +125,14 @@ where
 		C::CTYPE
 	}
 }
+impl<I> ChooserStatic<EventDiscriminants> for I
+where
+	I: EventType,
+{
+	fn identifier() -> EventDiscriminants {
+		I::ETYPE
+	}
+}
 
 impl Chooser<(&'static str, &'static str)> for Event {
 	fn identifier(&self) -> (&'static str, &'static str) {
@@ -118,3 +144,13 @@ impl Chooser<CommandDiscriminants> for Command {
 		self.ctype()
 	}
 }
+impl Chooser<EventDiscriminants> for ScreenReaderEvent {
+	fn identifier(&self) -> EventDiscriminants {
+		self.into()
+	}
+}
+impl Chooser<String> for Custom {
+	fn identifier(&self) -> String {
+		self.name.clone()
+	}
+}
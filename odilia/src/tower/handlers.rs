@@ -1,50 +1,96 @@
 #![allow(dead_code)]
 
+use crate::capture::{SpeechSource, CURRENT_SOURCE};
 use crate::state::ScreenReaderState;
 use crate::tower::{
 	choice::{ChoiceService, ChooserStatic},
 	from_state::TryFromState,
-	service_set::ServiceSet,
+	registry::HandlerKind,
+	service_set::{report, FailurePolicy, Order, ServiceInfo, ServiceSet},
 	Handler, ServiceExt as OdiliaServiceExt,
 };
+use atspi::events::{object::TextCaretMovedEvent, ObjectEvents};
 use atspi::AtspiError;
 use atspi::BusProperties;
 use atspi::Event;
 use atspi::EventProperties;
 use atspi::EventTypeProperties;
 use odilia_common::errors::OdiliaError;
+use odilia_common::events::{
+	Custom, EventType, ScreenReaderEvent, ScreenReaderEventDiscriminants as EventDiscriminants,
+};
+use ssip_client_async::{MessageScope, Priority, Request as SSIPRequest};
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::sync::{atomic::Ordering, Arc};
+use std::time::Duration;
 
-use futures::{Stream, StreamExt};
+use futures::{future::Either, Stream, StreamExt};
 
 use tower::util::BoxCloneService;
 use tower::Service;
 use tower::ServiceExt;
 
-use tokio::sync::mpsc::Receiver;
+use tokio::{sync::mpsc::Receiver, time::timeout};
 
 use odilia_common::command::{
-	CommandType, OdiliaCommand as Command, OdiliaCommandDiscriminants as CommandDiscriminants,
-	TryIntoCommands,
+	Category, CommandType, OdiliaCommand as Command,
+	OdiliaCommandDiscriminants as CommandDiscriminants, TryIntoCommands,
 };
 
 type Response = Vec<Command>;
 type Request = Event;
 type Error = OdiliaError;
 
-type AtspiHandler = BoxCloneService<Event, (), Error>;
+type AtspiHandler = BoxCloneService<Event, Vec<Command>, Error>;
 type CommandHandler = BoxCloneService<Command, (), Error>;
+type InputHandler = BoxCloneService<ScreenReaderEvent, (), Error>;
+type CustomHandler = BoxCloneService<Custom, (), Error>;
 
+#[derive(Clone)]
 pub struct Handlers {
 	state: Arc<ScreenReaderState>,
 	atspi: ChoiceService<(&'static str, &'static str), ServiceSet<AtspiHandler>, Event>,
 	command: ChoiceService<CommandDiscriminants, ServiceSet<CommandHandler>, Command>,
+	input: ChoiceService<EventDiscriminants, ServiceSet<InputHandler>, ScreenReaderEvent>,
+	/// Handlers for [`ScreenReaderEvent::Custom`] events, by name.
+	custom: ChoiceService<String, ServiceSet<CustomHandler>, Custom>,
 }
 
 impl Handlers {
 	pub fn new(state: Arc<ScreenReaderState>) -> Self {
-		Handlers { state, atspi: ChoiceService::new(), command: ChoiceService::new() }
+		Handlers {
+			state,
+			atspi: ChoiceService::new(),
+			command: ChoiceService::new(),
+			input: ChoiceService::new(),
+			custom: ChoiceService::new(),
+		}
+	}
+	/// Checks that the order handlers run in never depends on the order they were registered in, see [`ServiceSet::conflicts`].
+	/// # Errors
+	/// Describing every conflict found.
+	pub fn check(self) -> Result<Self, OdiliaError> {
+		fn describe<'a, K: Debug + 'a, S: 'a>(
+			kind: &str,
+			sets: impl Iterator<Item = (&'a K, &'a ServiceSet<S>)>,
+			conflicts: &mut Vec<String>,
+		) {
+			for (key, set) in sets {
+				for (first, second) in set.conflicts() {
+					conflicts.push(format!("{kind} handlers {first} and {second} for {key:?} have the same priority, but one short-circuits the other"));
+				}
+			}
+		}
+		let mut conflicts = Vec::new();
+		describe("command", self.command.iter(), &mut conflicts);
+		describe("atspi", self.atspi.iter(), &mut conflicts);
+		describe("input", self.input.iter(), &mut conflicts);
+		describe("custom", self.custom.iter(), &mut conflicts);
+		if conflicts.is_empty() {
+			Ok(self)
+		} else {
+			Err(OdiliaError::Generic(conflicts.join("; ")))
+		}
 	}
 	pub async fn command_handler(mut self, mut commands: Receiver<Command>) {
 		loop {
@@ -59,28 +105,210 @@ impl Handlers {
 			// we could move caching to a separate, ordered system, then parallelize the other functions,
 			// if we determine this is a performance problem.
 			if let Err(e) = self.command.call(cmd).await {
-				tracing::error!("{e:?}");
+				report(&e);
+			}
+		}
+	}
+	/// Runs the input listeners for every [`ScreenReaderEvent`] received, until all senders have been dropped.
+	#[tracing::instrument(skip_all)]
+	pub async fn input_handler(mut self, mut events: Receiver<ScreenReaderEvent>) {
+		while let Some(ev) = events.recv().await {
+			// a line of text asked for is handled as the event it was asked for
+			let ev = match ev {
+				ScreenReaderEvent::TextEntered(text) => {
+					match self.state.text_entry.finish() {
+						Some(_) if text.is_empty() => {
+							let cancelled = self
+								.state
+								.l10n
+								.get("text-entry-cancelled");
+							self.state
+								.say(
+									Category::System,
+									Priority::Message,
+									cancelled,
+								)
+								.await;
+							continue;
+						}
+						Some(purpose) => purpose.event(text),
+						None => ScreenReaderEvent::TextEntered(text),
+					}
+				}
+				ev => ev,
+			};
+			// any other input cancels an action waiting to be confirmed; key presses are reported as activity even when they confirm it, and heartbeats are not input at all
+			if !matches!(
+				ev,
+				ScreenReaderEvent::UserActivity
+					| ScreenReaderEvent::Heartbeat { .. }
+			) {
+				self.state.pending.cancel_unless(EventDiscriminants::from(&ev));
+			}
+			// reading stopped by a key press can only be skipped through right after
+			if !matches!(
+				ev,
+				ScreenReaderEvent::UserActivity
+					| ScreenReaderEvent::Heartbeat { .. }
+					| ScreenReaderEvent::SkipSayAll { .. }
+			) {
+				self.state.say_all.forget();
+			}
+			let source = SpeechSource::Input {
+				event: EventDiscriminants::from(&ev).to_string(),
+			};
+			let handled = match ev {
+				ScreenReaderEvent::Custom { name, args } => {
+					Either::Left(self.custom.call(Custom { name, args }))
+				}
+				ev => Either::Right(self.input.call(ev)),
+			};
+			if let Err(e) = CURRENT_SOURCE.scope(source, handled).await {
+				report(&e);
 			}
 		}
+		tracing::debug!("All input sources have closed; stopping input handler.");
 	}
+	/// Handles every AT-SPI event, in order.
+	///
+	/// Caret movements are held back for `speech.caret_coalesce_ms`: when another one for the same object arrives in that time, the older one is dropped, so that holding down an arrow key speaks only where the caret ends up, instead of stuttering through every position.
 	#[tracing::instrument(skip_all)]
 	pub async fn atspi_handler<R>(mut self, mut events: R)
 	where
 		R: Stream<Item = Result<Event, AtspiError>> + Unpin,
 	{
 		std::pin::pin!(&mut events);
+		let window = Duration::from_millis(self.state.config.speech.caret_coalesce_ms);
+		// the caret movement being held back, and whether it replaced any others
+		let mut pending: Option<(TextCaretMovedEvent, bool)> = None;
 		loop {
-			let maybe_ev = events.next().await;
-			let Some(Ok(ev)) = maybe_ev else {
+			let next = if pending.is_some() {
+				if let Ok(next) = timeout(window, events.next()).await {
+					next
+				} else {
+					if let Some((moved, coalesced)) = pending.take() {
+						self.caret_moved(moved, coalesced).await;
+					}
+					continue;
+				}
+			} else {
+				events.next().await
+			};
+			let Some(maybe_ev) = next else {
+				if let Some((moved, coalesced)) = pending.take() {
+					self.caret_moved(moved, coalesced).await;
+				}
+				tracing::debug!(
+					"The event stream has closed; stopping atspi handler."
+				);
+				break;
+			};
+			let Ok(ev) = maybe_ev else {
 				tracing::error!("Error in processing {maybe_ev:?}");
 				continue;
 			};
-			if let Err(e) = self.atspi.call(ev).await {
-				tracing::error!("{e:?}");
+			self.state.event_counts.record(ev.sender().as_str(), ev.interface());
+			match ev {
+				Event::Object(ObjectEvents::TextCaretMoved(moved))
+					if !window.is_zero() =>
+				{
+					pending = match pending.take() {
+						Some((previous, _))
+							if previous.item == moved.item =>
+						{
+							// the position we skipped over is where the caret is now moving from
+							if let Ok(position) =
+								previous.position.try_into()
+							{
+								self.state
+									.previous_caret_position
+									.store(
+										position,
+										Ordering::Relaxed,
+									);
+							}
+							Some((moved, true))
+						}
+						Some((previous, coalesced)) => {
+							self.caret_moved(previous, coalesced).await;
+							Some((moved, false))
+						}
+						None => Some((moved, false)),
+					};
+				}
+				ev => {
+					if let Some((moved, coalesced)) = pending.take() {
+						self.caret_moved(moved, coalesced).await;
+					}
+					self.dispatch_atspi(ev).await;
+				}
+			}
+		}
+	}
+	/// Dispatches a caret movement which was held back; if it replaced others, whatever is still being said about those is cut off first.
+	async fn caret_moved(&mut self, moved: TextCaretMovedEvent, coalesced: bool) {
+		if coalesced {
+			if let Err(e) =
+				self.state.ssip.send(SSIPRequest::Cancel(MessageScope::Last)).await
+			{
+				tracing::error!(
+					"Could not cancel speech for skipped caret movements: {e}"
+				);
 			}
 		}
+		self.dispatch_atspi(moved.into()).await;
 	}
-	pub fn command_listener<H, T, C, R>(mut self, handler: H) -> Self
+	/// Applies `ev` to the cache, then handles it; since events are dispatched one at a time, in order, handlers never read the cache as it was before the event they handle.
+	///
+	/// Handlers which [opt into it](Order::concurrent) run concurrently with others of the same priority; the commands a group of them returns are executed once all of them are done, in the order the handlers were registered, so that what is said does not depend on which handler finished first, and before the handlers after them run, so that those see what they did.
+	async fn dispatch_atspi(&mut self, ev: Event) {
+		if let Err(e) = self.state.cache.apply_event(&ev) {
+			tracing::error!("Could not apply an event to the cache: {e:?}");
+		}
+		let source = SpeechSource::Atspi {
+			interface: ev.interface(),
+			member: ev.member(),
+			sender: ev.sender().to_string(),
+			path: ev.path().to_string(),
+		};
+		let mut atspi = self.atspi.clone();
+		let command = self.command.clone();
+		let handled = async move {
+			// handlers which failed have been logged, and have nothing to execute
+			atspi.choose(&ev)?
+				.call_by_group(ev, move |group: Vec<Vec<Command>>| {
+					let mut command = command.clone();
+					async move {
+						for cmd in group.into_iter().flatten() {
+							if let Err(e) = command.call(cmd).await {
+								report(&e);
+							}
+						}
+					}
+				})
+				.await
+		};
+		if let Err(e) = CURRENT_SOURCE.scope(source, handled).await {
+			report(&e);
+		}
+	}
+	pub fn command_listener<H, T, C, R>(self, handler: H) -> Self
+	where
+		H: Handler<T, Response = R> + Send + Clone + 'static,
+		<H as Handler<T>>::Future: Send,
+		C: CommandType + ChooserStatic<CommandDiscriminants> + Send + 'static,
+		Command: TryInto<C>,
+		OdiliaError: From<<Command as TryInto<C>>::Error>
+			+ From<<T as TryFromState<Arc<ScreenReaderState>, C>>::Error>,
+		R: Into<Result<(), Error>> + Send + 'static,
+		T: TryFromState<Arc<ScreenReaderState>, C> + Send + 'static,
+		<T as TryFromState<Arc<ScreenReaderState>, C>>::Future: Send,
+		<T as TryFromState<Arc<ScreenReaderState>, C>>::Error: Send,
+	{
+		self.command_listener_with(handler, Order::default())
+	}
+	/// Like [`Handlers::command_listener`], but with an explicit [`Order`] among the other handlers for the same event.
+	pub fn command_listener_with<H, T, C, R>(mut self, handler: H, order: Order) -> Self
 	where
 		H: Handler<T, Response = R> + Send + Clone + 'static,
 		<H as Handler<T>>::Future: Send,
@@ -100,10 +328,39 @@ impl Handlers {
 			.with_state(Arc::clone(&self.state))
 			.request_try_from()
 			.boxed_clone();
-		self.command.entry(C::identifier()).or_default().push(bs);
-		Self { state: self.state, atspi: self.atspi, command: self.command }
+		let (name, enabled) =
+			self.state.handler_registry.register::<H>(HandlerKind::Command);
+		// the rest of a command's effects are no use once one of them failed
+		self.command
+			.entry(C::identifier())
+			.or_insert_with(|| ServiceSet::default().policy(FailurePolicy::FailFast))
+			.push(ServiceInfo { name, order, enabled }, bs);
+		self
+	}
+	pub fn atspi_listener<H, T, R, E>(self, handler: H) -> Self
+	where
+		H: Handler<T, Response = R> + Send + Clone + 'static,
+		<H as Handler<T>>::Future: Send,
+		E: EventTypeProperties
+			+ Debug
+			+ BusProperties
+			+ TryFrom<Event>
+			+ EventProperties
+			+ ChooserStatic<(&'static str, &'static str)>
+			+ Clone
+			+ Send
+			+ 'static,
+		OdiliaError: From<<Event as TryInto<E>>::Error>
+			+ From<<T as TryFromState<Arc<ScreenReaderState>, E>>::Error>,
+		R: TryIntoCommands + 'static,
+		T: TryFromState<Arc<ScreenReaderState>, E> + Send + 'static,
+		<T as TryFromState<Arc<ScreenReaderState>, E>>::Error: Send + 'static,
+		<T as TryFromState<Arc<ScreenReaderState>, E>>::Future: Send,
+	{
+		self.atspi_listener_with(handler, Order::default())
 	}
-	pub fn atspi_listener<H, T, R, E>(mut self, handler: H) -> Self
+	/// Like [`Handlers::atspi_listener`], but with an explicit [`Order`] among the other handlers for the same event.
+	pub fn atspi_listener_with<H, T, R, E>(mut self, handler: H, order: Order) -> Self
 	where
 		H: Handler<T, Response = R> + Send + Clone + 'static,
 		<H as Handler<T>>::Future: Send,
@@ -129,16 +386,116 @@ impl Handlers {
 			.request_async_try_from()
 			.with_state(Arc::clone(&self.state))
 			.request_try_from()
+			.boxed_clone();
+		let (name, enabled) = self.state.handler_registry.register::<H>(HandlerKind::Atspi);
+		self.atspi
+			.entry(E::identifier())
+			// one failing handler should not keep the others from being heard
+			.or_insert_with(|| {
+				ServiceSet::concurrent().policy(FailurePolicy::IgnoreAndLog)
+			})
+			.push(ServiceInfo { name, order, enabled }, bs);
+		self
+	}
+	pub fn input_listener<H, T, R, I>(self, handler: H) -> Self
+	where
+		H: Handler<T, Response = R> + Send + Clone + 'static,
+		<H as Handler<T>>::Future: Send,
+		I: EventType
+			+ ChooserStatic<EventDiscriminants>
+			+ TryFrom<ScreenReaderEvent>
+			+ Debug
+			+ Clone
+			+ Send
+			+ 'static,
+		OdiliaError: From<<ScreenReaderEvent as TryInto<I>>::Error>
+			+ From<<T as TryFromState<Arc<ScreenReaderState>, I>>::Error>,
+		R: TryIntoCommands + 'static,
+		T: TryFromState<Arc<ScreenReaderState>, I> + Send + 'static,
+		<T as TryFromState<Arc<ScreenReaderState>, I>>::Error: Send + 'static,
+		<T as TryFromState<Arc<ScreenReaderState>, I>>::Future: Send,
+	{
+		self.input_listener_with(handler, Order::default())
+	}
+	/// Like [`Handlers::input_listener`], but with an explicit [`Order`] among the other handlers for the same event.
+	pub fn input_listener_with<H, T, R, I>(mut self, handler: H, order: Order) -> Self
+	where
+		H: Handler<T, Response = R> + Send + Clone + 'static,
+		<H as Handler<T>>::Future: Send,
+		I: EventType
+			+ ChooserStatic<EventDiscriminants>
+			+ TryFrom<ScreenReaderEvent>
+			+ Debug
+			+ Clone
+			+ Send
+			+ 'static,
+		OdiliaError: From<<ScreenReaderEvent as TryInto<I>>::Error>
+			+ From<<T as TryFromState<Arc<ScreenReaderState>, I>>::Error>,
+		R: TryIntoCommands + 'static,
+		T: TryFromState<Arc<ScreenReaderState>, I> + Send + 'static,
+		<T as TryFromState<Arc<ScreenReaderState>, I>>::Error: Send + 'static,
+		<T as TryFromState<Arc<ScreenReaderState>, I>>::Future: Send,
+	{
+		let bs = handler
+			.into_service()
+			.unwrap_map(TryIntoCommands::try_into_commands)
+			.request_async_try_from()
+			.with_state(Arc::clone(&self.state))
+			.request_try_from()
+			.iter_into(self.command.clone())
+			.map_result(|res: Result<Vec<Vec<()>>, OdiliaError>| res.map(|_| ()))
+			.boxed_clone();
+		let (name, enabled) = self.state.handler_registry.register::<H>(HandlerKind::Input);
+		self.input
+			.entry(I::identifier())
+			.or_default()
+			.push(ServiceInfo { name, order, enabled }, bs);
+		self
+	}
+	/// Registers a handler for [`ScreenReaderEvent::Custom`] events called `event_name`.
+	/// The handler can use the same extractors as an [`Handlers::input_listener`], with [`InputEvent<Custom>`](crate::state::InputEvent) for the event's arguments.
+	pub fn custom_listener<H, T, R>(self, event_name: &str, handler: H) -> Self
+	where
+		H: Handler<T, Response = R> + Send + Clone + 'static,
+		<H as Handler<T>>::Future: Send,
+		OdiliaError: From<<T as TryFromState<Arc<ScreenReaderState>, Custom>>::Error>,
+		R: TryIntoCommands + 'static,
+		T: TryFromState<Arc<ScreenReaderState>, Custom> + Send + 'static,
+		<T as TryFromState<Arc<ScreenReaderState>, Custom>>::Error: Send + 'static,
+		<T as TryFromState<Arc<ScreenReaderState>, Custom>>::Future: Send,
+	{
+		self.custom_listener_with(event_name, handler, Order::default())
+	}
+	/// Like [`Handlers::custom_listener`], but with an explicit [`Order`] among the other handlers for the same event.
+	pub fn custom_listener_with<H, T, R>(
+		mut self,
+		event_name: &str,
+		handler: H,
+		order: Order,
+	) -> Self
+	where
+		H: Handler<T, Response = R> + Send + Clone + 'static,
+		<H as Handler<T>>::Future: Send,
+		OdiliaError: From<<T as TryFromState<Arc<ScreenReaderState>, Custom>>::Error>,
+		R: TryIntoCommands + 'static,
+		T: TryFromState<Arc<ScreenReaderState>, Custom> + Send + 'static,
+		<T as TryFromState<Arc<ScreenReaderState>, Custom>>::Error: Send + 'static,
+		<T as TryFromState<Arc<ScreenReaderState>, Custom>>::Future: Send,
+	{
+		let bs = handler
+			.into_service()
+			.unwrap_map(TryIntoCommands::try_into_commands)
+			.request_async_try_from()
+			.with_state(Arc::clone(&self.state))
 			.iter_into(self.command.clone())
-			.map_result(
-				|res: Result<Vec<Vec<Result<(), OdiliaError>>>, OdiliaError>| {
-					res?.into_iter()
-						.flatten()
-						.collect::<Result<(), OdiliaError>>()
-				},
-			)
+			.map_result(|res: Result<Vec<Vec<()>>, OdiliaError>| res.map(|_| ()))
 			.boxed_clone();
-		self.atspi.entry(E::identifier()).or_default().push(bs);
-		Self { state: self.state, atspi: self.atspi, command: self.command }
+		let (name, enabled) =
+			self.state.handler_registry.register::<H>(HandlerKind::Custom);
+		self.custom
+			.entry(event_name.to_string())
+			.or_default()
+			.push(ServiceInfo { name, order, enabled }, bs);
+		self
 	}
 }
@@ -5,6 +5,7 @@ pub mod choice;
 pub mod from_state;
 pub mod handler;
 pub mod iter_svc;
+pub mod registry;
 pub mod service_ext;
 pub mod service_set;
 pub mod state_changed;
@@ -0,0 +1,175 @@
+//! Every handler registered with [`Handlers`](crate::tower::Handlers), by name, so that each one can be turned off while Odilia is running.
+//!
+//! A handler's name is the name of the function implementing it, for example `caret_moved`.
+//! Handlers listed in the `handlers.disabled` setting start out disabled; any of them can be switched over D-Bus, see [`crate::dbus`].
+//! A disabled handler is skipped by its [`ServiceSet`](crate::tower::service_set::ServiceSet), so it does not short-circuit the handlers after it either.
+
+use std::{
+	collections::BTreeMap,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Mutex,
+	},
+};
+
+/// Which kind of event a handler is called for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandlerKind {
+	Atspi,
+	Command,
+	Input,
+	Custom,
+}
+
+impl HandlerKind {
+	pub fn as_str(self) -> &'static str {
+		match self {
+			HandlerKind::Atspi => "atspi",
+			HandlerKind::Command => "command",
+			HandlerKind::Input => "input",
+			HandlerKind::Custom => "custom",
+		}
+	}
+}
+
+#[derive(Debug)]
+struct Entry {
+	kind: HandlerKind,
+	enabled: Arc<AtomicBool>,
+}
+
+/// The names and states of every registered handler; cheap to clone.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Default)]
+pub struct HandlerRegistry {
+	entries: Arc<Mutex<BTreeMap<String, Entry>>>,
+	disabled_on_start: Arc<Vec<String>>,
+}
+
+impl HandlerRegistry {
+	/// A registry in which the handlers named in `disabled` start out disabled.
+	pub fn new(disabled: Vec<String>) -> Self {
+		HandlerRegistry { entries: Arc::default(), disabled_on_start: Arc::new(disabled) }
+	}
+	/// Adds the handler `H`, and returns its name and the switch which enables it.
+	/// Registering the same function twice gives both registrations the same switch.
+	pub fn register<H>(&self, kind: HandlerKind) -> (&'static str, Arc<AtomicBool>) {
+		let name = handler_name::<H>();
+		let mut entries = self.lock();
+		let entry = entries.entry(name.to_string()).or_insert_with(|| Entry {
+			kind,
+			enabled: Arc::new(AtomicBool::new(
+				!self.disabled_on_start.iter().any(|disabled| disabled == name),
+			)),
+		});
+		(name, Arc::clone(&entry.enabled))
+	}
+	/// Every registered handler, as `(name, kind, enabled)`.
+	pub fn list(&self) -> Vec<(String, HandlerKind, bool)> {
+		self.lock()
+			.iter()
+			.map(|(name, entry)| {
+				(name.clone(), entry.kind, entry.enabled.load(Ordering::Relaxed))
+			})
+			.collect()
+	}
+	/// Enables or disables the handler called `name`, returning `false` if there is no such handler.
+	pub fn set_enabled(&self, name: &str, enabled: bool) -> bool {
+		let entries = self.lock();
+		let Some(entry) = entries.get(name) else {
+			return false;
+		};
+		entry.enabled.store(enabled, Ordering::Relaxed);
+		tracing::info!(name, enabled, "Handler switched");
+		true
+	}
+	/// The names in the `handlers.disabled` setting which do not match any registered handler, most likely typos.
+	pub fn unknown_disabled(&self) -> Vec<String> {
+		let entries = self.lock();
+		self.disabled_on_start
+			.iter()
+			.filter(|name| !entries.contains_key(name.as_str()))
+			.cloned()
+			.collect()
+	}
+	fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Entry>> {
+		self.entries.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+	}
+}
+
+/// The name of the function `H`, without its module path.
+fn handler_name<H>() -> &'static str {
+	let full = std::any::type_name::<H>();
+	full.rsplit("::").next().unwrap_or(full)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn caret_moved() {}
+	fn focused() {}
+
+	/// Registers the function `handler`, the way [`Handlers`](crate::tower::Handlers) does with the type of a handler.
+	fn register<H>(
+		registry: &HandlerRegistry,
+		_handler: &H,
+		kind: HandlerKind,
+	) -> (&'static str, Arc<AtomicBool>) {
+		registry.register::<H>(kind)
+	}
+
+	#[test]
+	fn handlers_are_listed_by_the_name_of_their_function() {
+		let registry = HandlerRegistry::default();
+		let (name, enabled) = register(&registry, &focused, HandlerKind::Atspi);
+		register(&registry, &caret_moved, HandlerKind::Command);
+		assert_eq!(name, "focused");
+		assert!(enabled.load(Ordering::Relaxed));
+		assert_eq!(
+			registry.list(),
+			[
+				("caret_moved".to_string(), HandlerKind::Command, true),
+				("focused".to_string(), HandlerKind::Atspi, true),
+			]
+		);
+	}
+
+	#[test]
+	fn registering_a_handler_twice_gives_the_same_switch() {
+		let registry = HandlerRegistry::default();
+		let (_, first) = register(&registry, &focused, HandlerKind::Atspi);
+		let (_, second) = register(&registry, &focused, HandlerKind::Atspi);
+		assert!(Arc::ptr_eq(&first, &second));
+		assert_eq!(registry.list().len(), 1);
+	}
+
+	#[test]
+	fn handlers_can_be_switched_by_name() {
+		let registry = HandlerRegistry::default();
+		let (_, enabled) = register(&registry, &focused, HandlerKind::Atspi);
+		assert!(registry.set_enabled("focused", false));
+		assert!(!enabled.load(Ordering::Relaxed));
+		assert_eq!(registry.list(), [("focused".to_string(), HandlerKind::Atspi, false)]);
+		assert!(registry.set_enabled("focused", true));
+		assert!(enabled.load(Ordering::Relaxed));
+	}
+
+	#[test]
+	fn switching_an_unknown_handler_does_nothing() {
+		let registry = HandlerRegistry::default();
+		register(&registry, &focused, HandlerKind::Atspi);
+		assert!(!registry.set_enabled("focussed", false));
+		assert_eq!(registry.list(), [("focused".to_string(), HandlerKind::Atspi, true)]);
+	}
+
+	#[test]
+	fn handlers_disabled_in_the_settings_start_disabled() {
+		let registry = HandlerRegistry::new(vec!["focused".into(), "focussed".into()]);
+		let (_, focused) = register(&registry, &focused, HandlerKind::Atspi);
+		let (_, caret_moved) = register(&registry, &caret_moved, HandlerKind::Atspi);
+		assert!(!focused.load(Ordering::Relaxed));
+		assert!(caret_moved.load(Ordering::Relaxed));
+		assert_eq!(registry.unknown_disabled(), ["focussed"]);
+	}
+}
@@ -1,39 +1,212 @@
+use futures::future::join_all;
+use odilia_common::errors::OdiliaError;
 use std::future::Future;
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Arc,
+};
 use std::task::{Context, Poll};
 use tower::Service;
 
-/// A series of services which are executed in the order they are placed in the [`ServiceSet::new`]
-/// initializer.
-/// Useful when creating a set of handler functions that need to be run without concurrency.
+/// Where a service goes in a [`ServiceSet`], whether the ones after it still run, and which ones it may run alongside.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Order {
+	/// Services with a higher priority run first; services with the same priority run in the order they were added.
+	pub priority: i32,
+	/// If this service succeeds, the services after it are not called.
+	pub short_circuit: bool,
+	/// Whether this service may run at the same time as other concurrent services with the same priority, in a concurrent set; services which do not say so always run alone.
+	/// Only set this for services which change no part of the state, or which list every part they change in [`Order::uses`].
+	pub concurrent: bool,
+	/// The parts of the state this service changes, like `"modes"`; services which share any of them never run at the same time, even in a concurrent set.
+	pub uses: &'static [&'static str],
+}
+impl Order {
+	/// Whether a service with this order has to wait for one with `other` to finish, because they change the same part of the state.
+	fn conflicts_with(&self, other: &Order) -> bool {
+		self.uses.iter().any(|part| other.uses.contains(part))
+	}
+}
+
+/// What a [`ServiceSet`] does when one of its services fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailurePolicy {
+	/// Stops at the first failure, and returns its error; services after it are not called.
+	FailFast,
+	/// Calls every service, and returns all their errors in one [`OdiliaError::Handlers`], if any failed.
+	#[default]
+	CollectAll,
+	/// Calls every service, logs each failure with the name of the service, and returns what the others responded.
+	IgnoreAndLog,
+}
+
+/// What a [`ServiceSet`] knows about each of its services.
+#[derive(Debug, Clone)]
+pub struct ServiceInfo {
+	pub name: &'static str,
+	pub order: Order,
+	/// Disabled services are skipped, as if they were not in the set.
+	pub enabled: Arc<AtomicBool>,
+}
+
+/// A series of services which are executed in the order given by their [`Order`], or otherwise the order they were added in.
+/// A set made with [`ServiceSet::default`] runs them one at a time, each waiting for the one before it.
 ///
-/// Note that although calling the [`ServiceSet::call`] function seems to return a
-/// `Result<Vec<S::Response, S::Error>, S::Error>`, the outer error is gaurenteed never to be
-/// returned and can safely be unwrapped _from the caller function_.
+/// A set made with [`ServiceSet::concurrent`] instead runs services with the same priority at the same time, when all of them are [concurrent](Order::concurrent), none of them short-circuits and none of them [uses](Order::uses) a part of the state another one does, since their order is left to chance anyway; results are still given in order.
+///
+/// Calling it responds with what each service that succeeded responded, in order; what happens to failures depends on its [`FailurePolicy`].
 #[derive(Clone)]
 pub struct ServiceSet<S> {
-	services: Vec<S>,
+	services: Vec<(ServiceInfo, S)>,
+	concurrent: bool,
+	policy: FailurePolicy,
 }
 impl<S> Default for ServiceSet<S> {
 	fn default() -> Self {
-		ServiceSet { services: vec![] }
+		ServiceSet { services: vec![], concurrent: false, policy: FailurePolicy::default() }
 	}
 }
 impl<S> ServiceSet<S> {
-	pub fn push(&mut self, svc: S) {
-		self.services.push(svc);
+	/// An empty set whose services with the same priority run concurrently.
+	pub fn concurrent() -> Self {
+		ServiceSet { concurrent: true, ..Self::default() }
+	}
+	/// Sets what happens when a service fails.
+	#[must_use]
+	pub fn policy(mut self, policy: FailurePolicy) -> Self {
+		self.policy = policy;
+		self
+	}
+	/// Adds `svc` after every service with the same or a higher priority.
+	pub fn push(&mut self, info: ServiceInfo, svc: S) {
+		let at = self
+			.services
+			.partition_point(|(other, _)| other.order.priority >= info.order.priority);
+		self.services.insert(at, (info, svc));
+	}
+	/// Pairs of services whose relative order only depends on which was added first, even though it matters: they have the same priority, and one of them short-circuits the other.
+	pub fn conflicts(&self) -> Vec<(&'static str, &'static str)> {
+		let mut conflicts = Vec::new();
+		for (i, (first, _)) in self.services.iter().enumerate() {
+			for (second, _) in &self.services[i + 1..] {
+				if first.order.priority == second.order.priority
+					&& (first.order.short_circuit || second.order.short_circuit)
+				{
+					conflicts.push((first.name, second.name));
+				}
+			}
+		}
+		conflicts
+	}
+	/// The enabled services, in groups which can run at the same time: runs of concurrent services with the same priority, none of which short-circuits or shares a part of the state with another, if the set is concurrent, otherwise one service each.
+	fn groups(services: Vec<(ServiceInfo, S)>, concurrent: bool) -> Vec<Vec<(ServiceInfo, S)>> {
+		let mut groups: Vec<Vec<(ServiceInfo, S)>> = Vec::new();
+		for (info, svc) in services {
+			if !info.enabled.load(Ordering::Relaxed) {
+				continue;
+			}
+			let joins = concurrent
+				&& info.order.concurrent && !info.order.short_circuit
+				&& groups.last().is_some_and(|group: &Vec<(ServiceInfo, S)>| {
+					group.iter().all(|(other, _)| {
+						other.order.concurrent
+							&& !other.order.short_circuit && other
+							.order
+							.priority == info
+							.order
+							.priority && !other
+							.order
+							.conflicts_with(&info.order)
+					})
+				});
+			match groups.last_mut() {
+				Some(group) if joins => group.push((info, svc)),
+				_ => groups.push(vec![(info, svc)]),
+			}
+		}
+		groups
+	}
+}
+
+impl<S> ServiceSet<S> {
+	/// Calls the services with `req`, like [`Service::call`], but hands what each group of services which ran at the same time responded to `then`, in order, and waits for it before the next group is called; what a service which failed would have responded is left out.
+	/// This lets what the services respond take effect before the services after them run.
+	/// # Errors
+	/// Like [`Service::call`], depending on the set's [`FailurePolicy`].
+	pub async fn call_by_group<Req, F, Fut>(
+		&mut self,
+		req: Req,
+		then: F,
+	) -> Result<(), OdiliaError>
+	where
+		S: Service<Req, Error = OdiliaError> + Clone,
+		Req: Clone,
+		F: FnMut(Vec<S::Response>) -> Fut,
+		Fut: Future<Output = ()>,
+	{
+		let clone = self.services.clone();
+		let services = std::mem::replace(&mut self.services, clone);
+		Self::run(Self::groups(services, self.concurrent), self.policy, req, then).await
+	}
+	async fn run<Req, F, Fut>(
+		groups: Vec<Vec<(ServiceInfo, S)>>,
+		policy: FailurePolicy,
+		req: Req,
+		mut then: F,
+	) -> Result<(), OdiliaError>
+	where
+		S: Service<Req, Error = OdiliaError>,
+		Req: Clone,
+		F: FnMut(Vec<S::Response>) -> Fut,
+		Fut: Future<Output = ()>,
+	{
+		let mut failures = vec![];
+		for group in groups {
+			// a group of more than one never short-circuits
+			let short_circuit = group.len() == 1 && group[0].0.order.short_circuit;
+			let (names, calls): (Vec<_>, Vec<_>) = group
+				.into_iter()
+				.map(|(info, mut svc)| (info.name, svc.call(req.clone())))
+				.unzip();
+			let results = join_all(calls).await;
+			let stop = short_circuit && results.iter().all(Result::is_ok);
+			let mut responses = vec![];
+			for (name, result) in names.into_iter().zip(results) {
+				match (result, policy) {
+					(Ok(response), _) => responses.push(response),
+					(Err(e), FailurePolicy::FailFast) => return Err(e),
+					(Err(e), FailurePolicy::CollectAll) => {
+						failures.push((name, e));
+					}
+					(Err(e), FailurePolicy::IgnoreAndLog) => {
+						tracing::info_span!("handler", name)
+							.in_scope(|| report(&e));
+					}
+				}
+			}
+			then(responses).await;
+			if stop {
+				break;
+			}
+		}
+		if failures.is_empty() {
+			Ok(())
+		} else {
+			Err(OdiliaError::Handlers(failures))
+		}
 	}
 }
 
 impl<S, Req> Service<Req> for ServiceSet<S>
 where
-	S: Service<Req> + Clone,
+	S: Service<Req, Error = OdiliaError> + Clone,
 	Req: Clone,
 {
-	type Response = Vec<Result<S::Response, S::Error>>;
-	type Error = S::Error;
+	type Response = Vec<S::Response>;
+	type Error = OdiliaError;
 	type Future = impl Future<Output = Result<Self::Response, Self::Error>>;
 	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-		for svc in &mut self.services {
+		for (_, svc) in &mut self.services {
 			let _ = svc.poll_ready(cx)?;
 		}
 		Poll::Ready(Ok(()))
@@ -41,13 +214,245 @@ where
 	fn call(&mut self, req: Req) -> Self::Future {
 		let clone = self.services.clone();
 		let services = std::mem::replace(&mut self.services, clone);
+		let groups = Self::groups(services, self.concurrent);
+		let policy = self.policy;
 		async move {
-			let mut results = vec![];
-			for mut svc in services {
-				let result = svc.call(req.clone()).await;
-				results.push(result);
-			}
-			Ok(results)
+			let mut responses = vec![];
+			Self::run(groups, policy, req, |group| {
+				responses.extend(group);
+				async {}
+			})
+			.await?;
+			Ok(responses)
 		}
 	}
 }
+
+/// Logs an error from a handler: ones the user can do something about as warnings, with their message, and the rest as errors, with every detail for a bug report.
+pub(super) fn report(e: &OdiliaError) {
+	if e.is_user_actionable() {
+		tracing::warn!(category = %e.category(), "{e}");
+	} else {
+		tracing::error!(category = %e.category(), "{e}: {e:?}");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::{Mutex, PoisonError};
+	use tower::{service_fn, util::BoxCloneService};
+
+	type Log = Arc<Mutex<Vec<String>>>;
+	type Listener = BoxCloneService<(), &'static str, OdiliaError>;
+
+	fn note(log: &Log, entry: String) {
+		log.lock().unwrap_or_else(PoisonError::into_inner).push(entry);
+	}
+
+	fn entries(log: &Log) -> Vec<String> {
+		log.lock().unwrap_or_else(PoisonError::into_inner).clone()
+	}
+
+	/// A service which logs when it starts and when it is done, letting others run in between, and responds with its name.
+	fn listener(log: &Log, name: &'static str) -> Listener {
+		let log = Arc::clone(log);
+		BoxCloneService::new(service_fn(move |()| {
+			let log = Arc::clone(&log);
+			async move {
+				note(&log, format!("{name} start"));
+				tokio::task::yield_now().await;
+				note(&log, format!("{name} done"));
+				Ok(name)
+			}
+		}))
+	}
+
+	/// A service which logs that it started, and fails with its name.
+	fn failing(log: &Log, name: &'static str) -> Listener {
+		let log = Arc::clone(log);
+		BoxCloneService::new(service_fn(move |()| {
+			note(&log, format!("{name} start"));
+			async move { Err(OdiliaError::Static(name)) }
+		}))
+	}
+
+	/// A serial set with `policy` whose middle service, `"b"`, fails.
+	fn failing_in_the_middle(log: &Log, policy: FailurePolicy) -> ServiceSet<Listener> {
+		let mut set = ServiceSet::default().policy(policy);
+		set.push(info("a", Order::default()), listener(log, "a"));
+		set.push(info("b", Order::default()), failing(log, "b"));
+		set.push(info("c", Order::default()), listener(log, "c"));
+		set
+	}
+
+	fn info(name: &'static str, order: Order) -> ServiceInfo {
+		ServiceInfo { name, order, enabled: Arc::new(AtomicBool::new(true)) }
+	}
+
+	#[tokio::test]
+	async fn services_with_the_same_priority_run_at_the_same_time_in_a_concurrent_set() {
+		let log = Log::default();
+		let concurrent = Order { concurrent: true, ..Order::default() };
+		let mut set = ServiceSet::concurrent();
+		set.push(info("a", concurrent), listener(&log, "a"));
+		set.push(info("b", concurrent), listener(&log, "b"));
+		assert_eq!(set.call(()).await.expect("no listener fails"), ["a", "b"]);
+		assert_eq!(entries(&log), ["a start", "b start", "a done", "b done"]);
+	}
+
+	#[tokio::test]
+	async fn services_which_are_not_concurrent_run_alone_in_a_concurrent_set() {
+		let log = Log::default();
+		let concurrent = Order { concurrent: true, ..Order::default() };
+		let mut set = ServiceSet::concurrent();
+		set.push(info("a", concurrent), listener(&log, "a"));
+		set.push(info("b", Order::default()), listener(&log, "b"));
+		set.push(info("c", concurrent), listener(&log, "c"));
+		assert_eq!(set.call(()).await.expect("no listener fails"), ["a", "b", "c"]);
+		assert_eq!(
+			entries(&log),
+			["a start", "a done", "b start", "b done", "c start", "c done"]
+		);
+	}
+
+	#[tokio::test]
+	async fn what_each_group_responds_is_handed_over_before_the_next_group_runs() {
+		let log = Log::default();
+		let concurrent = Order { concurrent: true, ..Order::default() };
+		let mut set = ServiceSet::concurrent();
+		set.push(info("high", Order { priority: 1, ..concurrent }), listener(&log, "high"));
+		set.push(info("a", concurrent), listener(&log, "a"));
+		set.push(info("b", concurrent), listener(&log, "b"));
+		set.call_by_group((), |names: Vec<&str>| {
+			note(&log, format!("then {}", names.join(" ")));
+			async {}
+		})
+		.await
+		.expect("no listener fails");
+		assert_eq!(
+			entries(&log),
+			[
+				"high start",
+				"high done",
+				"then high",
+				"a start",
+				"b start",
+				"a done",
+				"b done",
+				"then a b"
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn services_which_use_the_same_state_wait_for_each_other() {
+		let log = Log::default();
+		let modes = Order { concurrent: true, uses: &["modes"], ..Order::default() };
+		let mut set = ServiceSet::concurrent();
+		set.push(info("a", modes), listener(&log, "a"));
+		set.push(info("b", modes), listener(&log, "b"));
+		set.push(
+			info("c", Order { concurrent: true, ..Order::default() }),
+			listener(&log, "c"),
+		);
+		assert_eq!(set.call(()).await.expect("no listener fails"), ["a", "b", "c"]);
+		assert_eq!(
+			entries(&log),
+			["a start", "a done", "b start", "c start", "b done", "c done"]
+		);
+	}
+
+	#[tokio::test]
+	async fn services_run_by_priority_then_in_the_order_they_were_added() {
+		let log = Log::default();
+		let mut set = ServiceSet::default();
+		set.push(info("low", Order::default()), listener(&log, "low"));
+		set.push(
+			info("high", Order { priority: 1, ..Order::default() }),
+			listener(&log, "high"),
+		);
+		set.push(
+			info("later", Order { priority: 1, ..Order::default() }),
+			listener(&log, "later"),
+		);
+		assert_eq!(
+			set.call(()).await.expect("no listener fails"),
+			["high", "later", "low"]
+		);
+	}
+
+	#[tokio::test]
+	async fn a_short_circuit_which_succeeds_stops_the_services_after_it() {
+		let log = Log::default();
+		let mut set = ServiceSet::concurrent();
+		let first = Order { priority: 1, short_circuit: true, ..Order::default() };
+		set.push(info("first", first), listener(&log, "first"));
+		set.push(info("second", Order::default()), listener(&log, "second"));
+		assert_eq!(set.call(()).await.expect("no listener fails"), ["first"]);
+		assert_eq!(entries(&log), ["first start", "first done"]);
+	}
+
+	#[tokio::test]
+	async fn a_disabled_short_circuit_does_not_stop_anything() {
+		let log = Log::default();
+		let mut set = ServiceSet::default();
+		let first = info(
+			"first",
+			Order { priority: 1, short_circuit: true, ..Order::default() },
+		);
+		first.enabled.store(false, Ordering::Relaxed);
+		set.push(first, listener(&log, "first"));
+		set.push(info("second", Order::default()), listener(&log, "second"));
+		assert_eq!(set.call(()).await.expect("no listener fails"), ["second"]);
+	}
+
+	#[test]
+	fn a_short_circuit_with_the_same_priority_as_another_service_conflicts() {
+		let log = Log::default();
+		let mut set = ServiceSet::default();
+		let short_circuit = Order { short_circuit: true, ..Order::default() };
+		set.push(info("a", short_circuit), listener(&log, "a"));
+		set.push(info("b", Order::default()), listener(&log, "b"));
+		set.push(info("c", Order { priority: 1, ..Order::default() }), listener(&log, "c"));
+		assert_eq!(set.conflicts(), [("a", "b")]);
+	}
+
+	#[test]
+	fn services_with_the_same_priority_which_do_not_short_circuit_do_not_conflict() {
+		let log = Log::default();
+		let mut set = ServiceSet::default();
+		set.push(info("a", Order::default()), listener(&log, "a"));
+		set.push(info("b", Order::default()), listener(&log, "b"));
+		assert!(set.conflicts().is_empty());
+	}
+
+	#[tokio::test]
+	async fn fail_fast_stops_at_the_first_failure() {
+		let log = Log::default();
+		let result = failing_in_the_middle(&log, FailurePolicy::FailFast).call(()).await;
+		assert!(matches!(result, Err(OdiliaError::Static("b"))));
+		assert_eq!(entries(&log), ["a start", "a done", "b start"]);
+	}
+
+	#[tokio::test]
+	async fn collect_all_runs_everything_and_returns_every_failure() {
+		let log = Log::default();
+		let result = failing_in_the_middle(&log, FailurePolicy::CollectAll).call(()).await;
+		let Err(OdiliaError::Handlers(failures)) = result else {
+			panic!("expected the failures of the handlers, got {result:?}");
+		};
+		assert!(matches!(failures.as_slice(), [("b", OdiliaError::Static("b"))]));
+		assert_eq!(entries(&log), ["a start", "a done", "b start", "c start", "c done"]);
+	}
+
+	#[tokio::test]
+	async fn ignore_and_log_returns_what_the_others_responded() {
+		let log = Log::default();
+		let result = failing_in_the_middle(&log, FailurePolicy::IgnoreAndLog)
+			.call(())
+			.await;
+		assert_eq!(result.expect("failures are ignored"), ["a", "c"]);
+		assert_eq!(entries(&log), ["a start", "a done", "b start", "c start", "c done"]);
+	}
+}
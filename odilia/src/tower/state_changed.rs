@@ -9,6 +9,8 @@ use zbus::{names::UniqueName, zvariant::ObjectPath};
 
 pub type Focused = StateChanged<StateFocused, True>;
 pub type Unfocused = StateChanged<StateFocused, False>;
+pub type Busy = StateChanged<StateBusy, True>;
+pub type NotBusy = StateChanged<StateBusy, False>;
 
 #[derive(Debug, Default, Clone, Deref, DerefMut)]
 pub struct StateChanged<S, E> {
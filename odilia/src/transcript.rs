@@ -0,0 +1,96 @@
+//! Everything Odilia said, kept so that the user can go back through it like a document: [`ReviewLine`](odilia_common::events::ReviewLine) moves through it an utterance at a time, and [`SearchHistory`](odilia_common::events::SearchHistory) finds one.
+//!
+//! Only the last `speech.history_size` utterances are kept, in memory; reading the history is not added to it.
+
+use std::{
+	collections::VecDeque,
+	sync::{Arc, Mutex, PoisonError},
+};
+
+use futures::future::{ok, Ready};
+use odilia_common::{errors::OdiliaError, events::Direction};
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+#[derive(Debug, Default)]
+struct Utterances {
+	texts: VecDeque<String>,
+	/// How many utterances were ever added, so that the cursor stays on the same one as old ones are dropped.
+	added: usize,
+	/// The utterance being reviewed, counting from the first ever added; `None` until the history is reviewed, or after an utterance is added.
+	cursor: Option<usize>,
+}
+
+impl Utterances {
+	/// The number of the oldest utterance kept.
+	fn first(&self) -> usize {
+		self.added - self.texts.len()
+	}
+	fn get(&self, number: usize) -> Option<&String> {
+		self.texts.get(number.checked_sub(self.first())?)
+	}
+}
+
+/// What Odilia said; cheap to clone.
+#[derive(Debug, Clone)]
+pub struct SpeechHistory {
+	utterances: Arc<Mutex<Utterances>>,
+	size: usize,
+}
+
+impl SpeechHistory {
+	/// Keeps the last `size` utterances.
+	pub fn new(size: usize) -> Self {
+		SpeechHistory { utterances: Arc::default(), size }
+	}
+	fn utterances(&self) -> std::sync::MutexGuard<'_, Utterances> {
+		self.utterances.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+	/// Adds `text` as the latest utterance; reviewing starts from it again.
+	pub fn push(&self, text: &str) {
+		if self.size == 0 || text.trim().is_empty() {
+			return;
+		}
+		let mut utterances = self.utterances();
+		if utterances.texts.len() == self.size {
+			utterances.texts.pop_front();
+		}
+		utterances.texts.push_back(text.to_string());
+		utterances.added += 1;
+		utterances.cursor = None;
+	}
+	/// Moves to the utterance after (or before) the one being reviewed, starting from the latest, and returns it; `None` at either end, where the cursor stays.
+	pub fn step(&self, direction: &Direction) -> Option<String> {
+		let mut utterances = self.utterances();
+		let next = match (direction, utterances.cursor) {
+			(Direction::Backward, None) => utterances.added.checked_sub(1)?,
+			(Direction::Backward, Some(cursor)) => cursor.checked_sub(1)?,
+			(Direction::Forward, None) => return None,
+			(Direction::Forward, Some(cursor)) => cursor + 1,
+		};
+		let text = utterances.get(next)?.clone();
+		utterances.cursor = Some(next);
+		Some(text)
+	}
+	/// Moves back to the last utterance before the one being reviewed which contains `text`, ignoring case, and returns it; `None` if there is none, in which case the cursor stays.
+	pub fn search(&self, text: &str) -> Option<String> {
+		let text = text.to_lowercase();
+		let mut utterances = self.utterances();
+		let before = utterances.cursor.unwrap_or(utterances.added);
+		let found = (utterances.first()..before).rev().find(|number| {
+			utterances
+				.get(*number)
+				.is_some_and(|said| said.to_lowercase().contains(&text))
+		})?;
+		utterances.cursor = Some(found);
+		utterances.get(found).cloned()
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for SpeechHistory {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.history.clone())
+	}
+}
@@ -0,0 +1,281 @@
+//! An icon in the panel's tray, showing that Odilia runs and which mode it is in, with a menu of common commands.
+//!
+//! The icon is a `StatusNotifierItem`, registered with the panel's `org.kde.StatusNotifierWatcher`; its menu is served with the `com.canonical.dbusmenu` interface.
+//! Choosing an entry of the menu sends the same event as the keyboard command for it, into the channel the input socket feeds, so that it is handled the same way.
+
+// the interfaces are fixed by their specifications, including arguments Odilia has no use for, which the generated code still binds
+#![allow(clippy::used_underscore_binding)]
+
+use std::{collections::HashMap, sync::Arc};
+
+use odilia_common::{
+	events::{Direction, ScreenReaderEvent},
+	i18n::Localizer,
+};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use zbus::{
+	fdo, interface,
+	object_server::SignalEmitter,
+	zvariant::{OwnedObjectPath, OwnedValue, Value},
+};
+
+use crate::{modes::Modes, state::ScreenReaderState};
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const MENU_PATH: &str = "/MenuBar";
+
+/// The entries of the menu, by id (their index, plus one; the root is 0): their message id, and the event choosing them sends.
+const ENTRIES: [(&str, ScreenReaderEvent); 6] = [
+	("tray-stop-speech", ScreenReaderEvent::StopSpeech),
+	("tray-next-mode", ScreenReaderEvent::CycleMode(Direction::Forward)),
+	("tray-punctuation", ScreenReaderEvent::CyclePunctuation),
+	("tray-echo", ScreenReaderEvent::CycleEcho),
+	("tray-reset", ScreenReaderEvent::ResetAdjustments),
+	("tray-quit", ScreenReaderEvent::Quit),
+];
+
+#[zbus::proxy(
+	interface = "org.kde.StatusNotifierWatcher",
+	default_service = "org.kde.StatusNotifierWatcher",
+	default_path = "/StatusNotifierWatcher"
+)]
+trait Watcher {
+	fn register_status_notifier_item(&self, service: &str) -> zbus::Result<()>;
+}
+
+/// Sends `event` as if it came from the input socket; the handlers stopping first is not an error.
+async fn send(events: &mpsc::Sender<ScreenReaderEvent>, event: ScreenReaderEvent) {
+	if let Err(e) = events.send(event).await {
+		tracing::debug!("Not sending {:?} from the tray, input handlers are gone", e.0);
+	}
+}
+
+/// The icon itself.
+struct Item {
+	modes: Modes,
+	l10n: Localizer,
+	events: mpsc::Sender<ScreenReaderEvent>,
+}
+
+/// A tooltip: an icon name, icon pixmaps (width, height, ARGB data), a title and a description.
+type ToolTip = (String, Vec<(i32, i32, Vec<u8>)>, String, String);
+/// A menu entry: its id, properties and children, each of them a [`Layout`] in a variant.
+type Layout = (i32, HashMap<String, Value<'static>>, Vec<Value<'static>>);
+
+#[interface(name = "org.kde.StatusNotifierItem")]
+impl Item {
+	#[zbus(property)]
+	#[allow(clippy::unused_self)]
+	fn category(&self) -> String {
+		"ApplicationStatus".to_string()
+	}
+	#[zbus(property)]
+	#[allow(clippy::unused_self)]
+	fn id(&self) -> String {
+		"odilia".to_string()
+	}
+	#[zbus(property)]
+	fn title(&self) -> String {
+		self.l10n
+			.format("tray-title", &[("mode", &self.modes.current().name)])
+	}
+	#[zbus(property)]
+	#[allow(clippy::unused_self)]
+	fn status(&self) -> String {
+		"Active".to_string()
+	}
+	#[zbus(property)]
+	#[allow(clippy::unused_self)]
+	fn icon_name(&self) -> String {
+		"preferences-desktop-accessibility".to_string()
+	}
+	/// The title again.
+	#[zbus(property)]
+	fn tool_tip(&self) -> ToolTip {
+		(String::new(), Vec::new(), self.title(), String::new())
+	}
+	#[zbus(property)]
+	#[allow(clippy::unused_self)]
+	fn item_is_menu(&self) -> bool {
+		false
+	}
+	#[zbus(property)]
+	#[allow(clippy::unused_self)]
+	fn menu(&self) -> OwnedObjectPath {
+		OwnedObjectPath::try_from(MENU_PATH).unwrap_or_default()
+	}
+	/// Clicking the icon says which mode Odilia is in.
+	async fn activate(&self, _x: i32, _y: i32) {
+		send(&self.events, ScreenReaderEvent::SayMode).await;
+	}
+	async fn secondary_activate(&self, _x: i32, _y: i32) {
+		send(&self.events, ScreenReaderEvent::StopSpeech).await;
+	}
+	#[zbus(signal)]
+	async fn new_title(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+	#[zbus(signal)]
+	async fn new_tool_tip(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+/// The properties of the menu entry with `id`, or `None` if there is none.
+fn properties(id: i32, l10n: &Localizer) -> Option<HashMap<String, Value<'static>>> {
+	if id == 0 {
+		return Some(HashMap::from([(
+			"children-display".to_string(),
+			Value::from("submenu".to_string()),
+		)]));
+	}
+	let (message, _) = ENTRIES.get(usize::try_from(id).ok()?.checked_sub(1)?)?;
+	Some(HashMap::from([("label".to_string(), Value::from(l10n.get(message)))]))
+}
+
+/// The menu of the icon.
+struct Menu {
+	l10n: Localizer,
+	events: mpsc::Sender<ScreenReaderEvent>,
+}
+
+impl Menu {
+	/// Handles a single event on a menu entry; only clicking one does anything.
+	async fn handle(&self, id: i32, event_id: &str) -> fdo::Result<()> {
+		let entry = usize::try_from(id)
+			.ok()
+			.and_then(|id| id.checked_sub(1))
+			.and_then(|index| ENTRIES.get(index));
+		let Some((_, event)) = entry else {
+			return Err(fdo::Error::InvalidArgs(format!(
+				"There is no menu entry {id}"
+			)));
+		};
+		if event_id == "clicked" {
+			send(&self.events, event.clone()).await;
+		}
+		Ok(())
+	}
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[interface(name = "com.canonical.dbusmenu")]
+impl Menu {
+	/// The whole menu, which is only one level deep; which properties are asked for is ignored, since there are so few.
+	fn get_layout(
+		&self,
+		_parent_id: i32,
+		_recursion_depth: i32,
+		_property_names: Vec<String>,
+	) -> (u32, Layout) {
+		let children = (1..=ENTRIES.len())
+			.filter_map(|id| i32::try_from(id).ok())
+			.filter_map(|id| {
+				let properties = properties(id, &self.l10n)?;
+				Some(Value::from((id, properties, Vec::<Value<'static>>::new())))
+			})
+			.collect();
+		(0, (0, properties(0, &self.l10n).unwrap_or_default(), children))
+	}
+	fn get_group_properties(
+		&self,
+		ids: Vec<i32>,
+		_property_names: Vec<String>,
+	) -> Vec<(i32, HashMap<String, Value<'static>>)> {
+		ids.into_iter()
+			.filter_map(|id| Some((id, properties(id, &self.l10n)?)))
+			.collect()
+	}
+	fn get_property(&self, id: i32, name: &str) -> fdo::Result<Value<'static>> {
+		properties(id, &self.l10n)
+			.and_then(|mut properties| properties.remove(name))
+			.ok_or_else(|| fdo::Error::InvalidArgs(format!("Entry {id} has no {name}")))
+	}
+	async fn event(
+		&self,
+		id: i32,
+		event_id: &str,
+		_data: OwnedValue,
+		_timestamp: u32,
+	) -> fdo::Result<()> {
+		self.handle(id, event_id).await
+	}
+	/// Handles each event; returns the ids of the entries which do not exist.
+	async fn event_group(&self, events: Vec<(i32, String, OwnedValue, u32)>) -> Vec<i32> {
+		let mut missing = Vec::new();
+		for (id, event_id, _, _) in events {
+			if self.handle(id, &event_id).await.is_err() {
+				missing.push(id);
+			}
+		}
+		missing
+	}
+	/// The menu never changes when it is shown.
+	#[allow(clippy::unused_self)]
+	fn about_to_show(&self, _id: i32) -> bool {
+		false
+	}
+	#[allow(clippy::unused_self)]
+	fn about_to_show_group(&self, _ids: Vec<i32>) -> (Vec<i32>, Vec<i32>) {
+		(Vec::new(), Vec::new())
+	}
+	#[zbus(property)]
+	#[allow(clippy::unused_self)]
+	fn version(&self) -> u32 {
+		3
+	}
+	#[zbus(property)]
+	#[allow(clippy::unused_self)]
+	fn text_direction(&self) -> String {
+		"ltr".to_string()
+	}
+	#[zbus(property)]
+	#[allow(clippy::unused_self)]
+	fn status(&self) -> String {
+		"normal".to_string()
+	}
+	#[zbus(property)]
+	#[allow(clippy::unused_self)]
+	fn icon_theme_path(&self) -> Vec<String> {
+		Vec::new()
+	}
+}
+
+/// Shows the icon, and keeps its title up to date with the mode, until `shutdown` is cancelled.
+/// If the panel has no tray, that is only logged.
+/// # Errors
+/// If the icon can not be served on `session`, or a change of mode can not be signalled.
+#[tracing::instrument(skip_all)]
+pub async fn show(
+	session: zbus::Connection,
+	state: Arc<ScreenReaderState>,
+	events: mpsc::Sender<ScreenReaderEvent>,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	let server = session.object_server();
+	let item = Item {
+		modes: state.modes.clone(),
+		l10n: state.l10n.clone(),
+		events: events.clone(),
+	};
+	server.at(ITEM_PATH, item).await?;
+	server.at(MENU_PATH, Menu { l10n: state.l10n.clone(), events })
+		.await?;
+	let registered = match (WatcherProxy::new(&session).await, session.unique_name()) {
+		(Ok(watcher), Some(name)) => {
+			watcher.register_status_notifier_item(name.as_str()).await
+		}
+		(Err(e), _) => Err(e),
+		(_, None) => Err(zbus::Error::Failure("not connected to a bus".to_string())),
+	};
+	if let Err(e) = registered {
+		tracing::info!("Not showing an icon, since the panel has no tray: {e}");
+	}
+	let item = server.interface::<_, Item>(ITEM_PATH).await?;
+	let mut changes = state.modes.subscribe();
+	while let Some(Ok(())) = shutdown.run_until_cancelled(changes.changed()).await {
+		Item::new_title(item.signal_emitter()).await?;
+		Item::new_tool_tip(item.signal_emitter()).await?;
+	}
+	// the menu and the icon hold senders of input events, which have to be dropped for the input handlers to stop
+	server.remove::<Menu, _>(MENU_PATH).await?;
+	server.remove::<Item, _>(ITEM_PATH).await?;
+	Ok(())
+}
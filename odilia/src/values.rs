@@ -0,0 +1,76 @@
+//! Announcing the values of sliders, spin buttons, scroll bars and progress bars as they change.
+//!
+//! The first change after moving to one of them also gives its range, like "50, 0 to 100"; after that only the value is said.
+//! A value is said the way the application describes it, if it does, and with how far along its range it is, like "medium, 50 percent"; see [`describe`].
+
+use std::{
+	sync::{Arc, Mutex, PoisonError},
+	time::{Duration, Instant},
+};
+
+use futures::future::{ok, Ready};
+use odilia_cache::Value;
+use odilia_common::{cache::AccessiblePrimitive, errors::OdiliaError, i18n::Localizer};
+
+use crate::{state::ScreenReaderState, tower::from_state::TryFromState};
+
+/// How a value change relates to the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+	/// The first change of this object's value since another object's value changed.
+	First,
+	/// Another change of the same object's value, and whether it came quickly after the last one.
+	Again { rapid: bool },
+}
+
+/// Which object's value last changed, and when; cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct ValueChanges(Arc<Mutex<Option<(AccessiblePrimitive, Instant)>>>);
+
+impl ValueChanges {
+	/// Records that the value of `object` changed; changes less than `window` apart are rapid.
+	pub fn record(&self, object: &AccessiblePrimitive, window: Duration) -> Change {
+		let mut last = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+		let change = match &*last {
+			Some((previous, at)) if previous == object => {
+				Change::Again { rapid: at.elapsed() < window }
+			}
+			_ => Change::First,
+		};
+		*last = Some((object.clone(), Instant::now()));
+		change
+	}
+}
+
+impl<E> TryFromState<Arc<ScreenReaderState>, E> for ValueChanges {
+	type Error = OdiliaError;
+	type Future = Ready<Result<Self, Self::Error>>;
+	fn try_from_state(state: Arc<ScreenReaderState>, _event: E) -> Self::Future {
+		ok(state.value_changes.clone())
+	}
+}
+
+/// `value` without a fractional part if it has none, and rounded to two decimals otherwise.
+pub fn format(value: f64) -> String {
+	let rounded = (value * 100.0).round() / 100.0;
+	if rounded.fract() == 0.0 {
+		format!("{rounded:.0}")
+	} else {
+		rounded.to_string()
+	}
+}
+
+/// How `value` is said: the application's description of it if it has one, or else the number, followed by how far along its range it is.
+/// The percentage is left out where it would only repeat the number, on a range from 0 to 100.
+pub fn describe(value: &Value, l10n: &Localizer) -> String {
+	let said = value.text.clone().unwrap_or_else(|| format(value.current));
+	let out_of_100 =
+		value.minimum.abs() < f64::EPSILON && (value.maximum - 100.0).abs() < f64::EPSILON;
+	match value.percent() {
+		Some(percent) if value.text.is_some() || !out_of_100 => l10n.format(
+			"value-percent",
+			&[("value", &said), ("percent", &format(percent.round()))],
+		),
+		_ => said,
+	}
+}
@@ -0,0 +1,101 @@
+//! Announcing changes to the volume of the default sound output, and to whether it is muted.
+//!
+//! Volume keys usually only show a popup, so without this, blind users get no feedback from them.
+//! Changes are followed with `pactl subscribe`, which works with `PulseAudio` as well as `PipeWire`'s replacement for it; the volume itself is read with `pactl` too.
+
+use std::{process::Stdio, sync::Arc};
+
+use odilia_common::command::Category;
+use ssip::Priority;
+use tokio::{
+	io::{AsyncBufReadExt, BufReader},
+	process::Command,
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::state::ScreenReaderState;
+
+/// A `pactl` command, with its output left untranslated so that it can be parsed.
+fn pactl(args: &[&str]) -> Command {
+	let mut command = Command::new("pactl");
+	command.args(args)
+		.env("LC_ALL", "C")
+		.stdin(Stdio::null())
+		.stderr(Stdio::null());
+	command
+}
+
+/// The volume of the first channel in the output of `pactl get-sink-volume`, like `Volume: front-left: 26214 /  40% / -23.88 dB, ...`, in percent.
+fn percent(output: &str) -> Option<u32> {
+	let (before, _) = output.split_once('%')?;
+	let digits = before.trim_end().rsplit(|c: char| !c.is_ascii_digit()).next()?;
+	digits.parse().ok()
+}
+
+/// The volume of the default output in percent, and whether it is muted; `None` if there is no default output.
+async fn volume() -> Option<(u32, bool)> {
+	let volume = pactl(&["get-sink-volume", "@DEFAULT_SINK@"]).output().await.ok()?;
+	let muted = pactl(&["get-sink-mute", "@DEFAULT_SINK@"]).output().await.ok()?;
+	if !volume.status.success() || !muted.status.success() {
+		return None;
+	}
+	let percent = percent(&String::from_utf8_lossy(&volume.stdout))?;
+	let muted = String::from_utf8_lossy(&muted.stdout).trim() == "Mute: yes";
+	Some((percent, muted))
+}
+
+/// Follows the default output until `shutdown` is cancelled, saying its volume whenever that changes, and when it is muted.
+/// Does nothing if disabled; if `pactl` is not available, that is said once, since the volume keys then give no feedback at all.
+#[tracing::instrument(skip_all)]
+pub async fn watch(state: Arc<ScreenReaderState>, shutdown: CancellationToken) -> eyre::Result<()> {
+	if !state.config.keyboard.announce_volume {
+		return Ok(());
+	}
+	// killed when this returns
+	let mut subscription =
+		match pactl(&["subscribe"])
+			.stdout(Stdio::piped())
+			.kill_on_drop(true)
+			.spawn()
+		{
+			Ok(subscription) => subscription,
+			Err(e) => {
+				tracing::warn!("Not announcing volume changes, since pactl is not available: {e}");
+				state.say(
+					Category::System,
+					Priority::Important,
+					state.l10n.get("volume-unavailable"),
+				)
+				.await;
+				return Ok(());
+			}
+		};
+	let stdout = subscription
+		.stdout
+		.take()
+		.ok_or_else(|| eyre::eyre!("pactl has no stdout"))?;
+	let mut lines = BufReader::new(stdout).lines();
+	let mut previous = volume().await;
+	while let Some(line) = shutdown.run_until_cancelled(lines.next_line()).await {
+		let Some(line) = line? else {
+			break;
+		};
+		// like `Event 'change' on sink #56`, or on the server when the default output is switched
+		if !line.contains(" on sink ") && !line.contains(" on server") {
+			continue;
+		}
+		let current = volume().await;
+		let Some((percent, muted)) = current.filter(|_| current != previous) else {
+			continue;
+		};
+		let message = if muted && previous.is_some_and(|(_, was_muted)| !was_muted) {
+			state.l10n.get("muted")
+		} else {
+			state.l10n.format("volume", &[("percent", &percent.to_string())])
+		};
+		previous = current;
+		// newer volumes cut off older ones while a volume key is held
+		state.say(Category::System, Priority::Text, message).await;
+	}
+	Ok(())
+}
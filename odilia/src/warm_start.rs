@@ -0,0 +1,143 @@
+//! Finding the applications which were already running when Odilia started.
+//!
+//! Odilia otherwise only learns about an application from its events, so one opened before Odilia stays unknown until it sends one; and with nothing focused yet, the handlers which follow the focused item, like reading the text a caret moves over, ignore everything.
+//! So at startup the applications on the bus and their top-level windows are put in the cache, and the focused control in the active window is announced as if focus had just moved there, so that the user is not left in silence until they move it.
+
+use std::{sync::Arc, time::Duration};
+
+use atspi::{events::object::StateChangedEvent, AtspiError, Event};
+use atspi_common::{ObjectRef, State};
+use futures::channel::mpsc::Sender;
+use odilia_cache::{CacheExt, CacheItem};
+use odilia_common::{cache::AccessiblePrimitive, errors::OdiliaError};
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+
+use crate::state::{ScreenReaderState, CACHE_LOOKUP_TIMEOUT};
+
+/// How many accessibles are looked at, at most, when searching a window for the focused one, and for how long; windows with huge trees, like long documents, are not worth waiting for.
+const MAX_SEARCHED: usize = 2000;
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The registry's root, whose children are the applications.
+pub fn registry_root() -> AccessiblePrimitive {
+	AccessiblePrimitive {
+		id: "/org/a11y/atspi/accessible/root".into(),
+		sender: "org.a11y.atspi.Registry".into(),
+	}
+}
+
+/// Caches `app` and its top-level windows, and returns the windows; gives up after [`CACHE_LOOKUP_TIMEOUT`], for applications which hang.
+async fn windows(
+	state: &ScreenReaderState,
+	app: &AccessiblePrimitive,
+) -> Result<Vec<CacheItem>, OdiliaError> {
+	let fetch = async {
+		let proxy = app.clone().into_accessible(state.connection()).await?;
+		let app = state
+			.cache
+			.get_or_create(&proxy, Arc::clone(&state.cache), CACHE_LOOKUP_TIMEOUT)
+			.await?;
+		let keys: Vec<_> = app.children.iter().map(|child| child.key.clone()).collect();
+		state.cache.get_or_create_all(&keys).await
+	};
+	timeout(CACHE_LOOKUP_TIMEOUT, fetch).await.map_err(|_| {
+		OdiliaError::ServiceUnavailable(format!(
+			"{app:?} did not describe its windows within {CACHE_LOOKUP_TIMEOUT:?}"
+		))
+	})?
+}
+
+/// The focused accessible in `window`, looking only into what is showing.
+async fn focused_in(
+	state: &ScreenReaderState,
+	window: AccessiblePrimitive,
+) -> Result<Option<ObjectRef>, OdiliaError> {
+	let window = window.into_accessible(state.connection()).await?;
+	let mut stack = window.get_children().await?;
+	let mut searched = 0;
+	while let Some(child) = stack.pop() {
+		searched += 1;
+		if searched > MAX_SEARCHED {
+			return Ok(None);
+		}
+		let accessible = AccessiblePrimitive::from(child.clone())
+			.into_accessible(state.connection())
+			.await?;
+		let states = accessible.get_state().await?;
+		if states.contains(State::Focused) {
+			return Ok(Some(child));
+		}
+		if states.contains(State::Showing) {
+			stack.extend(accessible.get_children().await?);
+		}
+	}
+	Ok(None)
+}
+
+/// Caches every running application and its windows, then announces the focused control in the active window through `events`, as a focus event, unless something was focused since Odilia started.
+/// If no control in it is focused, the window itself is taken as focused, silently.
+/// Applications which do not answer are skipped; gives up once `shutdown` is cancelled.
+#[tracing::instrument(skip_all)]
+pub async fn prime(
+	state: Arc<ScreenReaderState>,
+	mut events: Sender<Result<Event, AtspiError>>,
+	shutdown: CancellationToken,
+) -> eyre::Result<()> {
+	let root = registry_root().into_accessible(state.connection()).await?;
+	let apps = root.get_children().await?;
+	let mut active = None;
+	let mut primed = 0;
+	for app in apps {
+		let app = AccessiblePrimitive::from(app);
+		let Some(windows) =
+			shutdown.run_until_cancelled(Box::pin(windows(&state, &app))).await
+		else {
+			return Ok(());
+		};
+		match windows {
+			Ok(windows) => {
+				primed += 1;
+				active = active.or_else(|| {
+					windows.into_iter().find(|window| {
+						window.states.contains(State::Active)
+					})
+				});
+			}
+			Err(e) => tracing::debug!(
+				?app,
+				"Could not read the windows of a running application: {e}"
+			),
+		}
+	}
+	tracing::debug!(applications = primed, "Cached the applications already running");
+	let Some(window) = active else {
+		return Ok(());
+	};
+	let search = timeout(SEARCH_TIMEOUT, focused_in(&state, window.object.clone()));
+	let focused = match shutdown.run_until_cancelled(search).await {
+		None => return Ok(()),
+		Some(Ok(Ok(focused))) => focused,
+		Some(Ok(Err(e))) => {
+			tracing::debug!("Could not search the active window for focus: {e}");
+			None
+		}
+		Some(Err(_)) => {
+			tracing::debug!("Gave up searching the active window for focus");
+			None
+		}
+	};
+	// a real focus event may have come in meanwhile, which is newer
+	if state.history_item(0).is_some() {
+		return Ok(());
+	}
+	if let Some(item) = focused {
+		let event = StateChangedEvent { item, state: State::Focused, enabled: true };
+		events.try_send(Ok(event.into()))?;
+	} else if let Ok(mut history) = state.accessible_history.lock() {
+		if history.is_empty() {
+			history.push(window.object);
+		}
+	}
+	Ok(())
+}
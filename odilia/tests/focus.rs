@@ -0,0 +1,85 @@
+use atspi_common::{Role, State, StateSet};
+use odilia_client::Client;
+use odilia_test_support::{Harness, Node};
+
+#[tokio::test]
+async fn focusing_a_button_speaks_its_name_and_role() -> eyre::Result<()> {
+	let window = Node::new("window", "Main window", Role::Frame)
+		.child(Node::new("ok", "OK", Role::Button))
+		.child(Node::new("cancel", "Cancel", Role::Button));
+	let tree = Node::new("root", "Test application", Role::Application).child(window);
+	let mut harness = Harness::new(tree).await?;
+	harness.start_odilia(env!("CARGO_BIN_EXE_odilia")).await?;
+	harness.expect_speech("Welcome to Odilia").await?;
+
+	harness.app().focus("ok").await?;
+	let said = harness.expect_speech("OK").await?;
+	assert_eq!(said.text, format!("OK, {}", Role::Button.name()));
+
+	harness.app().focus("cancel").await?;
+	harness.expect_speech("Cancel").await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn a_renamed_item_is_announced_by_its_new_name() -> eyre::Result<()> {
+	let window = Node::new("window", "Main window", Role::Frame)
+		.child(Node::new("ok", "OK", Role::Button))
+		.child(Node::new("cancel", "Cancel", Role::Button));
+	let tree = Node::new("root", "Test application", Role::Application).child(window);
+	let mut harness = Harness::new(tree).await?;
+	harness.start_odilia(env!("CARGO_BIN_EXE_odilia")).await?;
+	harness.expect_speech("Welcome to Odilia").await?;
+
+	// caches the button under its old name
+	harness.app().focus("ok").await?;
+	harness.expect_speech("OK").await?;
+	harness.app().focus("cancel").await?;
+	harness.expect_speech("Cancel").await?;
+
+	// the rename is handled before the focus event right after it, so the cache is up to date by then
+	harness.app().rename("ok", "Apply").await?;
+	harness.app().focus("ok").await?;
+	let said = harness.expect_speech(Role::Button.name()).await?;
+	assert_eq!(said.text, format!("Apply, {}", Role::Button.name()));
+	Ok(())
+}
+
+/// The states of a control which can be seen and focused, and `extra` ones.
+fn showing(extra: State) -> StateSet {
+	[State::Enabled, State::Sensitive, State::Visible, State::Showing, State::Focusable, extra]
+		.into_iter()
+		.collect()
+}
+
+#[tokio::test]
+async fn the_control_focused_before_odilia_started_is_announced() -> eyre::Result<()> {
+	let window = Node::new("window", "Main window", Role::Frame)
+		.states(showing(State::Active))
+		.child(Node::new("ok", "OK", Role::Button))
+		.child(Node::new("cancel", "Cancel", Role::Button).states(showing(State::Focused)));
+	let tree = Node::new("root", "Test application", Role::Application).child(window);
+	let mut harness = Harness::new(tree).await?;
+	harness.start_odilia(env!("CARGO_BIN_EXE_odilia")).await?;
+
+	// without any focus event from the application
+	let said = harness.expect_speech("Cancel").await?;
+	assert_eq!(said.text, format!("Cancel, {}", Role::Button.name()));
+	Ok(())
+}
+
+#[tokio::test]
+async fn the_applications_running_at_startup_are_cached() -> eyre::Result<()> {
+	let window = Node::new("window", "Main window", Role::Frame)
+		.states(showing(State::Active))
+		.child(Node::new("ok", "OK", Role::Button).states(showing(State::Focused)));
+	let tree = Node::new("root", "Test application", Role::Application).child(window);
+	let mut harness = Harness::new(tree).await?;
+	harness.start_odilia(env!("CARGO_BIN_EXE_odilia")).await?;
+	harness.expect_speech("OK").await?;
+
+	// the name of the focused control's application is only known from the cache
+	let client = Client::with_connection(&harness.bus().connect().await?).await?;
+	assert_eq!(client.status().await?.app, "Test application");
+	Ok(())
+}
@@ -0,0 +1,280 @@
+//! A fake application, exposing a scripted tree of accessibles over AT-SPI.
+
+use std::collections::HashMap;
+
+use atspi_common::{Role, State, StateSet};
+use eyre::eyre;
+use zbus::{
+	names::OwnedUniqueName,
+	zvariant::{OwnedObjectPath, Value},
+};
+
+/// How every accessible is referred to over the bus: the owning connection, and the object path.
+type ObjectRef = (OwnedUniqueName, OwnedObjectPath);
+
+const ACCESSIBLE_PATH_PREFIX: &str = "/org/a11y/atspi/accessible/";
+
+/// A node in the scripted accessible tree.
+/// Each node is served at `/org/a11y/atspi/accessible/<id>`; by convention, the top of the tree has the id `root`.
+#[derive(Debug, Clone)]
+pub struct Node {
+	id: String,
+	name: String,
+	role: Role,
+	states: StateSet,
+	children: Vec<Node>,
+}
+
+impl Node {
+	/// A new node, which is enabled, visible and focusable.
+	/// `id` must be a valid object path element: ASCII letters, digits and underscores only.
+	pub fn new(id: impl Into<String>, name: impl Into<String>, role: Role) -> Self {
+		Node {
+			id: id.into(),
+			name: name.into(),
+			role,
+			states: [
+				State::Enabled,
+				State::Sensitive,
+				State::Visible,
+				State::Showing,
+				State::Focusable,
+			]
+			.into_iter()
+			.collect(),
+			children: Vec::new(),
+		}
+	}
+	#[must_use]
+	pub fn child(mut self, child: Node) -> Self {
+		self.children.push(child);
+		self
+	}
+	#[must_use]
+	pub fn states(mut self, states: StateSet) -> Self {
+		self.states = states;
+		self
+	}
+	fn path(&self) -> eyre::Result<OwnedObjectPath> {
+		Ok(OwnedObjectPath::try_from(format!("{ACCESSIBLE_PATH_PREFIX}{}", self.id))?)
+	}
+}
+
+/// The `org.a11y.atspi.Accessible` implementation for a single [`Node`].
+struct Accessible {
+	name: String,
+	role: Role,
+	states: StateSet,
+	index: i32,
+	parent: ObjectRef,
+	application: ObjectRef,
+	children: Vec<ObjectRef>,
+}
+
+// the interface macro requires every method to take `self`, whether or not it is used
+#[allow(clippy::unused_self)]
+#[zbus::interface(name = "org.a11y.atspi.Accessible")]
+impl Accessible {
+	#[zbus(property)]
+	fn name(&self) -> String {
+		self.name.clone()
+	}
+	#[zbus(property)]
+	fn description(&self) -> String {
+		String::new()
+	}
+	#[zbus(property)]
+	fn parent(&self) -> ObjectRef {
+		self.parent.clone()
+	}
+	#[zbus(property)]
+	fn child_count(&self) -> i32 {
+		i32::try_from(self.children.len()).unwrap_or(i32::MAX)
+	}
+	#[zbus(property)]
+	fn locale(&self) -> String {
+		String::from("en_US")
+	}
+	#[zbus(property)]
+	fn accessible_id(&self) -> String {
+		String::new()
+	}
+	fn get_child_at_index(&self, index: i32) -> zbus::fdo::Result<ObjectRef> {
+		usize::try_from(index)
+			.ok()
+			.and_then(|i| self.children.get(i))
+			.cloned()
+			.ok_or_else(|| {
+				zbus::fdo::Error::InvalidArgs(format!("No child at {index}"))
+			})
+	}
+	fn get_children(&self) -> Vec<ObjectRef> {
+		self.children.clone()
+	}
+	fn get_index_in_parent(&self) -> i32 {
+		self.index
+	}
+	fn get_relation_set(&self) -> Vec<(u32, Vec<ObjectRef>)> {
+		Vec::new()
+	}
+	fn get_role(&self) -> u32 {
+		self.role as u32
+	}
+	fn get_role_name(&self) -> String {
+		self.role.name().to_string()
+	}
+	fn get_localized_role_name(&self) -> String {
+		self.role.name().to_string()
+	}
+	fn get_state(&self) -> StateSet {
+		self.states
+	}
+	fn get_attributes(&self) -> HashMap<String, String> {
+		HashMap::new()
+	}
+	fn get_application(&self) -> ObjectRef {
+		self.application.clone()
+	}
+	fn get_interfaces(&self) -> Vec<String> {
+		vec![String::from("org.a11y.atspi.Accessible")]
+	}
+}
+
+/// Serves the registry's root at `desktop`, listing `apps` as its children, as the registry does with every running application.
+/// # Errors
+/// If the object can not be served.
+pub async fn serve_desktop(
+	connection: &zbus::Connection,
+	desktop: ObjectRef,
+	apps: Vec<ObjectRef>,
+) -> eyre::Result<()> {
+	let accessible = Accessible {
+		name: String::from("main"),
+		role: Role::DesktopFrame,
+		states: StateSet::empty(),
+		index: -1,
+		parent: desktop.clone(),
+		application: desktop.clone(),
+		children: apps,
+	};
+	connection.object_server().at(&desktop.1, accessible).await?;
+	Ok(())
+}
+
+/// A connection to the bus which serves a [`Node`] tree, and can emit events on behalf of any node in it.
+#[derive(Debug)]
+#[allow(clippy::module_name_repetitions)]
+pub struct FakeApp {
+	connection: zbus::Connection,
+	paths: HashMap<String, OwnedObjectPath>,
+	application: ObjectRef,
+}
+
+impl FakeApp {
+	/// Serves `tree` over `connection`.
+	/// The root of the tree reports `desktop` as its parent, as applications do with the registry's root.
+	/// # Errors
+	/// If any node id is not a valid object path element, or if the objects can not be served.
+	pub async fn serve(
+		connection: zbus::Connection,
+		tree: Node,
+		desktop: ObjectRef,
+	) -> eyre::Result<Self> {
+		let name: OwnedUniqueName = connection
+			.unique_name()
+			.ok_or_else(|| eyre!("The application is not connected to a bus"))?
+			.to_owned();
+		let application = (name.clone(), tree.path()?);
+		let mut paths = HashMap::new();
+		// (node, index in parent, parent)
+		let mut to_serve = vec![(tree, 0, desktop)];
+		while let Some((node, index, parent)) = to_serve.pop() {
+			let path = node.path()?;
+			let me = (name.clone(), path.clone());
+			let children = node
+				.children
+				.iter()
+				.map(|child| Ok((name.clone(), child.path()?)))
+				.collect::<eyre::Result<Vec<_>>>()?;
+			let accessible = Accessible {
+				name: node.name,
+				role: node.role,
+				states: node.states,
+				index,
+				parent,
+				application: application.clone(),
+				children,
+			};
+			connection.object_server().at(&path, accessible).await?;
+			paths.insert(node.id, path);
+			for (i, child) in node.children.into_iter().enumerate() {
+				to_serve.push((child, i32::try_from(i)?, me.clone()));
+			}
+		}
+		Ok(FakeApp { connection, paths, application })
+	}
+	/// The root of the application's tree, which the registry lists among its children.
+	#[must_use]
+	pub fn root(&self) -> (OwnedUniqueName, OwnedObjectPath) {
+		self.application.clone()
+	}
+	/// Emits an AT-SPI event from the node with the given `id`.
+	/// `interface` is the event interface, like `org.a11y.atspi.Event.Object`, and `member` the signal name within it, like `StateChanged`.
+	/// # Errors
+	/// If there is no node with that id, or if the signal can not be sent.
+	pub async fn emit(
+		&self,
+		id: &str,
+		interface: &str,
+		member: &str,
+		kind: &str,
+		detail1: i32,
+		detail2: i32,
+	) -> eyre::Result<()> {
+		self.emit_body(id, interface, member, (kind, detail1, detail2, Value::from(0i32)))
+			.await
+	}
+	/// Emits an AT-SPI event from the node with the given `id`, with `any_data` in its body, which some events carry the new value in.
+	async fn emit_body(
+		&self,
+		id: &str,
+		interface: &str,
+		member: &str,
+		(kind, detail1, detail2, any_data): (&str, i32, i32, Value<'_>),
+	) -> eyre::Result<()> {
+		let path = self.paths.get(id).ok_or_else(|| eyre!("No node with id {id}"))?;
+		let properties: HashMap<&str, Value<'_>> = HashMap::new();
+		self.connection
+			.emit_signal(
+				None::<&str>,
+				path,
+				interface,
+				member,
+				&(kind, detail1, detail2, any_data, properties),
+			)
+			.await?;
+		Ok(())
+	}
+	/// Tells the screen reader that the node with the given `id` has gained focus.
+	/// # Errors
+	/// See [`FakeApp::emit`].
+	pub async fn focus(&self, id: &str) -> eyre::Result<()> {
+		self.emit(id, "org.a11y.atspi.Event.Object", "StateChanged", "focused", 1, 0)
+			.await
+	}
+	/// Renames the node with the given `id`, and tells the screen reader, like applications do.
+	/// # Errors
+	/// If there is no node with that id, or if the signal can not be sent.
+	pub async fn rename(&self, id: &str, name: &str) -> eyre::Result<()> {
+		let path = self.paths.get(id).ok_or_else(|| eyre!("No node with id {id}"))?;
+		let accessible = self
+			.connection
+			.object_server()
+			.interface::<_, Accessible>(path)
+			.await?;
+		accessible.get_mut().await.name = name.to_string();
+		let body = ("accessible-name", 0, 0, Value::from(name));
+		self.emit_body(id, "org.a11y.atspi.Event.Object", "PropertyChange", body)
+			.await
+	}
+}
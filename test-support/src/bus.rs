@@ -0,0 +1,76 @@
+//! A private `dbus-daemon`, so that tests never touch the user's real session bus.
+
+use std::{fs, path::Path, process::Stdio};
+
+use eyre::{eyre, WrapErr};
+use tokio::{
+	io::{AsyncBufReadExt, BufReader},
+	process::{Child, Command},
+};
+
+/// Policy for the private bus: allow everything, including monitoring, which Odilia uses to listen for notifications.
+const BUS_CONFIG: &str = r#"<!DOCTYPE busconfig PUBLIC "-//freedesktop//DTD D-Bus Bus Configuration 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/busconfig.dtd">
+<busconfig>
+  <type>session</type>
+  <listen>unix:path=@SOCKET@</listen>
+  <auth>EXTERNAL</auth>
+  <policy context="default">
+    <allow send_destination="*" eavesdrop="true"/>
+    <allow eavesdrop="true"/>
+    <allow own="*"/>
+  </policy>
+</busconfig>
+"#;
+
+/// A `dbus-daemon` process which is killed when this is dropped.
+#[derive(Debug)]
+#[allow(clippy::module_name_repetitions)]
+pub struct PrivateBus {
+	address: String,
+	_daemon: Child,
+}
+
+impl PrivateBus {
+	/// Starts a new bus listening on a socket inside `dir`, and waits until it accepts connections.
+	/// # Errors
+	/// If `dbus-daemon` is not installed, or if it fails to start.
+	pub async fn start(dir: &Path) -> eyre::Result<Self> {
+		let socket = dir.join("bus");
+		let config = dir.join("bus.conf");
+		fs::write(&config, BUS_CONFIG.replace("@SOCKET@", &socket.to_string_lossy()))?;
+		let mut daemon = Command::new("dbus-daemon")
+			.arg("--nofork")
+			.arg("--print-address")
+			.arg(format!("--config-file={}", config.display()))
+			.stdout(Stdio::piped())
+			.stderr(Stdio::null())
+			.kill_on_drop(true)
+			.spawn()
+			.wrap_err("Could not run dbus-daemon; is it installed?")?;
+		let stdout = daemon
+			.stdout
+			.take()
+			.ok_or_else(|| eyre!("dbus-daemon has no stdout"))?;
+		// the address is only printed once the daemon is listening
+		let address =
+			BufReader::new(stdout).lines().next_line().await?.ok_or_else(|| {
+				eyre!("dbus-daemon exited before printing its address")
+			})?;
+		tracing::debug!(%address, "private bus started");
+		Ok(PrivateBus { address, _daemon: daemon })
+	}
+	/// The address to use for `DBUS_SESSION_BUS_ADDRESS`.
+	#[must_use]
+	pub fn address(&self) -> &str {
+		&self.address
+	}
+	/// Opens a new connection to this bus.
+	/// # Errors
+	/// If the connection can not be established.
+	pub async fn connect(&self) -> eyre::Result<zbus::Connection> {
+		Ok(zbus::connection::Builder::address(self.address.as_str())?
+			.build()
+			.await?)
+	}
+}
@@ -0,0 +1,150 @@
+#![deny(clippy::all, clippy::pedantic, clippy::map_unwrap_or, clippy::unwrap_used, unsafe_code)]
+#![allow(clippy::multiple_crate_versions, clippy::missing_errors_doc)]
+
+//! End-to-end test support for Odilia.
+//!
+//! A [`Harness`] starts a private D-Bus session bus, a fake accessibility registry, a fake application serving a
+//! scripted tree of [`Node`]s, and a fake speech-dispatcher; then runs the real `odilia` binary against all of them.
+//! Tests drive the application (for example, by moving focus), and assert on what Odilia would have said.
+
+pub mod app;
+pub mod bus;
+pub mod registry;
+pub mod speech;
+
+pub use app::{FakeApp, Node};
+pub use bus::PrivateBus;
+pub use speech::{FakeSpeechDispatcher, Utterance};
+
+use std::{path::Path, process::Stdio, time::Duration};
+
+use eyre::{bail, eyre, WrapErr};
+use tempfile::TempDir;
+use tokio::{
+	process::{Child, Command},
+	sync::{mpsc::UnboundedReceiver, watch},
+	time::{sleep, timeout, Instant},
+};
+use zbus::{names::OwnedUniqueName, zvariant::OwnedObjectPath};
+
+/// How long to wait for Odilia to start, or to say something, before failing the test.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Everything needed to run Odilia in isolation.
+/// All processes are stopped, and all files removed, when this is dropped.
+pub struct Harness {
+	// field order matters: Odilia must be stopped before the bus and directory go away
+	odilia: Option<Child>,
+	app: FakeApp,
+	_registry: zbus::Connection,
+	registered: watch::Receiver<Vec<String>>,
+	_speech: FakeSpeechDispatcher,
+	utterances: UnboundedReceiver<Utterance>,
+	bus: PrivateBus,
+	dir: TempDir,
+}
+
+impl Harness {
+	/// Starts the bus, registry, speech-dispatcher and an application serving `tree`.
+	/// Odilia itself is started separately, with [`Harness::start_odilia`].
+	pub async fn new(tree: Node) -> eyre::Result<Self> {
+		let dir = tempfile::Builder::new().prefix("odilia-test").tempdir()?;
+		for sub in ["runtime", "config", "state", "data"] {
+			std::fs::create_dir_all(dir.path().join(sub))?;
+		}
+		let bus = PrivateBus::start(dir.path()).await?;
+		let registry_connection = bus.connect().await?;
+		let registered = registry::serve(&registry_connection, bus.address()).await?;
+		let desktop: (OwnedUniqueName, OwnedObjectPath) = (
+			registry_connection
+				.unique_name()
+				.ok_or_else(|| eyre!("The registry is not connected to a bus"))?
+				.to_owned(),
+			OwnedObjectPath::try_from("/org/a11y/atspi/accessible/root")?,
+		);
+		let app = FakeApp::serve(bus.connect().await?, tree, desktop.clone()).await?;
+		app::serve_desktop(&registry_connection, desktop, vec![app.root()]).await?;
+		let (speech, utterances) =
+			FakeSpeechDispatcher::start(&dir.path().join("runtime"))?;
+		Ok(Harness {
+			odilia: None,
+			app,
+			_registry: registry_connection,
+			registered,
+			_speech: speech,
+			utterances,
+			bus,
+			dir,
+		})
+	}
+	/// Runs the `odilia` binary at `binary` against the fake environment, and waits until it is ready for events.
+	/// From an integration test in the `odilia` crate, pass `env!("CARGO_BIN_EXE_odilia")`.
+	pub async fn start_odilia(&mut self, binary: impl AsRef<Path>) -> eyre::Result<()> {
+		if self.odilia.is_some() {
+			bail!("Odilia has already been started");
+		}
+		let root = self.dir.path();
+		let runtime = root.join("runtime");
+		let child = Command::new(binary.as_ref())
+			.env("DBUS_SESSION_BUS_ADDRESS", self.bus.address())
+			.env("XDG_RUNTIME_DIR", &runtime)
+			.env("XDG_CONFIG_HOME", root.join("config"))
+			.env("XDG_STATE_HOME", root.join("state"))
+			.env("XDG_DATA_HOME", root.join("data"))
+			.stdin(Stdio::null())
+			.kill_on_drop(true)
+			.spawn()
+			.wrap_err("Could not start odilia")?;
+		self.odilia = Some(child);
+		// the input socket is only opened once the handlers are in place, so events sent after this point will be seen.
+		let socket = runtime.join("odilia.sock");
+		let deadline = Instant::now() + DEFAULT_TIMEOUT;
+		while !socket.exists() {
+			if let Some(status) =
+				self.odilia.as_mut().and_then(|c| c.try_wait().ok().flatten())
+			{
+				bail!("Odilia exited during startup with {status}");
+			}
+			if Instant::now() > deadline {
+				bail!("Odilia did not become ready within {DEFAULT_TIMEOUT:?}");
+			}
+			sleep(Duration::from_millis(20)).await;
+		}
+		Ok(())
+	}
+	/// The fake application, to emit events from.
+	#[must_use]
+	pub fn app(&self) -> &FakeApp {
+		&self.app
+	}
+	/// The private bus everything runs on, to connect to Odilia's own D-Bus interface.
+	#[must_use]
+	pub fn bus(&self) -> &PrivateBus {
+		&self.bus
+	}
+	/// Every event string Odilia currently has registered with the registry.
+	#[must_use]
+	pub fn registered_events(&self) -> Vec<String> {
+		self.registered.borrow().clone()
+	}
+	/// Waits until Odilia says something containing `expected`, skipping anything else it says first.
+	/// Fails after [`DEFAULT_TIMEOUT`], listing everything which was said in the meantime.
+	pub async fn expect_speech(&mut self, expected: &str) -> eyre::Result<Utterance> {
+		let mut heard = Vec::new();
+		let found = timeout(DEFAULT_TIMEOUT, async {
+			while let Some(utterance) = self.utterances.recv().await {
+				if utterance.text.contains(expected) {
+					return Some(utterance);
+				}
+				heard.push(utterance.text);
+			}
+			None
+		})
+		.await;
+		match found {
+			Ok(Some(utterance)) => Ok(utterance),
+			Ok(None) => bail!("Odilia disconnected from speech-dispatcher; it said: {heard:?}"),
+			Err(_) => bail!("Odilia did not say {expected:?} within {DEFAULT_TIMEOUT:?}; it said: {heard:?}"),
+		}
+	}
+}
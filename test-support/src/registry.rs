@@ -0,0 +1,82 @@
+//! The parts of `at-spi-bus-launcher` and `at-spi2-registryd` that Odilia talks to.
+//! The private bus doubles as the accessibility bus, so `org.a11y.Bus.GetAddress` simply returns its own address.
+
+use tokio::sync::watch;
+
+struct A11yBus {
+	address: String,
+}
+
+#[zbus::interface(name = "org.a11y.Bus")]
+impl A11yBus {
+	fn get_address(&self) -> String {
+		self.address.clone()
+	}
+}
+
+struct Status {
+	enabled: bool,
+	screen_reader_enabled: bool,
+}
+
+#[zbus::interface(name = "org.a11y.Status")]
+impl Status {
+	#[zbus(property)]
+	fn is_enabled(&self) -> bool {
+		self.enabled
+	}
+	#[zbus(property)]
+	fn set_is_enabled(&mut self, value: bool) {
+		self.enabled = value;
+	}
+	#[zbus(property)]
+	fn screen_reader_enabled(&self) -> bool {
+		self.screen_reader_enabled
+	}
+	#[zbus(property)]
+	fn set_screen_reader_enabled(&mut self, value: bool) {
+		self.screen_reader_enabled = value;
+	}
+}
+
+struct Registry {
+	events: watch::Sender<Vec<String>>,
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[zbus::interface(name = "org.a11y.atspi.Registry")]
+impl Registry {
+	fn register_event(&self, event: String) {
+		self.events.send_modify(|events| events.push(event));
+	}
+	fn deregister_event(&self, event: String) {
+		self.events.send_modify(|events| events.retain(|e| *e != event));
+	}
+	fn get_registered_events(&self) -> Vec<(String, String)> {
+		self.events
+			.borrow()
+			.iter()
+			.map(|e| (String::new(), e.clone()))
+			.collect()
+	}
+}
+
+/// Serves `org.a11y.Bus`, `org.a11y.Status` and `org.a11y.atspi.Registry` on `connection`.
+/// Returns a receiver which is updated with the list of registered events whenever it changes.
+/// # Errors
+/// If the names are already taken, or the objects can not be served.
+pub async fn serve(
+	connection: &zbus::Connection,
+	address: &str,
+) -> eyre::Result<watch::Receiver<Vec<String>>> {
+	let (events, registered) = watch::channel(Vec::new());
+	let server = connection.object_server();
+	server.at("/org/a11y/bus", A11yBus { address: address.to_string() })
+		.await?;
+	server.at("/org/a11y/bus", Status { enabled: false, screen_reader_enabled: false })
+		.await?;
+	server.at("/org/a11y/atspi/registry", Registry { events }).await?;
+	connection.request_name("org.a11y.Bus").await?;
+	connection.request_name("org.a11y.atspi.Registry").await?;
+	Ok(registered)
+}
@@ -0,0 +1,120 @@
+//! A stand-in for speech-dispatcher, which records everything it is asked to say instead of speaking it.
+
+use std::path::Path;
+
+use tokio::{
+	io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+	net::{UnixListener, UnixStream},
+	sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+	task::JoinHandle,
+};
+
+/// A single message received by the fake speech-dispatcher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utterance {
+	/// The priority set most recently before the message, as sent over SSIP (`text`, `important`, ...).
+	pub priority: String,
+	pub text: String,
+}
+
+/// Listens on `<runtime dir>/speech-dispatcher/speechd.sock`, which is where Odilia looks for speech-dispatcher.
+/// The listener is stopped when this is dropped.
+#[derive(Debug)]
+pub struct FakeSpeechDispatcher {
+	listener: JoinHandle<()>,
+}
+
+impl FakeSpeechDispatcher {
+	/// Starts listening inside `runtime_dir`, and returns the receiving end of every [`Utterance`].
+	/// # Errors
+	/// If the socket can not be created.
+	pub fn start(runtime_dir: &Path) -> eyre::Result<(Self, UnboundedReceiver<Utterance>)> {
+		let dir = runtime_dir.join("speech-dispatcher");
+		std::fs::create_dir_all(&dir)?;
+		let listener = UnixListener::bind(dir.join("speechd.sock"))?;
+		let (tx, rx) = unbounded_channel();
+		let listener = tokio::spawn(async move {
+			while let Ok((stream, _)) = listener.accept().await {
+				tokio::spawn(serve_client(stream, tx.clone()));
+			}
+		});
+		Ok((FakeSpeechDispatcher { listener }, rx))
+	}
+}
+impl Drop for FakeSpeechDispatcher {
+	fn drop(&mut self) {
+		self.listener.abort();
+	}
+}
+
+async fn serve_client(stream: UnixStream, utterances: UnboundedSender<Utterance>) {
+	let (read, mut write) = stream.into_split();
+	let mut lines = BufReader::new(read).lines();
+	let mut priority = String::from("text");
+	let mut message: Option<Vec<String>> = None;
+	let mut message_id = 0;
+	while let Ok(Some(line)) = lines.next_line().await {
+		let line = line.trim_end_matches('\r');
+		// inside a SPEAK block, every line is text until a lone "."
+		if let Some(text) = &mut message {
+			if line == "." {
+				message_id += 1;
+				let _ = utterances.send(Utterance {
+					priority: priority.clone(),
+					text: text.join("\n"),
+				});
+				message = None;
+				let reply =
+					format!("225-{message_id}\r\n225 OK MESSAGE QUEUED\r\n");
+				if write.write_all(reply.as_bytes()).await.is_err() {
+					return;
+				}
+			} else {
+				// lines starting with a dot are escaped by doubling it
+				text.push(line.strip_prefix('.').unwrap_or(line).to_string());
+			}
+			continue;
+		}
+		let words: Vec<&str> = line.split_whitespace().collect();
+		let reply = match words.as_slice() {
+			["SPEAK", ..] => {
+				message = Some(Vec::new());
+				"230 OK RECEIVING DATA"
+			}
+			["SET", _, setting, value, ..] => {
+				if setting.eq_ignore_ascii_case("PRIORITY") {
+					priority = value.to_lowercase();
+				}
+				set_reply(setting)
+			}
+			["CANCEL", ..] => "213 OK CANCELED",
+			["STOP", ..] => "210 OK STOPPED",
+			["QUIT", ..] => {
+				let _ = write.write_all(b"231 HAPPY HACKING\r\n").await;
+				return;
+			}
+			_ => "299 OK NOT IMPLEMENTED",
+		};
+		if write.write_all(format!("{reply}\r\n").as_bytes()).await.is_err() {
+			return;
+		}
+	}
+}
+
+/// The reply speech-dispatcher gives when a setting has been changed; the client checks the status code.
+fn set_reply(setting: &str) -> &'static str {
+	match setting.to_uppercase().as_str() {
+		"CLIENT_NAME" => "208 OK CLIENT NAME SET",
+		"LANGUAGE" => "201 OK LANGUAGE SET",
+		"PRIORITY" => "202 OK PRIORITY SET",
+		"RATE" => "203 OK RATE SET",
+		"PITCH" => "204 OK PITCH SET",
+		"PUNCTUATION" => "205 OK PUNCTUATION SET",
+		"SPELLING" => "207 OK SPELLING SET",
+		"VOICE_TYPE" | "SYNTHESIS_VOICE" => "209 OK VOICE SET",
+		"OUTPUT_MODULE" => "216 OK OUTPUT MODULE SET",
+		"VOLUME" => "218 OK VOLUME SET",
+		"NOTIFICATION" => "220 OK NOTIFICATION SET",
+		_ => "299 OK NOT IMPLEMENTED",
+	}
+}
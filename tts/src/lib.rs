@@ -10,20 +10,392 @@
 
 use eyre::Context;
 use ssip_client_async::{
-	fifo::asynchronous_tokio::Builder, tokio::AsyncClient, ClientName, Request,
+	fifo::asynchronous_tokio::Builder, tokio::AsyncClient, ClientName, MessageScope,
+	NotificationType, Priority, Request, Response, OK_NOTIFICATION_SET,
 };
 use std::{
+	collections::VecDeque,
 	io::ErrorKind,
 	process::{exit, Command, Stdio},
-	time,
+	sync::{
+		atomic::{AtomicBool, AtomicU64, Ordering},
+		Arc, Mutex, PoisonError,
+	},
+	time::{self, Duration, Instant},
 };
 use tokio::{
 	io::{BufReader, BufWriter},
 	net::unix::{OwnedReadHalf, OwnedWriteHalf},
-	sync::mpsc::Receiver,
+	sync::{
+		mpsc::{self, error::SendError},
+		watch,
+	},
 };
 use tokio_util::sync::CancellationToken;
 
+/// What is queued for speech-dispatcher.
+#[derive(Debug)]
+enum Queued {
+	Request(Request),
+	/// All the requests making up a message, held back while the user is typing rapidly, since `since`; see [`SpeechSender::speak`].
+	Held {
+		requests: Vec<Request>,
+		since: Instant,
+	},
+	/// All the requests making up a message which are sent together, with nothing from anyone else in between; see [`SpeechSender::speak_marked`].
+	Message(Vec<Request>),
+}
+
+impl Queued {
+	/// The request which could not be queued, for [`SendError`]; for a held message, the one with its text.
+	fn into_request(self) -> Request {
+		match self {
+			Queued::Request(request) => request,
+			Queued::Held { mut requests, .. } | Queued::Message(mut requests) => {
+				requests.pop().unwrap_or(Request::Speak)
+			}
+		}
+	}
+}
+
+/// A request (or held message), tagged with the generation of speech it was sent in.
+type Tagged = (u64, Queued);
+
+/// The sending half of the queue of requests for speech-dispatcher; see [`speech_channel`].
+///
+/// Every request is tagged with the current generation, which [`SpeechSender::stop`] advances.
+/// The task sending requests to speech-dispatcher drops any speech from an older generation, so that stopping speech also stops everything which was queued, but not yet sent.
+#[derive(Debug, Clone)]
+pub struct SpeechSender {
+	requests: mpsc::Sender<Tagged>,
+	generation: Arc<AtomicU64>,
+	/// Whether the last message queued can be cut off by user activity; see [`SpeechSender::interrupt`].
+	interruptible: Arc<AtomicBool>,
+	typing: Arc<Mutex<Typing>>,
+	timing: TypingTiming,
+	/// When the speech queued so far should be finished; see [`SpeechSender::is_speaking`].
+	busy_until: Arc<Mutex<Option<Instant>>>,
+	/// Whether messages are sent to speech-dispatcher; see [`SpeechSender::route`].
+	speech: Arc<AtomicBool>,
+	/// Whether messages are shown in braille; see [`SpeechSender::route`].
+	braille: Arc<AtomicBool>,
+	/// The last message shown in braille; see [`SpeechSender::braille`].
+	shown: Arc<watch::Sender<String>>,
+	/// The last index mark speech got to; see [`SpeechSender::marks`].
+	marks: Arc<watch::Sender<String>>,
+}
+
+/// How the speech gate decides that the user is typing, and for how long it holds speech back; see [`SpeechSender::speak`].
+#[derive(Debug, Clone, Copy)]
+pub struct TypingTiming {
+	/// Key presses closer together than this count as typing rapidly.
+	pub gap: Duration,
+	/// How long typing has to stop for held speech to be let through.
+	pub pause: Duration,
+	/// The longest any message is held, even if the user never pauses.
+	pub max_hold: Duration,
+}
+
+impl Default for TypingTiming {
+	fn default() -> Self {
+		Self {
+			gap: Duration::from_millis(300),
+			pause: Duration::from_millis(700),
+			max_hold: Duration::from_secs(5),
+		}
+	}
+}
+
+#[derive(Debug, Default)]
+struct Typing {
+	last_key: Option<Instant>,
+	/// Whether the last key was pressed within [`TypingTiming::gap`] of the one before it.
+	rapid: bool,
+}
+
+impl Typing {
+	/// When a message held since `since` may be spoken: once the user pauses typing rapidly, or after [`TypingTiming::max_hold`], whichever comes first.
+	fn release_at(&self, timing: &TypingTiming, since: Instant) -> Instant {
+		match self.last_key {
+			Some(last) if self.rapid => {
+				(last + timing.pause).min(since + timing.max_hold)
+			}
+			_ => since,
+		}
+	}
+}
+
+/// The receiving half of the queue of requests for speech-dispatcher; see [`speech_channel`].
+#[derive(Debug)]
+pub struct SpeechReceiver {
+	requests: mpsc::Receiver<Tagged>,
+	generation: Arc<AtomicU64>,
+	typing: Arc<Mutex<Typing>>,
+	timing: TypingTiming,
+	marks: Arc<watch::Sender<String>>,
+}
+
+/// How many characters a second speech is assumed to get through, to estimate when it finishes; roughly speech-dispatcher's default rate.
+pub const CHARACTERS_PER_SECOND: u64 = 15;
+
+/// Creates a queue of requests for speech-dispatcher, to be handled by [`handle_ssip_commands`].
+/// `timing` configures the gate holding back speech while the user is typing.
+#[must_use]
+pub fn speech_channel(capacity: usize, timing: TypingTiming) -> (SpeechSender, SpeechReceiver) {
+	let (tx, rx) = mpsc::channel(capacity);
+	let generation = Arc::new(AtomicU64::new(0));
+	let typing: Arc<Mutex<Typing>> = Arc::default();
+	let marks = Arc::new(watch::Sender::new(String::new()));
+	(
+		SpeechSender {
+			requests: tx,
+			generation: Arc::clone(&generation),
+			interruptible: Arc::new(AtomicBool::new(false)),
+			typing: Arc::clone(&typing),
+			timing,
+			busy_until: Arc::default(),
+			speech: Arc::new(AtomicBool::new(true)),
+			braille: Arc::new(AtomicBool::new(false)),
+			shown: Arc::new(watch::Sender::new(String::new())),
+			marks: Arc::clone(&marks),
+		},
+		SpeechReceiver { requests: rx, generation, typing, timing, marks },
+	)
+}
+
+impl SpeechSender {
+	/// Queues a single request.
+	/// # Errors
+	/// If the task handling requests has stopped.
+	pub async fn send(&self, request: Request) -> Result<(), SendError<Request>> {
+		let generation = self.generation.load(Ordering::Acquire);
+		self.queue(generation, Queued::Request(request)).await
+	}
+	async fn queue(&self, generation: u64, queued: Queued) -> Result<(), SendError<Request>> {
+		self.requests
+			.send((generation, queued))
+			.await
+			.map_err(|SendError((_, queued))| SendError(queued.into_request()))
+	}
+	/// Whether speech is likely still going on.
+	/// This is an estimate from the length of the text queued since speech was last stopped, since only continuous reading asks speech-dispatcher where speech got to; see [`SpeechSender::speak_marked`].
+	#[must_use]
+	pub fn is_speaking(&self) -> bool {
+		self.busy_until
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.is_some_and(|until| until > Instant::now())
+	}
+	/// Extends the estimate of [`SpeechSender::is_speaking`] by the time it takes to say `characters` characters.
+	fn queued(&self, characters: usize) {
+		let duration = Duration::from_millis(
+			u64::try_from(characters).unwrap_or(u64::MAX).saturating_mul(1000)
+				/ CHARACTERS_PER_SECOND,
+		);
+		let mut busy_until = self.busy_until.lock().unwrap_or_else(PoisonError::into_inner);
+		let now = Instant::now();
+		*busy_until =
+			Some(busy_until.filter(|until| *until > now).unwrap_or(now) + duration);
+	}
+	/// Sets where messages go from now on: to speech-dispatcher, to the braille display, to both, or (with both off) nowhere.
+	/// This applies to every message given to [`SpeechSender::speak`] and [`SpeechSender::speak_char`], so that no one sending them needs to know; requests sent with [`SpeechSender::send`] always go to speech-dispatcher.
+	pub fn route(&self, speech: bool, braille: bool) {
+		self.speech.store(speech, Ordering::Release);
+		self.braille.store(braille, Ordering::Release);
+	}
+	/// The last message shown in braille, which changes with every message while braille is on; see [`SpeechSender::route`].
+	/// Showing it is left to whoever drives the braille display, like `odilia-braille-input`, which follows it over D-Bus.
+	#[must_use]
+	pub fn braille(&self) -> watch::Receiver<String> {
+		self.shown.subscribe()
+	}
+	/// Shows `text` in braille in place of the last message, if braille is on, without speaking it; for when what is shown changed, like the name of the focused object.
+	pub fn refresh_braille(&self, text: &str) {
+		if self.braille.load(Ordering::Acquire) {
+			self.shown.send_replace(text.to_string());
+		}
+	}
+	/// Shows `text` in braille, if braille is on, and returns whether it should be spoken too.
+	fn show(&self, text: &str) -> bool {
+		self.refresh_braille(text);
+		self.speech.load(Ordering::Acquire)
+	}
+	/// Records that the user pressed a key, for the typing gate; see [`SpeechSender::speak`].
+	pub fn key_pressed(&self) {
+		let mut typing = self.typing.lock().unwrap_or_else(PoisonError::into_inner);
+		let now = Instant::now();
+		typing.rapid = typing
+			.last_key
+			.is_some_and(|last| now.duration_since(last) < self.timing.gap);
+		typing.last_key = Some(now);
+	}
+	/// Queues `text` to be spoken with `priority`, or shows it in braille, or both; see [`SpeechSender::route`].
+	/// All requests making up the message are sent in the same generation, so they are either all sent to speech-dispatcher, or all dropped.
+	///
+	/// Speech which is not about what the user is doing (notification, important and progress priorities) is held back while they are typing rapidly, until they pause, so that it does not talk over character echo in the middle of a word.
+	/// It is held by the task sending requests to speech-dispatcher, so this returns right away; other speech queued meanwhile goes first, and stopping speech drops it, like any other queued speech.
+	/// # Errors
+	/// If the task handling requests has stopped.
+	pub async fn speak(
+		&self,
+		priority: Priority,
+		text: String,
+	) -> Result<(), SendError<Request>> {
+		if !self.show(&text) {
+			return Ok(());
+		}
+		let generation = self.generation.load(Ordering::Acquire);
+		let held = matches!(
+			priority,
+			Priority::Notification | Priority::Important | Priority::Progress
+		);
+		self.interruptible.store(
+			matches!(
+				priority,
+				Priority::Text | Priority::Notification | Priority::Progress
+			),
+			Ordering::Release,
+		);
+		self.queued(text.chars().count());
+		let requests = [
+			Request::SetPriority(priority),
+			Request::Speak,
+			Request::SendLines(Vec::from([text])),
+		];
+		if held {
+			let held = Queued::Held {
+				requests: Vec::from(requests),
+				since: Instant::now(),
+			};
+			return self.queue(generation, held).await;
+		}
+		for request in requests {
+			self.queue(generation, Queued::Request(request)).await?;
+		}
+		Ok(())
+	}
+	/// Queues `text` to be spoken with `priority` like [`SpeechSender::speak`], with an index mark before the character at each offset in `marks`, which are in order; speech-dispatcher says when speech gets to each, see [`SpeechSender::marks`].
+	/// The message is sent as SSML, switching SSML mode on only for it, so it is sent in one go, with nothing else in between; it is never held back while the user is typing.
+	/// # Errors
+	/// If the task handling requests has stopped.
+	pub async fn speak_marked(
+		&self,
+		priority: Priority,
+		text: String,
+		marks: &[(usize, String)],
+	) -> Result<(), SendError<Request>> {
+		if !self.show(&text) {
+			return Ok(());
+		}
+		let generation = self.generation.load(Ordering::Acquire);
+		self.interruptible.store(
+			matches!(
+				priority,
+				Priority::Text | Priority::Notification | Priority::Progress
+			),
+			Ordering::Release,
+		);
+		self.queued(text.chars().count());
+		let requests = Vec::from([
+			Request::SetSsmlMode(true),
+			Request::SetPriority(priority),
+			Request::Speak,
+			Request::SendLines(Vec::from([ssml(&text, marks)])),
+			Request::SetSsmlMode(false),
+		]);
+		self.queue(generation, Queued::Message(requests)).await
+	}
+	/// The name of the last index mark speech got to, which changes whenever speech gets to another; see [`SpeechSender::speak_marked`].
+	#[must_use]
+	pub fn marks(&self) -> watch::Receiver<String> {
+		self.marks.subscribe()
+	}
+	/// Queues `ch` to be spoken as a single character, with `priority`; speech-dispatcher then names punctuation and symbols, and uses the voice's way of spelling letters.
+	/// Like [`SpeechSender::speak`], it may be shown in braille instead, or as well.
+	/// # Errors
+	/// If the task handling requests has stopped.
+	pub async fn speak_char(
+		&self,
+		priority: Priority,
+		ch: char,
+	) -> Result<(), SendError<Request>> {
+		if !self.show(&ch.to_string()) {
+			return Ok(());
+		}
+		let generation = self.generation.load(Ordering::Acquire);
+		self.queued(1);
+		for request in [Request::SetPriority(priority), Request::SpeakChar(ch)] {
+			self.queue(generation, Queued::Request(request)).await?;
+		}
+		Ok(())
+	}
+	/// Stops all speech: whatever speech-dispatcher is saying is cancelled, and any speech queued before this call is dropped.
+	/// # Errors
+	/// If the task handling requests has stopped.
+	pub async fn stop(&self) -> Result<(), SendError<Request>> {
+		self.generation.fetch_add(1, Ordering::AcqRel);
+		*self.busy_until.lock().unwrap_or_else(PoisonError::into_inner) = None;
+		self.send(Request::Cancel(MessageScope::All)).await
+	}
+	/// Stops all speech like [`SpeechSender::stop`], but only if the last message queued was of a low priority (text, notification or progress).
+	/// This is what happens when the user starts typing: it should cut off reading, but not an important message.
+	/// Returns whether speech was stopped.
+	/// # Errors
+	/// If the task handling requests has stopped.
+	pub async fn interrupt(&self) -> Result<bool, SendError<Request>> {
+		if !self.interruptible.swap(false, Ordering::AcqRel) {
+			return Ok(false);
+		}
+		self.stop().await?;
+		Ok(true)
+	}
+}
+
+/// Whether `request` is (part of) speech, rather than a setting or control request, which must be sent even if speech was stopped after it was queued.
+fn is_speech(request: &Request) -> bool {
+	matches!(
+		request,
+		Request::SetPriority(_)
+			| Request::Speak | Request::SendLine(_)
+			| Request::SendLines(_)
+			| Request::SpeakChar(_)
+			| Request::SpeakKey(_)
+	)
+}
+
+/// `text` as SSML, with an index mark before the character at each offset in `marks`; marks past the end of the text go at its end.
+fn ssml(text: &str, marks: &[(usize, String)]) -> String {
+	fn escape(text: &str, ssml: &mut String) {
+		for c in text.chars() {
+			match c {
+				'&' => ssml.push_str("&amp;"),
+				'<' => ssml.push_str("&lt;"),
+				'>' => ssml.push_str("&gt;"),
+				'"' => ssml.push_str("&quot;"),
+				c => ssml.push(c),
+			}
+		}
+	}
+	let mut ssml = String::from("<speak>");
+	let mut marks = marks.iter().peekable();
+	let mark = |ssml: &mut String, name: &str| {
+		ssml.push_str("<mark name=\"");
+		escape(name, ssml);
+		ssml.push_str("\"/>");
+	};
+	for (i, c) in text.chars().enumerate() {
+		while let Some((_, name)) = marks.next_if(|(offset, _)| *offset <= i) {
+			mark(&mut ssml, name);
+		}
+		escape(c.encode_utf8(&mut [0; 4]), &mut ssml);
+	}
+	for (_, name) in marks {
+		mark(&mut ssml, name);
+	}
+	ssml.push_str("</speak>");
+	ssml
+}
+
 /// Creates a new async SSIP client which can be sent commends, and can await responses to.
 /// # Errors
 /// There may be errors when trying to send the initial registration command, or when parsing the response.
@@ -63,9 +435,73 @@ pub async fn create_ssip_client(
 		.check_client_name_set()
 		.await?;
 	tracing::debug!("SSIP client registered as odilia:speech");
+	// for where continuous reading has got to; see `SpeechSender::speak_marked`
+	ssip_core
+		.set_notification(NotificationType::IndexMark, true)
+		.await?
+		.check_status(OK_NOTIFICATION_SET)
+		.await?;
 	Ok(ssip_core)
 }
 
+/// A request, as it is logged: requests carrying text only show that they do if `.1` is set, so that what the user reads stays out of the logs.
+struct Logged<'a>(&'a Request, bool);
+
+impl std::fmt::Debug for Logged<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Logged(Request::SendLine(_), true) => f.write_str("SendLine(<redacted>)"),
+			Logged(Request::SendLines(_), true) => f.write_str("SendLines(<redacted>)"),
+			Logged(Request::SpeakChar(_), true) => f.write_str("SpeakChar(<redacted>)"),
+			Logged(Request::SpeakKey(_), true) => f.write_str("SpeakKey(<redacted>)"),
+			Logged(request, _) => request.fmt(f),
+		}
+	}
+}
+
+/// Publishes the index mark in `response` to `marks`, if it is one; returns whether it was a notification, rather than a response to a request.
+fn notified(response: &Response, marks: &watch::Sender<String>) -> bool {
+	match response {
+		Response::EventIndexMark(_, mark) => {
+			marks.send_replace(mark.clone());
+			true
+		}
+		Response::EventBegin(_)
+		| Response::EventEnd(_)
+		| Response::EventCanceled(_)
+		| Response::EventPaused(_)
+		| Response::EventResumed(_) => true,
+		_ => false,
+	}
+}
+
+/// Waits for the response to the request sent last; notifications which come before it are published to `marks`.
+async fn receive(
+	client: &mut AsyncClient<BufReader<OwnedReadHalf>, BufWriter<OwnedWriteHalf>>,
+	marks: &watch::Sender<String>,
+) -> eyre::Result<Response> {
+	loop {
+		let response = client.receive().await?;
+		if !notified(&response, marks) {
+			return Ok(response);
+		}
+	}
+}
+
+/// Sends `request` to speech-dispatcher, and waits for its response.
+async fn send_request(
+	client: &mut AsyncClient<BufReader<OwnedReadHalf>, BufWriter<OwnedWriteHalf>>,
+	request: Request,
+	redact_text: bool,
+	marks: &watch::Sender<String>,
+) -> eyre::Result<()> {
+	tracing::debug!(request = ?Logged(&request, redact_text), "SSIP command received");
+	client.send(request).await?;
+	let response = receive(client, marks).await?;
+	tracing::debug!(?response, "Recieved response from server");
+	Ok(())
+}
+
 /// A handler task for incoming SSIP requests
 /// This function will run untill it gets canceled via the cancellation token
 ///
@@ -74,41 +510,116 @@ pub async fn create_ssip_client(
 /// This function will return an error if anything within it fails. It may fail to read a value from the channel, it may fail to run an SSIP command, or fail to parse the response.
 /// Errors may also be returned during cleanup via the `cancellation_token` parameter, since shutting down the connection to speech dispatcher can also potentially error.
 /// Any of these failures will result in this function exiting with an `Err(_)` variant.
+/// With `redact_text` set, the text of speech is left out of the logs.
+/// `goodbye` is said once `shutdown` is cancelled, before the connection is closed.
+/// Index marks speech-dispatcher says speech got to are published to [`SpeechSender::marks`].
 #[tracing::instrument(level = "debug", skip_all, err)]
 pub async fn handle_ssip_commands(
 	mut client: AsyncClient<BufReader<OwnedReadHalf>, BufWriter<OwnedWriteHalf>>,
-	requests: Receiver<Request>,
+	requests: SpeechReceiver,
+	redact_text: bool,
+	goodbye: String,
 	shutdown: CancellationToken,
 ) -> eyre::Result<()> {
+	let SpeechReceiver { requests, generation, typing, timing, marks } = requests;
 	tokio::pin!(requests);
+	// once `SPEAK` has been sent, speech-dispatcher waits for the text, so the rest of the message has to follow even if speech was stopped in between
+	let mut in_message = false;
+	// messages sent in one go, waiting for the one being sent to be finished, oldest first
+	let mut waiting: VecDeque<(u64, Vec<Request>)> = VecDeque::new();
+	// messages held back while the user is typing, oldest first
+	let mut held: VecDeque<(u64, Vec<Request>, Instant)> = VecDeque::new();
+	// when the oldest held message may be spoken
+	let release_at = |held: &VecDeque<(u64, Vec<Request>, Instant)>| {
+		held.front().map_or_else(Instant::now, |(_, _, since)| {
+			typing.lock()
+				.unwrap_or_else(PoisonError::into_inner)
+				.release_at(&timing, *since)
+		})
+	};
 	loop {
+		let release = release_at(&held);
 		tokio::select! {
-				      request_option = requests.recv() => {
-					      if let Some(request) = request_option {
-		  tracing::debug!(?request, "SSIP command received");
-		  let response = client
-		    .send(request).await?
-		    .receive().await?;
-		  tracing::debug!(?response, "Recieved response from server");
-		}
-				      }
-				      () = shutdown.cancelled() => {
-		      tracing::debug!("Saying goodbye message.");
-		      client
-			      .send(Request::Speak).await?
-			      .receive().await?;
-		      client
-			      .send(Request::SendLines(Vec::from(["Quitting Odilia".to_string()]))).await?
-			      .receive().await?;
-		      tracing::debug!("Attempting to quit SSIP.");
-		      let response = client
-			.send(Request::Quit).await?
-			.receive().await?;
-		      tracing::debug!(?response, "Recieved response from server");
-					      tracing::debug!("SSIP command interpreter shutdown completed");
-					      break;
-				      }
-			      }
+				    request_option = requests.recv() => {
+					    let Some((request_generation, queued)) = request_option else {
+						    continue;
+					    };
+					    let request = match queued {
+						    Queued::Request(request) => request,
+						    Queued::Held { requests, since } => {
+							    held.push_back((request_generation, requests, since));
+							    continue;
+						    }
+						    Queued::Message(requests) => {
+							    waiting.push_back((request_generation, requests));
+							    continue;
+						    }
+					    };
+		if !in_message
+			&& is_speech(&request)
+			&& request_generation < generation.load(Ordering::Acquire)
+		{
+			tracing::debug!(request = ?Logged(&request, redact_text), "Dropping speech queued before it was stopped");
+			continue;
+		}
+		match request {
+			Request::Speak => in_message = true,
+			Request::SendLine(_) | Request::SendLines(_) => in_message = false,
+			_ => {}
+		}
+		send_request(&mut client, request, redact_text, &marks).await?;
+				    }
+				    // speech-dispatcher writes each notification at once, so reading one is never cut off halfway by another branch
+				    response = client.receive() => {
+					    let response = response?;
+					    if !notified(&response, &marks) {
+						    tracing::debug!(?response, "Received a response no request was waiting for");
+					    }
+				    }
+				    () = std::future::ready(()), if !in_message && !waiting.is_empty() => {
+					    let Some((request_generation, requests)) = waiting.pop_front() else {
+						    continue;
+					    };
+					    if request_generation < generation.load(Ordering::Acquire) {
+						    tracing::debug!("Dropping a message queued before speech was stopped");
+						    continue;
+					    }
+					    for request in requests {
+						    send_request(&mut client, request, redact_text, &marks).await?;
+					    }
+				    }
+				    // never in the middle of another message
+				    () = tokio::time::sleep_until(release.into()), if !in_message && !held.is_empty() => {
+					    // the user may have gone on typing while this slept; if so, the wait starts over
+					    if release_at(&held) > Instant::now() {
+						    continue;
+					    }
+					    let Some((request_generation, requests, _)) = held.pop_front() else {
+						    continue;
+					    };
+					    if request_generation < generation.load(Ordering::Acquire) {
+						    tracing::debug!("Dropping held speech queued before it was stopped");
+						    continue;
+					    }
+					    for request in requests {
+						    send_request(&mut client, request, redact_text, &marks).await?;
+					    }
+				    }
+				    () = shutdown.cancelled() => {
+		    tracing::debug!("Saying goodbye message.");
+		    client.send(Request::Speak).await?;
+		    receive(&mut client, &marks).await?;
+		    client
+			    .send(Request::SendLines(Vec::from([goodbye]))).await?;
+		    receive(&mut client, &marks).await?;
+		    tracing::debug!("Attempting to quit SSIP.");
+		    client.send(Request::Quit).await?;
+		    let response = receive(&mut client, &marks).await?;
+		    tracing::debug!(?response, "Recieved response from server");
+					    tracing::debug!("SSIP command interpreter shutdown completed");
+					    break;
+				    }
+			    }
 	}
 	Ok(())
 }
@@ -0,0 +1,233 @@
+#![deny(
+	clippy::all,
+	clippy::pedantic,
+	clippy::cargo,
+	clippy::map_unwrap_or,
+	clippy::unwrap_used,
+	unsafe_code
+)]
+#![allow(clippy::multiple_crate_versions)]
+
+//! Turns spoken commands, like "next heading" or "stop", into Odilia events.
+//!
+//! Recognition is push-to-talk: sending this process `SIGUSR1` (for example, from a desktop shortcut running `pkill -USR1 odilia-voice-input`) starts recording, and sending it again stops it.
+//! The recording is then transcribed locally by an external recogniser, like whisper.cpp's `whisper-cli` or vosk's `vosk-transcriber`, and the phrase heard is looked up in `voice-input.toml`.
+
+use std::{
+	collections::HashMap,
+	env,
+	path::{Path, PathBuf},
+	process::Stdio,
+	time::Duration,
+};
+
+use atspi_common::Role;
+use eyre::{bail, Context};
+use nix::{sys::signal, unistd::Pid};
+use odilia_common::events::{Direction, ScreenReaderEvent};
+use serde::Deserialize;
+use tokio::{
+	process::{Child, Command},
+	signal::unix::{signal as unix_signal, SignalKind},
+};
+use tokio_util::sync::CancellationToken;
+use tracing_subscriber::EnvFilter;
+
+/// How long to wait for Odilia to create its input socket, when started along with it.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+///the configuration of the voice input server, read from `voice-input.toml` in odilia's configuration directory
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct Settings {
+	///the command recording from the microphone into a WAV file, followed by its arguments; the path of the file is appended, and it is stopped with `SIGINT`
+	record: Vec<String>,
+	///the command transcribing a WAV file, followed by its arguments; the path of the file is appended, and the text it prints is taken as what was said
+	transcribe: Vec<String>,
+	///the event sent for each phrase, in lowercase and without punctuation, for example `"next heading"`
+	commands: HashMap<String, ScreenReaderEvent>,
+	///whether odilia should say what was heard when it is not a known command, so that the user knows what went wrong
+	say_unrecognised: bool,
+	///how often to tell odilia that this server is still running, in milliseconds
+	heartbeat_ms: u64,
+}
+
+fn strings(words: &[&str]) -> Vec<String> {
+	words.iter().map(ToString::to_string).collect()
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		use Direction::{Backward, Forward};
+		// links are found through the text they are in, which finds ranges of text too
+		let links = [("next link", Forward), ("previous link", Backward)].map(
+			|(phrase, direction)| {
+				(
+					phrase.to_string(),
+					ScreenReaderEvent::NavigateLink {
+						direction,
+						unvisited: false,
+					},
+				)
+			},
+		);
+		let navigation = [
+			("heading", Role::Heading),
+			("button", Role::Button),
+			("table", Role::Table),
+		]
+		.into_iter()
+		.flat_map(|(name, role)| {
+			[
+				(
+					format!("next {name}"),
+					ScreenReaderEvent::StructuralNavigation(Forward, role),
+				),
+				(
+					format!("previous {name}"),
+					ScreenReaderEvent::StructuralNavigation(Backward, role),
+				),
+			]
+		});
+		Self {
+			record: strings(&[
+				"parecord",
+				"--channels=1",
+				"--rate=16000",
+				"--file-format=wav",
+			]),
+			transcribe: strings(&[
+				"whisper-cli",
+				"--no-prints",
+				"--no-timestamps",
+				"--file",
+			]),
+			commands: navigation
+				.chain(links)
+				.chain([
+					("stop".to_string(), ScreenReaderEvent::StopSpeech),
+					("be quiet".to_string(), ScreenReaderEvent::StopSpeech),
+				])
+				.collect(),
+			say_unrecognised: true,
+			heartbeat_ms: 5000,
+		}
+	}
+}
+
+fn load_settings() -> eyre::Result<Settings> {
+	let dirs = xdg::BaseDirectories::with_prefix("odilia")?;
+	let Some(path) = dirs.find_config_file("voice-input.toml") else {
+		return Ok(Settings::default());
+	};
+	let text = std::fs::read_to_string(&path)
+		.with_context(|| format!("Could not read {}", path.display()))?;
+	toml::from_str(&text).with_context(|| format!("{} is invalid", path.display()))
+}
+
+/// Runs `command`, with `path` appended to its arguments.
+fn command(command: &[String], path: &Path) -> eyre::Result<Command> {
+	let Some((program, args)) = command.split_first() else {
+		bail!("An empty command is configured");
+	};
+	let mut command = Command::new(program);
+	command.args(args).arg(path).stdin(Stdio::null());
+	Ok(command)
+}
+
+/// Lowercases `text`, and removes punctuation and extra whitespace, so that `" Next heading."` matches `next heading`.
+fn normalise(text: &str) -> String {
+	text.split_whitespace()
+		.map(|word| {
+			word.chars()
+				.filter(|c| c.is_alphanumeric() || *c == '\'')
+				.flat_map(char::to_lowercase)
+				.collect::<String>()
+		})
+		.filter(|word| !word.is_empty())
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+/// Stops `recorder` and transcribes what it recorded into `path`, then sends the event for it.
+async fn recognise(mut recorder: Child, path: &Path, settings: &Settings) -> eyre::Result<()> {
+	// recorders only finish writing the file when interrupted, not when killed
+	if let Some(pid) = recorder.id().and_then(|id| i32::try_from(id).ok()) {
+		signal::kill(Pid::from_raw(pid), signal::Signal::SIGINT)?;
+	}
+	recorder.wait().await?;
+	let output = command(&settings.transcribe, path)?
+		.stderr(Stdio::null())
+		.output()
+		.await?;
+	if let Err(e) = tokio::fs::remove_file(path).await {
+		tracing::warn!("Could not remove the recording {}: {e}", path.display());
+	}
+	if !output.status.success() {
+		bail!("The transcriber failed with {}", output.status);
+	}
+	let heard = String::from_utf8_lossy(&output.stdout);
+	let phrase = normalise(&heard);
+	tracing::debug!(phrase, "Heard");
+	let reply = odilia_input::send_event(&ScreenReaderEvent::UserActivity).await?;
+	// what was said is the text Odilia asked for, not a command
+	if reply.capture_text {
+		odilia_input::send_event(&ScreenReaderEvent::TextEntered(heard.trim().to_string()))
+			.await?;
+		return Ok(());
+	}
+	let event = match settings.commands.get(&phrase) {
+		Some(event) => event.clone(),
+		None if settings.say_unrecognised && !phrase.is_empty() => {
+			ScreenReaderEvent::Custom { name: "say".to_string(), args: vec![phrase] }
+		}
+		None => return Ok(()),
+	};
+	odilia_input::send_event(&event).await?;
+	Ok(())
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+	tracing_subscriber::fmt()
+		.with_env_filter(EnvFilter::from_default_env())
+		.init();
+	let settings = load_settings()?;
+	if let Err(e) = odilia_input::wait_for_socket(STARTUP_TIMEOUT).await {
+		tracing::error!("{e}");
+		std::process::exit(odilia_input::EXIT_UNAVAILABLE);
+	}
+	let runtime_dir =
+		env::var("XDG_RUNTIME_DIR").map_or_else(|_| env::temp_dir(), PathBuf::from);
+	let recording = runtime_dir.join(format!("odilia-voice-{}.wav", std::process::id()));
+	let shutdown = CancellationToken::new();
+	let heartbeats = tokio::spawn(odilia_input::send_heartbeats(
+		"voice-input".to_string(),
+		Duration::from_millis(settings.heartbeat_ms),
+		shutdown.clone(),
+	));
+	let mut push_to_talk = unix_signal(SignalKind::user_defined1())?;
+	let mut recorder: Option<Child> = None;
+	tracing::info!("Send SIGUSR1 to start listening, and again to stop");
+	loop {
+		tokio::select! {
+			_ = push_to_talk.recv() => {
+				match recorder.take() {
+					None => {
+						let started = command(&settings.record, &recording)?.kill_on_drop(true).spawn();
+						recorder = Some(started.context("Could not start recording")?);
+					}
+					Some(running) => {
+						if let Err(e) = recognise(running, &recording, &settings).await {
+							tracing::warn!("Could not recognise a command: {e:?}");
+						}
+					}
+				}
+			}
+			_ = tokio::signal::ctrl_c() => break,
+		}
+	}
+	shutdown.cancel();
+	heartbeats.await?;
+	Ok(())
+}